@@ -0,0 +1,478 @@
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use clawden_core::{
+    current_unix_ms, worker_descriptor_for, CodingTool, ExecutionMode, HealthStatus, LogStream,
+    ProcessManager, Task, TaskCost, TaskResult, TaskStatus, WorkerAdapter, WorkerConfig,
+    WorkerDescriptor, WorkerHandle,
+};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::Duration;
+
+/// Drives a CLI coding tool (Claude Code, Codex, ...) as a one-shot task
+/// worker. Each dispatched task is a child process launched through
+/// `ProcessManager`, so it gets the usual pid/log files under `~/.clawden`.
+pub struct CliWorkerAdapter {
+    descriptor: &'static WorkerDescriptor,
+}
+
+impl CliWorkerAdapter {
+    pub fn new(tool: CodingTool) -> Self {
+        Self {
+            descriptor: worker_descriptor_for(tool),
+        }
+    }
+
+    fn resolve_binary(&self, config: &WorkerConfig) -> Result<PathBuf> {
+        let found = match &config.binary {
+            Some(path) => path.is_file().then(|| path.clone()),
+            None => find_on_path(self.descriptor.binary),
+        };
+        found.ok_or_else(|| {
+            anyhow!(
+                "{} binary '{}' not found. Install it with: {}",
+                self.descriptor.display_name,
+                config
+                    .binary
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| self.descriptor.binary.to_string()),
+                self.descriptor.install_hint
+            )
+        })
+    }
+
+    fn task_args(&self, handle: &WorkerHandle, task: &Task) -> Vec<String> {
+        let mut args = self
+            .descriptor
+            .task_args
+            .iter()
+            .map(|arg| arg.replace("{prompt}", &task.prompt))
+            .collect::<Vec<_>>();
+        if let (Some(flag), Some(tools)) = (self.descriptor.allowed_tools_flag, &task.allowed_tools)
+        {
+            if !tools.is_empty() {
+                args.push(flag.to_string());
+                args.push(tools.join(","));
+            }
+        }
+        args.extend(handle.extra_args.iter().cloned());
+        args
+    }
+}
+
+#[async_trait]
+impl WorkerAdapter for CliWorkerAdapter {
+    fn metadata(&self) -> &'static WorkerDescriptor {
+        self.descriptor
+    }
+
+    async fn start(&self, config: &WorkerConfig) -> Result<WorkerHandle> {
+        let binary = self.resolve_binary(config)?;
+        Ok(WorkerHandle {
+            id: format!("{}-{}", config.name, current_unix_ms()),
+            name: config.name.clone(),
+            tool: self.descriptor.tool,
+            binary,
+            process_name: format!("worker-{}", config.name),
+            env_vars: config.env_vars.clone(),
+            extra_args: config.extra_args.clone(),
+        })
+    }
+
+    async fn stop(&self, handle: &WorkerHandle) -> Result<()> {
+        let process_manager = ProcessManager::new(ExecutionMode::Direct)?;
+        process_manager.stop(&handle.process_name)
+    }
+
+    async fn health(&self, handle: &WorkerHandle) -> Result<HealthStatus> {
+        let process_manager = ProcessManager::new(ExecutionMode::Direct)?;
+        if task_running(&process_manager, &handle.process_name)? {
+            return Ok(HealthStatus::Healthy);
+        }
+
+        let responsive = Command::new(&handle.binary)
+            .args(self.descriptor.version_args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false);
+        Ok(if responsive {
+            HealthStatus::Healthy
+        } else {
+            HealthStatus::Unhealthy
+        })
+    }
+
+    async fn dispatch(&self, handle: &WorkerHandle, task: &Task) -> Result<TaskResult> {
+        if !task.project_dir.is_dir() {
+            bail!(
+                "task '{}' project directory does not exist: {}",
+                task.id,
+                task.project_dir.display()
+            );
+        }
+
+        let process_manager = ProcessManager::new(ExecutionMode::Direct)?;
+        if task_running(&process_manager, &handle.process_name)? {
+            bail!(
+                "worker '{}' is already running a task; stop it or wait for it to finish",
+                handle.name
+            );
+        }
+
+        let args = self.task_args(handle, task);
+        let process = process_manager.spawn_task(
+            &handle.process_name,
+            &handle.binary,
+            &args,
+            &handle.env_vars,
+            &task.project_dir,
+        )?;
+        // Waiting polls the child with sleeps; keep it off the async workers.
+        let timeout = task.timeout_secs.map(Duration::from_secs);
+        let exit = tokio::task::spawn_blocking(move || process_manager.wait_task(process, timeout))
+            .await
+            .map_err(|err| anyhow!("task '{}' wait failed: {err}", task.id))??;
+
+        let parsed = parse_task_output(&exit.stdout);
+        let status = if exit.timed_out {
+            TaskStatus::TimedOut
+        } else if exit.exit_code != Some(0) || parsed.is_error {
+            TaskStatus::Failed
+        } else {
+            TaskStatus::Completed
+        };
+
+        Ok(TaskResult {
+            task_id: task.id.clone(),
+            status,
+            output: parsed.output,
+            exit_code: exit.exit_code,
+            duration_ms: exit.duration_ms,
+            cost: parsed.cost,
+            raw: parsed.raw,
+        })
+    }
+
+    async fn stream_output(&self, handle: &WorkerHandle) -> Result<LogStream> {
+        let process_manager = ProcessManager::new(ExecutionMode::Direct)?;
+        process_manager.stream_logs(std::slice::from_ref(&handle.process_name))
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ParsedOutput {
+    pub output: String,
+    pub raw: Option<serde_json::Value>,
+    pub cost: Option<TaskCost>,
+    pub is_error: bool,
+}
+
+/// Extract the task result from a tool's stdout. Tools either print one JSON
+/// document (`claude --output-format json`) or JSON lines where the last
+/// object carries the result (`codex exec --json`); anything else is kept as
+/// plain text.
+pub(crate) fn parse_task_output(stdout: &str) -> ParsedOutput {
+    let trimmed = stdout.trim();
+    let value = serde_json::from_str::<serde_json::Value>(trimmed)
+        .ok()
+        .or_else(|| {
+            trimmed
+                .lines()
+                .rev()
+                .find_map(|line| serde_json::from_str::<serde_json::Value>(line.trim()).ok())
+        })
+        .filter(|value| value.is_object());
+
+    let Some(value) = value else {
+        return ParsedOutput {
+            output: trimmed.to_string(),
+            ..ParsedOutput::default()
+        };
+    };
+
+    let output = ["result", "output", "message", "content", "text"]
+        .iter()
+        .find_map(|key| value.get(*key).and_then(|v| v.as_str()))
+        .map(str::to_string)
+        .unwrap_or_else(|| trimmed.to_string());
+    let is_error = value
+        .get("is_error")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+        || value.get("subtype").and_then(|v| v.as_str()) == Some("error");
+
+    let usage = value.get("usage");
+    let cost = TaskCost {
+        input_tokens: usage
+            .and_then(|u| u.get("input_tokens"))
+            .and_then(|v| v.as_u64()),
+        output_tokens: usage
+            .and_then(|u| u.get("output_tokens"))
+            .and_then(|v| v.as_u64()),
+        usd: value
+            .get("total_cost_usd")
+            .or_else(|| value.get("cost_usd"))
+            .and_then(|v| v.as_f64()),
+    };
+    let cost = (cost != TaskCost::default()).then_some(cost);
+
+    ParsedOutput {
+        output,
+        raw: Some(value),
+        cost,
+        is_error,
+    }
+}
+
+fn task_running(process_manager: &ProcessManager, process_name: &str) -> Result<bool> {
+    Ok(process_manager
+        .list_statuses()?
+        .into_iter()
+        .any(|status| status.runtime == process_name && status.running))
+}
+
+fn find_on_path(binary: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    std::env::split_paths(&path_var)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_task_output, CliWorkerAdapter};
+    use clawden_core::{CodingTool, HealthStatus, Task, TaskStatus, WorkerAdapter, WorkerConfig};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    const FAKE_CLAUDE: &str = r#"#!/usr/bin/env sh
+if [ "$1" = "--version" ]; then
+    echo "1.0.0 (fake claude)"
+    exit 0
+fi
+printf '%s\n' "$@" > "$FAKE_WORKER_ARGS"
+case "${FAKE_WORKER_MODE:-ok}" in
+  ok)
+    echo "thinking..." >&2
+    printf '{"type":"result","is_error":false,"result":"done in %s","total_cost_usd":0.25,"usage":{"input_tokens":12,"output_tokens":34}}\n' "$(pwd)"
+    ;;
+  error)
+    echo "boom" >&2
+    exit 3
+    ;;
+  slow)
+    sleep 5
+    ;;
+esac
+"#;
+
+    struct Fixture {
+        root: PathBuf,
+        original_home: Option<String>,
+    }
+
+    impl Fixture {
+        fn new(name: &str) -> Self {
+            let unique = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("system time should be after UNIX_EPOCH")
+                .as_nanos();
+            let root = std::env::temp_dir().join(format!("clawden-worker-{name}-{unique}"));
+            fs::create_dir_all(root.join("home")).expect("home dir");
+            fs::create_dir_all(root.join("project")).expect("project dir");
+            let original_home = std::env::var("HOME").ok();
+            std::env::set_var("HOME", root.join("home"));
+            write_executable(&root.join("claude"), FAKE_CLAUDE);
+            Self {
+                root,
+                original_home,
+            }
+        }
+
+        fn config(&self, mode: &str) -> WorkerConfig {
+            WorkerConfig {
+                name: "coder".to_string(),
+                tool: CodingTool::ClaudeCode,
+                binary: Some(self.root.join("claude")),
+                env_vars: vec![
+                    ("FAKE_WORKER_MODE".to_string(), mode.to_string()),
+                    (
+                        "FAKE_WORKER_ARGS".to_string(),
+                        self.root.join("args.log").display().to_string(),
+                    ),
+                ],
+                extra_args: Vec::new(),
+            }
+        }
+
+        fn task(&self, timeout_secs: Option<u64>) -> Task {
+            Task {
+                id: "task-1".to_string(),
+                prompt: "fix the build".to_string(),
+                project_dir: self.root.join("project"),
+                timeout_secs,
+                allowed_tools: Some(vec!["Read".to_string(), "Edit".to_string()]),
+            }
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            if let Some(home) = &self.original_home {
+                std::env::set_var("HOME", home);
+            } else {
+                std::env::remove_var("HOME");
+            }
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    fn write_executable(path: &Path, body: &str) {
+        fs::write(path, body).expect("script should be written");
+        let mut perms = fs::metadata(path).expect("metadata").permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms).expect("chmod");
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Runtime::new()
+            .expect("tokio runtime should initialize")
+            .block_on(future)
+    }
+
+    #[test]
+    fn dispatch_captures_json_result_and_cost() {
+        let _guard = crate::adapter_test_env_lock();
+        let fixture = Fixture::new("dispatch");
+        let adapter = CliWorkerAdapter::new(CodingTool::ClaudeCode);
+
+        let result = block_on(async {
+            let handle = adapter.start(&fixture.config("ok")).await.expect("start");
+            adapter
+                .dispatch(&handle, &fixture.task(Some(10)))
+                .await
+                .expect("dispatch")
+        });
+
+        assert_eq!(result.status, TaskStatus::Completed);
+        assert_eq!(result.exit_code, Some(0));
+        assert!(result.output.starts_with("done in "));
+        assert!(result.output.ends_with("project"), "{}", result.output);
+        let cost = result.cost.expect("cost should be parsed");
+        assert_eq!(cost.input_tokens, Some(12));
+        assert_eq!(cost.output_tokens, Some(34));
+        assert_eq!(cost.usd, Some(0.25));
+
+        let args = fs::read_to_string(fixture.root.join("args.log")).expect("args log");
+        let args: Vec<&str> = args.lines().collect();
+        assert_eq!(
+            args,
+            vec![
+                "-p",
+                "fix the build",
+                "--output-format",
+                "json",
+                "--allowedTools",
+                "Read,Edit"
+            ]
+        );
+
+        let log = fs::read_to_string(fixture.root.join("home/.clawden/logs/worker-coder.log"))
+            .expect("worker log");
+        assert!(log.contains("thinking..."));
+        assert!(!fixture
+            .root
+            .join("home/.clawden/run/worker-coder.pid")
+            .exists());
+    }
+
+    #[test]
+    fn dispatch_reports_failure_and_timeout() {
+        let _guard = crate::adapter_test_env_lock();
+        let fixture = Fixture::new("failure");
+        let adapter = CliWorkerAdapter::new(CodingTool::ClaudeCode);
+
+        let (failed, timed_out) = block_on(async {
+            let handle = adapter
+                .start(&fixture.config("error"))
+                .await
+                .expect("start");
+            let failed = adapter
+                .dispatch(&handle, &fixture.task(None))
+                .await
+                .expect("dispatch");
+            let handle = adapter.start(&fixture.config("slow")).await.expect("start");
+            let timed_out = adapter
+                .dispatch(&handle, &fixture.task(Some(1)))
+                .await
+                .expect("dispatch");
+            (failed, timed_out)
+        });
+
+        assert_eq!(failed.status, TaskStatus::Failed);
+        assert_eq!(failed.exit_code, Some(3));
+        assert_eq!(timed_out.status, TaskStatus::TimedOut);
+        assert!(timed_out.duration_ms < 4000);
+    }
+
+    #[test]
+    fn missing_binary_reports_install_hint() {
+        let _guard = crate::adapter_test_env_lock();
+        let fixture = Fixture::new("missing");
+        let adapter = CliWorkerAdapter::new(CodingTool::ClaudeCode);
+        let mut config = fixture.config("ok");
+        config.binary = Some(fixture.root.join("does-not-exist"));
+
+        let err = block_on(adapter.start(&config)).expect_err("start should fail");
+        assert!(err
+            .to_string()
+            .contains("npm i -g @anthropic-ai/claude-code"));
+    }
+
+    #[test]
+    fn health_tracks_binary_responsiveness() {
+        let _guard = crate::adapter_test_env_lock();
+        let fixture = Fixture::new("health");
+        let adapter = CliWorkerAdapter::new(CodingTool::ClaudeCode);
+
+        block_on(async {
+            let handle = adapter.start(&fixture.config("ok")).await.expect("start");
+            assert!(matches!(
+                adapter.health(&handle).await.expect("health"),
+                HealthStatus::Healthy
+            ));
+            fs::remove_file(fixture.root.join("claude")).expect("remove fake binary");
+            assert!(matches!(
+                adapter.health(&handle).await.expect("health"),
+                HealthStatus::Unhealthy
+            ));
+        });
+    }
+
+    #[test]
+    fn parse_task_output_handles_json_lines_and_plain_text() {
+        let parsed = parse_task_output(
+            "{\"type\":\"progress\"}\n{\"type\":\"result\",\"message\":\"all good\",\"is_error\":true}\n",
+        );
+        assert_eq!(parsed.output, "all good");
+        assert!(parsed.is_error);
+        assert!(parsed.cost.is_none());
+
+        let plain = parse_task_output("just some text\n");
+        assert_eq!(plain.output, "just some text");
+        assert!(plain.raw.is_none());
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{DirectAdapter, DockerAdapter};
+use clawden_core::{ConfigFormat, RuntimeDescriptor, RuntimeMetadata};
+use std::collections::HashMap;
+
+/// Marker for adapters of custom runtimes, whose runtime and metadata come
+/// from a registered descriptor rather than a `RuntimeMeta` type.
+pub struct CustomMeta;
+
+pub type CustomDirectAdapter = DirectAdapter<CustomMeta>;
+pub type CustomDockerAdapter = DockerAdapter<CustomMeta>;
+
+pub fn custom_runtime_metadata(descriptor: &RuntimeDescriptor) -> RuntimeMetadata {
+    let config_format = match descriptor.config_format {
+        ConfigFormat::Toml => Some("toml"),
+        ConfigFormat::Json => Some("json"),
+        ConfigFormat::EnvVars => Some("env"),
+        ConfigFormat::None => None,
+    };
+    RuntimeMetadata {
+        runtime: descriptor.runtime.clone(),
+        version: "unknown".to_string(),
+        language: "unknown".to_string(),
+        capabilities: vec!["chat".to_string()],
+        default_port: descriptor.health_port,
+        config_format: config_format.map(str::to_string),
+        channel_support: HashMap::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::builtin_registry;
+    use clawden_core::{register_custom_runtime, ClawRuntime, CustomRuntimeSpec, ExecutionMode};
+
+    #[test]
+    fn builtin_registry_includes_custom_runtimes() {
+        let spec: CustomRuntimeSpec = serde_json::from_value(serde_json::json!({
+            "slug": "registryclaw",
+            "health_port": 9912,
+            "config_format": "json",
+        }))
+        .expect("parse spec");
+        register_custom_runtime(&spec).expect("register runtime");
+
+        let runtime = ClawRuntime::Custom("registryclaw".to_string());
+        for mode in [ExecutionMode::Direct, ExecutionMode::Docker] {
+            let registry = builtin_registry(mode);
+            let adapter = registry.get(&runtime).expect("custom adapter registered");
+            let metadata = adapter.metadata();
+            assert_eq!(metadata.runtime, runtime);
+            assert_eq!(metadata.default_port, Some(9912));
+            assert_eq!(metadata.config_format.as_deref(), Some("json"));
+        }
+    }
+}
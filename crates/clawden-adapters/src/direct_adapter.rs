@@ -0,0 +1,510 @@
+use crate::custom::{custom_runtime_metadata, CustomMeta};
+use crate::docker_adapter::{ConfigStore, InMemoryConfigStore, RuntimeMeta};
+use crate::docker_runtime::runtime_config_values;
+use crate::runtime_api::send_message;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use clawden_core::{
+    instance_name, runtime_default_start_args, validate_skill_name, AgentConfig, AgentHandle,
+    AgentMessage, AgentMetrics, AgentResponse, ClawAdapter, ClawRuntime, DirectStartOptions,
+    EventFilter, EventHub, EventStream, ExecutionMode, HealthStatus, InstallConfig, ProcessManager,
+    RuntimeConfig, RuntimeDescriptor, RuntimeEvent, RuntimeEventKind, RuntimeInstaller,
+    RuntimeMetadata, Skill, SkillDirectory, SkillManifest,
+};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Arguments, environment and project scope for one direct-mode launch.
+#[derive(Debug, Clone, Default)]
+pub struct DirectLaunch {
+    pub args: Vec<String>,
+    pub env_vars: Vec<(String, String)>,
+    pub project_hash: Option<String>,
+}
+
+/// Turns an agent config into a direct launch. The CLI plugs in the
+/// `clawden.yaml` config generator; the default only forwards the agent config.
+pub trait DirectConfigGenerator: Send + Sync + 'static {
+    fn prepare(
+        &self,
+        runtime: &str,
+        config: &AgentConfig,
+        executable: &Path,
+    ) -> Result<DirectLaunch>;
+}
+
+/// Default start args plus the agent's env vars, with channels and tools
+/// passed as `CLAWDEN_CHANNELS` / `CLAWDEN_TOOLS` like `clawden up` does.
+#[derive(Debug, Default)]
+pub struct PassthroughConfigGenerator;
+
+impl DirectConfigGenerator for PassthroughConfigGenerator {
+    fn prepare(
+        &self,
+        runtime: &str,
+        config: &AgentConfig,
+        _executable: &Path,
+    ) -> Result<DirectLaunch> {
+        let mut env_vars = config.env_vars.clone();
+        if !config.channels.is_empty() {
+            env_vars.push(("CLAWDEN_CHANNELS".to_string(), config.channels.join(",")));
+        }
+        if !config.tools.is_empty() {
+            env_vars.push(("CLAWDEN_TOOLS".to_string(), config.tools.join(",")));
+        }
+        Ok(DirectLaunch {
+            args: runtime_default_start_args(runtime)
+                .iter()
+                .map(|arg| (*arg).to_string())
+                .collect(),
+            env_vars,
+            project_hash: None,
+        })
+    }
+}
+
+/// Runs a runtime as a host process from the `clawden install` tree,
+/// tracked by `ProcessManager` like `clawden up` in direct mode.
+pub struct DirectAdapter<R> {
+    runtime: ClawRuntime,
+    metadata: RuntimeMetadata,
+    generator: Arc<dyn DirectConfigGenerator>,
+    store: Arc<dyn ConfigStore>,
+    launches: Mutex<HashMap<String, AgentConfig>>,
+    events: EventHub,
+    _marker: PhantomData<R>,
+}
+
+impl<R: RuntimeMeta> DirectAdapter<R> {
+    pub fn with_generator(generator: Arc<dyn DirectConfigGenerator>) -> Self {
+        Self::new(R::RUNTIME, R::metadata(), generator)
+    }
+}
+
+impl DirectAdapter<CustomMeta> {
+    /// Adapter for a registered custom runtime.
+    pub fn custom(
+        descriptor: &RuntimeDescriptor,
+        generator: Arc<dyn DirectConfigGenerator>,
+    ) -> Self {
+        Self::new(
+            descriptor.runtime.clone(),
+            custom_runtime_metadata(descriptor),
+            generator,
+        )
+    }
+}
+
+impl<R> DirectAdapter<R> {
+    fn new(
+        runtime: ClawRuntime,
+        metadata: RuntimeMetadata,
+        generator: Arc<dyn DirectConfigGenerator>,
+    ) -> Self {
+        Self {
+            runtime,
+            metadata,
+            generator,
+            store: Arc::new(InMemoryConfigStore::default()),
+            launches: Mutex::new(HashMap::new()),
+            events: EventHub::default(),
+            _marker: PhantomData,
+        }
+    }
+
+    fn process_manager() -> Result<ProcessManager> {
+        ProcessManager::new(ExecutionMode::Direct)
+    }
+
+    async fn launch(&self, config: &AgentConfig) -> Result<AgentHandle> {
+        let slug = self.runtime.as_slug();
+        let instance = agent_instance_name(slug, &config.name);
+        let info = {
+            let runtime = self.runtime.clone();
+            let generator = Arc::clone(&self.generator);
+            let config = config.clone();
+            let instance = instance.clone();
+            blocking(format!("starting {instance}"), move || {
+                let executable = installed_executable(&runtime)?;
+                let launch = generator.prepare(&instance, &config, &executable)?;
+                ProcessManager::new(ExecutionMode::Direct)?.start_direct(&DirectStartOptions {
+                    args: launch.args,
+                    env_vars: launch.env_vars,
+                    project_hash: launch.project_hash,
+                    ..DirectStartOptions::new(instance, executable)
+                })
+            })
+            .await?
+        };
+
+        let handle = AgentHandle {
+            id: instance,
+            name: config.name.clone(),
+            runtime: self.runtime.clone(),
+        };
+        // Point the message API at the port this instance was given.
+        let mut stored = config.clone();
+        if let Some(port) = info.port {
+            stored.env_vars.push((
+                "CLAWDEN_RUNTIME_API_URL".to_string(),
+                format!("http://127.0.0.1:{port}"),
+            ));
+        }
+        self.store
+            .set(&handle.id, runtime_config_values(slug, &stored));
+        if let Ok(mut launches) = self.launches.lock() {
+            launches.insert(handle.id.clone(), config.clone());
+        }
+        Ok(handle)
+    }
+
+    fn skills(&self) -> Result<SkillDirectory> {
+        SkillDirectory::for_runtime(&self.runtime)
+    }
+}
+
+fn installed_executable(runtime: &ClawRuntime) -> Result<PathBuf> {
+    let slug = runtime.as_slug();
+    RuntimeInstaller::new()?
+        .runtime_executable(slug)
+        .ok_or_else(|| anyhow!("{slug} is not installed. Run 'clawden install {slug}'"))
+}
+
+async fn stop_instance(id: &str) -> Result<()> {
+    let id = id.to_string();
+    blocking(format!("stopping {id}"), move || {
+        ProcessManager::new(ExecutionMode::Direct)?.stop(&id)
+    })
+    .await
+}
+
+/// Runs process-manager and installer work, which spawns processes, sleeps
+/// and downloads, off the async worker threads.
+async fn blocking<T: Send + 'static>(
+    action: String,
+    work: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|err| anyhow!("{action} failed: {err}"))?
+}
+
+/// Instance name for an agent: `<runtime>-default` (or the bare runtime
+/// name) is the default instance, anything else becomes `runtime@name`.
+fn agent_instance_name(runtime: &str, agent_name: &str) -> String {
+    let name = agent_name
+        .strip_prefix(runtime)
+        .map(|rest| rest.trim_start_matches('-'))
+        .unwrap_or(agent_name);
+    let sanitized: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let sanitized = sanitized.trim_matches('-');
+    if sanitized.is_empty() || sanitized == "default" {
+        runtime.to_string()
+    } else {
+        instance_name(runtime, Some(sanitized))
+    }
+}
+
+impl<R: RuntimeMeta> Default for DirectAdapter<R> {
+    fn default() -> Self {
+        Self::with_generator(Arc::new(PassthroughConfigGenerator))
+    }
+}
+
+#[async_trait]
+impl<R: Send + Sync + 'static> ClawAdapter for DirectAdapter<R> {
+    fn metadata(&self) -> RuntimeMetadata {
+        self.metadata.clone()
+    }
+
+    async fn install(&self, _config: &InstallConfig) -> Result<()> {
+        let slug = self.runtime.as_slug().to_string();
+        blocking(format!("installing {slug}"), move || {
+            let installer = RuntimeInstaller::new()?;
+            if installer.runtime_executable(&slug).is_none() {
+                installer.install_runtime(&slug, None)?;
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    async fn start(&self, config: &AgentConfig) -> Result<AgentHandle> {
+        let handle = self.launch(config).await?;
+        self.events
+            .publish(&handle.id, RuntimeEvent::state_changed("running"));
+        Ok(handle)
+    }
+
+    async fn stop(&self, handle: &AgentHandle) -> Result<()> {
+        stop_instance(&handle.id).await?;
+        self.store.remove(&handle.id);
+        if let Ok(mut launches) = self.launches.lock() {
+            launches.remove(&handle.id);
+        }
+        self.events
+            .publish(&handle.id, RuntimeEvent::state_changed("stopped"));
+        self.events.close(&handle.id);
+        Ok(())
+    }
+
+    async fn restart(&self, handle: &AgentHandle) -> Result<()> {
+        let config = self
+            .launches
+            .lock()
+            .ok()
+            .and_then(|launches| launches.get(&handle.id).cloned())
+            .ok_or_else(|| anyhow!("{} was not started by this adapter", handle.id))?;
+        stop_instance(&handle.id).await?;
+        self.launch(&config).await?;
+        self.events
+            .publish(&handle.id, RuntimeEvent::state_changed("restarted"));
+        Ok(())
+    }
+
+    async fn health(&self, handle: &AgentHandle) -> Result<HealthStatus> {
+        let id = handle.id.clone();
+        // Probes run curl, shell commands and file reads.
+        tokio::task::spawn_blocking(move || {
+            let process_manager = Self::process_manager()?;
+            let Some(info) = process_manager
+                .list_processes()?
+                .into_iter()
+                .find(|info| info.runtime == id)
+            else {
+                return Ok(HealthStatus::Unhealthy);
+            };
+            // Only a configured `health:` probe decides; otherwise a running
+            // runtime counts as healthy.
+            let probed = info.health.is_some();
+            let status = process_manager.process_status(info);
+            Ok(match status.health.as_str() {
+                _ if !status.running => HealthStatus::Unhealthy,
+                _ if !probed => HealthStatus::Healthy,
+                "healthy" => HealthStatus::Healthy,
+                "starting" => HealthStatus::Unknown,
+                _ => HealthStatus::Unhealthy,
+            })
+        })
+        .await
+        .map_err(|err| anyhow!("health check for {} failed: {err}", handle.id))?
+    }
+
+    async fn metrics(&self, handle: &AgentHandle) -> Result<AgentMetrics> {
+        Self::process_manager()?
+            .metrics(&handle.id)?
+            .ok_or_else(|| anyhow!("{} is not running", handle.id))
+    }
+
+    async fn send(&self, handle: &AgentHandle, message: &AgentMessage) -> Result<AgentResponse> {
+        let config = self.store.get(&handle.id);
+        self.events
+            .publish(&handle.id, RuntimeEvent::message_in(&message.content));
+        let content = send_message(&self.runtime, config.as_ref(), message).await?;
+        self.events
+            .publish(&handle.id, RuntimeEvent::message_out(&content));
+        Ok(AgentResponse { content })
+    }
+
+    async fn subscribe(&self, handle: &AgentHandle, event: &str) -> Result<EventStream> {
+        let filter = EventFilter::parse(event)?;
+        let (events, stream) = self.events.subscribe(&handle.id, filter);
+        if filter.wants(RuntimeEventKind::Log) || filter.wants(RuntimeEventKind::State) {
+            Self::process_manager()?.subscribe_events(&handle.id, events)?;
+        }
+        Ok(stream)
+    }
+
+    async fn get_config(&self, handle: &AgentHandle) -> Result<RuntimeConfig> {
+        Ok(self.store.get(&handle.id).unwrap_or_else(|| RuntimeConfig {
+            values: serde_json::json!({ "runtime": self.runtime.as_slug() }),
+        }))
+    }
+
+    async fn set_config(&self, handle: &AgentHandle, config: &RuntimeConfig) -> Result<()> {
+        self.store.set(&handle.id, config.clone());
+        Ok(())
+    }
+
+    async fn list_skills(&self, _handle: &AgentHandle) -> Result<Vec<Skill>> {
+        self.skills()?.list()
+    }
+
+    async fn install_skill(&self, _handle: &AgentHandle, skill: &SkillManifest) -> Result<()> {
+        validate_skill_name(&skill.name)?;
+        skill.ensure_supports(&self.runtime)?;
+        self.skills()?.install(skill).map(|_| ())
+    }
+
+    async fn remove_skill(&self, _handle: &AgentHandle, name: &str) -> Result<bool> {
+        validate_skill_name(name)?;
+        self.skills()?.remove(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{agent_instance_name, DirectAdapter};
+    use crate::{DockerAdapter, ZeroClawMeta};
+    use clawden_core::{AgentConfig, AgentHandle, ClawAdapter, ClawRuntime, HealthStatus};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    const FAKE_RUNTIME: &str = "#!/usr/bin/env sh\necho \"args=$*\"\necho \"key=$OPENAI_API_KEY channels=$CLAWDEN_CHANNELS\"\nexec sleep 30\n";
+
+    fn install_fake_runtime(home: &Path) {
+        let runtime_dir = home.join(".clawden/runtimes/zeroclaw");
+        fs::create_dir_all(runtime_dir.join("1.0.0")).expect("runtime dir");
+        let executable = runtime_dir.join("1.0.0/zeroclaw");
+        fs::write(&executable, FAKE_RUNTIME).expect("fake runtime");
+        fs::set_permissions(&executable, fs::Permissions::from_mode(0o755)).expect("chmod");
+        std::os::unix::fs::symlink("1.0.0", runtime_dir.join("current")).expect("current link");
+    }
+
+    #[test]
+    fn start_launches_installed_executable_and_stop_kills_it() {
+        let _guard = crate::adapter_test_env_lock();
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let home = std::env::temp_dir().join(format!("clawden-direct-adapter-{stamp}"));
+        install_fake_runtime(&home);
+        let original_home = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+
+        let runtime = tokio::runtime::Runtime::new().expect("tokio runtime should initialize");
+        runtime.block_on(async {
+            let adapter = DirectAdapter::<ZeroClawMeta>::default();
+            let handle = adapter
+                .start(&AgentConfig {
+                    name: "direct-agent".to_string(),
+                    runtime: ClawRuntime::ZeroClaw,
+                    model: None,
+                    env_vars: vec![("OPENAI_API_KEY".to_string(), "sk-test".to_string())],
+                    channels: vec!["telegram".to_string()],
+                    tools: Vec::new(),
+                })
+                .await
+                .expect("direct start should succeed");
+            assert!(matches!(
+                adapter.health(&handle).await.expect("health"),
+                HealthStatus::Healthy
+            ));
+
+            assert_eq!(handle.id, "zeroclaw@direct-agent");
+            let log_path = home.join(".clawden/logs/zeroclaw@direct-agent.log");
+            let mut log = String::new();
+            for _ in 0..50 {
+                log = fs::read_to_string(&log_path).unwrap_or_default();
+                if log.contains("key=") {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            assert!(log.contains("key=sk-test channels=telegram"), "{log}");
+
+            adapter.stop(&handle).await.expect("stop");
+            assert!(matches!(
+                adapter.health(&handle).await.expect("health"),
+                HealthStatus::Unhealthy
+            ));
+        });
+
+        match original_home {
+            Some(value) => std::env::set_var("HOME", value),
+            None => std::env::remove_var("HOME"),
+        }
+        let _ = fs::remove_dir_all(home);
+    }
+
+    #[test]
+    fn start_reports_missing_runtime_install() {
+        let _guard = crate::adapter_test_env_lock();
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let home = std::env::temp_dir().join(format!("clawden-direct-missing-{stamp}"));
+        fs::create_dir_all(&home).expect("home dir");
+        let original_home = std::env::var("HOME").ok();
+        std::env::set_var("HOME", &home);
+
+        let runtime = tokio::runtime::Runtime::new().expect("tokio runtime should initialize");
+        let err = runtime
+            .block_on(
+                DirectAdapter::<ZeroClawMeta>::default().start(&AgentConfig {
+                    name: "missing".to_string(),
+                    runtime: ClawRuntime::ZeroClaw,
+                    model: None,
+                    env_vars: Vec::new(),
+                    channels: Vec::new(),
+                    tools: Vec::new(),
+                }),
+            )
+            .expect_err("start should fail without an install");
+        assert!(err.to_string().contains("clawden install zeroclaw"));
+
+        match original_home {
+            Some(value) => std::env::set_var("HOME", value),
+            None => std::env::remove_var("HOME"),
+        }
+        let _ = fs::remove_dir_all(home);
+    }
+
+    #[test]
+    fn agent_names_map_to_instances() {
+        assert_eq!(
+            agent_instance_name("zeroclaw", "zeroclaw-default"),
+            "zeroclaw"
+        );
+        assert_eq!(agent_instance_name("zeroclaw", "zeroclaw"), "zeroclaw");
+        assert_eq!(
+            agent_instance_name("zeroclaw", "zeroclaw-work"),
+            "zeroclaw@work"
+        );
+        assert_eq!(
+            agent_instance_name("zeroclaw", "Research Bot"),
+            "zeroclaw@research-bot"
+        );
+    }
+
+    #[test]
+    fn skill_operations_reject_names_outside_the_skills_directory() {
+        let handle = AgentHandle {
+            id: "zeroclaw@skills".to_string(),
+            name: "skills".to_string(),
+            runtime: ClawRuntime::ZeroClaw,
+        };
+        let runtime = tokio::runtime::Runtime::new().expect("tokio runtime should initialize");
+        runtime.block_on(async {
+            let direct = DirectAdapter::<ZeroClawMeta>::default();
+            let docker = DockerAdapter::<ZeroClawMeta>::default();
+            for name in ["..", "../x", "a/b", "/etc"] {
+                let err = direct
+                    .remove_skill(&handle, name)
+                    .await
+                    .expect_err("direct remove should reject the name");
+                assert!(err.to_string().contains("invalid skill name"), "{err}");
+                let err = docker
+                    .remove_skill(&handle, name)
+                    .await
+                    .expect_err("docker remove should reject the name");
+                assert!(err.to_string().contains("invalid skill name"), "{err}");
+            }
+        });
+    }
+}
@@ -1,11 +1,18 @@
+use crate::custom::{custom_runtime_metadata, CustomMeta};
 use crate::docker_runtime::{
-    container_running, restart_container, runtime_config_values, start_container, stop_container,
+    adapter_dry_run, container_metrics, container_running, container_skill_manifests,
+    container_skills_dir, copy_skill_into_container, follow_container_events,
+    follow_container_logs, remove_container_skill, restart_container, runtime_config_values,
+    start_container, stop_container,
 };
-use anyhow::Result;
+use crate::runtime_api::send_message;
+use anyhow::{anyhow, Context, Result};
 use async_trait::async_trait;
 use clawden_core::{
-    AgentConfig, AgentHandle, AgentMessage, AgentMetrics, AgentResponse, ClawAdapter, ClawRuntime,
-    EventStream, HealthStatus, InstallConfig, RuntimeConfig, RuntimeMetadata, Skill, SkillManifest,
+    parse_skill_manifest, validate_skill_name, AgentConfig, AgentHandle, AgentMessage,
+    AgentMetrics, AgentResponse, ClawAdapter, ClawRuntime, EventFilter, EventHub, EventStream,
+    HealthStatus, InstallConfig, RuntimeConfig, RuntimeDescriptor, RuntimeEvent, RuntimeEventKind,
+    RuntimeMetadata, Skill, SkillManifest,
 };
 use std::collections::HashMap;
 use std::marker::PhantomData;
@@ -50,15 +57,34 @@ impl ConfigStore for InMemoryConfigStore {
     }
 }
 
-pub struct DockerAdapter<R: RuntimeMeta> {
+pub struct DockerAdapter<R> {
+    runtime: ClawRuntime,
+    metadata: RuntimeMetadata,
     store: Arc<dyn ConfigStore>,
+    events: EventHub,
     _marker: PhantomData<R>,
 }
 
 impl<R: RuntimeMeta> DockerAdapter<R> {
     pub fn with_store(store: Arc<dyn ConfigStore>) -> Self {
         Self {
+            runtime: R::RUNTIME,
+            metadata: R::metadata(),
             store,
+            events: EventHub::default(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl DockerAdapter<CustomMeta> {
+    /// Adapter for a registered custom runtime.
+    pub fn custom(descriptor: &RuntimeDescriptor) -> Self {
+        Self {
+            runtime: descriptor.runtime.clone(),
+            metadata: custom_runtime_metadata(descriptor),
+            store: Arc::new(InMemoryConfigStore::default()),
+            events: EventHub::default(),
             _marker: PhantomData,
         }
     }
@@ -71,9 +97,9 @@ impl<R: RuntimeMeta> Default for DockerAdapter<R> {
 }
 
 #[async_trait]
-impl<R: RuntimeMeta> ClawAdapter for DockerAdapter<R> {
+impl<R: Send + Sync + 'static> ClawAdapter for DockerAdapter<R> {
     fn metadata(&self) -> RuntimeMetadata {
-        R::metadata()
+        self.metadata.clone()
     }
 
     async fn install(&self, _config: &InstallConfig) -> Result<()> {
@@ -81,28 +107,35 @@ impl<R: RuntimeMeta> ClawAdapter for DockerAdapter<R> {
     }
 
     async fn start(&self, config: &AgentConfig) -> Result<AgentHandle> {
-        let container_id = start_container(R::RUNTIME.clone(), config)?;
+        let container_id = start_container(self.runtime.clone(), config)?;
         let handle = AgentHandle {
             id: container_id,
             name: config.name.clone(),
-            runtime: R::RUNTIME.clone(),
+            runtime: self.runtime.clone(),
         };
 
         self.store.set(
             &handle.id,
-            runtime_config_values(R::RUNTIME.as_slug(), config),
+            runtime_config_values(self.runtime.as_slug(), config),
         );
+        self.events
+            .publish(&handle.id, RuntimeEvent::state_changed("running"));
         Ok(handle)
     }
 
     async fn stop(&self, handle: &AgentHandle) -> Result<()> {
         stop_container(&handle.id)?;
         self.store.remove(&handle.id);
+        self.events
+            .publish(&handle.id, RuntimeEvent::state_changed("stopped"));
+        self.events.close(&handle.id);
         Ok(())
     }
 
     async fn restart(&self, handle: &AgentHandle) -> Result<()> {
         restart_container(&handle.id)?;
+        self.events
+            .publish(&handle.id, RuntimeEvent::state_changed("restarted"));
         Ok(())
     }
 
@@ -114,27 +147,48 @@ impl<R: RuntimeMeta> ClawAdapter for DockerAdapter<R> {
         }
     }
 
-    async fn metrics(&self, _handle: &AgentHandle) -> Result<AgentMetrics> {
-        Ok(AgentMetrics {
-            cpu_percent: 0.0,
-            memory_mb: 0.0,
-            queue_depth: 0,
-        })
-    }
-
-    async fn send(&self, _handle: &AgentHandle, message: &AgentMessage) -> Result<AgentResponse> {
-        Ok(AgentResponse {
-            content: format!("{} echo: {}", R::NAME, message.content),
-        })
-    }
-
-    async fn subscribe(&self, _handle: &AgentHandle, _event: &str) -> Result<EventStream> {
-        Ok(vec![])
+    async fn metrics(&self, handle: &AgentHandle) -> Result<AgentMetrics> {
+        if adapter_dry_run() {
+            return Ok(AgentMetrics {
+                cpu_percent: 0.0,
+                memory_mb: 0.0,
+                queue_depth: 0,
+                open_fds: 0,
+            });
+        }
+        let container_id = handle.id.clone();
+        tokio::task::spawn_blocking(move || container_metrics(&container_id))
+            .await
+            .context("docker metrics task panicked")?
+    }
+
+    async fn send(&self, handle: &AgentHandle, message: &AgentMessage) -> Result<AgentResponse> {
+        let config = self.store.get(&handle.id);
+        self.events
+            .publish(&handle.id, RuntimeEvent::message_in(&message.content));
+        let content = send_message(&self.runtime, config.as_ref(), message).await?;
+        self.events
+            .publish(&handle.id, RuntimeEvent::message_out(&content));
+        Ok(AgentResponse { content })
+    }
+
+    async fn subscribe(&self, handle: &AgentHandle, event: &str) -> Result<EventStream> {
+        let filter = EventFilter::parse(event)?;
+        let (events, stream) = self.events.subscribe(&handle.id, filter);
+        if !adapter_dry_run() {
+            if filter.wants(RuntimeEventKind::Log) {
+                follow_container_logs(&handle.id, events.clone())?;
+            }
+            if filter.wants(RuntimeEventKind::State) {
+                follow_container_events(&handle.id, events)?;
+            }
+        }
+        Ok(stream)
     }
 
     async fn get_config(&self, handle: &AgentHandle) -> Result<RuntimeConfig> {
         Ok(self.store.get(&handle.id).unwrap_or_else(|| RuntimeConfig {
-            values: serde_json::json!({ "runtime": R::RUNTIME.as_slug() }),
+            values: serde_json::json!({ "runtime": self.runtime.as_slug() }),
         }))
     }
 
@@ -143,11 +197,40 @@ impl<R: RuntimeMeta> ClawAdapter for DockerAdapter<R> {
         Ok(())
     }
 
-    async fn list_skills(&self, _handle: &AgentHandle) -> Result<Vec<Skill>> {
-        Ok(vec![])
+    async fn list_skills(&self, handle: &AgentHandle) -> Result<Vec<Skill>> {
+        if adapter_dry_run() {
+            return Ok(vec![]);
+        }
+        let skills_dir = container_skills_dir(&handle.id, &self.runtime)?;
+        let mut skills: Vec<Skill> = container_skill_manifests(&handle.id, &skills_dir)?
+            .iter()
+            .filter_map(|raw| parse_skill_manifest(raw).ok())
+            .map(|manifest| manifest.to_skill())
+            .collect();
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(skills)
+    }
+
+    async fn install_skill(&self, handle: &AgentHandle, skill: &SkillManifest) -> Result<()> {
+        validate_skill_name(&skill.name)?;
+        skill.ensure_supports(&self.runtime)?;
+        let source = skill
+            .source
+            .as_deref()
+            .ok_or_else(|| anyhow!("skill '{}' has no package directory", skill.name))?;
+        if adapter_dry_run() {
+            return Ok(());
+        }
+        let skills_dir = container_skills_dir(&handle.id, &self.runtime)?;
+        copy_skill_into_container(&handle.id, &skills_dir, &skill.name, source)
     }
 
-    async fn install_skill(&self, _handle: &AgentHandle, _skill: &SkillManifest) -> Result<()> {
-        Ok(())
+    async fn remove_skill(&self, handle: &AgentHandle, name: &str) -> Result<bool> {
+        validate_skill_name(name)?;
+        if adapter_dry_run() {
+            return Ok(false);
+        }
+        let skills_dir = container_skills_dir(&handle.id, &self.runtime)?;
+        remove_container_skill(&handle.id, &skills_dir, name)
     }
 }
@@ -1,10 +1,18 @@
 use anyhow::{bail, Context, Result};
-use clawden_core::{AgentConfig, ClawRuntime, RuntimeConfig};
-use std::process::{Command, Stdio};
-use std::thread::sleep;
-use std::time::Duration;
+use clawden_core::{
+    process_tree_open_fds, runtime_descriptor_for, runtime_skills_path, AgentConfig, AgentMetrics,
+    ClawRuntime, EventSender, Redactor, RuntimeConfig, RuntimeEvent, SKILL_MANIFEST_FILE,
+};
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, sleep};
+use std::time::{Duration, Instant};
 
 const DEFAULT_STARTUP_GRACE_MS: u64 = 3_000;
+/// How often a followed `docker` child checks whether its subscriber left.
+const SUBSCRIBER_POLL_INTERVAL: Duration = Duration::from_millis(200);
 
 pub fn runtime_config_values(runtime: &str, config: &AgentConfig) -> RuntimeConfig {
     RuntimeConfig {
@@ -39,10 +47,7 @@ pub fn start_container(runtime: ClawRuntime, config: &AgentConfig) -> Result<Str
     let default_name = container_name(runtime.clone(), &config.name);
     let name =
         docker_override(config, "CLAWDEN_DOCKER_NAME").unwrap_or_else(|| default_name.clone());
-    if std::env::var("CLAWDEN_ADAPTER_DRY_RUN")
-        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
-        .unwrap_or(false)
-    {
+    if adapter_dry_run() {
         return Ok(name);
     }
 
@@ -82,7 +87,9 @@ pub fn start_container(runtime: ClawRuntime, config: &AgentConfig) -> Result<Str
     }
 
     if !container_running(&name)? {
-        let logs = container_logs(&name)?;
+        let logs = Redactor::new(&config.env_vars)
+            .redact(&container_logs(&name)?)
+            .into_owned();
         if logs.is_empty() {
             bail!("docker runtime {} exited during startup", runtime.as_slug());
         }
@@ -184,6 +191,12 @@ fn build_run_args(
     args
 }
 
+pub fn adapter_dry_run() -> bool {
+    std::env::var("CLAWDEN_ADAPTER_DRY_RUN")
+        .map(|v| v == "1" || v.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
 fn docker_override(config: &AgentConfig, key: &str) -> Option<String> {
     config
         .env_vars
@@ -242,6 +255,399 @@ pub fn container_running(container_id: &str) -> Result<bool> {
     Ok(stdout.trim() == "true")
 }
 
+/// Names of running ClawDen-managed containers for a runtime.
+pub fn running_runtime_containers(runtime: &ClawRuntime) -> Result<Vec<String>> {
+    ensure_docker_available()?;
+    let output = Command::new("docker")
+        .args([
+            "ps",
+            "--filter",
+            "label=clawden.managed=true",
+            "--filter",
+            &format!("label=clawden.runtime={}", runtime.as_slug()),
+            "--format",
+            "{{.Names}}",
+        ])
+        .output()
+        .context("failed to list docker containers")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("docker ps failed: {}", stderr.trim());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// Absolute skills directory inside a container, resolving `~` against the
+/// container user's `$HOME`.
+pub fn container_skills_dir(container_id: &str, runtime: &ClawRuntime) -> Result<String> {
+    let path = runtime_skills_path(runtime).ok_or_else(|| {
+        anyhow::anyhow!(
+            "{} has no workspace directory for skills",
+            runtime.as_slug()
+        )
+    })?;
+    match path.strip_prefix("~/") {
+        Some(rest) => {
+            let home = docker_exec(container_id, &["printenv", "HOME"])?;
+            let home = home.trim();
+            let home = if home.is_empty() { "/root" } else { home };
+            Ok(format!("{}/{rest}", home.trim_end_matches('/')))
+        }
+        None => Ok(path),
+    }
+}
+
+/// Replaces `<skills_dir>/<name>` in the container with the package files.
+pub fn copy_skill_into_container(
+    container_id: &str,
+    skills_dir: &str,
+    name: &str,
+    source: &Path,
+) -> Result<()> {
+    let target = format!("{skills_dir}/{name}");
+    docker_exec(container_id, &["mkdir", "-p", skills_dir])?;
+    docker_exec(container_id, &["rm", "-rf", &target])?;
+    let output = Command::new("docker")
+        .arg("cp")
+        .arg(format!("{}/.", source.display()))
+        .arg(format!("{container_id}:{target}"))
+        .output()
+        .context("failed to copy skill into docker container")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("docker cp failed for {container_id}: {}", stderr.trim());
+    }
+    Ok(())
+}
+
+/// Raw `skill.toml` bodies of every skill installed in the container.
+pub fn container_skill_manifests(container_id: &str, skills_dir: &str) -> Result<Vec<String>> {
+    let script = format!(
+        "for f in \"$1\"/*/{SKILL_MANIFEST_FILE}; do [ -f \"$f\" ] && cat \"$f\" && printf '\\n{SKILL_SEPARATOR}\\n'; done; true"
+    );
+    let stdout = docker_exec(container_id, &["sh", "-c", &script, "sh", skills_dir])?;
+    Ok(stdout
+        .split(SKILL_SEPARATOR)
+        .map(str::trim)
+        .filter(|chunk| !chunk.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+pub fn remove_container_skill(container_id: &str, skills_dir: &str, name: &str) -> Result<bool> {
+    let target = format!("{skills_dir}/{name}");
+    let existed = docker_exec(
+        container_id,
+        &[
+            "sh",
+            "-c",
+            "[ -d \"$1\" ] && echo yes || true",
+            "sh",
+            &target,
+        ],
+    )?;
+    if existed.trim() != "yes" {
+        return Ok(false);
+    }
+    docker_exec(container_id, &["rm", "-rf", &target])?;
+    Ok(true)
+}
+
+const SKILL_SEPARATOR: &str = "--- clawden-skill ---";
+
+fn docker_exec(container_id: &str, args: &[&str]) -> Result<String> {
+    ensure_docker_available()?;
+    let output = Command::new("docker")
+        .arg("exec")
+        .arg(container_id)
+        .args(args)
+        .output()
+        .context("failed to run docker exec")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!(
+            "docker exec {} failed in {container_id}: {}",
+            args.first().copied().unwrap_or_default(),
+            stderr.trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Reads CPU% and memory from `docker stats` and counts open FDs across the
+/// container's host process tree.
+pub fn container_metrics(container_id: &str) -> Result<AgentMetrics> {
+    let (cpu_percent, memory_mb) = container_stats(container_id)?;
+    let open_fds = container_pid(container_id)
+        .map(process_tree_open_fds)
+        .unwrap_or(0);
+
+    Ok(AgentMetrics {
+        cpu_percent,
+        memory_mb,
+        queue_depth: 0,
+        open_fds,
+    })
+}
+
+/// How long one `docker stats` snapshot serves every container sampled from
+/// it, so a metrics tick costs a single call however many agents run.
+const STATS_SNAPSHOT_TTL: Duration = Duration::from_secs(1);
+
+struct StatsRow {
+    id: String,
+    name: String,
+    cpu_percent: f32,
+    memory_mb: f32,
+}
+
+static STATS_SNAPSHOT: Mutex<Option<(Instant, Vec<StatsRow>)>> = Mutex::new(None);
+
+fn container_stats(container_id: &str) -> Result<(f32, f32)> {
+    // Callers wait on the lock while one of them refreshes the snapshot.
+    let mut snapshot = STATS_SNAPSHOT
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let fresh = snapshot
+        .as_ref()
+        .is_some_and(|(taken, _)| taken.elapsed() < STATS_SNAPSHOT_TTL);
+    if !fresh {
+        *snapshot = Some((Instant::now(), read_all_stats()?));
+    }
+    let rows = snapshot
+        .as_ref()
+        .map(|(_, rows)| rows.as_slice())
+        .unwrap_or_default();
+    rows.iter()
+        .find(|row| {
+            row.name == container_id || (!row.id.is_empty() && container_id.starts_with(&row.id))
+        })
+        .map(|row| (row.cpu_percent, row.memory_mb))
+        .with_context(|| format!("no docker stats for container {container_id}"))
+}
+
+fn read_all_stats() -> Result<Vec<StatsRow>> {
+    ensure_docker_available()?;
+    let output = Command::new("docker")
+        .args([
+            "stats",
+            "--no-stream",
+            "--format",
+            "{{.ID}}\t{{.Name}}\t{{.CPUPerc}}\t{{.MemUsage}}",
+        ])
+        .output()
+        .context("failed to read docker container stats")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        bail!("docker stats failed: {}", stderr.trim());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    Ok(stdout
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let id = fields.next()?.trim().to_string();
+            let name = fields.next()?.trim().to_string();
+            let (cpu_percent, memory_mb) = parse_stats_line(fields.next()?.trim())?;
+            Some(StatsRow {
+                id,
+                name,
+                cpu_percent,
+                memory_mb,
+            })
+        })
+        .collect())
+}
+
+fn container_pid(container_id: &str) -> Option<u32> {
+    let output = Command::new("docker")
+        .args(["inspect", "-f", "{{.State.Pid}}", container_id])
+        .output()
+        .ok()?;
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()
+        .filter(|pid| *pid > 0)
+}
+
+/// Parses `"<cpu>%\t<used> / <limit>"` into CPU percent and used memory in MiB.
+fn parse_stats_line(line: &str) -> Option<(f32, f32)> {
+    let (cpu, mem) = line.split_once('\t')?;
+    let cpu_percent = cpu.trim().trim_end_matches('%').parse().ok()?;
+    let used = mem.split('/').next()?.trim();
+    Some((cpu_percent, parse_size_mb(used)?))
+}
+
+/// Converts a docker size (`256MiB`, `1.5GB`, `512kB`) to MiB. Docker uses
+/// binary units for IEC suffixes and decimal ones for SI suffixes.
+fn parse_size_mb(size: &str) -> Option<f32> {
+    const MIB: f64 = 1024.0 * 1024.0;
+    let split = size
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let value: f64 = number.trim().parse().ok()?;
+    let bytes_per_unit = match unit.trim() {
+        "B" | "" => 1.0,
+        "KiB" => 1024.0,
+        "MiB" => MIB,
+        "GiB" => 1024.0 * MIB,
+        "TiB" => 1024.0 * 1024.0 * MIB,
+        "kB" | "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+    Some((value * bytes_per_unit / MIB) as f32)
+}
+
+/// Streams `docker logs -f` output for the container into `events` as log
+/// events until the subscriber goes away or the container exits.
+pub fn follow_container_logs(container_id: &str, events: EventSender) -> Result<()> {
+    ensure_docker_available()?;
+    let mut child = Command::new("docker")
+        .args(["logs", "-f", "--tail", "0", container_id])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("failed to follow docker container logs")?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let child = Arc::new(Mutex::new(child));
+    kill_when_unsubscribed(events.clone(), Arc::clone(&child));
+    let redactor = Arc::new(container_redactor(container_id));
+    if let Some(stdout) = stdout {
+        forward_lines(
+            stdout,
+            "stdout",
+            events.clone(),
+            Arc::clone(&child),
+            Arc::clone(&redactor),
+        );
+    }
+    if let Some(stderr) = stderr {
+        forward_lines(stderr, "stderr", events, child, redactor);
+    }
+    Ok(())
+}
+
+/// A redactor for the container's output that scrubs the values of its
+/// environment, which is where ClawDen injects credentials. Falls back to the
+/// built-in token patterns when the container cannot be inspected.
+pub fn container_redactor(container_id: &str) -> Redactor {
+    let env_vars = Command::new("docker")
+        .args(["inspect", "--format", "{{json .Config.Env}}", container_id])
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| serde_json::from_slice::<Vec<String>>(&output.stdout).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| {
+            entry
+                .split_once('=')
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+        })
+        .collect::<Vec<_>>();
+    Redactor::new(&env_vars)
+}
+
+/// Streams container lifecycle transitions (`start`, `die`, `stop`, ...) from
+/// `docker events` into `events` as state-change events.
+pub fn follow_container_events(container_id: &str, events: EventSender) -> Result<()> {
+    ensure_docker_available()?;
+    let mut child = Command::new("docker")
+        .args([
+            "events",
+            "--filter",
+            &format!("container={container_id}"),
+            "--filter",
+            "type=container",
+            "--format",
+            "{{.Status}}",
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .context("failed to follow docker container events")?;
+
+    let Some(stdout) = child.stdout.take() else {
+        let _ = child.kill();
+        bail!("docker events produced no stdout");
+    };
+    let child = Arc::new(Mutex::new(child));
+    kill_when_unsubscribed(events.clone(), Arc::clone(&child));
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(|line| line.ok()) {
+            let status = line.trim();
+            if status.is_empty() {
+                continue;
+            }
+            if !events.emit(RuntimeEvent::state_changed(status)) {
+                break;
+            }
+        }
+        reap(&child);
+    });
+    Ok(())
+}
+
+fn forward_lines<R: Read + Send + 'static>(
+    reader: R,
+    stream: &'static str,
+    events: EventSender,
+    child: Arc<Mutex<Child>>,
+    redactor: Arc<Redactor>,
+) {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines().map_while(|line| line.ok()) {
+            let line = redactor.redact(&line).into_owned();
+            if !events.emit(RuntimeEvent::log(stream, line)) {
+                break;
+            }
+        }
+        reap(&child);
+    });
+}
+
+/// Kills `child` as soon as the subscriber behind `events` drops its stream,
+/// rather than when the next line arrives, so a quiet container does not
+/// keep its `docker` follower running. Stops watching once the child exits.
+fn kill_when_unsubscribed(events: EventSender, child: Arc<Mutex<Child>>) {
+    thread::spawn(move || loop {
+        if events.is_closed() {
+            reap(&child);
+            return;
+        }
+        let exited = child
+            .lock()
+            .map_or(true, |mut child| !matches!(child.try_wait(), Ok(None)));
+        if exited {
+            return;
+        }
+        sleep(SUBSCRIBER_POLL_INTERVAL);
+    });
+}
+
+fn reap(child: &Arc<Mutex<Child>>) {
+    if let Ok(mut child) = child.lock() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
+
 fn container_logs(container_id: &str) -> Result<String> {
     ensure_docker_available()?;
     let output = Command::new("docker")
@@ -271,7 +677,10 @@ fn startup_grace_period() -> Duration {
 }
 
 fn default_runtime_image(runtime: &ClawRuntime) -> String {
-    format!("ghcr.io/codervisor/{}:latest", runtime.as_slug())
+    match runtime_descriptor_for(runtime) {
+        Some(descriptor) => descriptor.image_ref(None),
+        None => format!("ghcr.io/codervisor/{}:latest", runtime.as_slug()),
+    }
 }
 
 fn ensure_docker_available() -> Result<()> {
@@ -289,8 +698,42 @@ fn ensure_docker_available() -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::{build_run_args, container_name, default_runtime_image, runtime_config_values};
-    use clawden_core::{AgentConfig, ClawRuntime};
+    use super::{
+        build_run_args, container_name, default_runtime_image, kill_when_unsubscribed,
+        parse_stats_line, runtime_config_values,
+    };
+    use clawden_core::{AgentConfig, ClawRuntime, EventFilter, EventStream};
+    use std::process::{Command, Stdio};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn follower_is_killed_once_the_subscriber_leaves() {
+        let child = Command::new("sleep")
+            .arg("30")
+            .stdout(Stdio::null())
+            .spawn()
+            .expect("sleep should spawn");
+        let child = Arc::new(Mutex::new(child));
+        let (events, stream) = EventStream::channel(EventFilter::all());
+        kill_when_unsubscribed(events, Arc::clone(&child));
+        drop(stream);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let exited = child
+                .lock()
+                .expect("child lock")
+                .try_wait()
+                .expect("child status")
+                .is_some();
+            if exited {
+                break;
+            }
+            assert!(Instant::now() < deadline, "follower outlived its stream");
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
 
     #[test]
     fn container_name_is_sanitized() {
@@ -437,4 +880,22 @@ mod tests {
             "CLAWDEN_MEMORY_BRANCH should be forwarded to container"
         );
     }
+
+    #[test]
+    fn parse_stats_line_converts_memory_units() {
+        assert_eq!(
+            parse_stats_line("12.50%\t256MiB / 1.944GiB"),
+            Some((12.5, 256.0))
+        );
+        assert_eq!(
+            parse_stats_line("0.00%\t1.5GiB / 4GiB"),
+            Some((0.0, 1536.0))
+        );
+        assert_eq!(parse_stats_line("0.10%\t512KiB / 1GiB"), Some((0.1, 0.5)));
+        assert_eq!(
+            parse_stats_line("1.00%\t2.097152MB / 1GB"),
+            Some((1.0, 2.0))
+        );
+        assert_eq!(parse_stats_line("--\t-- / --"), None);
+    }
 }
@@ -1,3 +1,6 @@
+mod cli_worker;
+mod custom;
+mod direct_adapter;
 mod docker_adapter;
 mod docker_runtime;
 #[cfg(feature = "nanoclaw")]
@@ -9,6 +12,8 @@ mod openfang;
 #[cfg(feature = "picoclaw")]
 mod picoclaw;
 mod registry;
+mod runtime_api;
+mod worker_registry;
 #[cfg(feature = "zeroclaw")]
 mod zeroclaw;
 
@@ -17,8 +22,14 @@ use std::sync::Arc;
 #[cfg(test)]
 use std::sync::{Mutex, MutexGuard, OnceLock};
 
-use clawden_core::ClawRuntime;
+use clawden_core::{ClawAdapter, ExecutionMode, ProcessManager};
+pub use cli_worker::CliWorkerAdapter;
+pub use custom::{custom_runtime_metadata, CustomDirectAdapter, CustomDockerAdapter, CustomMeta};
+pub use direct_adapter::{
+    DirectAdapter, DirectConfigGenerator, DirectLaunch, PassthroughConfigGenerator,
+};
 pub use docker_adapter::{ConfigStore, DockerAdapter, InMemoryConfigStore, RuntimeMeta};
+pub use docker_runtime::{container_redactor, running_runtime_containers};
 
 #[cfg(feature = "nanoclaw")]
 pub use nanoclaw::{NanoClawAdapter, NanoClawMeta};
@@ -29,35 +40,90 @@ pub use openfang::{OpenFangAdapter, OpenFangMeta};
 #[cfg(feature = "picoclaw")]
 pub use picoclaw::{PicoClawAdapter, PicoClawMeta};
 pub use registry::AdapterRegistry;
+pub use worker_registry::WorkerRegistry;
 #[cfg(feature = "zeroclaw")]
 pub use zeroclaw::{ZeroClawAdapter, ZeroClawMeta};
 
-/// Creates a registry pre-populated with all compile-time enabled adapters.
-pub fn builtin_registry() -> AdapterRegistry {
+/// Creates a registry pre-populated with all compile-time enabled adapters
+/// and every registered custom runtime. `mode` picks Docker or direct
+/// adapters; `Auto` uses Docker when the daemon is reachable and falls back
+/// to direct host processes otherwise.
+pub fn builtin_registry(mode: ExecutionMode) -> AdapterRegistry {
+    builtin_registry_with_generator(mode, Arc::new(PassthroughConfigGenerator))
+}
+
+/// Like [`builtin_registry`], with a custom config generator for the direct
+/// adapters.
+pub fn builtin_registry_with_generator(
+    mode: ExecutionMode,
+    generator: Arc<dyn DirectConfigGenerator>,
+) -> AdapterRegistry {
+    let mode = match mode {
+        ExecutionMode::Auto if ProcessManager::docker_available() => ExecutionMode::Docker,
+        ExecutionMode::Auto => ExecutionMode::Direct,
+        explicit => explicit,
+    };
     let mut registry = AdapterRegistry::new();
 
     #[cfg(feature = "openclaw")]
-    registry.register(ClawRuntime::OpenClaw, Arc::new(OpenClawAdapter::default()));
+    register_builtin::<OpenClawMeta>(&mut registry, mode, &generator);
 
     #[cfg(feature = "openfang")]
-    registry.register(ClawRuntime::OpenFang, Arc::new(OpenFangAdapter::default()));
+    register_builtin::<OpenFangMeta>(&mut registry, mode, &generator);
 
     #[cfg(feature = "zeroclaw")]
-    registry.register(ClawRuntime::ZeroClaw, Arc::new(ZeroClawAdapter::default()));
+    register_builtin::<ZeroClawMeta>(&mut registry, mode, &generator);
 
     #[cfg(feature = "picoclaw")]
-    registry.register(ClawRuntime::PicoClaw, Arc::new(PicoClawAdapter::default()));
+    register_builtin::<PicoClawMeta>(&mut registry, mode, &generator);
 
     #[cfg(feature = "nanoclaw")]
-    registry.register(ClawRuntime::NanoClaw, Arc::new(NanoClawAdapter::default()));
+    register_builtin::<NanoClawMeta>(&mut registry, mode, &generator);
+
+    for descriptor in clawden_core::custom_runtime_descriptors() {
+        let adapter: Arc<dyn ClawAdapter> = match mode {
+            ExecutionMode::Direct => {
+                Arc::new(CustomDirectAdapter::custom(descriptor, generator.clone()))
+            }
+            ExecutionMode::Docker | ExecutionMode::Auto => {
+                Arc::new(CustomDockerAdapter::custom(descriptor))
+            }
+        };
+        registry.register(descriptor.runtime.clone(), adapter);
+    }
 
     tracing::info!(
         adapter_count = registry.list().len(),
+        ?mode,
         "built-in adapter registry initialized"
     );
     registry
 }
 
+fn register_builtin<R: RuntimeMeta>(
+    registry: &mut AdapterRegistry,
+    mode: ExecutionMode,
+    generator: &Arc<dyn DirectConfigGenerator>,
+) {
+    let adapter: Arc<dyn ClawAdapter> = match mode {
+        ExecutionMode::Direct => Arc::new(DirectAdapter::<R>::with_generator(generator.clone())),
+        ExecutionMode::Docker | ExecutionMode::Auto => Arc::new(DockerAdapter::<R>::default()),
+    };
+    registry.register(R::RUNTIME, adapter);
+}
+
+/// Creates a worker registry with a CLI adapter for every known coding tool.
+pub fn builtin_worker_registry() -> WorkerRegistry {
+    let mut registry = WorkerRegistry::new();
+    for descriptor in clawden_core::worker_descriptors() {
+        registry.register(
+            descriptor.tool,
+            Arc::new(CliWorkerAdapter::new(descriptor.tool)),
+        );
+    }
+    registry
+}
+
 #[cfg(test)]
 pub(crate) fn adapter_test_env_lock() -> MutexGuard<'static, ()> {
     static LOCK: OnceLock<Mutex<()>> = OnceLock::new();
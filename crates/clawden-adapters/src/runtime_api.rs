@@ -0,0 +1,164 @@
+use anyhow::{anyhow, bail, Context, Result};
+use clawden_core::{runtime_descriptor_for, AgentMessage, ClawRuntime, RuntimeConfig};
+use std::time::Duration;
+
+const DEFAULT_SEND_TIMEOUT_SECS: u64 = 120;
+
+/// Resolves the host URL of a runtime's local message API.
+///
+/// `CLAWDEN_RUNTIME_API_URL` in the agent env overrides the base URL outright;
+/// otherwise a `CLAWDEN_PORT_MAP` entry publishing the runtime port is used,
+/// falling back to the descriptor's default port on localhost.
+pub fn message_url(runtime: &ClawRuntime, config: Option<&RuntimeConfig>) -> Result<String> {
+    let descriptor = runtime_descriptor_for(runtime)
+        .ok_or_else(|| anyhow!("unknown runtime {}", runtime.as_slug()))?;
+    let (Some(api), Some(port)) = (descriptor.message_api, descriptor.health_port) else {
+        bail!(
+            "{} does not expose a local message API",
+            descriptor.display_name
+        );
+    };
+
+    let env_vars = config.map(env_vars_from_config).unwrap_or_default();
+    if let Some(base) = env_value(&env_vars, "CLAWDEN_RUNTIME_API_URL") {
+        return Ok(format!("{}{}", base.trim_end_matches('/'), api.path));
+    }
+
+    let host_port = env_value(&env_vars, "CLAWDEN_PORT_MAP")
+        .and_then(|mappings| published_host_port(&mappings, port))
+        .unwrap_or(port);
+    Ok(format!("http://127.0.0.1:{host_port}{}", api.path))
+}
+
+pub async fn send_message(
+    runtime: &ClawRuntime,
+    config: Option<&RuntimeConfig>,
+    message: &AgentMessage,
+) -> Result<String> {
+    let descriptor = runtime_descriptor_for(runtime)
+        .ok_or_else(|| anyhow!("unknown runtime {}", runtime.as_slug()))?;
+    let api = descriptor.message_api.ok_or_else(|| {
+        anyhow!(
+            "{} does not expose a local message API",
+            descriptor.display_name
+        )
+    })?;
+    let url = message_url(runtime, config)?;
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(DEFAULT_SEND_TIMEOUT_SECS))
+        .build()
+        .context("failed to build runtime API client")?;
+    let response = client
+        .post(&url)
+        .json(&api.request_body(message))
+        .send()
+        .await
+        .with_context(|| format!("failed to reach {} at {url}", descriptor.display_name))?;
+
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .with_context(|| format!("failed to read {} response", descriptor.display_name))?;
+    if !status.is_success() {
+        bail!(
+            "{} returned HTTP {} from {url}: {}",
+            descriptor.display_name,
+            status.as_u16(),
+            text.trim()
+        );
+    }
+
+    let body: serde_json::Value = serde_json::from_str(&text)
+        .with_context(|| format!("{} returned invalid JSON", descriptor.display_name))?;
+    api.parse_response(&body).ok_or_else(|| {
+        anyhow!(
+            "{} response did not contain a reply: {}",
+            descriptor.display_name,
+            text.trim()
+        )
+    })
+}
+
+fn env_vars_from_config(config: &RuntimeConfig) -> Vec<(String, String)> {
+    config
+        .values
+        .get("env_vars")
+        .and_then(|value| value.as_array())
+        .map(|pairs| {
+            pairs
+                .iter()
+                .filter_map(|pair| {
+                    let key = pair.get(0)?.as_str()?;
+                    let value = pair.get(1)?.as_str()?;
+                    Some((key.to_string(), value.to_string()))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn env_value(env_vars: &[(String, String)], key: &str) -> Option<String> {
+    env_vars
+        .iter()
+        .find(|(k, v)| k == key && !v.trim().is_empty())
+        .map(|(_, v)| v.trim().to_string())
+}
+
+/// Finds the host port that a `docker -p` style mapping list publishes for
+/// `container_port` (`host:container`, `ip:host:container`, optional `/tcp`).
+fn published_host_port(mappings: &str, container_port: u16) -> Option<u16> {
+    mappings
+        .split(',')
+        .map(str::trim)
+        .filter(|mapping| !mapping.is_empty())
+        .find_map(|mapping| {
+            let mapping = mapping.split('/').next().unwrap_or(mapping);
+            let parts: Vec<&str> = mapping.split(':').collect();
+            let (host, container) = match parts.as_slice() {
+                [host, container] | [_, host, container] => (*host, *container),
+                _ => return None,
+            };
+            (container.parse::<u16>().ok()? == container_port)
+                .then(|| host.parse::<u16>().ok())
+                .flatten()
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{message_url, published_host_port};
+    use clawden_core::{ClawRuntime, RuntimeConfig};
+
+    #[test]
+    fn published_host_port_matches_container_port() {
+        assert_eq!(published_host_port("9000:42617", 42617), Some(9000));
+        assert_eq!(
+            published_host_port("80:80, 127.0.0.1:9001:8080/tcp", 8080),
+            Some(9001)
+        );
+        assert_eq!(published_host_port("9000:42617", 8080), None);
+    }
+
+    #[test]
+    fn message_url_prefers_explicit_override() {
+        let config = RuntimeConfig {
+            values: serde_json::json!({
+                "env_vars": [
+                    ["CLAWDEN_PORT_MAP", "9000:42617"],
+                    ["CLAWDEN_RUNTIME_API_URL", "http://10.0.0.2:1234/"],
+                ],
+            }),
+        };
+        assert_eq!(
+            message_url(&ClawRuntime::ZeroClaw, Some(&config)).unwrap(),
+            "http://10.0.0.2:1234/webhook"
+        );
+        assert_eq!(
+            message_url(&ClawRuntime::ZeroClaw, None).unwrap(),
+            "http://127.0.0.1:42617/webhook"
+        );
+        assert!(message_url(&ClawRuntime::NanoClaw, None).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use clawden_core::{CodingTool, WorkerAdapter, WorkerDescriptor};
+
+/// Registry of coding-tool worker adapters, kept separate from
+/// `AdapterRegistry` since workers run tasks rather than long-lived agents.
+#[derive(Default)]
+pub struct WorkerRegistry {
+    workers: HashMap<CodingTool, Arc<dyn WorkerAdapter>>,
+}
+
+impl WorkerRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, tool: CodingTool, adapter: Arc<dyn WorkerAdapter>) {
+        self.workers.insert(tool, adapter);
+    }
+
+    pub fn unregister(&mut self, tool: &CodingTool) -> bool {
+        self.workers.remove(tool).is_some()
+    }
+
+    pub fn get(&self, tool: &CodingTool) -> Option<Arc<dyn WorkerAdapter>> {
+        self.workers.get(tool).cloned()
+    }
+
+    pub fn has(&self, tool: &CodingTool) -> bool {
+        self.workers.contains_key(tool)
+    }
+
+    pub fn list(&self) -> Vec<CodingTool> {
+        let mut tools: Vec<_> = self.workers.keys().copied().collect();
+        tools.sort_by_key(|tool| tool.to_string());
+        tools
+    }
+
+    pub fn list_metadata(&self) -> Vec<&'static WorkerDescriptor> {
+        let mut entries: Vec<_> = self
+            .workers
+            .values()
+            .map(|adapter| adapter.metadata())
+            .collect();
+        entries.sort_by_key(|descriptor| descriptor.name);
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WorkerRegistry;
+    use crate::CliWorkerAdapter;
+    use clawden_core::CodingTool;
+    use std::sync::Arc;
+
+    #[test]
+    fn registry_returns_registered_worker_metadata() {
+        let mut registry = WorkerRegistry::new();
+        registry.register(
+            CodingTool::ClaudeCode,
+            Arc::new(CliWorkerAdapter::new(CodingTool::ClaudeCode)),
+        );
+
+        assert!(registry.has(&CodingTool::ClaudeCode));
+        assert!(!registry.has(&CodingTool::CodexCli));
+        let adapter = registry.get(&CodingTool::ClaudeCode).expect("registered");
+        assert_eq!(adapter.metadata().binary, "claude");
+        assert_eq!(registry.list_metadata()[0].display_name, "Claude Code");
+        assert!(registry.unregister(&CodingTool::ClaudeCode));
+        assert!(registry.list().is_empty());
+    }
+}
@@ -0,0 +1,235 @@
+use clawden_adapters::{OpenClawAdapter, PicoClawAdapter, ZeroClawAdapter};
+use clawden_core::{AgentConfig, AgentMessage, ClawAdapter, ClawRuntime, RuntimeEvent};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+struct CapturedRequest {
+    request_line: String,
+    body: serde_json::Value,
+}
+
+/// Serves a single HTTP request with the given status and JSON body and
+/// reports what the client sent.
+fn start_stub_server(status: u16, response: &'static str) -> (u16, Receiver<CapturedRequest>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("stub server should bind");
+    let port = listener
+        .local_addr()
+        .expect("stub server addr should be available")
+        .port();
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let Ok((stream, _)) = listener.accept() else {
+            return;
+        };
+        let mut reader = BufReader::new(stream);
+        let mut request_line = String::new();
+        let _ = reader.read_line(&mut request_line);
+
+        let mut content_length = 0usize;
+        loop {
+            let mut header = String::new();
+            if reader.read_line(&mut header).unwrap_or(0) == 0 || header.trim().is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        let mut body = vec![0u8; content_length];
+        let _ = reader.read_exact(&mut body);
+
+        let reply = format!(
+            "HTTP/1.1 {status} Stub\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{response}",
+            response.len()
+        );
+        let _ = reader.get_mut().write_all(reply.as_bytes());
+        let _ = tx.send(CapturedRequest {
+            request_line: request_line.trim().to_string(),
+            body: serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null),
+        });
+    });
+    (port, rx)
+}
+
+fn agent_config(runtime: ClawRuntime, env_vars: Vec<(&str, String)>) -> AgentConfig {
+    AgentConfig {
+        name: "alpha".to_string(),
+        runtime,
+        model: None,
+        env_vars: env_vars
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value))
+            .collect(),
+        channels: vec![],
+        tools: vec![],
+    }
+}
+
+fn user_message(content: &str) -> AgentMessage {
+    AgentMessage {
+        role: "user".to_string(),
+        content: content.to_string(),
+    }
+}
+
+fn enable_dry_run() {
+    std::env::set_var("CLAWDEN_ADAPTER_DRY_RUN", "1");
+}
+
+#[test]
+fn zeroclaw_send_posts_to_webhook() {
+    enable_dry_run();
+    let (port, requests) = start_stub_server(200, r#"{"response":"pong from zeroclaw"}"#);
+    let adapter = ZeroClawAdapter::default();
+    let rt = tokio::runtime::Runtime::new().expect("runtime should start");
+
+    let response = rt.block_on(async {
+        let handle = adapter
+            .start(&agent_config(
+                ClawRuntime::ZeroClaw,
+                vec![(
+                    "CLAWDEN_RUNTIME_API_URL",
+                    format!("http://127.0.0.1:{port}"),
+                )],
+            ))
+            .await
+            .expect("dry-run start should succeed");
+        adapter.send(&handle, &user_message("ping")).await
+    });
+
+    assert_eq!(
+        response.expect("send should succeed").content,
+        "pong from zeroclaw"
+    );
+    let request = requests.recv().expect("stub should capture request");
+    assert!(request.request_line.starts_with("POST /webhook "));
+    assert_eq!(request.body["message"], "ping");
+}
+
+#[test]
+fn picoclaw_send_uses_published_port_mapping() {
+    enable_dry_run();
+    let (port, requests) = start_stub_server(200, r#"{"response":"pico says hi"}"#);
+    let adapter = PicoClawAdapter::default();
+    let rt = tokio::runtime::Runtime::new().expect("runtime should start");
+
+    let response = rt.block_on(async {
+        let handle = adapter
+            .start(&agent_config(
+                ClawRuntime::PicoClaw,
+                vec![("CLAWDEN_PORT_MAP", format!("{port}:8080"))],
+            ))
+            .await
+            .expect("dry-run start should succeed");
+        adapter.send(&handle, &user_message("hello")).await
+    });
+
+    assert_eq!(
+        response.expect("send should succeed").content,
+        "pico says hi"
+    );
+    let request = requests.recv().expect("stub should capture request");
+    assert!(request.request_line.starts_with("POST /api/chat "));
+    assert_eq!(request.body["message"], "hello");
+}
+
+#[test]
+fn openclaw_send_uses_chat_completions_format() {
+    enable_dry_run();
+    let (port, requests) = start_stub_server(
+        200,
+        r#"{"choices":[{"message":{"role":"assistant","content":"gateway reply"}}]}"#,
+    );
+    let adapter = OpenClawAdapter::default();
+    let rt = tokio::runtime::Runtime::new().expect("runtime should start");
+
+    let response = rt.block_on(async {
+        let handle = adapter
+            .start(&agent_config(
+                ClawRuntime::OpenClaw,
+                vec![("CLAWDEN_PORT_MAP", format!("{port}:18789"))],
+            ))
+            .await
+            .expect("dry-run start should succeed");
+        adapter.send(&handle, &user_message("status?")).await
+    });
+
+    assert_eq!(
+        response.expect("send should succeed").content,
+        "gateway reply"
+    );
+    let request = requests.recv().expect("stub should capture request");
+    assert!(request
+        .request_line
+        .starts_with("POST /v1/chat/completions "));
+    assert_eq!(request.body["messages"][0]["role"], "user");
+    assert_eq!(request.body["messages"][0]["content"], "status?");
+}
+
+#[test]
+fn send_surfaces_runtime_http_errors() {
+    enable_dry_run();
+    let (port, _requests) = start_stub_server(503, r#"{"error":"agent busy"}"#);
+    let adapter = ZeroClawAdapter::default();
+    let rt = tokio::runtime::Runtime::new().expect("runtime should start");
+
+    let err = rt
+        .block_on(async {
+            let handle = adapter
+                .start(&agent_config(
+                    ClawRuntime::ZeroClaw,
+                    vec![("CLAWDEN_PORT_MAP", format!("{port}:42617"))],
+                ))
+                .await
+                .expect("dry-run start should succeed");
+            adapter.send(&handle, &user_message("ping")).await
+        })
+        .expect_err("non-2xx should fail");
+
+    let message = err.to_string();
+    assert!(message.contains("HTTP 503"), "unexpected error: {message}");
+    assert!(
+        message.contains("agent busy"),
+        "unexpected error: {message}"
+    );
+}
+
+#[test]
+fn send_publishes_message_events_to_subscribers() {
+    enable_dry_run();
+    let (port, _requests) = start_stub_server(200, r#"{"response":"pong"}"#);
+    let adapter = ZeroClawAdapter::default();
+    let rt = tokio::runtime::Runtime::new().expect("runtime should start");
+
+    let events = rt.block_on(async {
+        let handle = adapter
+            .start(&agent_config(
+                ClawRuntime::ZeroClaw,
+                vec![("CLAWDEN_PORT_MAP", format!("{port}:42617"))],
+            ))
+            .await
+            .expect("dry-run start should succeed");
+        let mut stream = adapter
+            .subscribe(&handle, "message")
+            .await
+            .expect("subscribe should succeed");
+        adapter
+            .send(&handle, &user_message("ping"))
+            .await
+            .expect("send should succeed");
+        vec![stream.next().await, stream.next().await]
+    });
+
+    match &events[..] {
+        [Some(RuntimeEvent::MessageIn { content: sent, .. }), Some(RuntimeEvent::MessageOut { content: reply, .. })] =>
+        {
+            assert_eq!(sent, "ping");
+            assert_eq!(reply, "pong");
+        }
+        other => panic!("unexpected events: {other:?}"),
+    }
+}
@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Args, Parser, Subcommand};
 
 #[derive(Debug, Parser)]
 #[command(
@@ -51,9 +51,41 @@ pub enum Commands {
         upgrade: bool,
         #[arg(long)]
         outdated: bool,
-    },
-    /// Remove a directly installed runtime.
+        /// Refuse artifacts without a published checksum (also CLAWDEN_REQUIRE_CHECKSUMS=1)
+        #[arg(long)]
+        require_checksums: bool,
+        /// Fetch GitHub releases and npm packages from this mirror as <url>/<host>/<path> (also CLAWDEN_MIRROR)
+        #[arg(long, value_name = "URL")]
+        mirror: Option<String>,
+        /// Install exactly what clawden.lock records; fail instead of resolving new versions
+        #[arg(long, conflicts_with_all = ["upgrade", "outdated", "all"])]
+        locked: bool,
+        /// Like --locked, without network access
+        #[arg(long, conflicts_with_all = ["upgrade", "outdated", "all", "locked"])]
+        frozen: bool,
+    },
+    /// Remove a directly installed runtime, or one version with runtime@version.
     Uninstall { runtime: String },
+    /// Switch a runtime to another installed version (e.g. zeroclaw@0.1.6)
+    Use { runtime: String },
+    /// Switch a runtime back to its previous version and restart running instances
+    Rollback {
+        runtime: String,
+        /// Graceful shutdown timeout in seconds
+        #[arg(long, default_value_t = 10)]
+        timeout: u64,
+    },
+    /// Resolve and install the runtimes in clawden.yaml, recording them in clawden.lock
+    Lock {
+        /// Re-resolve these runtimes instead of keeping their locked versions
+        #[arg(long, value_name = "RUNTIME")]
+        update: Vec<String>,
+    },
+    /// Pack or install an offline bundle of the project's runtimes
+    Bundle {
+        #[command(subcommand)]
+        command: BundleCommand,
+    },
     /// Start all runtimes from clawden.yaml
     Up {
         /// Specific runtimes to start (starts all if empty)
@@ -76,6 +108,9 @@ pub enum Commands {
         /// Graceful shutdown timeout in seconds
         #[arg(long, default_value_t = 10)]
         timeout: u64,
+        /// Reload clawden.yaml and env files on change, restarting only affected runtimes
+        #[arg(long, default_value_t = false, conflicts_with = "detach")]
+        watch: bool,
     },
     /// Start previously configured runtimes without attaching logs
     Start {
@@ -162,7 +197,7 @@ pub enum Commands {
     Ps,
     /// Stop runtimes
     Stop {
-        /// Specific runtime to stop (stops all if empty)
+        /// Runtime or runtime@instance to stop (stops all if empty)
         runtime: Option<String>,
         /// Graceful shutdown timeout in seconds
         #[arg(long, default_value_t = 10)]
@@ -179,7 +214,15 @@ pub enum Commands {
         /// Prefix each line with a timestamp
         #[arg(long, default_value_t = false)]
         timestamps: bool,
-        /// Optional list of runtimes (defaults to all running)
+        /// Show the log of the previous session (the run before the current one)
+        #[arg(long, default_value_t = false, conflicts_with_all = ["follow", "session"])]
+        previous: bool,
+        /// Show an older session: 1 is the previous session, 2 the one before it
+        #[arg(long, conflicts_with = "follow")]
+        session: Option<usize>,
+        #[command(flatten)]
+        filter: LogFilterArgs,
+        /// Optional list of runtimes or runtime@instance names (defaults to all running)
         runtimes: Vec<String>,
     },
     /// Start local dashboard server and open browser.
@@ -199,6 +242,16 @@ pub enum Commands {
         #[command(subcommand)]
         command: Option<ProviderCommand>,
     },
+    /// Review and decide pending worker approval requests
+    Approvals {
+        #[command(subcommand)]
+        command: Option<ApprovalCommand>,
+    },
+    /// Install and manage portable skill packages
+    Skills {
+        #[command(subcommand)]
+        command: SkillCommand,
+    },
     /// Built-in tool management
     Tools {
         #[command(subcommand)]
@@ -214,6 +267,12 @@ pub enum Commands {
         #[command(subcommand)]
         command: WorkspaceCommand,
     },
+    /// Supervise a direct-mode runtime (spawned by clawden for restart policies)
+    #[command(hide = true)]
+    Supervise {
+        /// Supervisor spec written next to the runtime's pid file
+        spec: String,
+    },
 }
 
 #[cfg(test)]
@@ -316,6 +375,26 @@ mod tests {
     }
 }
 
+/// Filters and output format shared by `logs` and `docker logs`.
+#[derive(Debug, Clone, Default, Args)]
+pub struct LogFilterArgs {
+    /// Only show lines at or above this level (trace, debug, info, warn, error)
+    #[arg(long)]
+    pub level: Option<String>,
+    /// Only show lines matching this regular expression
+    #[arg(long)]
+    pub grep: Option<String>,
+    /// Only show lines logged since a time (e.g. 10m, 2h, 2024-05-01T12:00:00Z)
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Only show lines logged before a time (same formats as --since)
+    #[arg(long)]
+    pub until: Option<String>,
+    /// Print one JSON object per line with parsed fields
+    #[arg(long, default_value_t = false)]
+    pub json: bool,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum ChannelCommand {
     /// Test all channel credentials
@@ -376,6 +455,61 @@ pub enum ProviderCommand {
     },
 }
 
+#[derive(Debug, Subcommand)]
+pub enum ApprovalCommand {
+    /// List approval requests (pending only unless --all)
+    List {
+        /// Include decided and expired requests
+        #[arg(long, default_value_t = false)]
+        all: bool,
+    },
+    /// Approve a pending request
+    Approve {
+        /// Approval id (e.g. apr-1700000000000-0)
+        id: String,
+    },
+    /// Deny a pending request
+    Deny {
+        /// Approval id (e.g. apr-1700000000000-0)
+        id: String,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum SkillCommand {
+    /// List installed skills
+    List {
+        /// Runtime to inspect (all runtimes with a workspace if omitted)
+        #[arg(long)]
+        runtime: Option<String>,
+        /// Inspect running Docker containers instead of local workspaces
+        #[arg(long, default_value_t = false)]
+        docker: bool,
+    },
+    /// Install a skill package directory (containing skill.toml)
+    Install {
+        /// Path to the skill package directory
+        path: String,
+        /// Runtime to install into (every supported runtime if omitted)
+        #[arg(long)]
+        runtime: Option<String>,
+        /// Install into running Docker containers instead of local workspaces
+        #[arg(long, default_value_t = false)]
+        docker: bool,
+    },
+    /// Remove an installed skill
+    Remove {
+        /// Skill name
+        name: String,
+        /// Runtime to remove from (all runtimes with a workspace if omitted)
+        #[arg(long)]
+        runtime: Option<String>,
+        /// Remove from running Docker containers instead of local workspaces
+        #[arg(long, default_value_t = false)]
+        docker: bool,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum ToolCommand {
     /// List available built-in tools
@@ -391,6 +525,24 @@ pub enum ToolCommand {
     },
 }
 
+#[derive(Debug, Subcommand)]
+pub enum BundleCommand {
+    /// Pack installed runtimes, cache entries, tools and images for clawden.yaml
+    Create {
+        /// Archive to write
+        #[arg(short, long, default_value = "clawden-bundle.tar.gz")]
+        output: String,
+        /// Leave Docker images out of the bundle
+        #[arg(long, default_value_t = false)]
+        no_images: bool,
+    },
+    /// Verify a bundle and install its contents into ~/.clawden
+    Install {
+        /// Bundle archive
+        file: String,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum ConfigCommand {
     /// Show resolved runtime configuration.
@@ -588,14 +740,17 @@ pub enum DockerCommand {
     },
     /// View container logs
     Logs {
-        /// Runtime/container identifier
-        runtime: String,
+        /// Runtime/container identifiers
+        #[arg(required = true)]
+        runtimes: Vec<String>,
         /// Follow output
         #[arg(short = 'f', long, default_value_t = false)]
         follow: bool,
         /// Number of lines to show from end of log
         #[arg(long = "tail")]
         tail: Option<usize>,
+        #[command(flatten)]
+        filter: LogFilterArgs,
     },
     /// Execute command in a running container
     #[command(trailing_var_arg = true)]
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clawden_core::{ApprovalDecision, ApprovalRecord, ApprovalStore};
+
+use crate::cli::ApprovalCommand;
+use crate::util::append_audit_file;
+
+pub fn exec_approvals(command: Option<ApprovalCommand>) -> Result<()> {
+    let store = ApprovalStore::new()?;
+    // Settle anything whose deadline passed so the listing reflects reality.
+    store.expire_overdue()?;
+    match command.unwrap_or(ApprovalCommand::List { all: false }) {
+        ApprovalCommand::List { all } => list_approvals(&store, all),
+        ApprovalCommand::Approve { id } => decide(&store, &id, ApprovalDecision::Approve),
+        ApprovalCommand::Deny { id } => decide(&store, &id, ApprovalDecision::Deny),
+    }
+}
+
+fn list_approvals(store: &ApprovalStore, all: bool) -> Result<()> {
+    let records: Vec<ApprovalRecord> = if all { store.list()? } else { store.pending()? };
+    if records.is_empty() {
+        println!("No {}approval requests", if all { "" } else { "pending " });
+        return Ok(());
+    }
+    for record in records {
+        println!(
+            "approval={}\tstatus={}\tworker={}\ttask={}\tdefault={}\tdescription={}",
+            record.id,
+            record.status,
+            record.worker,
+            record.task_id,
+            record.default_decision,
+            record.description
+        );
+    }
+    Ok(())
+}
+
+fn decide(store: &ApprovalStore, id: &str, decision: ApprovalDecision) -> Result<()> {
+    let record = store.decide(id, decision, "cli")?;
+    append_audit_file(&format!("approval.{}", record.status), id, "ok")?;
+    println!("approval={}\tstatus={}", record.id, record.status);
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use anyhow::Result;
+use clawden_core::{
+    instance_runtime, runtime_descriptor, version_satisfies, ProcessManager, RuntimeInstaller,
+};
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use super::docker::resolve_runtime_image;
+use super::install::pinned_versions_map;
+use super::up::{load_config, runtimes_from_config, tools_for_runtime};
+use crate::cli::BundleCommand;
+
+pub fn exec_bundle(installer: &RuntimeInstaller, command: BundleCommand) -> Result<()> {
+    match command {
+        BundleCommand::Create { output, no_images } => {
+            exec_bundle_create(installer, Path::new(&output), no_images)
+        }
+        BundleCommand::Install { file } => exec_bundle_install(installer, Path::new(&file)),
+    }
+}
+
+fn exec_bundle_create(installer: &RuntimeInstaller, output: &Path, no_images: bool) -> Result<()> {
+    let Some(config) = load_config()? else {
+        anyhow::bail!("No clawden.yaml found in the current directory");
+    };
+    let instances = runtimes_from_config(&config);
+    let runtimes = instances
+        .iter()
+        .map(|name| instance_runtime(name).to_string())
+        .collect::<BTreeSet<_>>();
+    let tools = instances
+        .iter()
+        .flat_map(|name| tools_for_runtime(&config, name))
+        .collect::<BTreeSet<_>>();
+    let pins = pinned_versions_map();
+
+    let mut builder = installer.bundle_builder()?;
+    for runtime in &runtimes {
+        if !runtime_descriptor(runtime).is_some_and(|d| d.direct_install_supported) {
+            continue;
+        }
+        let pin = pins.get(runtime);
+        let locked = installer
+            .locked_version(runtime)?
+            .filter(|locked| pin.is_none_or(|pin| version_satisfies(locked, pin)));
+        let version = match locked {
+            Some(locked) => {
+                let installed = installer
+                    .list_installed_versions()?
+                    .into_iter()
+                    .any(|row| row.runtime == *runtime && row.version == locked);
+                if !installed {
+                    anyhow::bail!(
+                        "clawden.lock pins {runtime} {locked}, which is not installed; run 'clawden install' first"
+                    );
+                }
+                locked
+            }
+            None => match installer.installed_version(runtime)? {
+                Some(version) => version,
+                None => anyhow::bail!("{runtime} is not installed; run 'clawden install' first"),
+            },
+        };
+        if let Some(pin) = pin {
+            if !version_satisfies(&version, pin) {
+                anyhow::bail!(
+                    "installed {runtime}@{version} does not satisfy clawden.yaml version '{pin}'; run 'clawden install' first"
+                );
+            }
+        }
+        builder.add_runtime(runtime, &version)?;
+        println!("Added {runtime}@{version}");
+    }
+
+    for tool in &tools {
+        let dir = ["tools", "community-tools"]
+            .iter()
+            .map(|root| format!("{root}/{tool}"))
+            .find(|path| installer.root_dir().join(path).is_dir());
+        match dir {
+            Some(path) => {
+                builder.add_tree(&path)?;
+                println!("Added tool {tool}");
+            }
+            None => println!("Skipping tool {tool}: not found under ~/.clawden"),
+        }
+    }
+
+    if !no_images && !ProcessManager::docker_available() {
+        println!("Skipping Docker images: docker is not available");
+    } else if !no_images {
+        for runtime in &runtimes {
+            let image = resolve_runtime_image(runtime, None);
+            if image_present(&image) {
+                builder.add_image(&image)?;
+                println!("Added image {image}");
+            } else {
+                println!("Skipping image {image}: not present locally");
+            }
+        }
+    }
+
+    let manifest = builder.write(output)?;
+    println!(
+        "Wrote {} ({} runtimes, {} cache/tool entries, {} images)",
+        output.display(),
+        manifest.runtimes.len(),
+        manifest.files.len(),
+        manifest.images.len()
+    );
+    Ok(())
+}
+
+fn exec_bundle_install(installer: &RuntimeInstaller, file: &Path) -> Result<()> {
+    let constraints = pinned_versions_map()
+        .into_iter()
+        .map(|(runtime, pin)| (instance_runtime(&runtime).to_string(), pin))
+        .collect();
+    let result = installer.install_bundle(file, &constraints)?;
+    for entry in &result.manifest.runtimes {
+        println!("Installed {}@{}", entry.runtime, entry.version);
+    }
+    for entry in &result.manifest.files {
+        println!("Restored {}", entry.path);
+    }
+    for image in &result.manifest.images {
+        if result.images_loaded {
+            println!("Loaded image {}", image.image);
+        } else {
+            println!("Skipping image {}: docker is not available", image.image);
+        }
+    }
+    Ok(())
+}
+
+fn image_present(image: &str) -> bool {
+    Command::new("docker")
+        .args(["image", "inspect", image])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
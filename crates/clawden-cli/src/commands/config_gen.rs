@@ -1,8 +1,12 @@
 use anyhow::Result;
+use clawden_adapters::{DirectConfigGenerator, DirectLaunch, PassthroughConfigGenerator};
 use clawden_config::{ChannelCredentialMapper, ClawDenYaml};
-use clawden_core::{channel_descriptor, runtime_descriptor, ConfigDirFlag, ConfigFormat};
+use clawden_core::{
+    channel_descriptor, instance_runtime, runtime_descriptor, AgentConfig, ConfigDirFlag,
+    ConfigFormat, INSTANCE_SEPARATOR,
+};
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 #[cfg(unix)]
 use std::os::unix::fs::OpenOptionsExt;
@@ -11,7 +15,47 @@ use std::process::{Command, Stdio};
 use toml::Value as TomlValue;
 use tracing::debug;
 
-use super::up::{channel_credential_value, channels_for_runtime, runtime_provider_and_model};
+use super::up::{
+    build_runtime_env_vars, channel_credential_value, channels_for_runtime, load_config,
+    runtime_provider_and_model,
+};
+use crate::util::project_hash;
+
+/// Direct-adapter launches for the current project: the generated config
+/// dir, state dirs and provider env that `clawden up` uses in direct mode.
+/// Env vars on the agent config take precedence over `clawden.yaml`.
+pub(crate) struct ProjectConfigGenerator;
+
+impl DirectConfigGenerator for ProjectConfigGenerator {
+    fn prepare(
+        &self,
+        runtime: &str,
+        config: &AgentConfig,
+        executable: &Path,
+    ) -> Result<DirectLaunch> {
+        let mut launch = PassthroughConfigGenerator.prepare(runtime, config, executable)?;
+        let project_hash = project_hash()?;
+
+        if let Some(cfg) = load_config()? {
+            if let Some(config_dir) =
+                generate_config_dir(&cfg, runtime, &project_hash, Some(executable))?
+            {
+                inject_config_dir_arg(runtime, &mut launch.args, &config_dir);
+            }
+            let mut env_vars = build_runtime_env_vars(&cfg, runtime)?;
+            for (key, value) in launch.env_vars {
+                env_vars.retain(|(k, _)| *k != key);
+                env_vars.push((key, value));
+            }
+            launch.env_vars = env_vars;
+        }
+        launch
+            .env_vars
+            .extend(state_dir_env_vars(runtime, &project_hash)?);
+        launch.project_hash = Some(project_hash);
+        Ok(launch)
+    }
+}
 
 pub(crate) fn generate_config_dir(
     config: &ClawDenYaml,
@@ -19,41 +63,86 @@ pub(crate) fn generate_config_dir(
     project_hash: &str,
     executable: Option<&Path>,
 ) -> Result<Option<PathBuf>> {
-    if !supports_config_dir(runtime) {
+    let Some(rendered) = render_config_dir(config, runtime, project_hash, executable)? else {
         return Ok(None);
-    }
+    };
+    rendered.write()?;
+    Ok(Some(rendered.dir))
+}
 
-    let dir = runtime_config_dir(project_hash, runtime)?;
-    fs::create_dir_all(&dir)?;
+/// The config dir of one runtime and the files ClawDen generates in it.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RenderedConfig {
+    pub(crate) dir: PathBuf,
+    /// File name to contents. Anything else in the dir belongs to the
+    /// runtime.
+    pub(crate) files: BTreeMap<String, Vec<u8>>,
+}
+
+impl RenderedConfig {
+    pub(crate) fn write(&self) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        for (name, data) in &self.files {
+            write_secret_file(&self.dir.join(name), data)?;
+        }
+        Ok(())
+    }
+}
 
+/// Renders what [`generate_config_dir`] would write without touching the
+/// config dir; `onboard` seeds its template in a scratch dir.
+pub(crate) fn render_config_dir(
+    config: &ClawDenYaml,
+    runtime: &str,
+    project_hash: &str,
+    executable: Option<&Path>,
+) -> Result<Option<RenderedConfig>> {
+    if !supports_config_dir(runtime) {
+        return Ok(None);
+    }
     let Some(descriptor) = runtime_descriptor(runtime) else {
         return Ok(None);
     };
 
+    let mut files = BTreeMap::new();
     match descriptor.config_format {
         ConfigFormat::Toml => {
             let base = if descriptor.has_onboard_command {
-                seed_template_config(executable, runtime, config, &dir)
+                let unique = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or(0);
+                let scratch = std::env::temp_dir().join(format!(
+                    "clawden-onboard-{}-{unique}",
+                    runtime.replace(INSTANCE_SEPARATOR, "-")
+                ));
+                fs::create_dir_all(&scratch)?;
+                let base = seed_template_config(executable, runtime, config, &scratch);
+                let _ = fs::remove_dir_all(&scratch);
+                base
             } else {
                 None
             };
             let body = generate_toml_config(config, runtime, base.as_ref());
-            write_secret_file(
-                &dir.join("config.toml"),
-                toml::to_string_pretty(&body)?.as_bytes(),
-            )?;
+            files.insert(
+                "config.toml".to_string(),
+                toml::to_string_pretty(&body)?.into_bytes(),
+            );
         }
         ConfigFormat::Json => {
             let body = generate_picoclaw_config(config, runtime);
-            write_secret_file(
-                &dir.join("config.json"),
-                serde_json::to_string_pretty(&body)?.as_bytes(),
-            )?;
+            files.insert(
+                "config.json".to_string(),
+                serde_json::to_string_pretty(&body)?.into_bytes(),
+            );
         }
         _ => {}
     }
 
-    Ok(Some(dir))
+    Ok(Some(RenderedConfig {
+        dir: runtime_config_dir(project_hash, runtime)?,
+        files,
+    }))
 }
 
 /// Returns true for runtimes that support `<runtime> onboard --config-dir`
@@ -335,7 +424,7 @@ fn inject_security_profile(root: &mut toml::Table, runtime: &str) {
 
     // OpenFang-specific: relax gRPC TLS and bind restrictions so ClawDen can
     // reach the health endpoint and manage the network layer.
-    if runtime == "openfang" {
+    if instance_runtime(runtime) == "openfang" {
         table
             .entry("tls_required".to_string())
             .or_insert(TomlValue::Boolean(false));
@@ -491,7 +580,7 @@ pub(crate) fn write_env_runtime_config(
     let dir = runtime_config_dir(project_hash, runtime)?;
     fs::create_dir_all(&dir)?;
 
-    if runtime == "openclaw" {
+    if instance_runtime(runtime) == "openclaw" {
         let body = generate_openclaw_config(config, runtime);
         write_secret_file(
             &dir.join("openclaw.json"),
@@ -675,9 +764,7 @@ fn runtime_config_overrides<'a>(
         return &config.config;
     }
     config
-        .runtimes
-        .iter()
-        .find(|entry| entry.name == runtime)
+        .runtime_entry(runtime)
         .map(|entry| &entry.config)
         .unwrap_or(&config.config)
 }
@@ -1,9 +1,17 @@
 use anyhow::{Context, Result};
-use clawden_core::{LifecycleManager, ProcessManager, RuntimeInstaller};
+use clawden_adapters::container_redactor;
+use clawden_core::{
+    current_unix_ms, parse_time_bound, parse_timestamp_ms, runtime_descriptor, LifecycleManager,
+    LogLine, ProcessManager, RuntimeInstaller,
+};
 use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read};
 use std::process::{Command, Stdio};
+use std::sync::{mpsc, Arc};
+use std::thread;
 
-use crate::cli::DockerCommand;
+use crate::cli::{DockerCommand, LogFilterArgs};
+use crate::commands::log_query::LogQuery;
 use crate::commands::run::{exec_run, RunOptions};
 use crate::commands::up::{exec_up, UpOptions};
 use crate::util::command_exists;
@@ -131,6 +139,7 @@ pub async fn exec_docker(
                     detach,
                     no_log_prefix,
                     timeout,
+                    watch: false,
                     force_docker: true,
                 },
                 installer,
@@ -168,23 +177,11 @@ pub async fn exec_docker(
             run_docker_passthrough(&["pull", &image])
         }
         DockerCommand::Logs {
-            runtime,
+            runtimes,
             follow,
             tail,
-        } => {
-            let container = resolve_container_id_or_name(&runtime)?;
-            let mut owned = vec!["logs".to_string()];
-            if follow {
-                owned.push("-f".to_string());
-            }
-            if let Some(lines) = tail {
-                owned.push("--tail".to_string());
-                owned.push(lines.to_string());
-            }
-            owned.push(container);
-            let refs = owned.iter().map(String::as_str).collect::<Vec<_>>();
-            run_docker_passthrough(&refs)
-        }
+            filter,
+        } => docker_logs_filtered(&runtimes, follow, tail, &filter),
         DockerCommand::Exec {
             runtime,
             interactive,
@@ -250,6 +247,118 @@ pub async fn exec_docker(
     }
 }
 
+/// `docker logs` for one or more containers, parsed and filtered like
+/// `clawden logs`. Without `--follow` the lines of all containers are merged
+/// in time order. Values from each container's environment and known token
+/// patterns are redacted before anything is printed.
+fn docker_logs_filtered(
+    runtimes: &[String],
+    follow: bool,
+    tail: Option<usize>,
+    filter: &LogFilterArgs,
+) -> Result<()> {
+    let mut query = LogQuery::new(filter)?;
+    let use_prefix = runtimes.len() > 1;
+    let (tx, rx) = mpsc::channel::<(String, String)>();
+
+    let mut children = Vec::new();
+    for runtime in runtimes {
+        let container = resolve_container_id_or_name(runtime)?;
+        let mut args = vec!["logs".to_string(), "--timestamps".to_string()];
+        if follow {
+            args.push("-f".to_string());
+        }
+        if let Some(lines) = tail {
+            args.push("--tail".to_string());
+            args.push(lines.to_string());
+        }
+        // Let docker drop most lines up front; the query re-checks the
+        // runtime's own timestamps.
+        let now = current_unix_ms();
+        for (flag, value) in [("--since", &filter.since), ("--until", &filter.until)] {
+            if let Some(value) = value {
+                args.push(flag.to_string());
+                args.push((parse_time_bound(value, now)? / 1000).to_string());
+            }
+        }
+        args.push(container.clone());
+
+        let mut child = Command::new("docker")
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("failed to execute docker {}", args.join(" ")))?;
+        let streams: [Option<Box<dyn Read + Send>>; 2] = [
+            child
+                .stdout
+                .take()
+                .map(|s| Box::new(s) as Box<dyn Read + Send>),
+            child
+                .stderr
+                .take()
+                .map(|s| Box::new(s) as Box<dyn Read + Send>),
+        ];
+        let redactor = Arc::new(container_redactor(&container));
+        for stream in streams.into_iter().flatten() {
+            let tx = tx.clone();
+            let runtime = runtime.clone();
+            let redactor = Arc::clone(&redactor);
+            thread::spawn(move || {
+                for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                    let line = redactor.redact(&line).into_owned();
+                    if tx.send((runtime.clone(), line)).is_err() {
+                        return;
+                    }
+                }
+            });
+        }
+        children.push(child);
+    }
+    drop(tx);
+
+    let parse = |runtime: String, raw: String| {
+        // `--timestamps` prefixes each line with the time docker received it.
+        let (received, text) = match raw.split_once(' ') {
+            Some((ts, rest)) if parse_timestamp_ms(ts).is_some() => {
+                (parse_timestamp_ms(ts), rest.to_string())
+            }
+            _ => (None, raw),
+        };
+        LogLine::parse(runtime, text, received.unwrap_or_else(current_unix_ms))
+    };
+
+    if follow {
+        for (runtime, raw) in rx {
+            let mut line = parse(runtime, raw);
+            if query.accept(&mut line) {
+                println!("{}", query.render(&line, use_prefix, false));
+            }
+        }
+    } else {
+        let mut kept = Vec::new();
+        for (runtime, raw) in rx {
+            let mut line = parse(runtime, raw);
+            if query.accept(&mut line) {
+                kept.push(line);
+            }
+        }
+        kept.sort_by_key(|line| line.timestamp_ms);
+        for line in &kept {
+            println!("{}", query.render(line, use_prefix, false));
+        }
+    }
+
+    for mut child in children {
+        let status = child.wait()?;
+        if !status.success() {
+            anyhow::bail!("docker logs failed ({status})");
+        }
+    }
+    Ok(())
+}
+
 fn ensure_docker_available() -> Result<()> {
     if command_exists("docker") {
         return Ok(());
@@ -282,7 +391,7 @@ fn run_docker_capture(args: &[&str]) -> Result<String> {
     Ok(String::from_utf8_lossy(&output.stdout).to_string())
 }
 
-fn resolve_runtime_image(runtime: &str, tag: Option<&str>) -> String {
+pub(super) fn resolve_runtime_image(runtime: &str, tag: Option<&str>) -> String {
     if let Ok(override_image) = std::env::var("CLAWDEN_RUNTIME_IMAGE") {
         if !override_image.trim().is_empty() {
             return override_image;
@@ -297,7 +406,15 @@ fn resolve_runtime_image(runtime: &str, tag: Option<&str>) -> String {
         "zeroclaw" => ("zeroclaw", "latest"),
         "zeroclaw-browser" => ("zeroclaw", "browser"),
         "zeroclaw-computer" => ("zeroclaw", "computer"),
-        _ => (normalized.as_str(), "latest"),
+        _ => {
+            // Custom runtimes may name their own image.
+            if let Some(descriptor) =
+                runtime_descriptor(&normalized).filter(|descriptor| descriptor.image.is_some())
+            {
+                return descriptor.image_ref(tag);
+            }
+            (normalized.as_str(), "latest")
+        }
     };
     let resolved_tag = tag.unwrap_or(default_tag);
     format!("ghcr.io/codervisor/{repository}:{resolved_tag}")
@@ -1,12 +1,12 @@
 use anyhow::Result;
 use clawden_config::ClawDenYaml;
-use clawden_core::{ProcessManager, RuntimeInstaller};
+use clawden_core::{LimitStatus, ProcessManager, RuntimeInstaller};
 use reqwest::blocking::Client;
 use std::time::Duration;
 
 use crate::util::{command_exists, get_provider_key_from_vault};
 
-pub fn exec_doctor(installer: &RuntimeInstaller) -> Result<()> {
+pub fn exec_doctor(installer: &RuntimeInstaller, process_manager: &ProcessManager) -> Result<()> {
     println!("Prerequisites");
     println!(
         "  docker ............... {}",
@@ -90,6 +90,39 @@ pub fn exec_doctor(installer: &RuntimeInstaller) -> Result<()> {
     for row in installed {
         println!("  {} ............. {}", row.runtime, row.version);
     }
+
+    print_resource_limits(process_manager)
+}
+
+/// Shows, per running direct-mode runtime, which of the requested limits the
+/// kernel actually enforces on it.
+fn print_resource_limits(process_manager: &ProcessManager) -> Result<()> {
+    println!("\nResource limits");
+    let mut running = 0;
+    for info in process_manager.list_processes()? {
+        let Some((pid, checks)) = process_manager.limit_report(&info.runtime)? else {
+            continue;
+        };
+        running += 1;
+        println!("  {} (pid {pid})", info.runtime);
+        for check in checks {
+            let detail = match (&check.requested, check.status) {
+                (Some(requested), status) => {
+                    format!(" (requested {requested}, {})", status.as_str())
+                }
+                (None, LimitStatus::NotRequested) => String::new(),
+                (None, status) => format!(" ({})", status.as_str()),
+            };
+            println!(
+                "    {:.<21} {}{detail}",
+                format!("{} ", check.name),
+                check.effective
+            );
+        }
+    }
+    if running == 0 {
+        println!("  running .............. none");
+    }
     Ok(())
 }
 
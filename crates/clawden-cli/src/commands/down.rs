@@ -3,7 +3,7 @@ use clawden_core::ProcessManager;
 
 use crate::commands::config_gen::cleanup_project_config_dir;
 use crate::commands::up::{load_config, runtimes_from_config};
-use crate::util::{append_audit_file, project_hash};
+use crate::util::{append_audit_file, project_hash, record_stop_outcome};
 
 pub fn exec_down(
     process_manager: &ProcessManager,
@@ -24,8 +24,10 @@ pub fn exec_down(
         return Ok(());
     }
 
-    let declared = load_config()?
-        .map(|cfg| runtimes_from_config(&cfg))
+    let config = load_config()?;
+    let declared = config
+        .as_ref()
+        .map(runtimes_from_config)
         .unwrap_or_default();
 
     let mut targets = if !runtimes.is_empty() {
@@ -59,11 +61,21 @@ pub fn exec_down(
         return Ok(());
     }
 
-    for runtime in &targets {
+    // Stop dependents before the runtimes they depend on.
+    let order: Vec<String> = match config.as_ref() {
+        Some(cfg) => cfg
+            .startup_waves(&targets)
+            .map_err(anyhow::Error::msg)?
+            .into_iter()
+            .rev()
+            .flatten()
+            .collect(),
+        None => targets,
+    };
+
+    for runtime in &order {
         let outcome = process_manager.stop_with_timeout(runtime, timeout)?;
-        if outcome.forced {
-            append_audit_file("runtime.force_kill", runtime, "ok")?;
-        }
+        record_stop_outcome(runtime, &outcome)?;
         append_audit_file("runtime.down", runtime, "ok")?;
         println!("Stopped {runtime}");
     }
@@ -1,9 +1,12 @@
 use anyhow::Result;
-use clawden_core::{version_satisfies, RuntimeInstaller};
+use clawden_config::ClawDenYaml;
+use clawden_core::{version_satisfies, LockFile, LockMode, RuntimeInstaller};
 use indicatif::{ProgressBar, ProgressStyle};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
+use super::known_projects;
 use super::up::{load_config, pinned_version_for_runtime};
 use crate::util::append_audit_file;
 use crate::util::parse_runtime_version;
@@ -36,24 +39,49 @@ pub fn exec_install(
     list: bool,
     upgrade: bool,
     outdated: bool,
+    lock_mode: LockMode,
 ) -> Result<()> {
+    installer.set_lock_mode(lock_mode);
     if outdated && (list || all || runtime.is_some() || upgrade) {
         anyhow::bail!("--outdated cannot be combined with runtime, --all, --list, or --upgrade");
     }
 
     if list {
-        let installed = installer.list_installed()?;
+        let installed = installer.list_installed_versions()?;
         if installed.is_empty() {
             println!("No runtimes installed");
-        } else {
-            for row in installed {
-                println!(
-                    "{}\t{}\t{}",
-                    row.runtime,
-                    row.version,
-                    row.executable.display()
-                );
+            return Ok(());
+        }
+        let projects = project_pins();
+        for row in installed {
+            let current = installer.installed_version(&row.runtime)?;
+            let marker = if current.as_deref() == Some(row.version.as_str()) {
+                " (current)"
+            } else {
+                ""
+            };
+            let pinned_by = projects
+                .iter()
+                .filter(|(_, pins)| {
+                    pins.get(&row.runtime).is_some_and(|pin| match pin {
+                        ProjectPin::Locked(version) => *version == row.version,
+                        ProjectPin::Constraint(constraint) => {
+                            version_satisfies(&row.version, constraint)
+                        }
+                    })
+                })
+                .map(|(dir, _)| dir.display().to_string())
+                .collect::<Vec<_>>();
+            let mut line = format!(
+                "{}\t{}{marker}\t{}",
+                row.runtime,
+                row.version,
+                row.executable.display()
+            );
+            if !pinned_by.is_empty() {
+                line.push_str(&format!("\tpinned by {}", pinned_by.join(", ")));
             }
+            println!("{line}");
         }
         return Ok(());
     }
@@ -158,6 +186,9 @@ pub fn exec_install(
     }
 
     let Some(runtime_spec) = runtime else {
+        if lock_mode != LockMode::Update {
+            return install_locked(installer);
+        }
         anyhow::bail!("specify a runtime (e.g. clawden install zeroclaw or --all)");
     };
 
@@ -174,25 +205,87 @@ pub fn exec_install(
     Ok(())
 }
 
-fn pinned_versions_map() -> HashMap<String, String> {
+/// Installs every runtime recorded in `clawden.lock` at its locked version.
+fn install_locked(installer: &mut RuntimeInstaller) -> Result<()> {
+    let Some(path) = installer.lockfile() else {
+        anyhow::bail!("--locked and --frozen need a clawden.yaml project with a clawden.lock");
+    };
+    let lock = LockFile::load(path)?;
+    if lock.runtimes.is_empty() {
+        anyhow::bail!(
+            "{} records no runtimes; run 'clawden lock' first",
+            path.display()
+        );
+    }
+
+    let pins = pinned_versions_map();
+    for runtime in lock.runtimes.keys() {
+        let spinner = with_progress(installer);
+        let installed = installer.install_runtime(runtime, pins.get(runtime).map(String::as_str));
+        spinner.finish_and_clear();
+        let installed = installed?;
+        println!(
+            "Installed {}@{} at {}",
+            installed.runtime,
+            installed.version,
+            installed.executable.display()
+        );
+    }
+    Ok(())
+}
+
+pub(super) fn pinned_versions_map() -> HashMap<String, String> {
     let Ok(Some(config)) = load_config() else {
         return HashMap::new();
     };
+    pinned_versions_for(&config)
+}
 
+fn pinned_versions_for(config: &ClawDenYaml) -> HashMap<String, String> {
     let mut map = HashMap::new();
     if let Some(single_runtime) = &config.runtime {
-        if let Some(pin) = pinned_version_for_runtime(&config, single_runtime) {
+        if let Some(pin) = pinned_version_for_runtime(config, single_runtime) {
             map.insert(single_runtime.clone(), pin.to_string());
         }
     }
     for runtime in &config.runtimes {
-        if let Some(pin) = pinned_version_for_runtime(&config, &runtime.name) {
+        if let Some(pin) = pinned_version_for_runtime(config, &runtime.name) {
             map.insert(runtime.name.clone(), pin.to_string());
         }
     }
     map
 }
 
+enum ProjectPin {
+    Locked(String),
+    Constraint(String),
+}
+
+/// What each registered project pins: the `clawden.lock` version where
+/// there is one, otherwise the `clawden.yaml` constraint.
+fn project_pins() -> Vec<(PathBuf, HashMap<String, ProjectPin>)> {
+    let mut projects = Vec::new();
+    for dir in known_projects() {
+        let Ok(config) = ClawDenYaml::from_file(&dir.join("clawden.yaml")) else {
+            continue;
+        };
+        let mut pins = pinned_versions_for(&config)
+            .into_iter()
+            .filter(|(_, pin)| !pin.trim().eq_ignore_ascii_case("latest"))
+            .map(|(runtime, pin)| (runtime, ProjectPin::Constraint(pin)))
+            .collect::<HashMap<_, _>>();
+        if let Ok(lock) = LockFile::load(&dir.join("clawden.lock")) {
+            for (runtime, entry) in lock.runtimes {
+                pins.insert(runtime, ProjectPin::Locked(entry.version));
+            }
+        }
+        if !pins.is_empty() {
+            projects.push((dir, pins));
+        }
+    }
+    projects
+}
+
 fn resolve_upgrade_target(
     installer: &RuntimeInstaller,
     runtime: &str,
@@ -230,7 +323,15 @@ fn is_constraint(value: &str) -> bool {
 }
 
 pub fn exec_uninstall(installer: &RuntimeInstaller, runtime: String) -> Result<()> {
-    installer.uninstall_runtime(&runtime)?;
-    println!("Uninstalled {runtime}");
+    match parse_runtime_version(&runtime) {
+        (runtime, Some(version)) => {
+            installer.uninstall_version(&runtime, &version)?;
+            println!("Uninstalled {runtime}@{version}");
+        }
+        (runtime, None) => {
+            installer.uninstall_runtime(&runtime)?;
+            println!("Uninstalled {runtime}");
+        }
+    }
     Ok(())
 }
@@ -0,0 +1,71 @@
+use anyhow::Result;
+use clawden_core::{instance_runtime, version_satisfies, LockFile, RuntimeInstaller};
+
+use super::install::pinned_versions_map;
+use super::up::{load_config, runtimes_from_config};
+
+pub fn exec_lock(installer: &RuntimeInstaller, update: Vec<String>) -> Result<()> {
+    let Some(config) = load_config()? else {
+        anyhow::bail!("No clawden.yaml found in the current directory");
+    };
+    let Some(path) = installer.lockfile().map(ToOwned::to_owned) else {
+        anyhow::bail!("No clawden.yaml found in the current directory");
+    };
+
+    let mut runtimes = runtimes_from_config(&config)
+        .iter()
+        .map(|name| instance_runtime(name).to_string())
+        .collect::<Vec<_>>();
+    runtimes.sort();
+    runtimes.dedup();
+    let update = update
+        .iter()
+        .map(|name| instance_runtime(name).to_string())
+        .collect::<Vec<_>>();
+    if let Some(unknown) = update.iter().find(|name| !runtimes.contains(name)) {
+        anyhow::bail!("'{unknown}' is not a runtime in clawden.yaml");
+    }
+
+    let before = LockFile::load(&path)?;
+    let mut lock = before.clone();
+    lock.runtimes
+        .retain(|runtime, _| runtimes.contains(runtime) && !update.contains(runtime));
+    lock.save(&path)?;
+
+    let pins = pinned_versions_map();
+    for runtime in &runtimes {
+        let pin = pins.get(runtime).map(String::as_str);
+        let satisfied = lock
+            .runtimes
+            .get(runtime)
+            .is_some_and(|entry| pin.is_none_or(|pin| version_satisfies(&entry.version, pin)));
+        if satisfied {
+            continue;
+        }
+        // Installing records the resolved version, URL and digest.
+        if let Err(err) = installer.install_runtime(runtime, pin) {
+            before.save(&path)?;
+            return Err(err);
+        }
+    }
+
+    let after = LockFile::load(&path)?;
+    for (runtime, entry) in &after.runtimes {
+        match before.runtimes.get(runtime) {
+            Some(previous) if previous == entry => {
+                println!("{runtime}@{} unchanged", entry.version)
+            }
+            Some(previous) => println!(
+                "Updated {runtime} {} -> {} ({})",
+                previous.version, entry.version, entry.digest
+            ),
+            None => println!("Locked {runtime}@{} ({})", entry.version, entry.digest),
+        }
+    }
+    for runtime in before.runtimes.keys() {
+        if !after.runtimes.contains_key(runtime) {
+            println!("Removed {runtime} from {}", path.display());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,153 @@
+use anyhow::{anyhow, Context, Result};
+use clawden_core::{current_unix_ms, parse_time_bound, LogLevel, LogLine};
+use regex_lite::Regex;
+use std::collections::HashMap;
+
+use crate::cli::LogFilterArgs;
+use crate::commands::up::render_log_line;
+
+/// Applies `--level`/`--grep`/`--since`/`--until` to parsed log lines and
+/// renders the survivors as text or JSON.
+pub(crate) struct LogQuery {
+    min_level: Option<LogLevel>,
+    grep: Option<Regex>,
+    since_ms: Option<u64>,
+    until_ms: Option<u64>,
+    json: bool,
+    /// Last level and timestamp per runtime. Lines without their own
+    /// (stack traces, wrapped messages) inherit them.
+    carried: HashMap<String, (Option<LogLevel>, Option<u64>)>,
+}
+
+impl LogQuery {
+    pub(crate) fn new(args: &LogFilterArgs) -> Result<Self> {
+        let now = current_unix_ms();
+        let min_level = args
+            .level
+            .as_deref()
+            .map(|value| {
+                LogLevel::parse(value).ok_or_else(|| {
+                    anyhow!(
+                        "unknown log level '{value}' (expected trace, debug, info, warn or error)"
+                    )
+                })
+            })
+            .transpose()?;
+        let grep = args
+            .grep
+            .as_deref()
+            .map(|pattern| {
+                Regex::new(pattern).with_context(|| format!("invalid --grep pattern '{pattern}'"))
+            })
+            .transpose()?;
+        let since_ms = args
+            .since
+            .as_deref()
+            .map(|value| parse_time_bound(value, now))
+            .transpose()?;
+        let until_ms = args
+            .until
+            .as_deref()
+            .map(|value| parse_time_bound(value, now))
+            .transpose()?;
+        Ok(Self {
+            min_level,
+            grep,
+            since_ms,
+            until_ms,
+            json: args.json,
+            carried: HashMap::new(),
+        })
+    }
+
+    /// Parses a line read back from a log file. Lines without a timestamp
+    /// of their own take the previous line's, or the time they are read.
+    pub(crate) fn parse_stored(&self, runtime: &str, text: &str) -> LogLine {
+        let received = self
+            .carried
+            .get(runtime)
+            .and_then(|(_, timestamp)| *timestamp)
+            .unwrap_or_else(current_unix_ms);
+        LogLine::parse(runtime, text, received)
+    }
+
+    /// Whether the line passes every filter. Call once per line, in order.
+    pub(crate) fn accept(&mut self, line: &mut LogLine) -> bool {
+        let carried = self.carried.entry(line.runtime.clone()).or_default();
+        match line.level {
+            Some(level) => carried.0 = Some(level),
+            None => line.level = carried.0,
+        }
+        carried.1 = Some(line.timestamp_ms);
+
+        if let Some(min) = self.min_level {
+            if line.level.is_none_or(|level| level < min) {
+                return false;
+            }
+        }
+        if self.since_ms.is_some_and(|since| line.timestamp_ms < since)
+            || self.until_ms.is_some_and(|until| line.timestamp_ms > until)
+        {
+            return false;
+        }
+        self.grep
+            .as_ref()
+            .is_none_or(|grep| grep.is_match(&line.text))
+    }
+
+    pub(crate) fn render(&self, line: &LogLine, use_prefix: bool, timestamps: bool) -> String {
+        if self.json {
+            return serde_json::to_string(line).unwrap_or_else(|_| line.text.clone());
+        }
+        let ts = timestamps.then_some(line.timestamp_ms);
+        render_log_line(&line.runtime, &line.text, use_prefix, ts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogQuery;
+    use crate::cli::LogFilterArgs;
+
+    #[test]
+    fn filters_by_level_grep_and_time() {
+        let mut query = LogQuery::new(&LogFilterArgs {
+            level: Some("warn".to_string()),
+            grep: Some("disk|db".to_string()),
+            since: Some("2024-05-01T12:00:00Z".to_string()),
+            ..LogFilterArgs::default()
+        })
+        .expect("query should build");
+
+        let lines = [
+            "2024-05-01T11:59:00Z ERROR store: db down",
+            "2024-05-01T12:00:01Z WARN store: disk almost full",
+            "    at store::flush (disk.rs:10)",
+            "2024-05-01T12:00:02Z INFO store: disk ok",
+            "2024-05-01T12:00:03Z ERROR net: timeout",
+        ];
+        let kept: Vec<String> = lines
+            .iter()
+            .filter_map(|text| {
+                let mut line = query.parse_stored("zeroclaw", text);
+                query.accept(&mut line).then_some(line.text)
+            })
+            .collect();
+        assert_eq!(
+            kept,
+            vec![
+                "2024-05-01T12:00:01Z WARN store: disk almost full",
+                "    at store::flush (disk.rs:10)",
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_level() {
+        assert!(LogQuery::new(&LogFilterArgs {
+            level: Some("loud".to_string()),
+            ..LogFilterArgs::default()
+        })
+        .is_err());
+    }
+}
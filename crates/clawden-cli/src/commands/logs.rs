@@ -2,23 +2,38 @@ use anyhow::Result;
 use clawden_core::ProcessManager;
 use std::time::Duration;
 
-use crate::commands::up::render_log_line;
+use crate::cli::LogFilterArgs;
+use crate::commands::log_query::LogQuery;
 
-pub async fn exec_logs(
-    process_manager: &ProcessManager,
-    runtimes: Vec<String>,
-    tail: usize,
-    follow: bool,
-    timestamps: bool,
-) -> Result<()> {
-    let selected = if runtimes.is_empty() {
+pub struct LogsOptions {
+    pub runtimes: Vec<String>,
+    pub tail: usize,
+    pub follow: bool,
+    pub timestamps: bool,
+    /// 0 is the current session, 1 the previous one, and so on.
+    pub session: usize,
+    pub filter: LogFilterArgs,
+}
+
+pub async fn exec_logs(process_manager: &ProcessManager, opts: LogsOptions) -> Result<()> {
+    let mut query = LogQuery::new(&opts.filter)?;
+    let selected = if opts.runtimes.is_empty() {
         process_manager
             .list_statuses()?
             .into_iter()
             .map(|s| s.runtime)
             .collect::<Vec<_>>()
     } else {
-        runtimes
+        let mut selected = Vec::new();
+        for name in opts.runtimes {
+            let instances = process_manager.resolve_instances(&name)?;
+            if instances.is_empty() {
+                selected.push(name);
+            } else {
+                selected.extend(instances);
+            }
+        }
+        selected
     };
 
     if selected.is_empty() {
@@ -27,18 +42,31 @@ pub async fn exec_logs(
     }
 
     for runtime in &selected {
-        let logs = process_manager.tail_logs(runtime, tail)?;
-        for line in logs.lines() {
-            let ts = if timestamps { Some(now_ms()) } else { None };
-            println!("{}", render_log_line(runtime, line, true, ts));
+        let Some(logs) = process_manager.tail_log_session(runtime, opts.session, opts.tail)? else {
+            if opts.session > 0 {
+                println!(
+                    "{runtime}: no session {} (previous sessions kept: {})",
+                    opts.session,
+                    process_manager.previous_log_sessions(runtime)
+                );
+            }
+            continue;
+        };
+        for text in logs.lines() {
+            let mut line = query.parse_stored(runtime, text);
+            if query.accept(&mut line) {
+                println!("{}", query.render(&line, true, opts.timestamps));
+            }
         }
     }
 
-    if !follow {
+    if !opts.follow {
         return Ok(());
     }
 
-    println!("Following logs. Press Ctrl+C to stop.");
+    if !opts.filter.json {
+        println!("Following logs. Press Ctrl+C to stop.");
+    }
     let stream = process_manager.stream_logs(&selected)?;
     let mut tick = tokio::time::interval(Duration::from_millis(150));
     let ctrl_c = tokio::signal::ctrl_c();
@@ -48,9 +76,10 @@ pub async fn exec_logs(
         tokio::select! {
             _ = &mut ctrl_c => break,
             _ = tick.tick() => {
-                for line in stream.drain() {
-                    let ts = if timestamps { Some(line.timestamp_ms) } else { None };
-                    println!("{}", render_log_line(&line.runtime, &line.text, true, ts));
+                for mut line in stream.drain() {
+                    if query.accept(&mut line) {
+                        println!("{}", query.render(&line, true, opts.timestamps));
+                    }
                 }
             }
         }
@@ -58,10 +87,3 @@ pub async fn exec_logs(
 
     Ok(())
 }
-
-fn now_ms() -> u64 {
-    std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .expect("system time before unix epoch")
-        .as_millis() as u64
-}
@@ -1,3 +1,5 @@
+mod approvals;
+mod bundle;
 mod channels;
 mod config;
 mod config_gen;
@@ -8,41 +10,138 @@ mod down;
 mod feishu;
 mod init;
 mod install;
+mod lock;
+mod log_query;
 mod logs;
 mod providers;
 mod ps;
 mod restart;
 mod run;
+mod skills;
 mod start;
 mod stop;
+mod supervise;
 mod telegram;
 mod tools;
 mod up;
+mod versions;
+mod watch;
 mod workspace;
 
+use std::fs;
+use std::path::{Path, PathBuf};
 #[cfg(test)]
 use std::sync::{Mutex, OnceLock};
 
+pub use approvals::exec_approvals;
+pub use bundle::exec_bundle;
 pub use channels::exec_channels;
 pub use config::exec_config_env;
 pub use config::exec_config_show;
+pub(crate) use config_gen::ProjectConfigGenerator;
 pub use dashboard::exec_dashboard;
 pub use docker::exec_docker;
 pub use doctor::exec_doctor;
 pub use down::exec_down;
 pub use init::{exec_init, InitOptions};
 pub use install::{exec_install, exec_uninstall};
-pub use logs::exec_logs;
+pub use lock::exec_lock;
+pub use logs::{exec_logs, LogsOptions};
 pub use providers::exec_providers;
 pub use ps::exec_ps;
 pub use restart::exec_restart;
 pub use run::{exec_run, RunOptions};
+pub use skills::exec_skills;
 pub use start::exec_start;
 pub use stop::exec_stop;
+pub use supervise::exec_supervise;
 pub use tools::exec_tools;
 pub use up::{exec_up, UpOptions};
+pub use versions::{exec_rollback, exec_use};
 pub use workspace::exec_workspace;
 
+/// `clawden.lock` for the project in the current directory, when there is
+/// a `clawden.yaml` to put it next to.
+pub(crate) fn project_lockfile_path() -> Option<PathBuf> {
+    let cwd = std::env::current_dir().ok()?;
+    cwd.join("clawden.yaml")
+        .exists()
+        .then(|| cwd.join("clawden.lock"))
+}
+
+/// Registers custom runtimes from `~/.clawden/runtimes.d` and then from the
+/// current project's `clawden.yaml`, which wins for a slug defined in both.
+/// Broken definitions are reported and skipped so other commands still run.
+pub(crate) fn register_custom_runtimes() {
+    let mut specs = Vec::new();
+    if let Ok(home) = std::env::var("HOME") {
+        let dir = Path::new(&home)
+            .join(".clawden")
+            .join(clawden_core::CUSTOM_RUNTIMES_DIR);
+        match clawden_core::load_custom_runtime_specs(&dir) {
+            Ok(loaded) => specs.extend(loaded),
+            Err(err) => eprintln!("Warning: {err:#}"),
+        }
+    }
+    if let Ok(cwd) = std::env::current_dir() {
+        let yaml_path = cwd.join("clawden.yaml");
+        if yaml_path.exists() {
+            // Errors in the rest of the file surface when a command loads it.
+            if let Ok(config) = clawden_config::ClawDenYaml::from_file(&yaml_path) {
+                specs.extend(config.custom_runtimes);
+            }
+        }
+    }
+    for spec in &specs {
+        if let Err(err) = clawden_core::register_custom_runtime(spec) {
+            eprintln!("Warning: {err:#}");
+        }
+    }
+}
+
+/// Remembers the project in the current directory in
+/// `~/.clawden/projects.json`, so version listings can show which projects
+/// pin each installed runtime version. Only `install`, `up` and `lock`
+/// register, so merely running e.g. `status` in a directory does not.
+pub(crate) fn register_project() {
+    let Ok(cwd) = std::env::current_dir() else {
+        return;
+    };
+    if !cwd.join("clawden.yaml").exists() {
+        return;
+    }
+    let mut projects = known_projects();
+    if projects.contains(&cwd) {
+        return;
+    }
+    projects.push(cwd);
+    if let Some(path) = projects_file() {
+        if let Ok(body) = serde_json::to_vec_pretty(&projects) {
+            let _ = fs::write(path, body);
+        }
+    }
+}
+
+/// Registered project directories that still have a `clawden.yaml`.
+pub(crate) fn known_projects() -> Vec<PathBuf> {
+    let Some(path) = projects_file() else {
+        return Vec::new();
+    };
+    let projects: Vec<PathBuf> = fs::read(path)
+        .ok()
+        .and_then(|raw| serde_json::from_slice(&raw).ok())
+        .unwrap_or_default();
+    projects
+        .into_iter()
+        .filter(|dir| dir.join("clawden.yaml").exists())
+        .collect()
+}
+
+fn projects_file() -> Option<PathBuf> {
+    let home = std::env::var("HOME").ok()?;
+    Some(Path::new(&home).join(".clawden/projects.json"))
+}
+
 pub(crate) fn load_default_env() {
     let Ok(current_dir) = std::env::current_dir() else {
         return;
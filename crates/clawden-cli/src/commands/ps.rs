@@ -7,20 +7,29 @@ pub fn exec_ps(process_manager: &ProcessManager) -> Result<()> {
         println!("No running runtimes");
     } else {
         println!(
-            "{:<14} {:<8} {:<10} {:<10} {:<10} LOG",
-            "RUNTIME", "PID", "MODE", "STATE", "HEALTH"
+            "{:<22} {:<8} {:<7} {:<10} {:<10} {:<14} {:<9} {:<5} LOG",
+            "INSTANCE", "PID", "PORT", "MODE", "STATE", "HEALTH", "RESTARTS", "EXIT"
         );
         for status in statuses {
             println!(
-                "{:<14} {:<8} {:<10} {:<10} {:<10} {}",
+                "{:<22} {:<8} {:<7} {:<10} {:<10} {:<14} {:<9} {:<5} {}",
                 status.runtime,
                 status
                     .pid
                     .map(|pid| pid.to_string())
                     .unwrap_or_else(|| "-".to_string()),
+                status
+                    .port
+                    .map(|port| port.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
                 format!("{:?}", status.mode),
                 if status.running { "running" } else { "stopped" },
                 status.health,
+                status.restarts,
+                status
+                    .last_exit_code
+                    .map(|code| code.to_string())
+                    .unwrap_or_else(|| "-".to_string()),
                 status.log_path.display(),
             );
         }
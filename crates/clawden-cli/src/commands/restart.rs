@@ -31,6 +31,7 @@ pub async fn exec_restart(
             detach: true,
             no_log_prefix: false,
             timeout,
+            watch: false,
             force_docker: false,
         },
         installer,
@@ -1,8 +1,8 @@
 use anyhow::Result;
 use clawden_config::{ChannelInstanceYaml, ClawDenYaml, ProviderEntryYaml, ProviderRefYaml};
 use clawden_core::{
-    runtime_default_start_args, runtime_subcommand_hints, ExecutionMode, LifecycleManager,
-    ProcessManager, RuntimeInstaller,
+    runtime_default_start_args, runtime_subcommand_hints, DirectStartOptions, ExecutionMode,
+    LifecycleManager, ProcessManager, RuntimeInstaller,
 };
 use std::collections::HashMap;
 use std::fs;
@@ -14,11 +14,14 @@ use crate::commands::config_gen::{
 };
 use crate::commands::up::{
     build_runtime_env_vars, channel_credential_value, channels_for_runtime,
-    load_config_with_env_file, parse_env_overrides, pinned_version_for_runtime,
-    provider_type_from_name, render_log_line, runtime_provider_and_model, tools_for_runtime,
+    health_check_for_runtime, load_config_with_env_file, parse_env_overrides,
+    pinned_version_for_runtime, provider_type_from_name, render_log_line,
+    resource_limits_for_runtime, runtime_provider_and_model, tools_for_runtime,
     validate_direct_runtime_config, verify_runtime_startup,
 };
-use crate::util::{append_audit_file, ensure_installed_runtime, parse_runtime, project_hash};
+use crate::util::{
+    append_audit_file, ensure_installed_runtime, parse_runtime, project_hash, record_stop_outcome,
+};
 
 pub struct RunOptions {
     pub runtime: String,
@@ -311,13 +314,23 @@ pub async fn exec_run(
         ));
     }
 
-    let info = process_manager.start_direct_with_env_and_project(
-        &opts.runtime,
-        &installed.executable,
-        &args,
-        &combined_env,
-        Some(current_project_hash),
-    )?;
+    let limits = match config.as_ref() {
+        Some(cfg) => resource_limits_for_runtime(cfg, &opts.runtime)?,
+        None => Default::default(),
+    };
+    let mut info = process_manager.start_direct(&DirectStartOptions {
+        args,
+        env_vars: combined_env,
+        project_hash: Some(current_project_hash),
+        limits,
+        ..DirectStartOptions::new(opts.runtime.as_str(), installed.executable.as_path())
+    })?;
+    if let Some(check) = config
+        .as_ref()
+        .and_then(|cfg| health_check_for_runtime(cfg, &opts.runtime))
+    {
+        info = process_manager.set_health_check(&opts.runtime, check)?;
+    }
     // Start capturing logs immediately after launch (while the log file is
     // still near-empty) so that startup output is not lost.  Without this,
     // stream_logs would begin from the current file size — after
@@ -353,9 +366,7 @@ pub async fn exec_run(
         tokio::select! {
             _ = &mut ctrl_c => {
                 let outcome = process_manager.stop_with_timeout(&opts.runtime, 10)?;
-                if outcome.forced {
-                    append_audit_file("runtime.force_kill", &opts.runtime, "ok")?;
-                }
+                record_stop_outcome(&opts.runtime, &outcome)?;
                 append_audit_file("runtime.stop", &opts.runtime, "ok")?;
                 break;
             }
@@ -675,6 +686,10 @@ fn empty_clawden_yaml(runtime: &str) -> ClawDenYaml {
         version: None,
         mode: None,
         workspace: None,
+        hooks: Default::default(),
+        security: None,
+        health: None,
+        custom_runtimes: Vec::new(),
     }
 }
 
@@ -0,0 +1,208 @@
+use anyhow::{anyhow, bail, Result};
+use clawden_adapters::{running_runtime_containers, AdapterRegistry};
+use clawden_core::{
+    load_skill_package, runtime_descriptors, runtime_skills_path, AgentHandle, ClawRuntime, Skill,
+    SkillDirectory,
+};
+use std::path::Path;
+
+use crate::cli::SkillCommand;
+use crate::util::{append_audit_file, parse_runtime};
+
+pub async fn exec_skills(command: SkillCommand, registry: &AdapterRegistry) -> Result<()> {
+    match command {
+        SkillCommand::List { runtime, docker } => {
+            let runtimes = selected_runtimes(runtime.as_deref())?;
+            list_skills(&runtimes, docker, registry).await
+        }
+        SkillCommand::Install {
+            path,
+            runtime,
+            docker,
+        } => install_skill(Path::new(&path), runtime.as_deref(), docker, registry).await,
+        SkillCommand::Remove {
+            name,
+            runtime,
+            docker,
+        } => {
+            let runtimes = selected_runtimes(runtime.as_deref())?;
+            remove_skill(&name, &runtimes, docker, registry).await
+        }
+    }
+}
+
+/// The requested runtime, or every runtime whose workspace can hold skills.
+fn selected_runtimes(runtime: Option<&str>) -> Result<Vec<ClawRuntime>> {
+    match runtime {
+        Some(name) => Ok(vec![parse_runtime(name)?]),
+        None => Ok(runtime_descriptors()
+            .filter(|d| d.workspace_path.is_some())
+            .map(|d| d.runtime.clone())
+            .collect()),
+    }
+}
+
+/// Running containers for a runtime as adapter handles.
+fn container_handles(runtime: &ClawRuntime) -> Result<Vec<AgentHandle>> {
+    Ok(running_runtime_containers(runtime)?
+        .into_iter()
+        .map(|name| AgentHandle {
+            id: name.clone(),
+            name,
+            runtime: runtime.clone(),
+        })
+        .collect())
+}
+
+fn print_skill(skill: &Skill, runtime: &ClawRuntime, container: Option<&str>) {
+    let location = container
+        .map(|name| format!("\tcontainer={name}"))
+        .unwrap_or_default();
+    println!(
+        "skill={}\tversion={}\truntime={}{}\tdescription={}",
+        skill.name,
+        skill.version,
+        runtime.as_slug(),
+        location,
+        skill.description.as_deref().unwrap_or("-")
+    );
+}
+
+async fn list_skills(
+    runtimes: &[ClawRuntime],
+    docker: bool,
+    registry: &AdapterRegistry,
+) -> Result<()> {
+    let mut found = 0usize;
+    for runtime in runtimes {
+        if docker {
+            let Some(adapter) = registry.get(runtime) else {
+                continue;
+            };
+            for handle in container_handles(runtime)? {
+                for skill in adapter.list_skills(&handle).await? {
+                    print_skill(&skill, runtime, Some(&handle.id));
+                    found += 1;
+                }
+            }
+        } else {
+            for skill in SkillDirectory::for_runtime(runtime)?.list()? {
+                print_skill(&skill, runtime, None);
+                found += 1;
+            }
+        }
+    }
+    if found == 0 {
+        println!("No skills installed");
+    }
+    Ok(())
+}
+
+async fn install_skill(
+    path: &Path,
+    runtime: Option<&str>,
+    docker: bool,
+    registry: &AdapterRegistry,
+) -> Result<()> {
+    let manifest = load_skill_package(path)?;
+    let runtimes = match runtime {
+        Some(name) => {
+            let runtime = parse_runtime(name)?;
+            manifest.ensure_supports(&runtime)?;
+            vec![runtime]
+        }
+        None => manifest
+            .runtimes
+            .iter()
+            .filter(|runtime| runtime_skills_path(runtime).is_some())
+            .cloned()
+            .collect(),
+    };
+    if runtimes.is_empty() {
+        bail!(
+            "skill '{}' supports no runtime with a skills workspace",
+            manifest.name
+        );
+    }
+
+    let mut installed = 0usize;
+    for runtime in &runtimes {
+        if docker {
+            let adapter = registry
+                .get(runtime)
+                .ok_or_else(|| anyhow!("no adapter available for {}", runtime.as_slug()))?;
+            let handles = container_handles(runtime)?;
+            if handles.is_empty() && runtimes.len() == 1 {
+                bail!("no running {} containers", runtime.as_slug());
+            }
+            for handle in handles {
+                if let Err(err) = adapter.install_skill(&handle, &manifest).await {
+                    append_audit_file("skill.install", runtime.as_slug(), "failed")?;
+                    return Err(err);
+                }
+                append_audit_file("skill.install", runtime.as_slug(), "ok")?;
+                println!(
+                    "Installed skill {}@{} into {} container {}",
+                    manifest.name,
+                    manifest.version,
+                    runtime.as_slug(),
+                    handle.id
+                );
+                installed += 1;
+            }
+        } else {
+            let dir = SkillDirectory::for_runtime(runtime)?;
+            dir.install(&manifest)?;
+            println!(
+                "Installed skill {}@{} for {} ({})",
+                manifest.name,
+                manifest.version,
+                runtime.as_slug(),
+                dir.root().join(&manifest.name).display()
+            );
+            append_audit_file("skill.install", runtime.as_slug(), "ok")?;
+            installed += 1;
+        }
+    }
+
+    if installed == 0 {
+        bail!("no running containers found for skill '{}'", manifest.name);
+    }
+    Ok(())
+}
+
+async fn remove_skill(
+    name: &str,
+    runtimes: &[ClawRuntime],
+    docker: bool,
+    registry: &AdapterRegistry,
+) -> Result<()> {
+    let mut removed = 0usize;
+    for runtime in runtimes {
+        if docker {
+            let Some(adapter) = registry.get(runtime) else {
+                continue;
+            };
+            for handle in container_handles(runtime)? {
+                if adapter.remove_skill(&handle, name).await? {
+                    println!(
+                        "Removed skill {name} from {} container {}",
+                        runtime.as_slug(),
+                        handle.id
+                    );
+                    removed += 1;
+                    append_audit_file("skill.remove", runtime.as_slug(), "ok")?;
+                }
+            }
+        } else if SkillDirectory::for_runtime(runtime)?.remove(name)? {
+            println!("Removed skill {name} from {}", runtime.as_slug());
+            removed += 1;
+            append_audit_file("skill.remove", runtime.as_slug(), "ok")?;
+        }
+    }
+
+    if removed == 0 {
+        bail!("skill '{name}' is not installed");
+    }
+    Ok(())
+}
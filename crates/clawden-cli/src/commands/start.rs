@@ -18,6 +18,7 @@ pub async fn exec_start(
             detach: true,
             no_log_prefix: false,
             timeout: 10,
+            watch: false,
             force_docker: false,
         },
         installer,
@@ -1,29 +1,32 @@
 use anyhow::Result;
 use clawden_core::ProcessManager;
 
-use crate::util::append_audit_file;
+use crate::util::{append_audit_file, record_stop_outcome};
 
 pub fn exec_stop(
     process_manager: &ProcessManager,
     runtime: Option<String>,
     timeout: u64,
 ) -> Result<()> {
-    if let Some(rt) = runtime {
-        println!("Stopping {}...", rt);
-        let outcome = process_manager.stop_with_timeout(&rt, timeout)?;
-        if outcome.forced {
-            append_audit_file("runtime.force_kill", &rt, "ok")?;
+    if let Some(name) = runtime {
+        // A bare runtime name stops every instance of that runtime.
+        let mut instances = process_manager.resolve_instances(&name)?;
+        if instances.is_empty() {
+            instances.push(name);
+        }
+        for rt in instances {
+            println!("Stopping {}...", rt);
+            let outcome = process_manager.stop_with_timeout(&rt, timeout)?;
+            record_stop_outcome(&rt, &outcome)?;
+            append_audit_file("runtime.stop", &rt, "ok")?;
         }
-        append_audit_file("runtime.stop", &rt, "ok")?;
         return Ok(());
     }
 
     println!("Stopping all runtimes...");
     for status in process_manager.list_statuses()? {
         let outcome = process_manager.stop_with_timeout(&status.runtime, timeout)?;
-        if outcome.forced {
-            append_audit_file("runtime.force_kill", &status.runtime, "ok")?;
-        }
+        record_stop_outcome(&status.runtime, &outcome)?;
         append_audit_file("runtime.stop", &status.runtime, "ok")?;
         println!("Stopped {}", status.runtime);
     }
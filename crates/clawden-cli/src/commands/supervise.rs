@@ -0,0 +1,29 @@
+use anyhow::{Context, Result};
+use clawden_core::{run_supervisor, SupervisorSpec};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::signal::unix::{signal, SignalKind};
+
+/// Runs the supervisor loop for one runtime until it exits for good or
+/// clawden stops it with SIGTERM/SIGINT.
+pub async fn exec_supervise(spec_path: &str) -> Result<()> {
+    let spec = SupervisorSpec::load(Path::new(spec_path))?;
+    let shutdown = Arc::new(AtomicBool::new(false));
+
+    let mut terminate = signal(SignalKind::terminate()).context("installing SIGTERM handler")?;
+    let mut interrupt = signal(SignalKind::interrupt()).context("installing SIGINT handler")?;
+    let flag = shutdown.clone();
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = interrupt.recv() => {}
+        }
+        flag.store(true, Ordering::Relaxed);
+    });
+
+    tokio::task::spawn_blocking(move || run_supervisor(&spec, &shutdown))
+        .await
+        .context("supervisor task panicked")??;
+    Ok(())
+}
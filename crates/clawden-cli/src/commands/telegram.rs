@@ -1,6 +1,6 @@
 use anyhow::Result;
 use clawden_config::{is_numeric_telegram_id, ChannelInstanceYaml, ClawDenYaml};
-use clawden_core::ExecutionMode;
+use clawden_core::{instance_runtime, ExecutionMode};
 use reqwest::Client;
 use serde_json::{Map, Value};
 use std::collections::HashMap;
@@ -39,7 +39,7 @@ pub(crate) async fn resolve_openclaw_telegram_allowed_users_for_runtime(
     config: &mut ClawDenYaml,
     runtime: &str,
 ) -> Result<()> {
-    if runtime != "openclaw" {
+    if instance_runtime(runtime) != "openclaw" {
         return Ok(());
     }
 
@@ -425,7 +425,7 @@ fn is_openclaw_running() -> bool {
         .map(|statuses| {
             statuses
                 .into_iter()
-                .any(|s| s.runtime == "openclaw" && s.running)
+                .any(|s| instance_runtime(&s.runtime) == "openclaw" && s.running)
         })
         .unwrap_or(false)
 }
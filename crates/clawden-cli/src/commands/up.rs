@@ -1,23 +1,31 @@
 use anyhow::Result;
 use clawden_config::{
-    ChannelCredentialMapper, ClawDenYaml, LlmProvider, ProviderEntryYaml, ProviderRefYaml,
+    ChannelCredentialMapper, ClawDenYaml, DependencyCondition, LlmProvider, ProviderEntryYaml,
+    ProviderRefYaml,
 };
 use clawden_core::{
-    channel_descriptor, runtime_default_start_args, runtime_env_prefix, AgentState, ExecutionMode,
-    LifecycleManager, ProcessInfo, ProcessManager, ProviderDescriptor, RuntimeInstaller,
+    channel_descriptor, current_unix_ms, instance_runtime, runtime_default_start_args,
+    runtime_env_prefix, AgentState, DirectStartOptions, ExecutionMode, HealthCheck, HookEvent,
+    LifecycleManager, ProcessInfo, ProcessManager, ProviderDescriptor, ResourceLimits,
+    RuntimeInstaller, Task, TaskResult, TaskStatus, INSTANCE_SEPARATOR,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tracing::{debug, warn};
 
-use crate::commands::config_gen::{generate_config_dir, inject_config_dir_arg, state_dir_env_vars};
+use crate::commands::config_gen::{
+    inject_config_dir_arg, render_config_dir, state_dir_env_vars, RenderedConfig,
+};
+use crate::commands::watch::{ConfigWatch, FileEnv};
 use crate::commands::workspace::{collect_sync_tasks, spawn_auto_sync};
 use crate::commands::InitOptions;
 use crate::util::{
-    append_audit_file, ensure_installed_runtime, get_provider_key_from_vault, is_first_run_context,
-    parse_runtime, project_hash, prompt_yes_no,
+    append_audit_file, ensure_installed_runtime, get_provider_key_from_vault, hook_engine,
+    installed_runtime_executable, is_first_run_context, parse_runtime, project_hash, prompt_yes_no,
+    record_stop_outcome,
 };
 
 pub struct UpOptions {
@@ -28,6 +36,7 @@ pub struct UpOptions {
     pub detach: bool,
     pub no_log_prefix: bool,
     pub timeout: u64,
+    pub watch: bool,
     pub force_docker: bool,
 }
 
@@ -59,6 +68,9 @@ pub async fn exec_up(
 
     let mut config = load_config_with_env_file(opts.env_file.as_deref())?;
     let mode = resolve_up_mode(&opts, config.as_ref(), process_manager);
+    if opts.watch && mode == ExecutionMode::Docker {
+        anyhow::bail!("--watch is only supported for direct-mode runtimes");
+    }
     let target_runtimes =
         resolve_target_runtimes(opts.runtimes.clone(), config.as_ref(), installer)?;
 
@@ -68,129 +80,112 @@ pub async fn exec_up(
     }
     let current_project_hash = project_hash()?;
 
-    let mut started_runtimes = Vec::new();
-
-    for runtime in target_runtimes {
-        if let Some(cfg) = config.as_mut() {
-            super::telegram::resolve_openclaw_telegram_allowed_users_for_runtime(cfg, &runtime)
-                .await?;
-        }
+    let target_runtimes = match config.as_ref() {
+        // Dependencies start too, unless they are already running.
+        Some(cfg) => cfg
+            .with_dependencies(&target_runtimes)
+            .into_iter()
+            .filter(|runtime| {
+                target_runtimes.contains(runtime) || !runtime_running(process_manager, runtime)
+            })
+            .collect(),
+        None => target_runtimes,
+    };
+    let waves = match config.as_ref() {
+        Some(cfg) => cfg
+            .startup_waves(&target_runtimes)
+            .map_err(anyhow::Error::msg)?,
+        None => vec![target_runtimes],
+    };
 
-        let env_vars = if let Some(cfg) = config.as_ref() {
-            build_runtime_env_vars(cfg, &runtime)?
-        } else {
-            Vec::new()
-        };
-        let env_overrides = parse_env_overrides(&opts.env_vars)?;
-        if !env_overrides.is_empty() {
-            let keys = env_overrides
-                .iter()
-                .map(|(k, _)| k.as_str())
-                .collect::<Vec<_>>()
-                .join(",");
-            let _ = append_audit_file("runtime.env_override", &runtime, &keys);
+    let mut started_runtimes = Vec::new();
+    let mut plans = HashMap::new();
+    for wave in waves {
+        if let Some(cfg) = config.as_ref() {
+            wait_for_dependencies(cfg, &wave, mode, process_manager)?;
         }
-
-        let channels = if let Some(cfg) = config.as_ref() {
-            channels_for_runtime(cfg, &runtime)
-        } else {
-            Vec::new()
-        };
-
-        let tools = if let Some(cfg) = config.as_ref() {
-            tools_for_runtime(cfg, &runtime)
-        } else {
-            Vec::new()
-        };
-
-        let pinned_version = config
-            .as_ref()
-            .and_then(|cfg| pinned_version_for_runtime(cfg, &runtime));
-
-        match mode {
-            ExecutionMode::Docker => {
-                let rt = parse_runtime(&runtime)?;
-                let mut docker_env = env_vars.clone();
-                // Forward CLAWDEN_MEMORY_* so Docker entrypoint can bootstrap workspace
-                crate::commands::run::inject_host_env_memory_vars(&mut docker_env);
-                for (key, value) in &env_overrides {
-                    docker_env.retain(|(k, _)| k != key);
-                    docker_env.push((key.clone(), value.clone()));
-                }
-                let record = manager.register_agent_with_config(
-                    format!("{}-default", rt.as_slug()),
-                    rt.clone(),
-                    vec!["chat".to_string()],
-                    clawden_core::AgentConfig {
-                        name: format!("{}-default", rt.as_slug()),
-                        runtime: rt,
-                        model: None,
-                        env_vars: docker_env,
-                        channels: channels.clone(),
-                        tools: tools.clone(),
-                    },
-                );
-                manager
-                    .start_agent(&record.id)
-                    .await
-                    .map_err(anyhow::Error::msg)?;
-                append_audit_file("runtime.start", &runtime, "ok")?;
-                println!("Started {runtime} via adapter (docker mode)");
-                started_runtimes.push(runtime.clone());
+        for runtime in wave {
+            if let Some(cfg) = config.as_mut() {
+                super::telegram::resolve_openclaw_telegram_allowed_users_for_runtime(cfg, &runtime)
+                    .await?;
             }
-            ExecutionMode::Direct | ExecutionMode::Auto => {
-                if let Some(cfg) = config.as_ref() {
-                    if !opts.allow_missing_credentials {
-                        validate_direct_runtime_config(cfg, &runtime, &env_vars, &channels)?;
-                    } else {
-                        warn!(
-                            "missing credential checks are skipped (--allow-missing-credentials)"
-                        );
-                    }
-                }
-                let installed = ensure_installed_runtime(installer, &runtime, pinned_version)?;
 
-                let mut args = runtime_default_start_args(&runtime)
+            let env_overrides = parse_env_overrides(&opts.env_vars)?;
+            if !env_overrides.is_empty() {
+                let keys = env_overrides
                     .iter()
-                    .map(|arg| (*arg).to_string())
-                    .collect::<Vec<_>>();
-                if let Some(cfg) = config.as_ref() {
-                    if let Some(config_dir) = generate_config_dir(
-                        cfg,
-                        &runtime,
-                        &current_project_hash,
-                        Some(&installed.executable),
-                    )? {
-                        inject_config_dir_arg(&runtime, &mut args, &config_dir);
-                    }
-                }
+                    .map(|(k, _)| k.as_str())
+                    .collect::<Vec<_>>()
+                    .join(",");
+                let _ = append_audit_file("runtime.env_override", &runtime, &keys);
+            }
 
-                // Channel and tool lists are passed via env vars — runtimes
-                // do NOT accept --channels / --tools CLI flags.
-                let mut combined_env = env_vars.clone();
-                combined_env.extend(state_dir_env_vars(&runtime, &current_project_hash)?);
-                if !channels.is_empty() {
-                    combined_env.push(("CLAWDEN_CHANNELS".to_string(), channels.join(",")));
-                }
-                if !tools.is_empty() {
-                    combined_env.push(("CLAWDEN_TOOLS".to_string(), tools.join(",")));
+            match mode {
+                ExecutionMode::Docker => {
+                    let rt = parse_runtime(&runtime)?;
+                    let mut docker_env = if let Some(cfg) = config.as_ref() {
+                        build_runtime_env_vars(cfg, &runtime)?
+                    } else {
+                        Vec::new()
+                    };
+                    let (channels, tools) = match config.as_ref() {
+                        Some(cfg) => (
+                            channels_for_runtime(cfg, &runtime),
+                            tools_for_runtime(cfg, &runtime),
+                        ),
+                        None => (Vec::new(), Vec::new()),
+                    };
+                    // Forward CLAWDEN_MEMORY_* so Docker entrypoint can bootstrap workspace
+                    crate::commands::run::inject_host_env_memory_vars(&mut docker_env);
+                    for (key, value) in &env_overrides {
+                        docker_env.retain(|(k, _)| k != key);
+                        docker_env.push((key.clone(), value.clone()));
+                    }
+                    let agent_name = match runtime.split_once(INSTANCE_SEPARATOR) {
+                        Some((_, instance)) => format!("{}-{instance}", rt.as_slug()),
+                        None => format!("{}-default", rt.as_slug()),
+                    };
+                    let record = manager.register_agent_with_config(
+                        agent_name.clone(),
+                        rt.clone(),
+                        vec!["chat".to_string()],
+                        clawden_core::AgentConfig {
+                            name: agent_name,
+                            runtime: rt,
+                            model: None,
+                            env_vars: docker_env,
+                            channels,
+                            tools,
+                        },
+                    );
+                    manager
+                        .start_agent(&record.id)
+                        .await
+                        .map_err(anyhow::Error::msg)?;
+                    append_audit_file("runtime.start", &runtime, "ok")?;
+                    println!("Started {runtime} via adapter (docker mode)");
+                    started_runtimes.push(runtime.clone());
                 }
-                for (key, value) in &env_overrides {
-                    combined_env.retain(|(k, _)| k != key);
-                    combined_env.push((key.clone(), value.clone()));
+                ExecutionMode::Direct | ExecutionMode::Auto => {
+                    let plan = plan_direct_launch(
+                        config.as_ref(),
+                        installer,
+                        &PlanRequest {
+                            runtime: &runtime,
+                            env_overrides: &env_overrides,
+                            file_env: &FileEnv::default(),
+                            allow_missing_credentials: opts.allow_missing_credentials,
+                            install: PlanInstall::Allowed,
+                            project_hash: &current_project_hash,
+                        },
+                    )?;
+                    let info =
+                        launch_direct(process_manager, &runtime, &plan, &current_project_hash)?;
+                    append_audit_file("runtime.start", &runtime, "ok")?;
+                    println!("Started {runtime} (pid {})", info.pid);
+                    started_runtimes.push(runtime.clone());
+                    plans.insert(runtime.clone(), plan);
                 }
-
-                let info = process_manager.start_direct_with_env_and_project(
-                    &runtime,
-                    &installed.executable,
-                    &args,
-                    &combined_env,
-                    Some(current_project_hash.clone()),
-                )?;
-                verify_runtime_startup(process_manager, &runtime, &info)?;
-                append_audit_file("runtime.start", &runtime, "ok")?;
-                println!("Started {runtime} (pid {})", info.pid);
-                started_runtimes.push(runtime.clone());
             }
         }
     }
@@ -224,6 +219,26 @@ pub async fn exec_up(
         Vec::new()
     };
 
+    let mut watch = if opts.watch {
+        let watch = ConfigWatch::new(opts.env_file.as_deref())?;
+        let names = watch
+            .paths()
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!("Watching {names} for changes");
+        Some(watch)
+    } else {
+        None
+    };
+
+    // Hooks follow the config the runtimes were started with; a reload does
+    // not rewire them.
+    let hooks = config.as_ref().and_then(hook_engine);
+    let mut exited = BTreeSet::new();
+    let mut deliveries = Vec::new();
+
     println!("Attaching logs. Press Ctrl+C to stop.");
     let stream = process_manager.stream_logs(&started_runtimes)?;
     let mut tick = tokio::time::interval(Duration::from_millis(150));
@@ -242,11 +257,10 @@ pub async fn exec_up(
                 let timeout = opts.timeout;
                 let stop_task = tokio::task::spawn_blocking(move || {
                     let pm = ProcessManager::new(ExecutionMode::Auto)?;
-                    for runtime in &to_stop {
+                    // Dependents first: runtimes were started in dependency order.
+                    for runtime in to_stop.iter().rev() {
                         if let Ok(outcome) = pm.stop_with_timeout(runtime, timeout) {
-                            if outcome.forced {
-                                let _ = append_audit_file("runtime.force_kill", runtime, "ok");
-                            }
+                            let _ = record_stop_outcome(runtime, &outcome);
                             let _ = append_audit_file("runtime.stop", runtime, "ok");
                         }
                     }
@@ -282,12 +296,39 @@ pub async fn exec_up(
                     );
                 }
 
+                if let Some(watch) = watch.as_mut() {
+                    if watch.poll() {
+                        reload_watched_config(
+                            watch,
+                            &mut config,
+                            &mut plans,
+                            &started_runtimes,
+                            &opts,
+                            installer,
+                            process_manager,
+                        )
+                        .await;
+                    }
+                }
+
                 let all_stopped = match mode {
                     ExecutionMode::Docker => {
                         manager.list_agents().iter().all(|a| a.state != AgentState::Running)
                     }
                     _ => {
-                        started_runtimes.iter().all(|runtime| !runtime_running(process_manager, runtime))
+                        for runtime in &started_runtimes {
+                            if exited.contains(runtime) || runtime_running(process_manager, runtime) {
+                                continue;
+                            }
+                            exited.insert(runtime.clone());
+                            if let Some(hooks) = hooks.clone() {
+                                let event = runtime_exit_event(process_manager, runtime).await;
+                                deliveries.push(tokio::spawn(async move {
+                                    hooks.fire(&event).await;
+                                }));
+                            }
+                        }
+                        exited.len() == started_runtimes.len()
                     }
                 };
                 if all_stopped {
@@ -297,9 +338,386 @@ pub async fn exec_up(
         }
     }
 
+    // Let in-flight hook notifications finish before the CLI exits.
+    for delivery in deliveries {
+        let _ = delivery.await;
+    }
+
     Ok(())
 }
 
+/// A runtime that exits while `up` is attached is reported to the hooks:
+/// `on_task_complete` for a clean exit, `on_task_failed` for a non-zero
+/// status, a signal or an unknown outcome, with its last log line as the
+/// error.
+async fn runtime_exit_event(process_manager: &ProcessManager, runtime: &str) -> HookEvent {
+    // The exit status is recorded by the thread reaping the runtime, which
+    // may lag behind the liveness check that noticed the exit.
+    let deadline = Instant::now() + Duration::from_secs(1);
+    let status = loop {
+        let status = process_manager.runtime_status(runtime).ok().flatten();
+        let known = status
+            .as_ref()
+            .is_some_and(|s| s.last_exit_code.is_some() || s.last_exit_signal.is_some());
+        if known || Instant::now() >= deadline {
+            break status;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    };
+    let last_line = process_manager
+        .tail_logs(runtime, 1)
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let task = Task {
+        id: format!("{runtime}-{}", current_unix_ms()),
+        prompt: format!("clawden up {runtime}"),
+        project_dir: std::env::current_dir().unwrap_or_default(),
+        timeout_secs: None,
+        allowed_tools: None,
+    };
+    let (code, signal) = status.map_or((None, None), |s| (s.last_exit_code, s.last_exit_signal));
+    let reason = match (code, signal) {
+        (Some(0), _) => {
+            return HookEvent::TaskComplete {
+                worker: runtime.to_string(),
+                result: TaskResult {
+                    task_id: task.id.clone(),
+                    status: TaskStatus::Completed,
+                    output: last_line,
+                    exit_code: Some(0),
+                    duration_ms: 0,
+                    cost: None,
+                    raw: None,
+                },
+                task,
+            };
+        }
+        (Some(code), _) => format!("exited with status {code}"),
+        (None, Some(signal)) => format!("killed by signal {signal}"),
+        (None, None) => "exited unexpectedly".to_string(),
+    };
+    let error = if last_line.is_empty() {
+        reason
+    } else {
+        format!("{reason}: {last_line}")
+    };
+    HookEvent::TaskFailed {
+        worker: runtime.to_string(),
+        task,
+        error,
+        result: None,
+    }
+}
+
+/// Everything `up` hands to a direct-mode runtime. `--watch` compares plans
+/// across reloads to decide which runtimes need a restart.
+#[derive(Debug, Clone, PartialEq)]
+struct DirectStartPlan {
+    executable: PathBuf,
+    args: Vec<String>,
+    env: Vec<(String, String)>,
+    /// Env-file values a reload picked up. Restarted runtimes get them in
+    /// place of what this process loaded at startup; on their own they do
+    /// not call for a restart.
+    file_env: FileEnv,
+    config: Option<RenderedConfig>,
+    limits: ResourceLimits,
+    health: Option<HealthCheck>,
+}
+
+impl DirectStartPlan {
+    /// Names what differs from `next`. Env changes list keys only so values
+    /// never reach the terminal.
+    fn changes(&self, next: &DirectStartPlan) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.executable != next.executable {
+            changes.push("executable".to_string());
+        }
+        if self.args != next.args {
+            changes.push("args".to_string());
+        }
+        let before = self.env.iter().cloned().collect::<BTreeMap<_, _>>();
+        let after = next.env.iter().cloned().collect::<BTreeMap<_, _>>();
+        let env_keys = before
+            .keys()
+            .chain(after.keys())
+            .filter(|key| before.get(*key) != after.get(*key))
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>();
+        if !env_keys.is_empty() {
+            changes.push(format!("env {}", join_names(env_keys)));
+        }
+        let no_files = BTreeMap::new();
+        let before = self
+            .config
+            .as_ref()
+            .map_or(&no_files, |config| &config.files);
+        let after = next
+            .config
+            .as_ref()
+            .map_or(&no_files, |config| &config.files);
+        let files = before
+            .keys()
+            .chain(after.keys())
+            .filter(|name| before.get(*name) != after.get(*name))
+            .map(String::as_str)
+            .collect::<BTreeSet<_>>();
+        if !files.is_empty() {
+            changes.push(join_names(files));
+        }
+        if self.limits != next.limits {
+            changes.push("resource limits".to_string());
+        }
+        if self.health != next.health {
+            changes.push("health check".to_string());
+        }
+        changes
+    }
+}
+
+fn join_names(names: BTreeSet<&str>) -> String {
+    names.into_iter().collect::<Vec<_>>().join(",")
+}
+
+/// How a plan gets at the runtime executable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlanInstall {
+    /// Install or switch versions as needed, as a fresh `up` does.
+    Allowed,
+    /// Use what is installed; a reload never installs.
+    Forbidden,
+}
+
+/// One runtime's inputs to [`plan_direct_launch`].
+struct PlanRequest<'a> {
+    runtime: &'a str,
+    env_overrides: &'a [(String, String)],
+    file_env: &'a FileEnv,
+    allow_missing_credentials: bool,
+    install: PlanInstall,
+    project_hash: &'a str,
+}
+
+/// Validates credentials, renders the config dir and assembles the env for
+/// a direct-mode start, without starting or writing anything (besides an
+/// install, when `request.install` allows it).
+fn plan_direct_launch(
+    config: Option<&ClawDenYaml>,
+    installer: &RuntimeInstaller,
+    request: &PlanRequest,
+) -> Result<DirectStartPlan> {
+    let PlanRequest {
+        runtime,
+        env_overrides,
+        file_env,
+        allow_missing_credentials,
+        install,
+        project_hash,
+    } = *request;
+    let env_vars = match config {
+        Some(cfg) => build_runtime_env_vars(cfg, runtime)?,
+        None => Vec::new(),
+    };
+    let (channels, tools) = match config {
+        Some(cfg) => (
+            channels_for_runtime(cfg, runtime),
+            tools_for_runtime(cfg, runtime),
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    if let Some(cfg) = config {
+        if !allow_missing_credentials {
+            validate_direct_runtime_config(cfg, runtime, &env_vars, &channels)?;
+        } else {
+            warn!("missing credential checks are skipped (--allow-missing-credentials)");
+        }
+    }
+    let pinned = config.and_then(|cfg| pinned_version_for_runtime(cfg, runtime));
+    let executable = match install {
+        PlanInstall::Allowed => {
+            ensure_installed_runtime(installer, instance_runtime(runtime), pinned)?.executable
+        }
+        PlanInstall::Forbidden => {
+            installed_runtime_executable(installer, instance_runtime(runtime), pinned)?
+        }
+    };
+
+    let mut args = runtime_default_start_args(runtime)
+        .iter()
+        .map(|arg| (*arg).to_string())
+        .collect::<Vec<_>>();
+    let rendered = match config {
+        Some(cfg) => render_config_dir(cfg, runtime, project_hash, Some(&executable))?,
+        None => None,
+    };
+    if let Some(rendered) = &rendered {
+        inject_config_dir_arg(runtime, &mut args, &rendered.dir);
+    }
+
+    // Channel and tool lists are passed via env vars — runtimes
+    // do NOT accept --channels / --tools CLI flags.
+    let mut env = env_vars;
+    env.extend(state_dir_env_vars(runtime, project_hash)?);
+    if !channels.is_empty() {
+        env.push(("CLAWDEN_CHANNELS".to_string(), channels.join(",")));
+    }
+    if !tools.is_empty() {
+        env.push(("CLAWDEN_TOOLS".to_string(), tools.join(",")));
+    }
+    for (key, value) in env_overrides {
+        env.retain(|(k, _)| k != key);
+        env.push((key.clone(), value.clone()));
+    }
+
+    let limits = match config {
+        Some(cfg) => resource_limits_for_runtime(cfg, runtime)?,
+        None => ResourceLimits::default(),
+    };
+    Ok(DirectStartPlan {
+        executable,
+        args,
+        env,
+        file_env: file_env.clone(),
+        config: rendered,
+        limits,
+        health: config.and_then(|cfg| health_check_for_runtime(cfg, runtime)),
+    })
+}
+
+fn launch_direct(
+    process_manager: &ProcessManager,
+    runtime: &str,
+    plan: &DirectStartPlan,
+    project_hash: &str,
+) -> Result<ProcessInfo> {
+    if let Some(config) = &plan.config {
+        config.write()?;
+    }
+    // The plan's own env wins over raw env-file values.
+    let mut env = plan.file_env.set.clone();
+    env.retain(|(key, _)| !plan.env.iter().any(|(k, _)| k == key));
+    env.extend(plan.env.iter().cloned());
+    let mut info = process_manager.start_direct(&DirectStartOptions {
+        args: plan.args.clone(),
+        env_vars: env,
+        removed_env: plan.file_env.removed.clone(),
+        project_hash: Some(project_hash.to_string()),
+        limits: plan.limits.clone(),
+        ..DirectStartOptions::new(runtime, plan.executable.as_path())
+    })?;
+    if let Some(check) = plan.health.clone() {
+        info = process_manager.set_health_check(runtime, check)?;
+    }
+    verify_runtime_startup(process_manager, runtime, &info)?;
+    Ok(info)
+}
+
+/// Applies an edit to the watched files: runtimes whose start plan changed
+/// are restarted and the rest keep running. Invalid config is reported
+/// without touching anything that runs.
+async fn reload_watched_config(
+    watch: &mut ConfigWatch,
+    config: &mut Option<ClawDenYaml>,
+    plans: &mut HashMap<String, DirectStartPlan>,
+    started_runtimes: &[String],
+    opts: &UpOptions,
+    installer: &RuntimeInstaller,
+    process_manager: &ProcessManager,
+) {
+    println!("Change detected, reloading clawden.yaml...");
+    let (mut next, file_env) = match watch.reload() {
+        Ok(reloaded) => reloaded,
+        Err(e) => {
+            let _ = append_audit_file("config.reload", "clawden.yaml", "invalid");
+            eprintln!("✗ {e:#}");
+            eprintln!("Keeping running runtimes on the previous config");
+            return;
+        }
+    };
+
+    let previous = config
+        .as_ref()
+        .map(runtimes_from_config)
+        .unwrap_or_default();
+    let current = next.as_ref().map(runtimes_from_config).unwrap_or_default();
+    for runtime in current.iter().filter(|rt| !previous.contains(rt)) {
+        println!("  {runtime} was added; run 'clawden up {runtime}' to start it");
+    }
+    let has_config = next.is_some();
+    let (removed, kept): (Vec<&String>, Vec<&String>) = started_runtimes
+        .iter()
+        .partition(|rt| has_config && !current.contains(rt));
+    for runtime in removed {
+        println!("  {runtime} was removed; stop it with 'clawden stop {runtime}'");
+    }
+
+    let result: Result<usize> = async {
+        let env_overrides = parse_env_overrides(&opts.env_vars)?;
+        let project_hash = project_hash()?;
+        let mut restarted = 0;
+        for runtime in kept {
+            let Some(previous_plan) = plans.get(runtime) else {
+                continue;
+            };
+            if let Some(cfg) = next.as_mut() {
+                super::telegram::resolve_openclaw_telegram_allowed_users_for_runtime(cfg, runtime)
+                    .await?;
+            }
+            let plan = match plan_direct_launch(
+                next.as_ref(),
+                installer,
+                &PlanRequest {
+                    runtime,
+                    env_overrides: &env_overrides,
+                    file_env: &file_env,
+                    allow_missing_credentials: opts.allow_missing_credentials,
+                    install: PlanInstall::Forbidden,
+                    project_hash: &project_hash,
+                },
+            ) {
+                Ok(plan) => plan,
+                Err(e) => {
+                    eprintln!("✗ {runtime}: {e:#}");
+                    eprintln!("Keeping {runtime} running on the previous config");
+                    continue;
+                }
+            };
+            let changes = previous_plan.changes(&plan);
+            if changes.is_empty() {
+                continue;
+            }
+
+            println!("Restarting {runtime} ({} changed)", changes.join("; "));
+            let outcome = process_manager.stop_with_timeout(runtime, opts.timeout)?;
+            record_stop_outcome(runtime, &outcome)?;
+            match launch_direct(process_manager, runtime, &plan, &project_hash) {
+                Ok(info) => {
+                    append_audit_file("runtime.reload", runtime, "ok")?;
+                    println!("Restarted {runtime} (pid {})", info.pid);
+                    plans.insert(runtime.clone(), plan);
+                }
+                Err(e) => {
+                    // The old plan stays, so the next reload tries again.
+                    append_audit_file("runtime.reload", runtime, "failed")?;
+                    eprintln!("✗ Failed to restart {runtime}: {e:#}");
+                }
+            }
+            restarted += 1;
+        }
+        Ok(restarted)
+    }
+    .await;
+
+    match result {
+        Ok(0) => println!("Config reloaded; no runtime changes"),
+        Ok(_) => {}
+        Err(e) => eprintln!("✗ Reload failed: {e:#}"),
+    }
+    *config = next;
+}
+
 fn print_status_table(process_manager: &ProcessManager) -> Result<()> {
     let statuses = process_manager.list_statuses()?;
     if statuses.is_empty() {
@@ -308,12 +726,12 @@ fn print_status_table(process_manager: &ProcessManager) -> Result<()> {
     }
 
     println!(
-        "{:<14} {:<8} {:<10} {:<10}",
-        "RUNTIME", "PID", "STATE", "HEALTH"
+        "{:<22} {:<8} {:<10} {:<10}",
+        "INSTANCE", "PID", "STATE", "HEALTH"
     );
     for status in statuses {
         println!(
-            "{:<14} {:<8} {:<10} {:<10}",
+            "{:<22} {:<8} {:<10} {:<10}",
             status.runtime,
             status
                 .pid
@@ -616,6 +1034,54 @@ pub(crate) fn validate_direct_runtime_config(
     anyhow::bail!(lines.join("\n"));
 }
 
+/// How long a dependent waits for a `condition: healthy` dependency, unless
+/// the dependency's start period is longer.
+const DEPENDENCY_HEALTH_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Before a wave starts, waits for the dependencies its runtimes need
+/// healthy. Earlier waves have already started every dependency.
+fn wait_for_dependencies(
+    config: &ClawDenYaml,
+    wave: &[String],
+    mode: ExecutionMode,
+    process_manager: &ProcessManager,
+) -> Result<()> {
+    let mut ready: Vec<String> = Vec::new();
+    for runtime in wave {
+        for (dependency, condition) in config.dependencies(runtime) {
+            if condition != DependencyCondition::Healthy || ready.contains(&dependency) {
+                continue;
+            }
+            if mode == ExecutionMode::Docker {
+                warn!(
+                    "{runtime}: docker mode has no health probes; treating {dependency} as started"
+                );
+                continue;
+            }
+            let timeout = health_check_for_runtime(config, &dependency)
+                .map(|check| check.start_period())
+                .unwrap_or_default()
+                .max(DEPENDENCY_HEALTH_TIMEOUT);
+            println!("Waiting for {dependency} to become healthy before starting {runtime}...");
+            let healthy = process_manager
+                .wait_until_healthy(&dependency, timeout)
+                .map_err(|err| anyhow::anyhow!("✗ cannot start {runtime}: {err}"))?;
+            if !healthy {
+                append_audit_file("runtime.dependency_unhealthy", runtime, &dependency)?;
+                anyhow::bail!(
+                    "✗ {dependency} did not become healthy within {}s; not starting {runtime}",
+                    timeout.as_secs()
+                );
+            }
+            ready.push(dependency);
+        }
+    }
+    Ok(())
+}
+
+/// How long `up` waits for a runtime's health check to pass.
+const STARTUP_HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub(crate) fn verify_runtime_startup(
     process_manager: &ProcessManager,
     runtime: &str,
@@ -635,21 +1101,22 @@ pub(crate) fn verify_runtime_startup(
         }
     }
 
-    if let Some(url) = info.health_url.as_deref() {
-        for _ in 0..5 {
-            if health_probe_ok(url) {
-                println!("✓ {} ready", runtime);
-                return Ok(());
-            }
-            std::thread::sleep(Duration::from_secs(1));
-            if !runtime_running(process_manager, runtime) {
-                let tail = process_manager.tail_logs(runtime, 50)?;
-                anyhow::bail!("✗ {} crashed on startup\n{}", runtime, tail);
-            }
+    if let Some(check) = info.health_check() {
+        // A configured start period extends the wait.
+        let timeout = check.start_period().max(STARTUP_HEALTH_TIMEOUT);
+        if process_manager.wait_until_healthy(runtime, timeout)? {
+            println!("✓ {} ready", runtime);
+            return Ok(());
+        }
+        if !runtime_running(process_manager, runtime) {
+            let tail = process_manager.tail_logs(runtime, 50)?;
+            anyhow::bail!("✗ {} crashed on startup\n{}", runtime, tail);
         }
         println!(
-            "⚠ {} started (pid {}) but health check not responding",
-            runtime, info.pid
+            "⚠ {} started (pid {}) but health check ({}) not passing",
+            runtime,
+            info.pid,
+            check.describe()
         );
         return Ok(());
     }
@@ -658,16 +1125,6 @@ pub(crate) fn verify_runtime_startup(
     Ok(())
 }
 
-fn health_probe_ok(url: &str) -> bool {
-    std::process::Command::new("curl")
-        .args(["-fsS", "--max-time", "2", url])
-        .stdout(std::process::Stdio::null())
-        .stderr(std::process::Stdio::null())
-        .status()
-        .map(|status| status.success())
-        .unwrap_or(false)
-}
-
 pub(crate) fn render_log_line(
     runtime: &str,
     text: &str,
@@ -712,13 +1169,26 @@ pub(crate) fn load_config_with_env_file(env_file: Option<&str>) -> Result<Option
             .map_err(|e| anyhow::anyhow!("failed to load {path}: {e}"))?;
     }
 
+    load_config_file()
+}
+
+/// Loads, resolves and validates `clawden.yaml` from the current directory
+/// against the already-loaded process environment.
+pub(crate) fn load_config_file() -> Result<Option<ClawDenYaml>> {
+    load_config_file_with(&|key| std::env::var(key).ok())
+}
+
+/// Like [`load_config_file`], resolving `$VAR` references through `env`.
+pub(crate) fn load_config_file_with(
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<Option<ClawDenYaml>> {
     let yaml_path = std::env::current_dir()?.join("clawden.yaml");
     if !yaml_path.exists() {
         return Ok(None);
     }
 
     let mut cfg = ClawDenYaml::from_file(&yaml_path).map_err(|e| anyhow::anyhow!("{}", e))?;
-    if let Err(errs) = cfg.resolve_env_vars() {
+    if let Err(errs) = cfg.resolve_env_vars_with(env) {
         anyhow::bail!(
             "failed to resolve environment variables in clawden.yaml:\n{}",
             errs.join("\n")
@@ -757,7 +1227,10 @@ pub fn resolve_target_runtimes(
     installer: &RuntimeInstaller,
 ) -> Result<Vec<String>> {
     let mut resolved = if !runtimes.is_empty() {
-        runtimes
+        match config {
+            Some(cfg) => expand_instance_names(runtimes, cfg),
+            None => runtimes,
+        }
     } else if let Some(cfg) = config {
         runtimes_from_config(cfg)
     } else {
@@ -773,12 +1246,33 @@ pub fn resolve_target_runtimes(
     Ok(resolved)
 }
 
-/// Extract runtime names from a parsed clawden.yaml config.
+/// Maps names given on the command line to configured instances: a bare
+/// runtime name selects every configured instance of that runtime.
+fn expand_instance_names(names: Vec<String>, config: &ClawDenYaml) -> Vec<String> {
+    let configured = runtimes_from_config(config);
+    let mut expanded = Vec::new();
+    for name in names {
+        let matches: Vec<String> = configured
+            .iter()
+            .filter(|instance| instance_runtime(instance) == name)
+            .cloned()
+            .collect();
+        if configured.contains(&name) || matches.is_empty() {
+            expanded.push(name);
+        } else {
+            expanded.extend(matches);
+        }
+    }
+    expanded
+}
+
+/// Extract runtime instance names (`runtime` or `runtime@instance`) from a
+/// parsed clawden.yaml config.
 pub fn runtimes_from_config(config: &ClawDenYaml) -> Vec<String> {
     if let Some(rt) = &config.runtime {
         vec![rt.clone()]
     } else {
-        config.runtimes.iter().map(|r| r.name.clone()).collect()
+        config.runtimes.iter().map(|r| r.instance_name()).collect()
     }
 }
 
@@ -789,7 +1283,7 @@ pub(crate) fn channels_for_runtime(config: &ClawDenYaml, runtime: &str) -> Vec<S
         return config.channels.keys().cloned().collect();
     }
     // Multi-runtime: use the channel list from the runtime entry
-    if let Some(entry) = config.runtimes.iter().find(|e| e.name == runtime) {
+    if let Some(entry) = config.runtime_entry(runtime) {
         return entry.channels.clone();
     }
     Vec::new()
@@ -803,20 +1297,46 @@ pub(crate) fn pinned_version_for_runtime<'a>(
         return config.version.as_deref();
     }
     config
-        .runtimes
-        .iter()
-        .find(|entry| entry.name == runtime)
+        .runtime_entry(runtime)
         .and_then(|entry| entry.version.as_deref())
 }
 
+/// Resource limits from the runtime's `security` settings, enforced by
+/// ClawDen itself in direct mode.
+pub(crate) fn resource_limits_for_runtime(
+    config: &ClawDenYaml,
+    runtime: &str,
+) -> Result<ResourceLimits> {
+    let security = if config.runtime.as_deref() == Some(runtime) {
+        config.security.as_ref()
+    } else {
+        config
+            .runtime_entry(runtime)
+            .and_then(|entry| entry.security.as_ref())
+    };
+    security
+        .map(|security| security.resource_limits())
+        .transpose()
+        .map_err(|err| anyhow::anyhow!("invalid security settings for {runtime}: {err}"))
+        .map(Option::unwrap_or_default)
+}
+
+/// The runtime's `health:` probe, if it configures one.
+pub(crate) fn health_check_for_runtime(config: &ClawDenYaml, runtime: &str) -> Option<HealthCheck> {
+    if config.runtime.as_deref() == Some(runtime) {
+        return config.health.clone();
+    }
+    config
+        .runtime_entry(runtime)
+        .and_then(|entry| entry.health.clone())
+}
+
 pub(crate) fn tools_for_runtime(config: &ClawDenYaml, runtime: &str) -> Vec<String> {
     if config.runtime.as_deref() == Some(runtime) {
         return config.tools.clone();
     }
     config
-        .runtimes
-        .iter()
-        .find(|entry| entry.name == runtime)
+        .runtime_entry(runtime)
         .map(|entry| entry.tools.clone())
         .unwrap_or_default()
 }
@@ -875,7 +1395,9 @@ pub fn build_runtime_env_vars(
 
     // --- Channel credential env vars ---
     let channel_names = channels_for_runtime(config, runtime);
-    let runtime_slug = runtime.to_ascii_lowercase().replace('-', "");
+    let runtime_slug = instance_runtime(runtime)
+        .to_ascii_lowercase()
+        .replace('-', "");
     for ch_name in &channel_names {
         if let Some(ch_instance) = config.channels.get(ch_name) {
             let ch_type = ClawDenYaml::resolve_channel_type(ch_name, ch_instance)
@@ -912,7 +1434,7 @@ pub fn build_runtime_env_vars(
     env.insert("CLAWDEN_MANAGED".to_string(), "1".to_string());
 
     // For OpenClaw: relax worker isolation and sandbox when managed.
-    if runtime == "openclaw" {
+    if instance_runtime(runtime) == "openclaw" {
         env.insert("OPENCLAW_WORKER_ISOLATION".to_string(), "none".to_string());
         env.insert("OPENCLAW_SANDBOX_MODE".to_string(), "external".to_string());
     }
@@ -960,7 +1482,7 @@ pub(crate) fn runtime_provider_and_model(
         }
     }
 
-    let entry = config.runtimes.iter().find(|entry| entry.name == runtime)?;
+    let entry = config.runtime_entry(runtime)?;
     let provider_name = entry.provider.clone()?;
     let provider = config
         .providers
@@ -1035,7 +1557,7 @@ mod tests {
         validate_direct_runtime_config, verify_runtime_startup, ClawDenYaml,
     };
     use crate::commands::test_env_lock;
-    use clawden_core::{ExecutionMode, ProcessManager};
+    use clawden_core::{DirectStartOptions, ExecutionMode, ProcessManager};
     use std::fs;
     use std::net::TcpListener;
     use std::os::unix::fs::PermissionsExt;
@@ -1168,7 +1690,10 @@ channels:
         let script = tmp_home.join("crash-runtime.sh");
         write_executable(&script, "#!/usr/bin/env sh\necho boom\nexit 1\n");
         let info = manager
-            .start_direct_with_env_and_project("testruntime", &script, &[], &[], Some("ph".into()))
+            .start_direct(&DirectStartOptions {
+                project_hash: Some("ph".into()),
+                ..DirectStartOptions::new("testruntime", &script)
+            })
             .expect("runtime should start");
         let err = verify_runtime_startup(&manager, "testruntime", &info)
             .expect_err("startup checker should fail");
@@ -1202,7 +1727,10 @@ channels:
         let script = tmp_home.join("slow-runtime.sh");
         write_executable(&script, "#!/usr/bin/env sh\nsleep 8\n");
         let info = manager
-            .start_direct_with_env_and_project("zeroclaw", &script, &[], &[], Some("ph".into()))
+            .start_direct(&DirectStartOptions {
+                project_hash: Some("ph".into()),
+                ..DirectStartOptions::new("zeroclaw", &script)
+            })
             .expect("runtime should start");
         verify_runtime_startup(&manager, "zeroclaw", &info)
             .expect("startup check should warn and continue");
@@ -1263,25 +1791,19 @@ channels:
         write_executable(&openfang_script, &openfang_body);
 
         let zero_info = manager
-            .start_direct_with_env_and_project(
-                "zeroclaw",
-                &zeroclaw_script,
-                &[],
-                &[],
-                Some("multi-ph".into()),
-            )
+            .start_direct(&DirectStartOptions {
+                project_hash: Some("multi-ph".into()),
+                ..DirectStartOptions::new("zeroclaw", &zeroclaw_script)
+            })
             .expect("zeroclaw should start");
         verify_runtime_startup(&manager, "zeroclaw", &zero_info)
             .expect("zeroclaw health check should pass");
 
         let openfang_info = manager
-            .start_direct_with_env_and_project(
-                "openfang",
-                &openfang_script,
-                &[],
-                &[],
-                Some("multi-ph".into()),
-            )
+            .start_direct(&DirectStartOptions {
+                project_hash: Some("multi-ph".into()),
+                ..DirectStartOptions::new("openfang", &openfang_script)
+            })
             .expect("openfang should start");
         verify_runtime_startup(&manager, "openfang", &openfang_info)
             .expect("openfang health check should pass");
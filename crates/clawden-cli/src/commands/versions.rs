@@ -0,0 +1,83 @@
+use anyhow::Result;
+use clawden_core::{
+    instance_runtime, version_satisfies, ExecutionMode, LifecycleManager, ProcessManager,
+    RuntimeInstaller,
+};
+use std::collections::HashSet;
+
+use super::install::pinned_versions_map;
+use super::restart::exec_restart;
+use crate::util::{parse_runtime_version, project_hash};
+
+pub fn exec_use(installer: &RuntimeInstaller, spec: String) -> Result<()> {
+    let (runtime, Some(version)) = parse_runtime_version(&spec) else {
+        anyhow::bail!("specify a version, e.g. clawden use {spec}@0.1.6");
+    };
+    ensure_pin_allows(&runtime, &version)?;
+    let installed = installer.use_version(&runtime, &version)?;
+    println!("Now using {}@{}", installed.runtime, installed.version);
+    Ok(())
+}
+
+pub async fn exec_rollback(
+    runtime: String,
+    timeout: u64,
+    installer: &RuntimeInstaller,
+    process_manager: &ProcessManager,
+    manager: &mut LifecycleManager,
+) -> Result<()> {
+    let runtime = instance_runtime(&runtime).to_string();
+    let Some(previous) = installer.previous_version(&runtime) else {
+        anyhow::bail!("no previous version of {runtime} to roll back to");
+    };
+    ensure_pin_allows(&runtime, &previous)?;
+    let from = installer.installed_version(&runtime)?;
+    let rolled = installer.rollback_runtime(&runtime)?;
+    println!(
+        "Rolled back {runtime} {} -> {}",
+        from.as_deref().unwrap_or("unknown"),
+        rolled.version
+    );
+
+    // Only this project's instances are restarted; others are left to
+    // their own project, which may not even accept the new version.
+    let current_hash = project_hash()?;
+    let in_project = std::env::current_dir()?.join("clawden.yaml").exists();
+    let owned = process_manager
+        .list_processes()?
+        .into_iter()
+        .filter(|info| in_project && info.project_hash.as_deref() == Some(current_hash.as_str()))
+        .map(|info| info.runtime)
+        .collect::<HashSet<_>>();
+    let (ours, others): (Vec<_>, Vec<_>) = process_manager
+        .list_statuses()?
+        .into_iter()
+        .filter(|status| {
+            status.running
+                && status.mode == ExecutionMode::Direct
+                && instance_runtime(&status.runtime) == runtime
+        })
+        .map(|status| status.runtime)
+        .partition(|name| owned.contains(name));
+    if !others.is_empty() {
+        println!(
+            "Restart running instances from their project to pick it up: clawden restart {}",
+            others.join(" ")
+        );
+    }
+    if ours.is_empty() {
+        return Ok(());
+    }
+    exec_restart(ours, timeout, installer, process_manager, manager).await
+}
+
+/// A project's `clawden.yaml` version must still accept the selected
+/// version, or the next `up` would switch straight back.
+fn ensure_pin_allows(runtime: &str, version: &str) -> Result<()> {
+    if let Some(pin) = pinned_versions_map().get(runtime) {
+        if !version_satisfies(version, pin) {
+            anyhow::bail!("{runtime}@{version} does not satisfy clawden.yaml version '{pin}'");
+        }
+    }
+    Ok(())
+}
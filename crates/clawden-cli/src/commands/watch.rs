@@ -0,0 +1,257 @@
+//! File watching for `clawden up --watch`.
+//!
+//! The watched files are tiny, so changes are detected by comparing their
+//! contents on the attached-log tick rather than through filesystem events;
+//! this also catches editors that replace files via rename.
+
+use anyhow::Result;
+use clawden_config::ClawDenYaml;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::up::load_config_file_with;
+
+pub(crate) struct ConfigWatch {
+    files: WatchedFiles,
+    env: EnvSources,
+}
+
+impl ConfigWatch {
+    /// Watches `clawden.yaml` and `.env` in the current directory plus the
+    /// `--env-file` path. Must be created after the initial config load so
+    /// the environment it snapshots already includes the file values.
+    pub(crate) fn new(env_file: Option<&str>) -> Result<Self> {
+        let cwd = std::env::current_dir()?;
+        let dotenv = cwd.join(".env");
+        let env_file = env_file.map(PathBuf::from);
+        let mut paths = vec![cwd.join("clawden.yaml"), dotenv.clone()];
+        paths.extend(env_file.clone());
+        Ok(Self {
+            files: WatchedFiles::new(paths),
+            env: EnvSources::new(dotenv, env_file),
+        })
+    }
+
+    pub(crate) fn paths(&self) -> &[PathBuf] {
+        &self.files.paths
+    }
+
+    /// Returns true once a change to any watched file has settled.
+    pub(crate) fn poll(&mut self) -> bool {
+        self.files.poll()
+    }
+
+    /// Re-reads the env files and loads, resolves and validates
+    /// `clawden.yaml` against them. The process environment is left alone;
+    /// restarted runtimes get the returned [`FileEnv`] instead.
+    pub(crate) fn reload(&mut self) -> Result<(Option<ClawDenYaml>, FileEnv)> {
+        let env = self.env.read()?;
+        let config = load_config_file_with(&|key| env.var(key))?;
+        Ok((config, env))
+    }
+}
+
+/// Env-file values as of the last reload. They replace what this process
+/// loaded from the files at startup, which may be stale by now.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct FileEnv {
+    /// Variables the files set now.
+    pub(crate) set: Vec<(String, String)>,
+    /// Variables the files set at startup that nothing provides any more.
+    pub(crate) removed: Vec<String>,
+}
+
+impl FileEnv {
+    /// Looks `key` up as if the process had loaded the current files.
+    pub(crate) fn var(&self, key: &str) -> Option<String> {
+        if let Some((_, value)) = self.set.iter().rev().find(|(k, _)| k == key) {
+            return Some(value.clone());
+        }
+        if self.removed.iter().any(|removed| removed == key) {
+            return None;
+        }
+        std::env::var(key).ok()
+    }
+}
+
+/// Debounced content snapshot of a set of files. A missing file is a state
+/// of its own, so deleting or creating one counts as a change.
+struct WatchedFiles {
+    paths: Vec<PathBuf>,
+    snapshot: Vec<Option<Vec<u8>>>,
+    pending: bool,
+}
+
+impl WatchedFiles {
+    fn new(paths: Vec<PathBuf>) -> Self {
+        let snapshot = read_all(&paths);
+        Self {
+            paths,
+            snapshot,
+            pending: false,
+        }
+    }
+
+    /// Reports a change only after the contents were stable for one poll,
+    /// so a save that writes in several steps triggers a single reload.
+    fn poll(&mut self) -> bool {
+        let current = read_all(&self.paths);
+        if current != self.snapshot {
+            self.snapshot = current;
+            self.pending = true;
+            false
+        } else {
+            std::mem::take(&mut self.pending)
+        }
+    }
+}
+
+fn read_all(paths: &[PathBuf]) -> Vec<Option<Vec<u8>>> {
+    paths.iter().map(|path| fs::read(path).ok()).collect()
+}
+
+/// Tracks which process env vars came from `.env` / `--env-file` so a
+/// reload can drop removed keys and pick up edited values. Variables set in
+/// the shell keep precedence over `.env`, while `--env-file` overrides both,
+/// matching the initial load.
+struct EnvSources {
+    dotenv: PathBuf,
+    env_file: Option<PathBuf>,
+    base: HashMap<String, String>,
+    loaded: HashSet<String>,
+}
+
+impl EnvSources {
+    fn new(dotenv: PathBuf, env_file: Option<PathBuf>) -> Self {
+        let mut from_files = read_env_file(&dotenv).unwrap_or_default();
+        if let Some(path) = &env_file {
+            from_files.extend(read_env_file(path).unwrap_or_default());
+        }
+        // A shell variable with the same value as the file entry cannot be
+        // told apart from one the file set; treat it as file-provided.
+        let base = std::env::vars()
+            .filter(|(key, value)| from_files.get(key) != Some(value))
+            .collect();
+        Self {
+            dotenv,
+            env_file,
+            base,
+            loaded: from_files.into_keys().collect(),
+        }
+    }
+
+    fn read(&self) -> Result<FileEnv> {
+        let mut set = read_env_file(&self.dotenv)?
+            .into_iter()
+            .filter(|(key, _)| !self.base.contains_key(key))
+            .collect::<HashMap<_, _>>();
+        if let Some(path) = &self.env_file {
+            set.extend(read_env_file(path)?);
+        }
+        let mut removed = self
+            .loaded
+            .iter()
+            .filter(|key| !set.contains_key(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+        // Shell values the files had shadowed come back.
+        let mut set = set.into_iter().collect::<Vec<_>>();
+        removed.retain(|key| match self.base.get(key) {
+            Some(value) => {
+                set.push((key.clone(), value.clone()));
+                false
+            }
+            None => true,
+        });
+        set.sort();
+        removed.sort();
+        Ok(FileEnv { set, removed })
+    }
+}
+
+fn read_env_file(path: &Path) -> Result<HashMap<String, String>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+    let entries = dotenvy::from_path_iter(path)
+        .map_err(|e| anyhow::anyhow!("failed to load {}: {e}", path.display()))?;
+    let mut values = HashMap::new();
+    for entry in entries {
+        let (key, value) =
+            entry.map_err(|e| anyhow::anyhow!("failed to load {}: {e}", path.display()))?;
+        values.insert(key, value);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EnvSources, WatchedFiles};
+    use std::collections::{HashMap, HashSet};
+    use std::fs;
+
+    #[test]
+    fn watched_files_report_settled_changes_once() {
+        let dir = std::env::temp_dir().join(format!("clawden-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let file = dir.join("clawden.yaml");
+        fs::write(&file, "runtimes: []\n").expect("write file");
+
+        let mut watched = WatchedFiles::new(vec![file.clone(), dir.join(".env")]);
+        assert!(!watched.poll());
+
+        fs::write(&file, "runtimes: [zeroclaw]\n").expect("rewrite file");
+        assert!(!watched.poll(), "change is reported once it settles");
+        assert!(watched.poll());
+        assert!(!watched.poll());
+
+        fs::write(dir.join(".env"), "KEY=value\n").expect("create env");
+        assert!(!watched.poll());
+        assert!(watched.poll(), "a newly created file counts as a change");
+
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn env_sources_report_edits_without_touching_the_process_env() {
+        let dir = std::env::temp_dir().join(format!("clawden-watch-env-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("create temp dir");
+        let dotenv = dir.join(".env");
+        fs::write(
+            &dotenv,
+            "CLAWDEN_WATCH_EDITED=new\nCLAWDEN_WATCH_SHELL=file\n",
+        )
+        .expect("write env");
+
+        let sources = EnvSources {
+            dotenv,
+            env_file: None,
+            base: HashMap::from([
+                ("CLAWDEN_WATCH_RESTORED".to_string(), "shell".to_string()),
+                ("CLAWDEN_WATCH_SHELL".to_string(), "shell".to_string()),
+            ]),
+            loaded: HashSet::from([
+                "CLAWDEN_WATCH_EDITED".to_string(),
+                "CLAWDEN_WATCH_GONE".to_string(),
+                "CLAWDEN_WATCH_RESTORED".to_string(),
+            ]),
+        };
+        let env = sources.read().expect("read env files");
+
+        assert_eq!(
+            env.set,
+            vec![
+                ("CLAWDEN_WATCH_EDITED".to_string(), "new".to_string()),
+                ("CLAWDEN_WATCH_RESTORED".to_string(), "shell".to_string()),
+            ],
+            "shell values keep precedence over .env"
+        );
+        assert_eq!(env.removed, vec!["CLAWDEN_WATCH_GONE".to_string()]);
+        assert_eq!(env.var("CLAWDEN_WATCH_EDITED").as_deref(), Some("new"));
+        assert_eq!(env.var("CLAWDEN_WATCH_GONE"), None);
+        assert!(std::env::var("CLAWDEN_WATCH_EDITED").is_err());
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -856,7 +856,6 @@ fn available_runtimes(cfg: &clawden_config::ClawDenYaml) -> String {
 /// executable (slug) is available on PATH.
 fn detect_installed_runtimes() -> Vec<String> {
     runtime_descriptors()
-        .iter()
         .filter(|d| d.workspace_path.is_some())
         .filter(|d| crate::util::command_exists(d.slug))
         .map(|d| d.slug.to_string())
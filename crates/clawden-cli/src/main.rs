@@ -4,18 +4,24 @@ mod util;
 
 use anyhow::Result;
 use clap::Parser;
-use clawden_core::{ExecutionMode, LifecycleManager, ProcessManager, RuntimeInstaller};
+use clawden_core::{ExecutionMode, LifecycleManager, LockMode, ProcessManager, RuntimeInstaller};
 use cli::{Cli, Commands, ConfigCommand};
+use std::sync::Arc;
 use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
     commands::load_default_env();
+    commands::register_custom_runtimes();
     init_logging(cli.verbose, cli.log_level.as_deref())?;
     let mut installer = RuntimeInstaller::new()?;
+    installer.set_lockfile(commands::project_lockfile_path());
     let process_manager = ProcessManager::new(ExecutionMode::Auto)?;
-    let registry = clawden_adapters::builtin_registry();
+    let registry = clawden_adapters::builtin_registry_with_generator(
+        ExecutionMode::Auto,
+        Arc::new(commands::ProjectConfigGenerator),
+    );
     let mut manager = LifecycleManager::new(registry.adapters_map());
 
     match cli.command {
@@ -40,8 +46,46 @@ async fn main() -> Result<()> {
             list,
             upgrade,
             outdated,
-        } => commands::exec_install(&mut installer, runtime, all, list, upgrade, outdated)?,
+            require_checksums,
+            mirror,
+            locked,
+            frozen,
+        } => {
+            if require_checksums {
+                installer.set_require_checksums(true);
+            }
+            if mirror.is_some() {
+                installer.set_mirror(mirror);
+            }
+            commands::register_project();
+            let lock_mode = if frozen {
+                LockMode::Frozen
+            } else if locked {
+                LockMode::Locked
+            } else {
+                LockMode::Update
+            };
+            commands::exec_install(
+                &mut installer,
+                runtime,
+                all,
+                list,
+                upgrade,
+                outdated,
+                lock_mode,
+            )?
+        }
         Commands::Uninstall { runtime } => commands::exec_uninstall(&installer, runtime)?,
+        Commands::Use { runtime } => commands::exec_use(&installer, runtime)?,
+        Commands::Rollback { runtime, timeout } => {
+            commands::exec_rollback(runtime, timeout, &installer, &process_manager, &mut manager)
+                .await?
+        }
+        Commands::Lock { update } => {
+            commands::register_project();
+            commands::exec_lock(&installer, update)?
+        }
+        Commands::Bundle { command } => commands::exec_bundle(&installer, command)?,
         Commands::Up {
             runtimes,
             env_vars,
@@ -50,7 +94,9 @@ async fn main() -> Result<()> {
             detach,
             no_log_prefix,
             timeout,
+            watch,
         } => {
+            commands::register_project();
             commands::exec_up(
                 commands::UpOptions {
                     runtimes,
@@ -60,6 +106,7 @@ async fn main() -> Result<()> {
                     detach,
                     no_log_prefix,
                     timeout,
+                    watch,
                     force_docker: false,
                 },
                 &installer,
@@ -147,12 +194,30 @@ async fn main() -> Result<()> {
             follow,
             tail,
             timestamps,
+            previous,
+            session,
+            filter,
             runtimes,
-        } => commands::exec_logs(&process_manager, runtimes, tail, follow, timestamps).await?,
+        } => {
+            commands::exec_logs(
+                &process_manager,
+                commands::LogsOptions {
+                    runtimes,
+                    tail,
+                    follow,
+                    timestamps,
+                    session: if previous { 1 } else { session.unwrap_or(0) },
+                    filter,
+                },
+            )
+            .await?
+        }
         Commands::Dashboard { port } => commands::exec_dashboard(port)?,
-        Commands::Doctor => commands::exec_doctor(&installer)?,
+        Commands::Doctor => commands::exec_doctor(&installer, &process_manager)?,
         Commands::Channels { command } => commands::exec_channels(command, &mut manager).await?,
         Commands::Providers { command } => commands::exec_providers(command).await?,
+        Commands::Approvals { command } => commands::exec_approvals(command)?,
+        Commands::Skills { command } => commands::exec_skills(command, &registry).await?,
         Commands::Tools { command } => commands::exec_tools(command)?,
         Commands::Config { command } => match command {
             ConfigCommand::Show {
@@ -170,6 +235,7 @@ async fn main() -> Result<()> {
             ConfigCommand::Env { reveal } => commands::exec_config_env(reveal)?,
         },
         Commands::Workspace { command } => commands::exec_workspace(command)?,
+        Commands::Supervise { spec } => commands::exec_supervise(&spec).await?,
     }
 
     Ok(())
@@ -178,9 +244,12 @@ async fn main() -> Result<()> {
 fn init_logging(verbose: bool, log_level: Option<&str>) -> Result<()> {
     let level = resolved_log_level(verbose, log_level);
     let filter = EnvFilter::try_new(level).or_else(|_| EnvFilter::try_new("info"))?;
+    // Diagnostics go to stderr so command output (e.g. `logs --json`) stays
+    // machine-readable.
     let _ = tracing_subscriber::fmt()
         .with_env_filter(filter)
         .with_target(false)
+        .with_writer(std::io::stderr)
         .try_init();
     Ok(())
 }
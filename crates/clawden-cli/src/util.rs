@@ -1,12 +1,16 @@
 use anyhow::Result;
-use clawden_config::SecretVault;
-use clawden_core::{version_satisfies, ClawRuntime, InstalledRuntime, RuntimeInstaller};
+use clawden_config::{ClawDenYaml, SecretVault};
+use clawden_core::{
+    version_satisfies, AuditLog, ClawRuntime, HookEngine, HttpChannelNotifier, InstalledRuntime,
+    RuntimeInstaller, StopOutcome,
+};
 use std::collections::hash_map::DefaultHasher;
 use std::fs::OpenOptions;
 use std::hash::{Hash, Hasher};
 use std::io::{self, IsTerminal, Write};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn parse_runtime(value: &str) -> Result<ClawRuntime> {
@@ -37,8 +41,29 @@ pub fn ensure_installed_runtime(
     pinned_version: Option<&str>,
 ) -> Result<InstalledRuntime> {
     if let Some(exe) = installer.runtime_executable(runtime) {
+        let installed_version = installer.installed_version(runtime)?;
+        if let Some(locked) = installer.locked_version(runtime)? {
+            let lock_applies = pinned_version.is_none_or(|pin| version_satisfies(&locked, pin));
+            if lock_applies && installed_version.as_deref() != Some(locked.as_str()) {
+                let side_by_side = installer
+                    .list_installed_versions()?
+                    .into_iter()
+                    .any(|row| row.runtime == runtime && row.version == locked);
+                if side_by_side {
+                    println!("Switching '{runtime}' to {locked} as pinned by clawden.lock");
+                    return installer.use_version(runtime, &locked);
+                }
+                println!(
+                    "Runtime '{runtime}' installed at {} but clawden.lock pins {locked}. Installing locked version...",
+                    installed_version.as_deref().unwrap_or("unknown")
+                );
+                let installed = installer.install_runtime(runtime, pinned_version)?;
+                println!("Installed {}@{}", installed.runtime, installed.version);
+                return Ok(installed);
+            }
+        }
         if let Some(pin) = pinned_version {
-            if let Some(installed_version) = installer.installed_version(runtime)? {
+            if let Some(installed_version) = installed_version {
                 if !version_satisfies(&installed_version, pin) {
                     println!(
                         "Runtime '{runtime}' installed at {installed_version} but clawden.yaml requires {pin}. Installing compatible version..."
@@ -63,6 +88,33 @@ pub fn ensure_installed_runtime(
     Ok(installed)
 }
 
+/// The executable `ensure_installed_runtime` would settle on, found without
+/// installing or switching versions. Fails when that would be needed.
+pub fn installed_runtime_executable(
+    installer: &RuntimeInstaller,
+    runtime: &str,
+    pinned_version: Option<&str>,
+) -> Result<PathBuf> {
+    let Some(exe) = installer.runtime_executable(runtime) else {
+        anyhow::bail!("runtime '{runtime}' is not installed; run 'clawden install {runtime}'");
+    };
+    let installed_version = installer.installed_version(runtime)?;
+    if let Some(locked) = installer.locked_version(runtime)? {
+        let lock_applies = pinned_version.is_none_or(|pin| version_satisfies(&locked, pin));
+        if lock_applies && installed_version.as_deref() != Some(locked.as_str()) {
+            anyhow::bail!("clawden.lock pins {runtime} {locked}; run 'clawden install {runtime}'");
+        }
+    }
+    if let (Some(pin), Some(installed)) = (pinned_version, installed_version.as_deref()) {
+        if !version_satisfies(installed, pin) {
+            anyhow::bail!(
+                "clawden.yaml requires {runtime} {pin} but {installed} is installed; run 'clawden install {runtime}'"
+            );
+        }
+    }
+    Ok(exe)
+}
+
 pub fn project_hash() -> Result<String> {
     let cwd = std::env::current_dir()?;
     let config_path = cwd.join("clawden.yaml");
@@ -96,6 +148,69 @@ pub fn append_audit_file(action: &str, runtime: &str, outcome: &str) -> Result<(
     Ok(())
 }
 
+/// Builds the engine for the `hooks:` section of clawden.yaml, delivering to
+/// the channel instances declared under `channels:`. Returns `None` when no
+/// hook is configured.
+pub fn hook_engine(config: &ClawDenYaml) -> Option<Arc<HookEngine>> {
+    if config.hooks.is_empty() {
+        return None;
+    }
+    let notifier = match HttpChannelNotifier::new() {
+        Ok(notifier) => notifier,
+        Err(err) => {
+            eprintln!("Warning: hooks disabled: {err:#}");
+            return None;
+        }
+    };
+    Some(Arc::new(HookEngine::new(
+        config.hooks.clone(),
+        config.channel_instance_configs(),
+        Arc::new(notifier),
+        Arc::new(AuditLog::with_sink(Arc::new(|event| {
+            // The CLI exits long before anyone could read an in-memory log.
+            if let Err(err) = append_audit_file(&event.action, &event.target, &event.actor) {
+                eprintln!("Warning: failed to write audit log: {err:#}");
+            }
+        }))),
+    )))
+}
+
+/// Audits what a stop had to do beyond a clean exit and reports leftover
+/// descendants and a still-bound port to the user.
+pub fn record_stop_outcome(runtime: &str, outcome: &StopOutcome) -> Result<()> {
+    if outcome.forced {
+        append_audit_file("runtime.force_kill", runtime, "ok")?;
+    }
+    if !outcome.leftovers.is_empty() {
+        let pids = outcome
+            .leftovers
+            .iter()
+            .map(|p| p.pid.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        append_audit_file(
+            "runtime.force_kill_descendants",
+            runtime,
+            &format!("pids={pids}"),
+        )?;
+        let described = outcome
+            .leftovers
+            .iter()
+            .map(|p| format!("{} ({})", p.command, p.pid))
+            .collect::<Vec<_>>()
+            .join(", ");
+        println!(
+            "Force-killed {} leftover process(es) of {runtime}: {described}",
+            outcome.leftovers.len()
+        );
+    }
+    if let Some(port) = outcome.port_in_use {
+        append_audit_file("runtime.port_in_use", runtime, &format!("port={port}"))?;
+        eprintln!("Warning: port {port} is still in use after stopping {runtime}");
+    }
+    Ok(())
+}
+
 pub fn is_first_run_context(installer: &RuntimeInstaller) -> Result<bool> {
     let home = std::env::var("HOME")?;
     let clawden_home_exists = PathBuf::from(home).join(".clawden").exists();
@@ -0,0 +1,89 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+fn run(home: &Path, args: &[&str]) -> Output {
+    Command::new(binary_path())
+        .current_dir(home)
+        .env("HOME", home)
+        .args(args)
+        .output()
+        .expect("clawden should run")
+}
+
+fn write_pending(home: &Path, id: &str, expires_at_unix_ms: Option<u64>) {
+    let dir = home.join(".clawden/approvals");
+    fs::create_dir_all(&dir).expect("approvals dir should be created");
+    let record = serde_json::json!({
+        "id": id,
+        "worker": "coder",
+        "task_id": "task-1",
+        "description": "drop table users",
+        "channels": ["eng-leads"],
+        "status": "pending",
+        "default_decision": "deny",
+        "created_at_unix_ms": 1,
+        "expires_at_unix_ms": expires_at_unix_ms,
+    });
+    fs::write(dir.join(format!("{id}.json")), record.to_string())
+        .expect("approval should be written");
+}
+
+fn stored_status(home: &Path, id: &str) -> String {
+    let raw = fs::read_to_string(home.join(format!(".clawden/approvals/{id}.json")))
+        .expect("approval should exist");
+    let record: serde_json::Value = serde_json::from_str(&raw).expect("approval json");
+    record["status"].as_str().unwrap_or_default().to_string()
+}
+
+#[test]
+fn approvals_list_and_approve_pending_request() {
+    let home = temp_dir("approvals-approve");
+    write_pending(&home, "apr-1-0", None);
+
+    let output = run(&home, &["approvals", "list"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("approval=apr-1-0\tstatus=pending\tworker=coder"));
+
+    let output = run(&home, &["approvals", "approve", "apr-1-0"]);
+    assert!(output.status.success());
+    assert_eq!(stored_status(&home, "apr-1-0"), "approved");
+
+    let output = run(&home, &["approvals", "deny", "apr-1-0"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("already approved"));
+
+    let stdout = String::from_utf8_lossy(&run(&home, &["approvals"]).stdout).to_string();
+    assert!(stdout.contains("No pending approval requests"));
+}
+
+#[test]
+fn approvals_expire_overdue_requests_with_default_decision() {
+    let home = temp_dir("approvals-expire");
+    write_pending(&home, "apr-2-0", Some(2));
+
+    let output = run(&home, &["approvals", "list", "--all"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("approval=apr-2-0\tstatus=expired"));
+    assert_eq!(stored_status(&home, "apr-2-0"), "expired");
+
+    let output = run(&home, &["approvals", "deny", "apr-2-0"]);
+    assert!(!output.status.success());
+}
@@ -0,0 +1,324 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+/// Installs zeroclaw 1.0.0 with an artifact record and a cache entry, as a
+/// release install would leave them.
+fn setup_installed_runtime(home: &Path) {
+    let runtime_dir = home.join(".clawden/runtimes/zeroclaw/1.0.0");
+    fs::create_dir_all(&runtime_dir).expect("runtime directory should be created");
+    let executable = runtime_dir.join("zeroclaw");
+    fs::write(&executable, "#!/usr/bin/env sh\nexit 0\n").expect("runtime should be written");
+    let mut perms = fs::metadata(&executable)
+        .expect("metadata should be available")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&executable, perms).expect("runtime should be executable");
+    fs::write(
+        runtime_dir.join(".artifact.json"),
+        r#"{"url":"https://example.invalid/zeroclaw.tar.gz","digest":"sha256:abc","verified":true}"#,
+    )
+    .expect("artifact record should be written");
+    std::os::unix::fs::symlink("1.0.0", home.join(".clawden/runtimes/zeroclaw/current"))
+        .expect("current symlink should be created");
+
+    let cache = home.join(".clawden/cache/downloads/zeroclaw/1.0.0");
+    fs::create_dir_all(&cache).expect("cache should be created");
+    fs::write(cache.join("zeroclaw.tar.gz"), b"archive").expect("cache entry should be written");
+}
+
+fn clawden(home: &Path, project: &Path, args: &[&str]) -> Output {
+    Command::new(binary_path())
+        .current_dir(project)
+        .env("HOME", home)
+        .args(args)
+        .output()
+        .expect("clawden should execute")
+}
+
+fn project(dir: &Path, name: &str, yaml: &str) -> PathBuf {
+    let project = dir.join(name);
+    fs::create_dir_all(&project).expect("project should be created");
+    fs::write(project.join("clawden.yaml"), yaml).expect("yaml should be written");
+    project
+}
+
+#[test]
+fn bundle_round_trips_runtimes_into_a_fresh_home() {
+    let dir = temp_dir("bundle");
+    let source_home = dir.join("source-home");
+    setup_installed_runtime(&source_home);
+    let source = project(&dir, "source", "runtime: zeroclaw\nversion: 1.0.x\n");
+    let bundle = dir.join("offline.tar.gz");
+
+    let output = clawden(
+        &source_home,
+        &source,
+        &[
+            "bundle",
+            "create",
+            "-o",
+            bundle.to_str().expect("utf-8 bundle path"),
+            "--no-images",
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Added zeroclaw@1.0.0"), "stdout: {stdout}");
+    assert!(bundle.exists());
+
+    let target_home = dir.join("target-home");
+    fs::create_dir_all(&target_home).expect("home should be created");
+    let target = project(&dir, "target", "runtime: zeroclaw\nversion: 1.0.x\n");
+    let output = clawden(
+        &target_home,
+        &target,
+        &[
+            "bundle",
+            "install",
+            bundle.to_str().expect("utf-8 bundle path"),
+        ],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Installed zeroclaw@1.0.0"),
+        "stdout: {stdout}"
+    );
+    let runtimes = target_home.join(".clawden/runtimes/zeroclaw");
+    assert!(runtimes.join("1.0.0/zeroclaw").exists());
+    assert_eq!(
+        fs::read_link(runtimes.join("current")).expect("current symlink"),
+        PathBuf::from("1.0.0")
+    );
+    assert!(target_home
+        .join(".clawden/cache/downloads/zeroclaw/1.0.0/zeroclaw.tar.gz")
+        .exists());
+    let lock = fs::read_to_string(target.join("clawden.lock")).expect("lock should be recorded");
+    assert!(lock.contains("version = \"1.0.0\""), "{lock}");
+
+    // Version constraints of the installing project are enforced.
+    let other_home = dir.join("other-home");
+    fs::create_dir_all(&other_home).expect("home should be created");
+    let pinned = project(&dir, "pinned", "runtime: zeroclaw\nversion: 2.0.0\n");
+    let output = clawden(
+        &other_home,
+        &pinned,
+        &[
+            "bundle",
+            "install",
+            bundle.to_str().expect("utf-8 bundle path"),
+        ],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("does not satisfy"), "stderr: {stderr}");
+    assert!(!other_home.join(".clawden/runtimes/zeroclaw").exists());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn bundle_install_rejects_tampered_entries() {
+    let dir = temp_dir("bundle-tampered");
+    let source_home = dir.join("source-home");
+    setup_installed_runtime(&source_home);
+    let source = project(&dir, "source", "runtime: zeroclaw\n");
+    let bundle = dir.join("offline.tar.gz");
+    let output = clawden(
+        &source_home,
+        &source,
+        &[
+            "bundle",
+            "create",
+            "-o",
+            bundle.to_str().expect("utf-8 bundle path"),
+            "--no-images",
+        ],
+    );
+    assert!(output.status.success());
+
+    // Repack the bundle with a modified runtime executable.
+    let unpacked = dir.join("unpacked");
+    fs::create_dir_all(&unpacked).expect("unpack dir should be created");
+    let status = Command::new("tar")
+        .arg("-xzf")
+        .arg(&bundle)
+        .arg("-C")
+        .arg(&unpacked)
+        .status()
+        .expect("tar should run");
+    assert!(status.success());
+    fs::write(
+        unpacked.join("runtimes/zeroclaw/1.0.0/zeroclaw"),
+        "#!/usr/bin/env sh\necho pwned\n",
+    )
+    .expect("runtime should be overwritten");
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(&bundle)
+        .arg("-C")
+        .arg(&unpacked)
+        .args(["bundle.json", "images", "runtimes", "cache"])
+        .status()
+        .expect("tar should run");
+    assert!(status.success());
+
+    let target_home = dir.join("target-home");
+    fs::create_dir_all(&target_home).expect("home should be created");
+    let target = project(&dir, "target", "runtime: zeroclaw\n");
+    let output = clawden(
+        &target_home,
+        &target,
+        &[
+            "bundle",
+            "install",
+            bundle.to_str().expect("utf-8 bundle path"),
+        ],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("digest mismatch"), "stderr: {stderr}");
+    assert!(!target_home.join(".clawden/runtimes/zeroclaw").exists());
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn bundle_install_checks_the_lock_digest_and_keeps_installed_versions() {
+    let dir = temp_dir("bundle-lock");
+    let source_home = dir.join("source-home");
+    setup_installed_runtime(&source_home);
+    let source = project(&dir, "source", "runtime: zeroclaw\n");
+    let bundle = dir.join("offline.tar.gz");
+    let bundle_arg = bundle.to_str().expect("utf-8 bundle path");
+    let output = clawden(
+        &source_home,
+        &source,
+        &["bundle", "create", "-o", bundle_arg, "--no-images"],
+    );
+    assert!(output.status.success());
+
+    // The lock pins the same version but a different download.
+    let target_home = dir.join("target-home");
+    fs::create_dir_all(&target_home).expect("home should be created");
+    let locked = project(&dir, "locked", "runtime: zeroclaw\n");
+    fs::write(
+        locked.join("clawden.lock"),
+        "version = 1\n\n[runtimes.zeroclaw]\nversion = \"1.0.0\"\nurl = \"https://example.invalid/zeroclaw.tar.gz\"\ndigest = \"sha256:def\"\n",
+    )
+    .expect("lock should be written");
+    let output = clawden(&target_home, &locked, &["bundle", "install", bundle_arg]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("clawden.lock pins sha256:def"),
+        "stderr: {stderr}"
+    );
+    assert!(!target_home.join(".clawden/runtimes/zeroclaw").exists());
+
+    // A different tree already installed under the same version stays put.
+    let installed = target_home.join(".clawden/runtimes/zeroclaw/1.0.0");
+    fs::create_dir_all(&installed).expect("runtime directory should be created");
+    fs::write(installed.join("zeroclaw"), "local build").expect("runtime should be written");
+    let target = project(&dir, "target", "runtime: zeroclaw\n");
+    let output = clawden(&target_home, &target, &["bundle", "install", bundle_arg]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("already installed with different contents"),
+        "stderr: {stderr}"
+    );
+    assert_eq!(
+        fs::read_to_string(installed.join("zeroclaw")).expect("runtime should remain"),
+        "local build"
+    );
+
+    // The identical tree is accepted as already installed.
+    let fresh_home = dir.join("fresh-home");
+    setup_installed_runtime(&fresh_home);
+    let output = clawden(&fresh_home, &target, &["bundle", "install", bundle_arg]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn bundle_create_takes_the_version_pinned_by_the_lock() {
+    let dir = temp_dir("bundle-locked-version");
+    let home = dir.join("home");
+    setup_installed_runtime(&home);
+    let older = home.join(".clawden/runtimes/zeroclaw/0.9.0");
+    fs::create_dir_all(&older).expect("runtime directory should be created");
+    fs::write(older.join("zeroclaw"), "#!/usr/bin/env sh\nexit 0\n")
+        .expect("runtime should be written");
+    let source = project(&dir, "source", "runtime: zeroclaw\n");
+    fs::write(
+        source.join("clawden.lock"),
+        "version = 1\n\n[runtimes.zeroclaw]\nversion = \"0.9.0\"\nurl = \"https://example.invalid/zeroclaw.tar.gz\"\ndigest = \"sha256:abc\"\n",
+    )
+    .expect("lock should be written");
+    let bundle = dir.join("offline.tar.gz");
+    let bundle_arg = bundle.to_str().expect("utf-8 bundle path");
+
+    let output = clawden(
+        &home,
+        &source,
+        &["bundle", "create", "-o", bundle_arg, "--no-images"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("Added zeroclaw@0.9.0"), "stdout: {stdout}");
+
+    // A locked version that is not installed is not swapped for `current`.
+    fs::write(
+        source.join("clawden.lock"),
+        "version = 1\n\n[runtimes.zeroclaw]\nversion = \"2.0.0\"\nurl = \"https://example.invalid/zeroclaw.tar.gz\"\ndigest = \"sha256:abc\"\n",
+    )
+    .expect("lock should be written");
+    let output = clawden(
+        &home,
+        &source,
+        &["bundle", "create", "-o", bundle_arg, "--no-images"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("clawden.lock pins zeroclaw 2.0.0, which is not installed"),
+        "stderr: {stderr}"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,179 @@
+//! Helpers shared by the integration tests that install runtimes from a
+//! local release mirror.
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+pub fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+pub fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+pub fn write_executable(path: &Path, content: &str) {
+    fs::write(path, content).expect("script should be written");
+    let mut perms = fs::metadata(path)
+        .expect("metadata should be available")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(path, perms).expect("script should be executable");
+}
+
+/// Serves files under `root` as a `CLAWDEN_MIRROR` stand-in: `GET
+/// /<host>/<path>` returns `root/<host>/<path>`.
+pub fn serve_dir(root: PathBuf) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+    let base = format!("http://{}", listener.local_addr().expect("local addr"));
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            let mut reader = BufReader::new(stream.try_clone().expect("stream should clone"));
+            let mut request = String::new();
+            if reader.read_line(&mut request).is_err() {
+                continue;
+            }
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).map_or(true, |read| read == 0)
+                    || line.trim().is_empty()
+                {
+                    break;
+                }
+            }
+            let path = request.split_whitespace().nth(1).unwrap_or("/");
+            let (status, body) = match fs::read(root.join(path.trim_start_matches('/'))) {
+                Ok(body) => ("200 OK", body),
+                Err(_) => ("404 Not Found", Vec::new()),
+            };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(&body);
+        }
+    });
+    base
+}
+
+/// Release asset name the installer picks for `runtime` on this machine.
+pub fn release_asset(runtime: &str) -> String {
+    format!(
+        "{runtime}-{}-unknown-linux-musl.tar.gz",
+        std::env::consts::ARCH
+    )
+}
+
+/// Publishes a v1.0.0 release of GitHub repo `repo` on the mirror under
+/// `mirror`, with a single archive holding `script` as the `runtime`
+/// executable and, if `checksums` is set, a `checksums.txt` asset. Returns
+/// the archive's SHA-256.
+pub fn publish_release(
+    mirror: &Path,
+    repo: &str,
+    runtime: &str,
+    script: &str,
+    checksums: bool,
+) -> String {
+    let name = release_asset(runtime);
+    let staging = mirror.join(format!("staging-{runtime}"));
+    let assets = mirror.join("example.invalid");
+    fs::create_dir_all(&staging).expect("staging dir should be created");
+    fs::create_dir_all(&assets).expect("assets dir should be created");
+    write_executable(&staging.join(runtime), script);
+    let archive = assets.join(&name);
+    let status = Command::new("tar")
+        .arg("-czf")
+        .arg(&archive)
+        .arg("-C")
+        .arg(&staging)
+        .arg(runtime)
+        .status()
+        .expect("tar should run");
+    assert!(status.success());
+    let digest = sha256(&archive);
+
+    let mut listed = vec![format!(
+        r#"{{"name":"{name}","browser_download_url":"https://example.invalid/{name}"}}"#
+    )];
+    if checksums {
+        fs::write(assets.join("checksums.txt"), format!("{digest}  {name}\n"))
+            .expect("checksums should be written");
+        listed.push(
+            r#"{"name":"checksums.txt","browser_download_url":"https://example.invalid/checksums.txt"}"#
+                .to_string(),
+        );
+    }
+    let releases = mirror.join(format!("api.github.com/repos/{repo}/releases"));
+    fs::create_dir_all(releases.join("tags")).expect("releases dir should be created");
+    let release = format!(r#"{{"tag_name":"v1.0.0","assets":[{}]}}"#, listed.join(","));
+    fs::write(releases.join("latest"), &release).expect("release should be written");
+    fs::write(releases.join("tags/v1.0.0"), &release).expect("release should be written");
+    digest
+}
+
+fn sha256(path: &Path) -> String {
+    let output = Command::new("sha256sum")
+        .arg(path)
+        .output()
+        .expect("sha256sum should run");
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .expect("digest")
+        .to_string()
+}
+
+/// Runs clawden in `project` with `HOME` and `CLAWDEN_MIRROR` pointed at
+/// the test fixtures.
+pub fn clawden(home: &Path, project: &Path, mirror: &str, args: &[&str]) -> Output {
+    Command::new(binary_path())
+        .current_dir(project)
+        .env("HOME", home)
+        .env("CLAWDEN_MIRROR", mirror)
+        .args(args)
+        .output()
+        .expect("clawden should execute")
+}
+
+pub fn assert_success(output: &Output) -> String {
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(
+        output.status.success(),
+        "stdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    stdout
+}
+
+/// Waits up to ten seconds for `path` to have `count` lines.
+pub fn wait_for_lines(path: &Path, count: usize) -> Vec<String> {
+    let deadline = Instant::now() + Duration::from_secs(10);
+    loop {
+        let lines = fs::read_to_string(path)
+            .unwrap_or_default()
+            .lines()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        if lines.len() >= count || Instant::now() > deadline {
+            return lines;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+}
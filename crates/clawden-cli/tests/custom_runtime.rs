@@ -0,0 +1,119 @@
+mod common;
+
+use common::{assert_success, clawden, publish_release, serve_dir, temp_dir, wait_for_lines};
+use std::fs;
+use std::path::Path;
+
+/// Publishes a v1.0.0 release of `example/<runtime>` on the mirror. The
+/// runtime records its arguments in `started` and keeps running.
+fn publish_runtime(mirror: &Path, runtime: &str, started: &Path) {
+    let script = format!(
+        "#!/usr/bin/env sh\n\
+         [ \"$1\" = serve ] || exit 0\n\
+         echo {runtime} \"$@\" >> {}\n\
+         exec sleep 30\n",
+        started.display()
+    );
+    publish_release(
+        mirror,
+        &format!("example/{runtime}"),
+        runtime,
+        &script,
+        false,
+    );
+}
+
+#[test]
+fn custom_runtimes_install_run_and_show_in_ps() {
+    let dir = temp_dir("custom-runtime");
+    let home = dir.join("home");
+    let started = dir.join("started");
+    let mirror_root = dir.join("mirror");
+    publish_runtime(&mirror_root, "testclaw", &started);
+    publish_runtime(&mirror_root, "yamlclaw", &started);
+    let mirror = serve_dir(mirror_root);
+
+    let runtimes_d = home.join(".clawden/runtimes.d");
+    fs::create_dir_all(&runtimes_d).expect("runtimes.d should be created");
+    fs::write(
+        runtimes_d.join("testclaw.toml"),
+        "display_name = \"TestClaw\"\n\
+         aliases = [\"tc\"]\n\
+         default_start_args = [\"serve\", \"--port\", \"9911\"]\n\
+         \n\
+         [install_source]\n\
+         type = \"github-release\"\n\
+         owner = \"example\"\n\
+         repo = \"testclaw\"\n",
+    )
+    .expect("runtime definition should be written");
+
+    // A runtime from runtimes.d installs and runs like a built-in.
+    let global = dir.join("global");
+    fs::create_dir_all(&global).expect("project should be created");
+    fs::write(
+        global.join("clawden.yaml"),
+        "mode: direct\nruntime: testclaw\n",
+    )
+    .expect("yaml should be written");
+    let stdout = assert_success(&clawden(&home, &global, &mirror, &["install", "tc"]));
+    assert!(
+        stdout.contains("Installed testclaw@1.0.0"),
+        "stdout: {stdout}"
+    );
+    assert!(home
+        .join(".clawden/runtimes/testclaw/1.0.0/testclaw")
+        .exists());
+    assert_success(&clawden(
+        &home,
+        &global,
+        &mirror,
+        &["up", "-d", "--allow-missing-credentials"],
+    ));
+    assert_eq!(wait_for_lines(&started, 1), ["testclaw serve --port 9911"]);
+    let stdout = assert_success(&clawden(&home, &global, &mirror, &["ps"]));
+    let _ = clawden(&home, &global, &mirror, &["down", "--timeout", "1"]);
+    assert!(stdout.contains("testclaw"), "stdout: {stdout}");
+
+    // clawden.yaml can declare its own runtime; `up` installs it on demand.
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    fs::write(
+        project.join("clawden.yaml"),
+        "mode: direct\n\
+         runtime: yamlclaw\n\
+         custom_runtimes:\n\
+         \x20 - slug: yamlclaw\n\
+         \x20   default_start_args: [serve]\n\
+         \x20   install_source:\n\
+         \x20     type: github-release\n\
+         \x20     owner: example\n\
+         \x20     repo: yamlclaw\n",
+    )
+    .expect("yaml should be written");
+    assert_success(&clawden(
+        &home,
+        &project,
+        &mirror,
+        &["up", "-d", "--allow-missing-credentials"],
+    ));
+    assert_eq!(
+        wait_for_lines(&started, 2),
+        ["testclaw serve --port 9911", "yamlclaw serve"]
+    );
+    let stdout = assert_success(&clawden(&home, &project, &mirror, &["ps"]));
+    let _ = clawden(&home, &project, &mirror, &["down", "--timeout", "1"]);
+    assert!(stdout.contains("yamlclaw"), "stdout: {stdout}");
+
+    // Built-in runtimes cannot be redefined.
+    fs::write(runtimes_d.join("zero.toml"), "slug = \"zeroclaw\"\n")
+        .expect("runtime definition should be written");
+    let output = clawden(&home, &global, &mirror, &["install", "--list"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("conflicts with built-in runtime 'zeroclaw'"),
+        "stderr: {stderr}"
+    );
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+fn setup_direct_runtime(home: &Path, script: &str) {
+    let runtime_dir = home.join(".clawden/runtimes/zeroclaw/latest");
+    fs::create_dir_all(&runtime_dir).expect("runtime directory should be created");
+    let executable = runtime_dir.join("zeroclaw");
+    fs::write(&executable, script).expect("runtime script should be written");
+    let mut perms = fs::metadata(&executable)
+        .expect("metadata should be available")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&executable, perms).expect("runtime script should be executable");
+    std::os::unix::fs::symlink("latest", home.join(".clawden/runtimes/zeroclaw/current"))
+        .expect("current symlink should be created");
+}
+
+fn clawden(home: &Path, project: &Path, args: &[&str]) -> Output {
+    Command::new(binary_path())
+        .current_dir(project)
+        .env("HOME", home)
+        .args(args)
+        .output()
+        .expect("clawden should execute")
+}
+
+#[test]
+fn up_starts_dependencies_first_and_down_stops_them_last() {
+    let dir = temp_dir("depends-on");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    let events = dir.join("events");
+    setup_direct_runtime(
+        &home,
+        &format!(
+            "#!/usr/bin/env sh\n\
+             [ \"$1\" = onboard ] && exit 0\n\
+             echo \"start $*\" >> {}\n\
+             sleep 1\n\
+             echo ready\n\
+             exec sleep 30\n",
+            events.display()
+        ),
+    );
+    fs::write(
+        project.join("clawden.yaml"),
+        "mode: direct\n\
+         runtimes:\n  \
+           - name: zeroclaw\n    \
+             instance: app\n    \
+             depends_on:\n      \
+               - name: zeroclaw@db\n        \
+                 condition: healthy\n    \
+             health:\n      \
+               log: ready\n  \
+           - name: zeroclaw\n    \
+             instance: db\n    \
+             health:\n      \
+               log: ready\n",
+    )
+    .expect("yaml should be written");
+
+    let output = clawden(
+        &home,
+        &project,
+        &["up", "-d", "--allow-missing-credentials"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains("Waiting for zeroclaw@db to become healthy before starting zeroclaw@app"),
+        "stdout: {stdout}"
+    );
+    let started: Vec<String> = fs::read_to_string(&events)
+        .expect("runtimes should have started")
+        .lines()
+        .filter_map(|line| line.rsplit('/').next().map(ToString::to_string))
+        .collect();
+    assert_eq!(started, vec!["zeroclaw@db", "zeroclaw@app"]);
+
+    let output = clawden(&home, &project, &["down", "--timeout", "1"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    let app = stdout.find("Stopped zeroclaw@app").expect("app stopped");
+    let db = stdout.find("Stopped zeroclaw@db").expect("db stopped");
+    assert!(app < db, "dependents should stop first: {stdout}");
+    let _ = fs::remove_dir_all(dir);
+}
@@ -44,6 +44,10 @@ case "$1" in
     echo "Docker version 27.0.0, build fake"
     exit 0
     ;;
+  version)
+    echo "27.0.0"
+    exit 0
+    ;;
   rm)
         if [ "${{FAKE_DOCKER_RM_FAIL:-0}}" = "1" ]; then
             echo "Error response from daemon: No such container: $3" >&2
@@ -68,6 +72,12 @@ case "$1" in
     exit 0
     ;;
   inspect)
+        case "$*" in
+          *Config.Env*)
+            echo "${{FAKE_DOCKER_ENV:-[]}}"
+            exit 0
+            ;;
+        esac
         echo "${{FAKE_DOCKER_INSPECT_RUNNING:-true}}"
     exit 0
     ;;
@@ -452,3 +462,101 @@ channels:
         "combined output was: {combined}"
     );
 }
+
+#[test]
+fn docker_logs_filters_parsed_lines_across_containers() {
+    let dir = temp_dir("docker-fake-logs");
+    let home = dir.join("home");
+    let bin_dir = dir.join("bin");
+    let docker_log = dir.join("docker.log");
+
+    fs::create_dir_all(&home).expect("home should be created");
+    fs::create_dir_all(&bin_dir).expect("bin dir should be created");
+    setup_fake_docker(&bin_dir, &docker_log);
+
+    let base_path = std::env::var("PATH").unwrap_or_default();
+    let path = format!("{}:{}", bin_dir.display(), base_path);
+    let output = Command::new(binary_path())
+        .env("HOME", &home)
+        .env("PATH", path)
+        .env(
+            "FAKE_DOCKER_LOGS",
+            "2024-05-01T12:00:00.000000000Z INFO gateway started\n\
+             2024-05-01T12:00:01.000000000Z {\"level\":\"error\",\"msg\":\"provider rejected key\"}",
+        )
+        .args([
+            "docker",
+            "logs",
+            "--level",
+            "error",
+            "--json",
+            "clawden-zeroclaw-a",
+            "clawden-zeroclaw-b",
+        ])
+        .output()
+        .expect("docker logs should run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let lines: Vec<serde_json::Value> = stdout
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("each line should be JSON"))
+        .collect();
+    assert_eq!(lines.len(), 2, "stdout: {stdout}");
+    for line in &lines {
+        assert_eq!(line["level"], "error");
+        assert_eq!(line["message"], "provider rejected key");
+        assert_eq!(line["timestamp_ms"], 1_714_564_801_000u64);
+    }
+
+    let log = fs::read_to_string(&docker_log).expect("docker log should exist");
+    assert!(log.contains("logs --timestamps clawden-zeroclaw-a"));
+}
+
+#[test]
+fn docker_logs_redacts_container_env_values_and_tokens() {
+    let dir = temp_dir("docker-fake-redact");
+    let home = dir.join("home");
+    let bin_dir = dir.join("bin");
+    let docker_log = dir.join("docker.log");
+
+    fs::create_dir_all(&home).expect("home should be created");
+    fs::create_dir_all(&bin_dir).expect("bin dir should be created");
+    setup_fake_docker(&bin_dir, &docker_log);
+
+    let base_path = std::env::var("PATH").unwrap_or_default();
+    let path = format!("{}:{}", bin_dir.display(), base_path);
+    let output = Command::new(binary_path())
+        .env("HOME", &home)
+        .env("PATH", path)
+        .env(
+            "FAKE_DOCKER_ENV",
+            r#"["PATH=/usr/local/bin:/usr/bin","OPENAI_API_KEY=opaque-provider-secret"]"#,
+        )
+        .env(
+            "FAKE_DOCKER_LOGS",
+            "2024-05-01T12:00:00.000000000Z INFO loaded key opaque-provider-secret\n\
+             2024-05-01T12:00:01.000000000Z INFO telegram 123456789:AAHdqTcvCH1vGWJxfSeofSAs0K5PALDsawq",
+        )
+        .args(["docker", "logs", "clawden-zeroclaw-a"])
+        .output()
+        .expect("docker logs should run");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("loaded key [REDACTED:OPENAI_API_KEY]"),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("telegram [REDACTED:telegram-bot-token]"));
+    assert!(!stdout.contains("opaque-provider-secret"));
+    assert!(!stdout.contains("AAHdqTcv"));
+}
@@ -0,0 +1,143 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+fn setup_direct_runtime(home: &Path, script: &str) {
+    let runtime_dir = home.join(".clawden/runtimes/zeroclaw/latest");
+    fs::create_dir_all(&runtime_dir).expect("runtime directory should be created");
+    let executable = runtime_dir.join("zeroclaw");
+    fs::write(&executable, script).expect("runtime script should be written");
+    let mut perms = fs::metadata(&executable)
+        .expect("metadata should be available")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&executable, perms).expect("runtime script should be executable");
+    std::os::unix::fs::symlink("latest", home.join(".clawden/runtimes/zeroclaw/current"))
+        .expect("current symlink should be created");
+}
+
+#[test]
+fn doctor_reports_limits_enforced_on_direct_runtime() {
+    let dir = temp_dir("doctor-limits");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    setup_direct_runtime(
+        &home,
+        "#!/usr/bin/env sh\n\
+         [ \"$1\" = onboard ] && exit 0\n\
+         echo started\n\
+         exec sleep 30\n",
+    );
+    fs::write(
+        project.join("clawden.yaml"),
+        "runtime: zeroclaw\nsecurity:\n  max_open_files: 200\n  memory_limit: 1g\n",
+    )
+    .expect("yaml should be written");
+
+    let output = Command::new(binary_path())
+        .current_dir(&project)
+        .env("HOME", &home)
+        .args(["run", "-d", "--allow-missing-credentials", "zeroclaw"])
+        .output()
+        .expect("run should execute");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = Command::new(binary_path())
+        .current_dir(&project)
+        .env("HOME", &home)
+        .arg("doctor")
+        .output()
+        .expect("doctor should execute");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let _ = Command::new(binary_path())
+        .current_dir(&project)
+        .env("HOME", &home)
+        .args(["down", "--timeout", "1"])
+        .output();
+
+    assert!(output.status.success(), "stdout: {stdout}");
+    let section = stdout
+        .split("Resource limits")
+        .nth(1)
+        .expect("doctor should print a resource limits section");
+    assert!(section.contains("  zeroclaw (pid "), "stdout: {stdout}");
+    assert!(
+        section.contains("    open files .......... 200 (requested 200, applied)"),
+        "stdout: {stdout}"
+    );
+    assert!(
+        section.contains("(requested 1073741824 bytes, "),
+        "stdout: {stdout}"
+    );
+    assert!(
+        section.contains("    cpu ................. unlimited\n"),
+        "stdout: {stdout}"
+    );
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn run_refuses_seccomp_it_cannot_apply() {
+    let status = fs::read_to_string("/proc/self/status").unwrap_or_default();
+    if !status.lines().any(|line| line == "Seccomp:\t0") {
+        // A filter inherited from this process would satisfy the request.
+        return;
+    }
+    let dir = temp_dir("run-seccomp");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    setup_direct_runtime(
+        &home,
+        "#!/usr/bin/env sh\n\
+         [ \"$1\" = onboard ] && exit 0\n\
+         echo started\n\
+         exec sleep 30\n",
+    );
+    fs::write(
+        project.join("clawden.yaml"),
+        "runtime: zeroclaw\nsecurity:\n  seccomp_enabled: true\n",
+    )
+    .expect("yaml should be written");
+
+    let output = Command::new(binary_path())
+        .current_dir(&project)
+        .env("HOME", &home)
+        .args(["run", "-d", "--allow-missing-credentials", "zeroclaw"])
+        .output()
+        .expect("run should execute");
+    let _ = Command::new(binary_path())
+        .current_dir(&project)
+        .env("HOME", &home)
+        .args(["down", "--timeout", "1"])
+        .output();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!output.status.success(), "stderr: {stderr}");
+    assert!(
+        stderr.contains("requests seccomp filtering"),
+        "stderr: {stderr}"
+    );
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,99 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+fn setup_direct_runtime(home: &Path, script: &str) {
+    let runtime_dir = home.join(".clawden/runtimes/zeroclaw/latest");
+    fs::create_dir_all(&runtime_dir).expect("runtime directory should be created");
+    let executable = runtime_dir.join("zeroclaw");
+    fs::write(&executable, script).expect("runtime script should be written");
+    let mut perms = fs::metadata(&executable)
+        .expect("metadata should be available")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&executable, perms).expect("runtime script should be executable");
+    std::os::unix::fs::symlink("latest", home.join(".clawden/runtimes/zeroclaw/current"))
+        .expect("current symlink should be created");
+}
+
+#[test]
+fn down_kills_and_reports_leftover_descendants() {
+    let dir = temp_dir("down-leftovers");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    let pid_file = dir.join("worker.pid");
+    // The worker ignores SIGTERM and outlives the runtime that spawned it.
+    setup_direct_runtime(
+        &home,
+        &format!(
+            "#!/usr/bin/env sh\n\
+             [ \"$1\" = onboard ] && exit 0\n\
+             sh -c 'trap \"\" TERM; echo $$ > {}; while true; do sleep 0.2; done' &\n\
+             echo started\n\
+             wait\n",
+            pid_file.display()
+        ),
+    );
+    fs::write(project.join("clawden.yaml"), "runtime: zeroclaw\n").expect("yaml should be written");
+
+    let output = Command::new(binary_path())
+        .current_dir(&project)
+        .env("HOME", &home)
+        .args(["run", "-d", "--allow-missing-credentials", "zeroclaw"])
+        .output()
+        .expect("run should execute");
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let worker: u32 = fs::read_to_string(&pid_file)
+        .expect("worker should have started")
+        .trim()
+        .parse()
+        .expect("worker pid");
+
+    let output = Command::new(binary_path())
+        .current_dir(&project)
+        .env("HOME", &home)
+        .args(["down", "--timeout", "1"])
+        .output()
+        .expect("down should execute");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains(&format!(
+            "Force-killed 1 leftover process(es) of zeroclaw: sh ({worker})"
+        )),
+        "stdout: {stdout}"
+    );
+    assert!(stdout.contains("Stopped zeroclaw"), "stdout: {stdout}");
+
+    let stat = fs::read_to_string(format!("/proc/{worker}/stat")).unwrap_or_default();
+    assert!(
+        stat.is_empty() || stat.contains(") Z"),
+        "worker {worker} should be gone: {stat}"
+    );
+    let audit = fs::read_to_string(home.join(".clawden/logs/audit.log")).expect("audit log");
+    assert!(audit.contains(&format!(
+        "\truntime.force_kill_descendants\tzeroclaw\tpids={worker}"
+    )));
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,128 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+fn setup_direct_runtime(home: &Path, script: &str) {
+    let runtime_dir = home.join(".clawden/runtimes/zeroclaw/latest");
+    fs::create_dir_all(&runtime_dir).expect("runtime directory should be created");
+    let executable = runtime_dir.join("zeroclaw");
+    fs::write(&executable, script).expect("runtime script should be written");
+    let mut perms = fs::metadata(&executable)
+        .expect("metadata should be available")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&executable, perms).expect("runtime script should be executable");
+    std::os::unix::fs::symlink("latest", home.join(".clawden/runtimes/zeroclaw/current"))
+        .expect("current symlink should be created");
+}
+
+fn clawden(home: &Path, project: &Path, args: &[&str]) -> Output {
+    Command::new(binary_path())
+        .current_dir(project)
+        .env("HOME", home)
+        .args(args)
+        .output()
+        .expect("clawden should execute")
+}
+
+#[test]
+fn run_and_ps_use_configured_health_probe() {
+    let dir = temp_dir("health-probe");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    setup_direct_runtime(
+        &home,
+        "#!/usr/bin/env sh\n\
+         [ \"$1\" = onboard ] && exit 0\n\
+         echo booting\n\
+         sleep 1\n\
+         echo ready to serve\n\
+         exec sleep 30\n",
+    );
+    fs::write(
+        project.join("clawden.yaml"),
+        "runtime: zeroclaw\nhealth:\n  log: \"ready to serve\"\n  interval: 200ms\n",
+    )
+    .expect("yaml should be written");
+
+    let output = clawden(
+        &home,
+        &project,
+        &["run", "-d", "--allow-missing-credentials", "zeroclaw"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(stdout.contains("✓ zeroclaw ready"), "stdout: {stdout}");
+
+    let output = clawden(&home, &project, &["ps"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let _ = clawden(&home, &project, &["down", "--timeout", "1"]);
+    assert!(
+        stdout.lines().any(|line| line.contains("zeroclaw")
+            && line.contains("healthy")
+            && !line.contains("unhealthy")),
+        "stdout: {stdout}"
+    );
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn failing_probe_warns_on_start_and_shows_unhealthy() {
+    let dir = temp_dir("health-probe-failing");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    setup_direct_runtime(
+        &home,
+        "#!/usr/bin/env sh\n[ \"$1\" = onboard ] && exit 0\nexec sleep 30\n",
+    );
+    fs::write(
+        project.join("clawden.yaml"),
+        "runtime: zeroclaw\nhealth:\n  exec: \"exit 1\"\n  interval: 0s\n  failure_threshold: 2\n",
+    )
+    .expect("yaml should be written");
+
+    let output = clawden(
+        &home,
+        &project,
+        &["run", "-d", "--allow-missing-credentials", "zeroclaw"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "stdout: {stdout}");
+    assert!(
+        stdout.contains("but health check (exec exit 1) not passing"),
+        "stdout: {stdout}"
+    );
+
+    let output = clawden(&home, &project, &["ps"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let _ = clawden(&home, &project, &["down", "--timeout", "1"]);
+    assert!(
+        stdout
+            .lines()
+            .any(|line| line.contains("zeroclaw") && line.contains("unhealthy")),
+        "stdout: {stdout}"
+    );
+    let _ = fs::remove_dir_all(dir);
+}
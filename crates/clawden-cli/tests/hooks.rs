@@ -0,0 +1,197 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+fn setup_direct_runtime(home: &Path, script: &str) {
+    let runtime_dir = home.join(".clawden/runtimes/zeroclaw/latest");
+    fs::create_dir_all(&runtime_dir).expect("runtime directory should be created");
+    let executable = runtime_dir.join("zeroclaw");
+    fs::write(&executable, script).expect("runtime script should be written");
+    let mut perms = fs::metadata(&executable)
+        .expect("metadata should be available")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&executable, perms).expect("runtime script should be executable");
+    std::os::unix::fs::symlink("latest", home.join(".clawden/runtimes/zeroclaw/current"))
+        .expect("current symlink should be created");
+}
+
+/// Accepts webhook POSTs and forwards each request body.
+fn webhook_receiver() -> (String, mpsc::Receiver<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+    let url = format!("http://{}/hook", listener.local_addr().expect("local addr"));
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(mut stream) = stream else {
+                continue;
+            };
+            let mut reader = BufReader::new(stream.try_clone().expect("stream should clone"));
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).map_or(true, |read| read == 0)
+                    || line.trim().is_empty()
+                {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+            }
+            let mut body = vec![0; length];
+            let _ = reader.read_exact(&mut body);
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{}");
+            let _ = sender.send(String::from_utf8_lossy(&body).to_string());
+        }
+    });
+    (url, receiver)
+}
+
+fn clawden(home: &Path, project: &Path, args: &[&str]) -> Output {
+    Command::new(binary_path())
+        .current_dir(project)
+        .env("HOME", home)
+        .args(args)
+        .output()
+        .expect("clawden should execute")
+}
+
+#[test]
+fn up_reports_runtime_exit_to_on_task_failed_hook() {
+    let dir = temp_dir("hooks");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    setup_direct_runtime(
+        &home,
+        "#!/usr/bin/env sh\n\
+         [ \"$1\" = onboard ] && exit 0\n\
+         echo ready\n\
+         sleep 3\n\
+         echo 'fatal: upstream closed'\n\
+         exit 1\n",
+    );
+    let (url, deliveries) = webhook_receiver();
+    fs::write(
+        project.join("clawden.yaml"),
+        format!(
+            "mode: direct\n\
+             runtimes:\n  \
+               - name: zeroclaw\n    \
+                 health:\n      \
+                   log: ready\n\
+             channels:\n  \
+               alerts:\n    \
+                 type: slack\n    \
+                 webhook_url: {url}\n\
+             hooks:\n  \
+               on_task_failed:\n    \
+                 notify:\n      \
+                   - channel: slack/alerts\n        \
+                     template: \"{{{{worker}}}} down: {{{{task.error}}}}\"\n"
+        ),
+    )
+    .expect("yaml should be written");
+
+    let output = clawden(&home, &project, &["up", "--allow-missing-credentials"]);
+    assert!(
+        output.status.success(),
+        "stdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let body = deliveries
+        .recv_timeout(Duration::from_secs(10))
+        .expect("hook should be delivered");
+    let payload: serde_json::Value = serde_json::from_str(&body).expect("body should be json");
+    assert_eq!(
+        payload["text"],
+        "zeroclaw down: exited with status 1: fatal: upstream closed"
+    );
+    let audit =
+        fs::read_to_string(home.join(".clawden/logs/audit.log")).expect("audit log should exist");
+    assert!(audit.contains("hook.delivered"), "audit: {audit}");
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn up_reports_clean_runtime_exit_to_on_task_complete_hook() {
+    let dir = temp_dir("hooks-complete");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    setup_direct_runtime(
+        &home,
+        "#!/usr/bin/env sh\n\
+         [ \"$1\" = onboard ] && exit 0\n\
+         echo ready\n\
+         sleep 3\n\
+         echo 'all done'\n\
+         exit 0\n",
+    );
+    let (url, deliveries) = webhook_receiver();
+    fs::write(
+        project.join("clawden.yaml"),
+        format!(
+            "mode: direct\n\
+             runtimes:\n  \
+               - name: zeroclaw\n    \
+                 health:\n      \
+                   log: ready\n\
+             channels:\n  \
+               alerts:\n    \
+                 type: slack\n    \
+                 webhook_url: {url}\n\
+             hooks:\n  \
+               on_task_complete:\n    \
+                 notify:\n      \
+                   - channel: slack/alerts\n        \
+                     template: \"{{{{worker}}}} finished: {{{{task.output_summary}}}}\"\n  \
+               on_task_failed:\n    \
+                 notify:\n      \
+                   - channel: slack/alerts\n        \
+                     template: \"{{{{worker}}}} down\"\n"
+        ),
+    )
+    .expect("yaml should be written");
+
+    let output = clawden(&home, &project, &["up", "--allow-missing-credentials"]);
+    assert!(
+        output.status.success(),
+        "stdout: {}\nstderr: {}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let body = deliveries
+        .recv_timeout(Duration::from_secs(10))
+        .expect("hook should be delivered");
+    let payload: serde_json::Value = serde_json::from_str(&body).expect("body should be json");
+    assert_eq!(payload["text"], "zeroclaw finished: all done");
+    assert!(deliveries.recv_timeout(Duration::from_millis(500)).is_err());
+    let _ = fs::remove_dir_all(dir);
+}
@@ -1,54 +1,19 @@
+mod common;
+
+use common::{binary_path, serve_dir, temp_dir, write_executable};
 use std::fs;
-use std::os::unix::fs::PermissionsExt;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::process::Command;
-use std::time::{SystemTime, UNIX_EPOCH};
-
-fn temp_dir(name: &str) -> PathBuf {
-    let stamp = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("clock should be after unix epoch")
-        .as_nanos();
-    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
-    fs::create_dir_all(&path).expect("temp dir should be created");
-    path
-}
-
-fn binary_path() -> PathBuf {
-    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
-}
 
-fn write_executable(path: &Path, content: &str) {
-    fs::write(path, content).expect("script should be written");
-    let mut perms = fs::metadata(path)
-        .expect("metadata should be available")
-        .permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(path, perms).expect("script should be executable");
-}
-
-fn setup_fake_curl(bin_dir: &Path, latest_zeroclaw: &str) {
-    let script = format!(
-        r#"#!/usr/bin/env sh
-set -eu
-url=""
-for arg in "$@"; do
-  url="$arg"
-done
-
-case "$url" in
-  *"/repos/zeroclaw-labs/zeroclaw/releases/latest")
-    printf '%s' '{{"tag_name":"v{}","assets":[]}}'
-    ;;
-  *)
-    echo "unexpected curl url: $url" >&2
-    exit 1
-    ;;
-esac
-"#,
-        latest_zeroclaw
-    );
-    write_executable(&bin_dir.join("curl"), &script);
+fn setup_mirror(dir: &Path, latest_zeroclaw: &str) -> String {
+    let releases = dir.join("mirror/api.github.com/repos/zeroclaw-labs/zeroclaw/releases");
+    fs::create_dir_all(&releases).expect("releases dir should be created");
+    fs::write(
+        releases.join("latest"),
+        format!(r#"{{"tag_name":"v{latest_zeroclaw}","assets":[]}}"#),
+    )
+    .expect("release should be written");
+    serve_dir(dir.join("mirror"))
 }
 
 fn setup_installed_zeroclaw(home: &Path, version: &str) {
@@ -59,13 +24,7 @@ fn setup_installed_zeroclaw(home: &Path, version: &str) {
         .join(version);
     fs::create_dir_all(&runtime_dir).expect("runtime dir should be created");
 
-    let executable = runtime_dir.join("zeroclaw");
-    fs::write(&executable, "#!/usr/bin/env sh\nexit 0\n").expect("runtime script should exist");
-    let mut perms = fs::metadata(&executable)
-        .expect("metadata should exist")
-        .permissions();
-    perms.set_mode(0o755);
-    fs::set_permissions(&executable, perms).expect("runtime script should be executable");
+    write_executable(&runtime_dir.join("zeroclaw"), "#!/usr/bin/env sh\nexit 0\n");
 
     let current = home
         .join(".clawden")
@@ -79,19 +38,14 @@ fn setup_installed_zeroclaw(home: &Path, version: &str) {
 fn install_outdated_exits_zero_when_up_to_date() {
     let dir = temp_dir("install-outdated-ok");
     let home = dir.join("home");
-    let bin_dir = dir.join("bin");
 
     fs::create_dir_all(&home).expect("home should be created");
-    fs::create_dir_all(&bin_dir).expect("bin dir should be created");
-    setup_fake_curl(&bin_dir, "0.2.1");
+    let mirror = setup_mirror(&dir, "0.2.1");
     setup_installed_zeroclaw(&home, "0.2.1");
 
-    let base_path = std::env::var("PATH").unwrap_or_default();
-    let path = format!("{}:{}", bin_dir.display(), base_path);
-
     let output = Command::new(binary_path())
         .env("HOME", &home)
-        .env("PATH", path)
+        .env("CLAWDEN_MIRROR", &mirror)
         .args(["install", "--outdated"])
         .output()
         .expect("install --outdated should run");
@@ -107,19 +61,14 @@ fn install_outdated_exits_zero_when_up_to_date() {
 fn install_outdated_exits_one_when_update_available() {
     let dir = temp_dir("install-outdated-update");
     let home = dir.join("home");
-    let bin_dir = dir.join("bin");
 
     fs::create_dir_all(&home).expect("home should be created");
-    fs::create_dir_all(&bin_dir).expect("bin dir should be created");
-    setup_fake_curl(&bin_dir, "0.2.1");
+    let mirror = setup_mirror(&dir, "0.2.1");
     setup_installed_zeroclaw(&home, "0.1.0");
 
-    let base_path = std::env::var("PATH").unwrap_or_default();
-    let path = format!("{}:{}", bin_dir.display(), base_path);
-
     let output = Command::new(binary_path())
         .env("HOME", &home)
-        .env("PATH", path)
+        .env("CLAWDEN_MIRROR", &mirror)
         .args(["install", "--outdated"])
         .output()
         .expect("install --outdated should run");
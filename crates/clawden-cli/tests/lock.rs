@@ -0,0 +1,96 @@
+mod common;
+
+use common::{clawden, publish_release, release_asset, serve_dir, temp_dir};
+use std::fs;
+use std::path::Path;
+
+/// Publishes a zeroclaw v1.0.0 release with a `checksums.txt` asset on a
+/// local mirror and returns the mirror URL with the archive digest.
+fn setup_release(dir: &Path) -> (String, String) {
+    let mirror = dir.join("mirror");
+    let digest = publish_release(
+        &mirror,
+        "zeroclaw-labs/zeroclaw",
+        "zeroclaw",
+        "#!/usr/bin/env sh\nexit 0\n",
+        true,
+    );
+    (serve_dir(mirror), digest)
+}
+
+#[test]
+fn lock_records_release_and_frozen_install_reuses_it_offline() {
+    let dir = temp_dir("lockfile");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&home).expect("home should be created");
+    fs::create_dir_all(&project).expect("project should be created");
+    fs::write(
+        project.join("clawden.yaml"),
+        "runtime: zeroclaw\nversion: 1.0.x\n",
+    )
+    .expect("yaml should be written");
+    let (online, digest) = setup_release(&dir);
+
+    let output = clawden(&home, &project, &online, &["lock"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        output.status.success(),
+        "stdout: {stdout}\nstderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(
+        stdout.contains(&format!("Locked zeroclaw@1.0.0 (sha256:{digest})")),
+        "stdout: {stdout}"
+    );
+    let lock = fs::read_to_string(project.join("clawden.lock")).expect("lock should exist");
+    assert!(lock.contains("[runtimes.zeroclaw]"), "{lock}");
+    assert!(lock.contains("version = \"1.0.0\""), "{lock}");
+    assert!(
+        lock.contains(&format!(
+            "https://example.invalid/{}",
+            release_asset("zeroclaw")
+        )),
+        "{lock}"
+    );
+
+    // Without network access the lock is served from the cache.
+    fs::remove_dir_all(home.join(".clawden/runtimes/zeroclaw")).expect("uninstall");
+    let offline = "http://127.0.0.1:1";
+    let output = clawden(&home, &project, offline, &["install", "--frozen"]);
+    assert!(
+        output.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Installed zeroclaw@1.0.0"));
+    assert!(home
+        .join(".clawden/runtimes/zeroclaw/1.0.0/zeroclaw")
+        .exists());
+
+    let output = clawden(
+        &home,
+        &project,
+        offline,
+        &["install", "--locked", "zeroclaw@2.0.0"],
+    );
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("does not satisfy 2.0.0"),
+        "stderr: {stderr}"
+    );
+
+    // A cached artifact that no longer matches the lock is refused.
+    let cached = home
+        .join(".clawden/cache/downloads/zeroclaw/1.0.0")
+        .join(release_asset("zeroclaw"));
+    fs::write(&cached, b"tampered").expect("cache should be overwritten");
+    fs::remove_dir_all(home.join(".clawden/runtimes/zeroclaw")).expect("uninstall");
+    let output = clawden(&home, &project, offline, &["install", "--frozen"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("--frozen"), "stderr: {stderr}");
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,148 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+fn run(home: &Path, args: &[&str]) -> Output {
+    Command::new(binary_path())
+        .current_dir(home)
+        .env("HOME", home)
+        .args(args)
+        .output()
+        .expect("clawden should run")
+}
+
+fn write_package(root: &Path, runtimes: &str) -> PathBuf {
+    let package = root.join("web-digest");
+    fs::create_dir_all(package.join("prompts")).expect("package dir should be created");
+    fs::write(
+        package.join("skill.toml"),
+        format!(
+            "name = \"web-digest\"\nversion = \"0.3.1\"\ndescription = \"Daily digest\"\nruntimes = [{runtimes}]\n"
+        ),
+    )
+    .expect("manifest should be written");
+    fs::write(package.join("prompts/main.md"), "Summarize the web").expect("prompt");
+    package
+}
+
+#[test]
+fn skills_install_list_and_remove_in_direct_mode() {
+    let home = temp_dir("skills-direct");
+    let package = write_package(&home, "\"zeroclaw\"");
+
+    let output = run(
+        &home,
+        &["skills", "install", package.to_str().expect("utf8 path")],
+    );
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let installed = home.join(".zeroclaw/workspace/skills/web-digest");
+    assert_eq!(
+        fs::read_to_string(installed.join("prompts/main.md")).expect("skill files copied"),
+        "Summarize the web"
+    );
+
+    let output = run(&home, &["skills", "list", "--runtime", "zeroclaw"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("skill=web-digest\tversion=0.3.1\truntime=zeroclaw"));
+
+    let output = run(&home, &["skills", "remove", "web-digest"]);
+    assert!(output.status.success());
+    assert!(!installed.exists());
+
+    let output = run(&home, &["skills", "remove", "web-digest"]);
+    assert!(!output.status.success());
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn skills_install_rejects_unsupported_runtime() {
+    let home = temp_dir("skills-unsupported");
+    let package = write_package(&home, "\"zeroclaw\"");
+
+    let output = run(
+        &home,
+        &[
+            "skills",
+            "install",
+            package.to_str().expect("utf8 path"),
+            "--runtime",
+            "openclaw",
+        ],
+    );
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("does not support openclaw"));
+    assert!(!home.join(".openclaw").exists());
+    let _ = fs::remove_dir_all(home);
+}
+
+#[test]
+fn skills_install_audits_only_containers_that_received_the_skill() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let home = temp_dir("skills-docker-audit");
+    let package = write_package(&home, "\"zeroclaw\", \"openclaw\"");
+    let bin = home.join("bin");
+    fs::create_dir_all(&bin).expect("bin dir should be created");
+    let docker = bin.join("docker");
+    fs::write(
+        &docker,
+        "#!/bin/sh\ncase \"$*\" in\n  ps*clawden.runtime=zeroclaw*) echo clawden-zeroclaw-a ;;\nesac\nexit 0\n",
+    )
+    .expect("fake docker should be written");
+    fs::set_permissions(&docker, fs::Permissions::from_mode(0o755))
+        .expect("fake docker should be executable");
+
+    let output = Command::new(binary_path())
+        .current_dir(&home)
+        .env("HOME", &home)
+        .env(
+            "PATH",
+            format!(
+                "{}:{}",
+                bin.display(),
+                std::env::var("PATH").unwrap_or_default()
+            ),
+        )
+        .args([
+            "skills",
+            "install",
+            package.to_str().expect("utf8 path"),
+            "--docker",
+        ])
+        .output()
+        .expect("clawden should run");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let audit = fs::read_to_string(home.join(".clawden/logs/audit.log")).expect("audit log");
+    let installs: Vec<_> = audit
+        .lines()
+        .filter(|line| line.contains("\tskill.install\t"))
+        .collect();
+    assert_eq!(installs.len(), 1, "audit: {audit}");
+    assert!(installs[0].ends_with("\tskill.install\tzeroclaw\tok"));
+    let _ = fs::remove_dir_all(home);
+}
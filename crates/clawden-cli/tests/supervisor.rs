@@ -0,0 +1,103 @@
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+fn setup_direct_runtime(home: &Path, script: &str) {
+    let runtime_dir = home.join(".clawden/runtimes/zeroclaw/latest");
+    fs::create_dir_all(&runtime_dir).expect("runtime directory should be created");
+    let executable = runtime_dir.join("zeroclaw");
+    fs::write(&executable, script).expect("runtime script should be written");
+    let mut perms = fs::metadata(&executable)
+        .expect("metadata should be available")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&executable, perms).expect("runtime script should be executable");
+    std::os::unix::fs::symlink("latest", home.join(".clawden/runtimes/zeroclaw/current"))
+        .expect("current symlink should be created");
+}
+
+#[test]
+fn supervisor_restarts_failing_runtime_until_limit() {
+    let dir = temp_dir("supervisor-limit");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    setup_direct_runtime(&home, "#!/usr/bin/env sh\necho attempt\nexit 4\n");
+    fs::write(project.join("clawden.yaml"), "runtime: zeroclaw\n").expect("yaml should be written");
+
+    // The runtime keeps crashing, so `run` may report a failed startup; the
+    // supervisor outcome is what matters here.
+    let _ = Command::new(binary_path())
+        .current_dir(&project)
+        .env("HOME", &home)
+        .args([
+            "run",
+            "-d",
+            "--allow-missing-credentials",
+            "zeroclaw",
+            "--restart=on-failure:2",
+        ])
+        .output()
+        .expect("run should execute");
+
+    let mut ps = String::new();
+    for _ in 0..60 {
+        let output = Command::new(binary_path())
+            .env("HOME", &home)
+            .arg("ps")
+            .output()
+            .expect("ps should execute");
+        ps = String::from_utf8_lossy(&output.stdout).to_string();
+        if ps.contains("restart-limit") {
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(200));
+    }
+    let row = ps
+        .lines()
+        .find(|line| line.starts_with("zeroclaw "))
+        .unwrap_or_else(|| panic!("zeroclaw should be listed: {ps}"));
+    let columns: Vec<&str> = row.split_whitespace().collect();
+    assert_eq!(columns[5], "restart-limit", "row: {row}");
+    assert_eq!(columns[6], "2", "row: {row}");
+    assert_eq!(columns[7], "4", "row: {row}");
+
+    let audit = fs::read_to_string(home.join(".clawden/logs/audit.log")).expect("audit log");
+    assert_eq!(audit.matches("\truntime.crash\tzeroclaw\t").count(), 3);
+    // Each restart starts a new log session instead of wiping the old one.
+    for args in [
+        vec!["logs", "zeroclaw"],
+        vec!["logs", "--previous", "zeroclaw"],
+        vec!["logs", "--session", "2", "zeroclaw"],
+    ] {
+        let output = Command::new(binary_path())
+            .env("HOME", &home)
+            .args(&args)
+            .output()
+            .expect("logs should execute");
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        assert_eq!(stdout.matches("attempt").count(), 1, "{args:?}: {stdout}");
+    }
+
+    let _ = Command::new(binary_path())
+        .env("HOME", &home)
+        .args(["stop", "zeroclaw"])
+        .status();
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,149 @@
+mod common;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use common::{assert_success, clawden, serve_dir, temp_dir, wait_for_lines, write_executable};
+
+/// Installs a fake zeroclaw `version` that appends its version to `started`
+/// and then keeps running.
+fn install_version(home: &Path, version: &str, started: &Path) {
+    let runtime_dir = home.join(".clawden/runtimes/zeroclaw").join(version);
+    fs::create_dir_all(&runtime_dir).expect("runtime directory should be created");
+    write_executable(
+        &runtime_dir.join("zeroclaw"),
+        &format!(
+            "#!/usr/bin/env sh\n\
+             [ \"$1\" = onboard ] && exit 0\n\
+             echo {version} >> {}\n\
+             exec sleep 30\n",
+            started.display()
+        ),
+    );
+}
+
+fn current(home: &Path) -> PathBuf {
+    fs::read_link(home.join(".clawden/runtimes/zeroclaw/current")).expect("current symlink")
+}
+
+#[test]
+fn use_and_rollback_switch_between_installed_versions() {
+    let dir = temp_dir("versions");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    let started = dir.join("started");
+    let mirror = serve_dir(dir.join("mirror"));
+    install_version(&home, "0.1.6", &started);
+    install_version(&home, "0.2.0", &started);
+    std::os::unix::fs::symlink("0.2.0", home.join(".clawden/runtimes/zeroclaw/current"))
+        .expect("current symlink should be created");
+    fs::write(
+        project.join("clawden.yaml"),
+        "mode: direct\nruntime: zeroclaw\nversion: \">=0.1.0\"\n",
+    )
+    .expect("yaml should be written");
+
+    let stdout = assert_success(&clawden(&home, &project, &mirror, &["install", "--list"]));
+    let pinned = format!("pinned by {}", project.display());
+    let rows = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(rows.len(), 2, "stdout: {stdout}");
+    assert!(rows[0].starts_with("zeroclaw\t0.1.6\t"), "stdout: {stdout}");
+    assert!(rows[0].ends_with(&pinned), "stdout: {stdout}");
+    assert!(
+        rows[1].starts_with("zeroclaw\t0.2.0 (current)\t"),
+        "stdout: {stdout}"
+    );
+
+    let output = clawden(&home, &project, &mirror, &["use", "zeroclaw@0.3.0"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("is not installed"));
+
+    let stdout = assert_success(&clawden(
+        &home,
+        &project,
+        &mirror,
+        &["use", "zeroclaw@0.1.6"],
+    ));
+    assert!(
+        stdout.contains("Now using zeroclaw@0.1.6"),
+        "stdout: {stdout}"
+    );
+    assert_eq!(current(&home), PathBuf::from("0.1.6"));
+
+    assert_success(&clawden(
+        &home,
+        &project,
+        &mirror,
+        &["up", "-d", "--allow-missing-credentials"],
+    ));
+    assert_eq!(wait_for_lines(&started, 1), ["0.1.6"]);
+
+    // Rolling back swaps to the previous version and restarts the instance.
+    let stdout = assert_success(&clawden(
+        &home,
+        &project,
+        &mirror,
+        &["rollback", "zeroclaw", "--timeout", "1"],
+    ));
+    assert!(
+        stdout.contains("Rolled back zeroclaw 0.1.6 -> 0.2.0"),
+        "stdout: {stdout}"
+    );
+    assert_eq!(current(&home), PathBuf::from("0.2.0"));
+    assert_eq!(wait_for_lines(&started, 2), ["0.1.6", "0.2.0"]);
+
+    // Rolling back from another project leaves this project's instance be.
+    let other = dir.join("other");
+    fs::create_dir_all(&other).expect("other project should be created");
+    fs::write(
+        other.join("clawden.yaml"),
+        "mode: direct\nruntime: zeroclaw\nversion: \">=0.1.0\"\n",
+    )
+    .expect("yaml should be written");
+    let stdout = assert_success(&clawden(&home, &other, &mirror, &["rollback", "zeroclaw"]));
+    assert!(
+        stdout.contains("from their project to pick it up: clawden restart zeroclaw"),
+        "stdout: {stdout}"
+    );
+    assert_eq!(current(&home), PathBuf::from("0.1.6"));
+    std::thread::sleep(Duration::from_millis(500));
+    assert_eq!(wait_for_lines(&started, 2), ["0.1.6", "0.2.0"]);
+    let projects =
+        fs::read_to_string(home.join(".clawden/projects.json")).expect("projects are registered");
+    assert!(
+        !projects.contains(other.to_str().expect("utf-8 project path")),
+        "rollback should not register a project: {projects}"
+    );
+    assert_success(&clawden(
+        &home,
+        &project,
+        &mirror,
+        &["use", "zeroclaw@0.2.0"],
+    ));
+    let _ = clawden(&home, &project, &mirror, &["down", "--timeout", "1"]);
+
+    let output = clawden(&home, &project, &mirror, &["uninstall", "zeroclaw@0.2.0"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("current version"));
+    let stdout = assert_success(&clawden(
+        &home,
+        &project,
+        &mirror,
+        &["uninstall", "zeroclaw@0.1.6"],
+    ));
+    assert!(
+        stdout.contains("Uninstalled zeroclaw@0.1.6"),
+        "stdout: {stdout}"
+    );
+    assert!(!home.join(".clawden/runtimes/zeroclaw/0.1.6").exists());
+    assert!(home
+        .join(".clawden/runtimes/zeroclaw/0.2.0/zeroclaw")
+        .exists());
+    let output = clawden(&home, &project, &mirror, &["rollback", "zeroclaw"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("no previous version"));
+
+    let _ = fs::remove_dir_all(dir);
+}
@@ -0,0 +1,175 @@
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+fn temp_dir(name: &str) -> PathBuf {
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("clock should be after unix epoch")
+        .as_nanos();
+    let path = std::env::temp_dir().join(format!("clawden-{name}-{stamp}"));
+    fs::create_dir_all(&path).expect("temp dir should be created");
+    path
+}
+
+fn binary_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_BIN_EXE_clawden"))
+}
+
+fn setup_direct_runtime(home: &Path, script: &str) {
+    let runtime_dir = home.join(".clawden/runtimes/zeroclaw/latest");
+    fs::create_dir_all(&runtime_dir).expect("runtime directory should be created");
+    let executable = runtime_dir.join("zeroclaw");
+    fs::write(&executable, script).expect("runtime script should be written");
+    let mut perms = fs::metadata(&executable)
+        .expect("metadata should be available")
+        .permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&executable, perms).expect("runtime script should be executable");
+    std::os::unix::fs::symlink("latest", home.join(".clawden/runtimes/zeroclaw/current"))
+        .expect("current symlink should be created");
+}
+
+fn clawden(home: &Path, project: &Path, args: &[&str]) -> Output {
+    Command::new(binary_path())
+        .current_dir(project)
+        .env("HOME", home)
+        .args(args)
+        .output()
+        .expect("clawden should execute")
+}
+
+fn spawn_watch(home: &Path, project: &Path) -> (Child, Receiver<String>) {
+    let mut child = Command::new(binary_path())
+        .current_dir(project)
+        .env("HOME", home)
+        .args([
+            "up",
+            "--watch",
+            "--allow-missing-credentials",
+            "--timeout",
+            "1",
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("clawden should start");
+    let (tx, rx) = mpsc::channel();
+    for stream in [
+        Box::new(child.stdout.take().expect("stdout")) as Box<dyn std::io::Read + Send>,
+        Box::new(child.stderr.take().expect("stderr")),
+    ] {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stream).lines().map_while(Result::ok) {
+                let _ = tx.send(line);
+            }
+        });
+    }
+    (child, rx)
+}
+
+fn wait_for_line(lines: &Receiver<String>, needle: &str, seen: &mut Vec<String>) {
+    let deadline = Instant::now() + Duration::from_secs(30);
+    while Instant::now() < deadline {
+        if let Ok(line) = lines.recv_timeout(Duration::from_millis(200)) {
+            let found = line.contains(needle);
+            seen.push(line);
+            if found {
+                return;
+            }
+        }
+    }
+    panic!("'{needle}' not printed; output:\n{}", seen.join("\n"));
+}
+
+fn started(events: &Path) -> Vec<String> {
+    fs::read_to_string(events)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| line.rsplit('/').next().map(ToString::to_string))
+        .collect()
+}
+
+fn project_yaml(app_temperature: &str) -> String {
+    format!(
+        "mode: direct\n\
+         runtimes:\n  \
+           - name: zeroclaw\n    \
+             instance: app\n    \
+             config:\n      \
+               default_temperature: {app_temperature}\n    \
+             health:\n      \
+               log: ready\n  \
+           - name: zeroclaw\n    \
+             instance: db\n    \
+             health:\n      \
+               log: ready\n"
+    )
+}
+
+#[test]
+fn watch_restarts_only_changed_runtimes_and_survives_invalid_config() {
+    let dir = temp_dir("up-watch");
+    let home = dir.join("home");
+    let project = dir.join("project");
+    fs::create_dir_all(&project).expect("project should be created");
+    let events = dir.join("events");
+    setup_direct_runtime(
+        &home,
+        &format!(
+            "#!/usr/bin/env sh\n\
+             [ \"$1\" = onboard ] && exit 0\n\
+             echo \"start $*\" >> {}\n\
+             echo ready\n\
+             exec sleep 60\n",
+            events.display()
+        ),
+    );
+    fs::write(project.join("clawden.yaml"), project_yaml("0.5")).expect("yaml should be written");
+
+    let (mut child, lines) = spawn_watch(&home, &project);
+    let mut seen = Vec::new();
+    wait_for_line(&lines, "Attaching logs", &mut seen);
+    let mut initial = started(&events);
+    initial.sort();
+    assert_eq!(initial, vec!["zeroclaw@app", "zeroclaw@db"]);
+
+    fs::write(project.join("clawden.yaml"), project_yaml("0.9")).expect("yaml should be edited");
+    wait_for_line(&lines, "Restarted zeroclaw@app", &mut seen);
+    assert!(
+        seen.iter()
+            .any(|line| line.contains("Restarting zeroclaw@app (config.toml changed)")),
+        "output:\n{}",
+        seen.join("\n")
+    );
+    assert_eq!(
+        started(&events).len(),
+        3,
+        "only the edited runtime restarts"
+    );
+    assert_eq!(started(&events)[2], "zeroclaw@app");
+
+    fs::write(project.join("clawden.yaml"), "runtimes: [\n").expect("yaml should be broken");
+    wait_for_line(&lines, "Keeping running runtimes", &mut seen);
+    fs::write(project.join(".env"), "UNUSED_SETTING=1\n").expect("env should be written");
+    fs::write(project.join("clawden.yaml"), project_yaml("0.9")).expect("yaml should be fixed");
+    wait_for_line(&lines, "no runtime changes", &mut seen);
+    assert_eq!(started(&events).len(), 3, "invalid config stops nothing");
+
+    let output = clawden(&home, &project, &["ps"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(stdout.matches("running").count(), 2, "ps: {stdout}");
+
+    Command::new("kill")
+        .args(["-INT", &child.id().to_string()])
+        .status()
+        .expect("kill should run");
+    assert!(child.wait().expect("clawden should exit").success());
+    let _ = clawden(&home, &project, &["down", "--timeout", "1"]);
+    let _ = fs::remove_dir_all(dir);
+}
@@ -1,4 +1,8 @@
-use clawden_core::ClawRuntime;
+use clawden_core::{
+    hook_template_variables, instance_name, instance_runtime, parse_memory_limit,
+    validate_instance_name, ChannelInstanceConfig, ChannelType, ClawRuntime, CustomRuntimeSpec,
+    HealthCheck, HookConfig, HookEventKind, ResourceLimits, HOOK_TEMPLATE_VARIABLES,
+};
 use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -29,6 +33,8 @@ use std::path::Path;
 ///   - name: zeroclaw
 ///     channels: [support-tg]
 ///     tools: [git, http]
+///   - name: zeroclaw
+///     instance: research
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClawDenYaml {
@@ -75,6 +81,23 @@ pub struct ClawDenYaml {
     /// Single-runtime workspace persistence config.
     #[serde(default)]
     pub workspace: Option<WorkspaceYaml>,
+
+    /// Task event hooks that notify channel instances.
+    #[serde(default, skip_serializing_if = "HookConfig::is_empty")]
+    pub hooks: HookConfig,
+
+    /// Single-runtime resource limits and sandbox settings shorthand.
+    #[serde(default)]
+    pub security: Option<SecurityConfig>,
+
+    /// Single-runtime health probe shorthand.
+    #[serde(default)]
+    pub health: Option<HealthCheck>,
+
+    /// Project-local runtime definitions, in the format of
+    /// `~/.clawden/runtimes.d/*.toml`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub custom_runtimes: Vec<CustomRuntimeSpec>,
 }
 
 /// A channel instance entry in `clawden.yaml`.
@@ -135,6 +158,10 @@ pub fn is_numeric_telegram_id(value: &str) -> bool {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeEntryYaml {
     pub name: String,
+    /// Instance name, required when the same runtime is listed more than
+    /// once. The entry runs as `name@instance`.
+    #[serde(default)]
+    pub instance: Option<String>,
     #[serde(default)]
     pub version: Option<String>,
     #[serde(default)]
@@ -150,6 +177,75 @@ pub struct RuntimeEntryYaml {
     /// Per-runtime workspace persistence config.
     #[serde(default)]
     pub workspace: Option<WorkspaceYaml>,
+    /// Per-runtime resource limits and sandbox settings.
+    #[serde(default)]
+    pub security: Option<SecurityConfig>,
+    /// Health probe replacing the runtime's built-in health endpoint.
+    #[serde(default)]
+    pub health: Option<HealthCheck>,
+    /// Instances that must be up before this one starts.
+    #[serde(default)]
+    pub depends_on: Vec<DependsOnYaml>,
+}
+
+/// A `depends_on` entry: an instance name, or `{ name, condition }`.
+///
+/// ```yaml
+/// depends_on:
+///   - openfang
+///   - name: zeroclaw@work
+///     condition: healthy
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DependsOnYaml {
+    Name(String),
+    Detailed {
+        name: String,
+        #[serde(default)]
+        condition: DependencyCondition,
+    },
+}
+
+impl DependsOnYaml {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Name(name) | Self::Detailed { name, .. } => name,
+        }
+    }
+
+    pub fn condition(&self) -> DependencyCondition {
+        match self {
+            Self::Name(_) => DependencyCondition::default(),
+            Self::Detailed { condition, .. } => *condition,
+        }
+    }
+}
+
+/// What a dependency must reach before its dependents start.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DependencyCondition {
+    /// The dependency's process has been launched.
+    #[default]
+    Started,
+    /// The dependency's health probe passes.
+    Healthy,
+}
+
+impl RuntimeEntryYaml {
+    /// The runtime slug, even when `name` is written as `runtime@instance`.
+    pub fn runtime(&self) -> &str {
+        instance_runtime(&self.name)
+    }
+
+    /// `runtime` for the default instance, otherwise `runtime@instance`.
+    pub fn instance_name(&self) -> String {
+        match self.instance.as_deref() {
+            Some(instance) => instance_name(self.runtime(), Some(instance)),
+            None => self.name.clone(),
+        }
+    }
 }
 
 /// Workspace persistence configuration.
@@ -310,15 +406,11 @@ impl LlmProvider {
         }
     }
 
-    fn resolve_api_key_from_env(&self) -> Option<String> {
+    fn resolve_api_key_from_env(&self, env: &dyn Fn(&str) -> Option<String>) -> Option<String> {
         match self {
             // Match SDK behavior: GOOGLE_API_KEY takes precedence over GEMINI_API_KEY.
-            Self::Google => std::env::var("GOOGLE_API_KEY")
-                .ok()
-                .or_else(|| std::env::var("GEMINI_API_KEY").ok()),
-            _ => self
-                .default_api_key_env()
-                .and_then(|env| std::env::var(env).ok()),
+            Self::Google => env("GOOGLE_API_KEY").or_else(|| env("GEMINI_API_KEY")),
+            _ => self.default_api_key_env().and_then(env),
         }
     }
 }
@@ -361,6 +453,101 @@ const KNOWN_CHANNEL_TYPES: &[&str] = &[
 ];
 
 impl ClawDenYaml {
+    /// Finds a `runtimes` entry by instance name (`zeroclaw`, `zeroclaw@work`).
+    pub fn runtime_entry(&self, name: &str) -> Option<&RuntimeEntryYaml> {
+        self.runtimes
+            .iter()
+            .find(|entry| entry.instance_name() == name)
+    }
+
+    /// `depends_on` of an instance as (instance, condition) pairs.
+    pub fn dependencies(&self, name: &str) -> Vec<(String, DependencyCondition)> {
+        self.runtime_entry(name)
+            .map(|entry| {
+                entry
+                    .depends_on
+                    .iter()
+                    .map(|dep| (dep.name().to_string(), dep.condition()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// `names` plus everything they depend on, directly or not.
+    pub fn with_dependencies(&self, names: &[String]) -> Vec<String> {
+        let mut all: Vec<String> = Vec::new();
+        let mut pending: Vec<String> = names.to_vec();
+        while let Some(name) = pending.pop() {
+            if all.contains(&name) {
+                continue;
+            }
+            pending.extend(self.dependencies(&name).into_iter().map(|(dep, _)| dep));
+            all.push(name);
+        }
+        all.sort();
+        all
+    }
+
+    /// Groups `names` into start waves: every instance comes after the
+    /// instances it depends on. Dependencies outside `names` are ignored.
+    /// Stopping in reverse wave order tears dependents down first.
+    pub fn startup_waves(&self, names: &[String]) -> Result<Vec<Vec<String>>, String> {
+        let mut remaining: Vec<String> = names.to_vec();
+        remaining.sort();
+        remaining.dedup();
+        let mut waves: Vec<Vec<String>> = Vec::new();
+        while !remaining.is_empty() {
+            let (ready, blocked): (Vec<String>, Vec<String>) =
+                remaining.iter().cloned().partition(|name| {
+                    self.dependencies(name)
+                        .iter()
+                        .all(|(dep, _)| dep == name || !remaining.contains(dep))
+                });
+            if ready.is_empty() {
+                return Err(format!(
+                    "dependency cycle between runtimes: {}",
+                    blocked.join(", ")
+                ));
+            }
+            remaining = blocked;
+            waves.push(ready);
+        }
+        Ok(waves)
+    }
+
+    /// Finds a `depends_on` cycle, returned as the path that closes it.
+    fn dependency_cycle(&self) -> Option<Vec<String>> {
+        fn visit(
+            config: &ClawDenYaml,
+            name: &str,
+            path: &mut Vec<String>,
+            done: &mut HashSet<String>,
+        ) -> Option<Vec<String>> {
+            if let Some(start) = path.iter().position(|seen| seen == name) {
+                let mut cycle = path[start..].to_vec();
+                cycle.push(name.to_string());
+                return Some(cycle);
+            }
+            if done.contains(name) {
+                return None;
+            }
+            path.push(name.to_string());
+            for (dep, _) in config.dependencies(name) {
+                if let Some(cycle) = visit(config, &dep, path, done) {
+                    return Some(cycle);
+                }
+            }
+            path.pop();
+            done.insert(name.to_string());
+            None
+        }
+
+        let mut done = HashSet::new();
+        self.runtimes
+            .iter()
+            .find_map(|entry| visit(self, &entry.instance_name(), &mut Vec::new(), &mut done))
+    }
+
     /// Parse a clawden.yaml file from disk. Auto-loads `.env` from the same directory.
     pub fn from_file(path: &Path) -> Result<Self, String> {
         // Auto-load .env from the directory containing clawden.yaml
@@ -395,6 +582,11 @@ impl ClawDenYaml {
         if self.runtime.is_none() && self.runtimes.is_empty() {
             errors.push("must specify either 'runtime' or 'runtimes'".to_string());
         }
+        for custom in &self.custom_runtimes {
+            if custom.slug.trim().is_empty() {
+                errors.push("every 'custom_runtimes' entry needs a 'slug'".to_string());
+            }
+        }
 
         // Validate channel types can be resolved
         for (name, ch) in &self.channels {
@@ -414,6 +606,20 @@ impl ClawDenYaml {
             }
         }
 
+        // Each runtime entry needs a distinct instance name.
+        let mut instances: HashSet<String> = HashSet::new();
+        for rt in &self.runtimes {
+            let name = rt.instance_name();
+            if let Err(err) = validate_instance_name(&name) {
+                errors.push(format!("Runtime '{}': {err}", rt.name));
+            } else if !instances.insert(name.clone()) {
+                errors.push(format!(
+                    "Runtime '{name}' is listed more than once. \
+                     Give each entry of the same runtime a distinct 'instance' name."
+                ));
+            }
+        }
+
         // Validate channel references exist and enforce 1:1 instance→runtime
         let mut channel_owners: HashMap<String, String> = HashMap::new();
         for rt in &self.runtimes {
@@ -500,6 +706,40 @@ impl ClawDenYaml {
                     ));
                 }
             }
+
+            if let Some(Err(err)) = rt.security.as_ref().map(SecurityConfig::resource_limits) {
+                errors.push(format!("Runtime '{}' has invalid security: {err}", rt.name));
+            }
+
+            if let Some(Err(err)) = rt.health.as_ref().map(HealthCheck::validate) {
+                errors.push(format!("Runtime '{}' has invalid health: {err}", rt.name));
+            }
+
+            for dep in &rt.depends_on {
+                if dep.name() == rt.instance_name() {
+                    errors.push(format!("Runtime '{}' depends on itself", rt.name));
+                } else if self.runtime_entry(dep.name()).is_none() {
+                    errors.push(format!(
+                        "Runtime '{}' depends on '{}' which is not defined in 'runtimes:'",
+                        rt.name,
+                        dep.name()
+                    ));
+                }
+            }
+        }
+
+        if let Some(cycle) = self.dependency_cycle() {
+            if cycle.len() > 2 {
+                errors.push(format!("Runtime dependency cycle: {}", cycle.join(" -> ")));
+            }
+        }
+
+        if let Some(Err(err)) = self.security.as_ref().map(SecurityConfig::resource_limits) {
+            errors.push(format!("Top-level 'security' is invalid: {err}"));
+        }
+
+        if let Some(Err(err)) = self.health.as_ref().map(HealthCheck::validate) {
+            errors.push(format!("Top-level 'health' is invalid: {err}"));
         }
 
         if let Some(version) = self.version.as_deref() {
@@ -511,6 +751,8 @@ impl ClawDenYaml {
             }
         }
 
+        self.validate_hooks(&mut errors);
+
         if errors.is_empty() {
             Ok(())
         } else {
@@ -520,13 +762,36 @@ impl ClawDenYaml {
 
     /// Resolve `$ENV_VAR` references in all credential fields.
     pub fn resolve_env_vars(&mut self) -> Result<(), Vec<String>> {
+        self.resolve_env_vars_with(&|name| std::env::var(name).ok())
+    }
+
+    /// Like [`ClawDenYaml::resolve_env_vars`], reading variables through
+    /// `env` instead of the process environment.
+    pub fn resolve_env_vars_with(
+        &mut self,
+        env: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<(), Vec<String>> {
         let mut errors = Vec::new();
         for (name, ch) in &mut self.channels {
-            resolve_field(&mut ch.token, "Channel", name, "token", &mut errors);
-            resolve_field(&mut ch.bot_token, "Channel", name, "bot_token", &mut errors);
-            resolve_field(&mut ch.app_token, "Channel", name, "app_token", &mut errors);
-            resolve_field(&mut ch.phone, "Channel", name, "phone", &mut errors);
-            resolve_field(&mut ch.guild, "Channel", name, "guild", &mut errors);
+            resolve_field(&mut ch.token, "Channel", name, "token", &mut errors, env);
+            resolve_field(
+                &mut ch.bot_token,
+                "Channel",
+                name,
+                "bot_token",
+                &mut errors,
+                env,
+            );
+            resolve_field(
+                &mut ch.app_token,
+                "Channel",
+                name,
+                "app_token",
+                &mut errors,
+                env,
+            );
+            resolve_field(&mut ch.phone, "Channel", name, "phone", &mut errors, env);
+            resolve_field(&mut ch.guild, "Channel", name, "guild", &mut errors, env);
         }
         for (name, provider) in &mut self.providers {
             resolve_field(
@@ -535,6 +800,7 @@ impl ClawDenYaml {
                 name,
                 "api_key",
                 &mut errors,
+                env,
             );
             resolve_field(
                 &mut provider.base_url,
@@ -542,11 +808,12 @@ impl ClawDenYaml {
                 name,
                 "base_url",
                 &mut errors,
+                env,
             );
 
             if let Some(provider_type) = provider.resolved_type(name) {
                 if provider.api_key.is_none() {
-                    provider.api_key = provider_type.resolve_api_key_from_env();
+                    provider.api_key = provider_type.resolve_api_key_from_env(env);
                 }
                 if provider.base_url.is_none() {
                     provider.base_url = provider_type.default_base_url().map(str::to_string);
@@ -577,6 +844,7 @@ impl ClawDenYaml {
                 "provider",
                 "api_key",
                 &mut errors,
+                env,
             );
             resolve_field(
                 &mut resolved_provider.base_url,
@@ -584,6 +852,7 @@ impl ClawDenYaml {
                 "provider",
                 "base_url",
                 &mut errors,
+                env,
             );
 
             let provider_name = match provider_ref {
@@ -592,7 +861,7 @@ impl ClawDenYaml {
             };
             if let Some(provider_type) = resolved_provider.resolved_type(provider_name) {
                 if resolved_provider.api_key.is_none() {
-                    resolved_provider.api_key = provider_type.resolve_api_key_from_env();
+                    resolved_provider.api_key = provider_type.resolve_api_key_from_env(env);
                 }
                 if resolved_provider.base_url.is_none() {
                     resolved_provider.base_url =
@@ -609,11 +878,19 @@ impl ClawDenYaml {
                 "workspace",
                 "token",
                 &mut errors,
+                env,
             );
         }
         for rt in &mut self.runtimes {
             if let Some(ws) = &mut rt.workspace {
-                resolve_field(&mut ws.token, "Workspace", &rt.name, "token", &mut errors);
+                resolve_field(
+                    &mut ws.token,
+                    "Workspace",
+                    &rt.name,
+                    "token",
+                    &mut errors,
+                    env,
+                );
             }
         }
         if errors.is_empty() {
@@ -623,6 +900,118 @@ impl ClawDenYaml {
         }
     }
 
+    fn validate_hooks(&self, errors: &mut Vec<String>) {
+        for (kind, rule) in self.hooks.rules() {
+            let key = kind.config_key();
+            if rule.wait && kind != HookEventKind::ApprovalNeeded {
+                errors.push(format!(
+                    "Hook '{key}' sets 'wait: true', which is only supported for 'on_approval_needed'"
+                ));
+            }
+            if !rule.wait && (rule.timeout_secs.is_some() || rule.default_decision.is_some()) {
+                errors.push(format!(
+                    "Hook '{key}' sets 'timeout_secs' or 'default' without 'wait: true'"
+                ));
+            }
+            for target in &rule.notify {
+                let (channel_type, instance) = target.channel_ref();
+                match self.channels.get(instance) {
+                    None => errors.push(format!(
+                        "Hook '{key}' notifies channel '{}' which is not defined in 'channels:'.",
+                        target.channel
+                    )),
+                    Some(ch) => {
+                        let resolved = Self::resolve_channel_type(instance, ch);
+                        if let (Some(expected), Some(actual)) = (channel_type, resolved) {
+                            if !expected.eq_ignore_ascii_case(&actual) {
+                                errors.push(format!(
+                                    "Hook '{key}' notifies '{}' but channel '{instance}' is of type '{actual}'",
+                                    target.channel
+                                ));
+                            }
+                        }
+                    }
+                }
+                for variable in target
+                    .template
+                    .as_deref()
+                    .map(hook_template_variables)
+                    .unwrap_or_default()
+                {
+                    if !HOOK_TEMPLATE_VARIABLES.contains(&variable.as_str()) {
+                        errors.push(format!(
+                            "Hook '{key}' template for '{}' uses unknown variable '{{{{{variable}}}}}'. \
+                             Known variables: {}",
+                            target.channel,
+                            HOOK_TEMPLATE_VARIABLES.join(", ")
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Build the core channel config for a named channel instance, as used by
+    /// the hook engine and channel store.
+    pub fn channel_instance_config(&self, name: &str) -> Option<ChannelInstanceConfig> {
+        let ch = self.channels.get(name)?;
+        let channel_type = ChannelType::from_str_loose(&Self::resolve_channel_type(name, ch)?)?;
+
+        let mut credentials = HashMap::new();
+        for (key, value) in [
+            ("token", &ch.token),
+            ("bot_token", &ch.bot_token),
+            ("app_token", &ch.app_token),
+            ("phone", &ch.phone),
+            ("guild", &ch.guild),
+        ] {
+            if let Some(value) = value {
+                credentials.insert(key.to_string(), value.clone());
+            }
+        }
+
+        let mut options = HashMap::new();
+        for (key, value) in &ch.extra {
+            match value.as_str() {
+                Some(text) => {
+                    credentials.insert(key.clone(), text.to_string());
+                }
+                None => {
+                    options.insert(key.clone(), value.clone());
+                }
+            }
+        }
+        for (key, list) in [
+            ("allowed_users", &ch.allowed_users),
+            ("allowed_roles", &ch.allowed_roles),
+            ("allowed_channels", &ch.allowed_channels),
+        ] {
+            if !list.is_empty() {
+                options.insert(key.to_string(), Value::from(list.clone()));
+            }
+        }
+        if let Some(group_mode) = &ch.group_mode {
+            options.insert("group_mode".to_string(), Value::from(group_mode.clone()));
+        }
+
+        Some(ChannelInstanceConfig {
+            instance_name: name.to_string(),
+            channel_type,
+            credentials,
+            options,
+        })
+    }
+
+    /// Core channel configs for every channel instance with a known type.
+    pub fn channel_instance_configs(&self) -> Vec<ChannelInstanceConfig> {
+        let mut names: Vec<_> = self.channels.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .filter_map(|name| self.channel_instance_config(name))
+            .collect()
+    }
+
     /// Resolve the channel type for a given instance name.
     pub fn resolve_channel_type(name: &str, ch: &ChannelInstanceYaml) -> Option<String> {
         ch.channel_type.clone().or_else(|| {
@@ -675,12 +1064,13 @@ fn resolve_field(
     instance: &str,
     field_name: &str,
     errors: &mut Vec<String>,
+    env: &dyn Fn(&str) -> Option<String>,
 ) {
     if let Some(val) = field.as_ref() {
         if let Some(env_name) = val.strip_prefix('$') {
-            match std::env::var(env_name) {
-                Ok(resolved) => *field = Some(resolved),
-                Err(_) => errors.push(format!(
+            match env(env_name) {
+                Some(resolved) => *field = Some(resolved),
+                None => errors.push(format!(
                     "{} '{}' field '{}': environment variable '{}' is not set",
                     kind, instance, field_name, env_name
                 )),
@@ -745,6 +1135,9 @@ pub struct SecurityConfig {
     /// Memory limit, e.g. "4g" or "unlimited".
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub memory_limit: Option<String>,
+    /// CPU limit in cores, e.g. 1.5.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_limit: Option<f64>,
     /// Maximum open file descriptors.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub max_open_files: Option<u64>,
@@ -759,6 +1152,33 @@ pub struct SecurityConfig {
     pub delegate_sandbox: Option<bool>,
 }
 
+impl SecurityConfig {
+    /// The limits ClawDen enforces itself for direct-mode processes.
+    pub fn resource_limits(&self) -> Result<ResourceLimits, String> {
+        let memory_bytes = match self.memory_limit.as_deref() {
+            Some(limit) => parse_memory_limit(limit).map_err(|err| err.to_string())?,
+            None => None,
+        };
+        if let Some(cores) = self.cpu_limit {
+            if !(cores.is_finite() && cores > 0.0) {
+                return Err(format!(
+                    "cpu_limit must be a positive number of cores, got {cores}"
+                ));
+            }
+        }
+        if self.max_open_files == Some(0) {
+            return Err("max_open_files must be greater than zero".to_string());
+        }
+        Ok(ResourceLimits {
+            memory_bytes,
+            cpu_cores: self.cpu_limit,
+            max_open_files: self.max_open_files,
+            drop_capabilities: self.drop_capabilities.unwrap_or(false),
+            seccomp: self.seccomp_enabled.unwrap_or(false),
+        })
+    }
+}
+
 impl ClawDenConfig {
     pub fn validate(&self) -> Result<(), String> {
         if self.agent.name.trim().is_empty() {
@@ -1063,6 +1483,7 @@ fn base_config_with_runtime(
                 sandboxed: false,
                 profile: None,
                 memory_limit: None,
+                cpu_limit: None,
                 max_open_files: None,
                 seccomp_enabled: None,
                 drop_capabilities: None,
@@ -1629,12 +2050,12 @@ impl ChannelCredentialMapper {
 mod tests {
     use super::{
         diff_configs, ChannelCredentialMapper, ChannelInstanceYaml, ClawDenConfig, ClawDenYaml,
-        LlmProvider, ModelConfig, NanoClawConfigTranslator, OpenClawConfigTranslator,
-        PicoClawConfigTranslator, ProviderRefYaml, RuntimeConfigTranslator, SecretVault,
-        WorkspaceYaml, ZeroClawConfigTranslator,
+        DependencyCondition, LlmProvider, ModelConfig, NanoClawConfigTranslator,
+        OpenClawConfigTranslator, PicoClawConfigTranslator, ProviderRefYaml,
+        RuntimeConfigTranslator, SecretVault, WorkspaceYaml, ZeroClawConfigTranslator,
     };
     use crate::{AgentConfig, ChannelConfig, SecurityConfig, ToolConfig};
-    use clawden_core::ClawRuntime;
+    use clawden_core::{ClawRuntime, TcpTarget};
     use serde_json::Map;
     use std::fs;
     use std::time::{SystemTime, UNIX_EPOCH};
@@ -1688,6 +2109,7 @@ mod tests {
                     sandboxed: true,
                     profile: None,
                     memory_limit: None,
+                    cpu_limit: None,
                     max_open_files: None,
                     seccomp_enabled: None,
                     drop_capabilities: None,
@@ -2042,6 +2464,33 @@ channels:
         assert!(errors.iter().any(|e| e.contains("assigned to both")));
     }
 
+    #[test]
+    fn runtime_instances_need_distinct_names() {
+        let yaml = r#"
+runtimes:
+  - name: zeroclaw
+  - name: zeroclaw
+    instance: work
+"#;
+        let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
+        parsed
+            .validate()
+            .expect("distinct instances should validate");
+        let entry = parsed
+            .runtime_entry("zeroclaw@work")
+            .expect("instance entry should resolve");
+        assert_eq!(entry.runtime(), "zeroclaw");
+
+        let yaml = r#"
+runtimes:
+  - name: zeroclaw
+  - name: zeroclaw
+"#;
+        let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
+        let errors = parsed.validate().expect_err("validation should fail");
+        assert!(errors.iter().any(|e| e.contains("listed more than once")));
+    }
+
     #[test]
     fn validation_fails_when_same_token_reused_for_same_channel_type() {
         let yaml = r#"
@@ -2165,6 +2614,111 @@ runtimes:
         );
     }
 
+    #[test]
+    fn runtime_health_block_parses_and_validates() {
+        let yaml = r#"
+runtimes:
+  - name: nanoclaw
+    health:
+      tcp: 9000
+      interval: 5s
+      start_period: 30s
+  - name: zeroclaw
+    health:
+      http: /healthz
+      log: "ready"
+"#;
+        let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
+        let health = parsed.runtimes[0]
+            .health
+            .as_ref()
+            .expect("nanoclaw health should parse");
+        assert_eq!(health.tcp, Some(TcpTarget::Port(9000)));
+        assert_eq!(health.interval(), std::time::Duration::from_secs(5));
+
+        let errors = parsed.validate().expect_err("validation should fail");
+        assert_eq!(
+            errors
+                .iter()
+                .filter(|e| e.contains("has invalid health"))
+                .collect::<Vec<_>>(),
+            vec!["Runtime 'zeroclaw' has invalid health: health takes only one of 'http', 'tcp', 'exec' or 'log'"]
+        );
+    }
+
+    #[test]
+    fn depends_on_orders_startup_waves() {
+        let yaml = r#"
+runtimes:
+  - name: openfang
+  - name: zeroclaw
+    depends_on:
+      - name: openfang
+        condition: healthy
+  - name: picoclaw
+    depends_on: [zeroclaw, openfang]
+  - name: nanoclaw
+"#;
+        let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
+        parsed.validate().expect("dependencies should validate");
+        assert_eq!(
+            parsed.dependencies("zeroclaw"),
+            vec![("openfang".to_string(), DependencyCondition::Healthy)]
+        );
+
+        let all: Vec<String> = ["nanoclaw", "openfang", "picoclaw", "zeroclaw"]
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            parsed.startup_waves(&all).expect("waves"),
+            vec![
+                vec!["nanoclaw".to_string(), "openfang".to_string()],
+                vec!["zeroclaw".to_string()],
+                vec!["picoclaw".to_string()],
+            ]
+        );
+        assert_eq!(
+            parsed.with_dependencies(&["picoclaw".to_string()]),
+            vec!["openfang", "picoclaw", "zeroclaw"]
+        );
+    }
+
+    #[test]
+    fn validation_rejects_dependency_cycles_and_unknown_dependencies() {
+        let yaml = r#"
+runtimes:
+  - name: openfang
+    depends_on: [picoclaw]
+  - name: zeroclaw
+    depends_on: [openfang, ironclaw]
+  - name: picoclaw
+    depends_on: [zeroclaw]
+"#;
+        let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
+        let errors = parsed.validate().expect_err("validation should fail");
+        assert!(
+            errors.contains(
+                &"Runtime dependency cycle: openfang -> picoclaw -> zeroclaw -> openfang"
+                    .to_string()
+            ),
+            "got: {errors:?}"
+        );
+        assert!(
+            errors.contains(
+                &"Runtime 'zeroclaw' depends on 'ironclaw' which is not defined in 'runtimes:'"
+                    .to_string()
+            ),
+            "got: {errors:?}"
+        );
+        assert!(parsed
+            .startup_waves(&["openfang".to_string(), "picoclaw".to_string()])
+            .is_ok());
+        assert!(parsed
+            .startup_waves(&parsed.with_dependencies(&["openfang".to_string()]))
+            .is_err());
+    }
+
     #[test]
     fn zeroclaw_signal_mapping_uses_phone_and_token() {
         let mut ch = sample_channel();
@@ -2453,4 +3007,90 @@ runtimes:
         assert_eq!(ws1.path.as_deref(), Some("agents/coder-1"));
         assert_eq!(ws1.sync_interval_secs(), 3600);
     }
+
+    #[test]
+    fn hooks_parse_and_validate_against_channels() {
+        let yaml = r#"
+runtime: zeroclaw
+channels:
+  eng-leads:
+    type: slack
+    bot_token: xoxb
+    app_token: xapp
+    allowed_users: [U123]
+  telegram:
+    token: t1
+hooks:
+  on_task_complete:
+    notify:
+      - channel: slack/eng-leads
+        template: "{{worker}} completed: {{task.summary}}"
+      - channel: telegram
+  on_approval_needed:
+    notify:
+      - channel: eng-leads
+    wait: true
+    timeout_secs: 600
+    default: approve
+"#;
+        let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
+        parsed.validate().expect("hooks should validate");
+        let rule = parsed.hooks.on_task_complete.as_ref().expect("rule");
+        assert_eq!(rule.notify.len(), 2);
+        let approval = parsed.hooks.on_approval_needed.as_ref().expect("rule");
+        assert!(approval.wait);
+        assert_eq!(approval.timeout_secs, Some(600));
+        assert_eq!(
+            approval.default_decision,
+            Some(clawden_core::ApprovalDecision::Approve)
+        );
+        assert!(parsed.hooks.on_task_failed.is_none());
+
+        let slack = parsed
+            .channel_instance_config("eng-leads")
+            .expect("slack config");
+        assert_eq!(slack.channel_type, clawden_core::ChannelType::Slack);
+        assert_eq!(
+            slack.credentials.get("bot_token").map(String::as_str),
+            Some("xoxb")
+        );
+        assert_eq!(
+            slack.options.get("allowed_users"),
+            Some(&serde_json::json!(["U123"]))
+        );
+        assert_eq!(parsed.channel_instance_configs().len(), 2);
+    }
+
+    #[test]
+    fn hooks_validation_reports_bad_channels_templates_and_wait() {
+        let yaml = r#"
+runtime: zeroclaw
+channels:
+  pm-bot:
+    type: telegram
+    token: t1
+hooks:
+  on_task_failed:
+    wait: true
+    notify:
+      - channel: ghost
+      - channel: slack/pm-bot
+      - channel: pm-bot
+        template: "{{worker}} {{task.nope}}"
+"#;
+        let parsed = ClawDenYaml::parse_yaml(yaml).expect("yaml should parse");
+        let errors = parsed.validate().expect_err("validation should fail");
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("notifies channel 'ghost' which is not defined")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("channel 'pm-bot' is of type 'telegram'")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("unknown variable '{{task.nope}}'")));
+        assert!(errors
+            .iter()
+            .any(|e| e.contains("only supported for 'on_approval_needed'")));
+    }
 }
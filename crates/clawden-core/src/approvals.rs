@@ -0,0 +1,557 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::process_group::pid_alive;
+use crate::{current_unix_ms, ChannelStore};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalDecision {
+    Approve,
+    Deny,
+}
+
+impl std::fmt::Display for ApprovalDecision {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ApprovalDecision::Approve => "approve",
+            ApprovalDecision::Deny => "deny",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Denied,
+    /// Nobody answered in time; the default decision was applied.
+    Expired,
+}
+
+impl std::fmt::Display for ApprovalStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ApprovalStatus::Pending => "pending",
+            ApprovalStatus::Approved => "approved",
+            ApprovalStatus::Denied => "denied",
+            ApprovalStatus::Expired => "expired",
+        })
+    }
+}
+
+/// A persisted approval request. Stored as JSON under
+/// `~/.clawden/approvals/` so a restart of the server (or a separate
+/// `clawden approvals` invocation) sees the same pending requests.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalRecord {
+    pub id: String,
+    pub worker: String,
+    pub task_id: String,
+    pub description: String,
+    /// Channel instances the approval prompt was posted to.
+    #[serde(default)]
+    pub channels: Vec<String>,
+    pub status: ApprovalStatus,
+    pub default_decision: ApprovalDecision,
+    pub created_at_unix_ms: u64,
+    #[serde(default)]
+    pub expires_at_unix_ms: Option<u64>,
+    #[serde(default)]
+    pub decided_by: Option<String>,
+    #[serde(default)]
+    pub decided_at_unix_ms: Option<u64>,
+}
+
+impl ApprovalRecord {
+    pub fn is_pending(&self) -> bool {
+        self.status == ApprovalStatus::Pending
+    }
+
+    /// The effective decision once the request is no longer pending.
+    pub fn decision(&self) -> Option<ApprovalDecision> {
+        match self.status {
+            ApprovalStatus::Pending => None,
+            ApprovalStatus::Approved => Some(ApprovalDecision::Approve),
+            ApprovalStatus::Denied => Some(ApprovalDecision::Deny),
+            ApprovalStatus::Expired => Some(self.default_decision),
+        }
+    }
+
+    pub fn is_overdue(&self, now_unix_ms: u64) -> bool {
+        self.is_pending()
+            && self
+                .expires_at_unix_ms
+                .is_some_and(|expires| now_unix_ms >= expires)
+    }
+}
+
+/// Why a decision on an approval request was refused.
+#[derive(Debug, thiserror::Error)]
+pub enum ApprovalError {
+    #[error("approval '{0}' not found")]
+    NotFound(String),
+    #[error("approval '{id}' is already {status}")]
+    AlreadyDecided { id: String, status: ApprovalStatus },
+    #[error("sender '{sender}' is not allowed to approve on channel '{channel}'")]
+    NotAllowed { sender: String, channel: String },
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// How long a decision waits for another process updating the same record.
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// File-backed store of approval requests, one JSON file per request.
+pub struct ApprovalStore {
+    dir: PathBuf,
+}
+
+impl ApprovalStore {
+    pub fn new() -> Result<Self> {
+        let home = std::env::var("HOME").context("HOME is not set")?;
+        Ok(Self::at(
+            PathBuf::from(home).join(".clawden").join("approvals"),
+        ))
+    }
+
+    pub fn at(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Create and persist a pending request.
+    pub fn create(
+        &self,
+        worker: &str,
+        task_id: &str,
+        description: &str,
+        channels: Vec<String>,
+        default_decision: ApprovalDecision,
+        timeout_secs: Option<u64>,
+    ) -> Result<ApprovalRecord> {
+        let now = current_unix_ms();
+        let record = ApprovalRecord {
+            id: next_approval_id(now),
+            worker: worker.to_string(),
+            task_id: task_id.to_string(),
+            description: description.to_string(),
+            channels,
+            status: ApprovalStatus::Pending,
+            default_decision,
+            created_at_unix_ms: now,
+            expires_at_unix_ms: timeout_secs.map(|secs| now + secs.saturating_mul(1000)),
+            decided_by: None,
+            decided_at_unix_ms: None,
+        };
+        self.save(&record)?;
+        Ok(record)
+    }
+
+    pub fn get(&self, id: &str) -> Result<Option<ApprovalRecord>> {
+        let path = self.record_path(id)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("reading approval {}", path.display()))?;
+        let record = serde_json::from_str(&raw)
+            .with_context(|| format!("parsing approval {}", path.display()))?;
+        Ok(Some(record))
+    }
+
+    /// All requests, oldest first.
+    pub fn list(&self) -> Result<Vec<ApprovalRecord>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut records = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Ok(raw) = fs::read_to_string(&path) else {
+                continue;
+            };
+            if let Ok(record) = serde_json::from_str::<ApprovalRecord>(&raw) {
+                records.push(record);
+            }
+        }
+        records.sort_by(|a, b| {
+            a.created_at_unix_ms
+                .cmp(&b.created_at_unix_ms)
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        Ok(records)
+    }
+
+    pub fn pending(&self) -> Result<Vec<ApprovalRecord>> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .filter(ApprovalRecord::is_pending)
+            .collect())
+    }
+
+    /// Record a human decision. Fails if the request is unknown or no longer
+    /// pending so a late reply cannot flip an earlier outcome.
+    pub fn decide(
+        &self,
+        id: &str,
+        decision: ApprovalDecision,
+        actor: &str,
+    ) -> Result<ApprovalRecord, ApprovalError> {
+        if !is_valid_id(id) {
+            return Err(ApprovalError::NotFound(id.to_string()));
+        }
+        let _lock = self.lock(id)?;
+        let mut record = self
+            .get(id)?
+            .ok_or_else(|| ApprovalError::NotFound(id.to_string()))?;
+        if !record.is_pending() {
+            return Err(ApprovalError::AlreadyDecided {
+                id: id.to_string(),
+                status: record.status,
+            });
+        }
+        let now = current_unix_ms();
+        if record.is_overdue(now) {
+            return Ok(self.expire(record)?);
+        }
+        record.status = match decision {
+            ApprovalDecision::Approve => ApprovalStatus::Approved,
+            ApprovalDecision::Deny => ApprovalStatus::Denied,
+        };
+        record.decided_by = Some(actor.to_string());
+        record.decided_at_unix_ms = Some(now);
+        self.save(&record)?;
+        Ok(record)
+    }
+
+    /// Apply the default decision to every pending request past its deadline.
+    pub fn expire_overdue(&self) -> Result<Vec<ApprovalRecord>> {
+        let now = current_unix_ms();
+        let mut expired = Vec::new();
+        for record in self.pending()? {
+            if !record.is_overdue(now) {
+                continue;
+            }
+            let _lock = self.lock(&record.id)?;
+            // Another process may have decided it since the listing.
+            if let Some(current) = self.get(&record.id)?.filter(|r| r.is_overdue(now)) {
+                expired.push(self.expire(current)?);
+            }
+        }
+        Ok(expired)
+    }
+
+    /// Resolve a pending request from a chat reply such as `approve` or
+    /// `deny apr-123`. Returns `Ok(None)` when the text is not an approval
+    /// reply or no request is pending on that channel. A reply naming a
+    /// request that was not posted to the channel is reported as not found.
+    /// Only senders allowed by the channel instance's allowlist may decide.
+    pub fn handle_reply(
+        &self,
+        channels: &ChannelStore,
+        instance_name: &str,
+        sender_id: &str,
+        sender_role: Option<&str>,
+        text: &str,
+    ) -> Result<Option<ApprovalRecord>, ApprovalError> {
+        let Some((decision, id)) = parse_approval_reply(text) else {
+            return Ok(None);
+        };
+        let allowed = channels
+            .authorize_sender_for_channel(instance_name, sender_id, sender_role)
+            .map_err(|e| anyhow!(e))?;
+        if !allowed {
+            return Err(ApprovalError::NotAllowed {
+                sender: sender_id.to_string(),
+                channel: instance_name.to_string(),
+            });
+        }
+
+        let target = match id {
+            Some(id) => Some(
+                self.get(&id)?
+                    .filter(|record| record.channels.iter().any(|c| c == instance_name))
+                    .ok_or(ApprovalError::NotFound(id))?,
+            ),
+            None => self
+                .pending()?
+                .into_iter()
+                .rev()
+                .find(|record| record.channels.iter().any(|c| c == instance_name)),
+        };
+        let Some(target) = target else {
+            return Ok(None);
+        };
+        self.decide(
+            &target.id,
+            decision,
+            &format!("{instance_name}:{sender_id}"),
+        )
+        .map(Some)
+    }
+
+    fn expire(&self, mut record: ApprovalRecord) -> Result<ApprovalRecord> {
+        record.status = ApprovalStatus::Expired;
+        record.decided_by = Some("timeout".to_string());
+        record.decided_at_unix_ms = Some(current_unix_ms());
+        self.save(&record)?;
+        Ok(record)
+    }
+
+    /// Serialises read-modify-write of one record across processes.
+    fn lock(&self, id: &str) -> Result<RecordLock> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating {}", self.dir.display()))?;
+        RecordLock::acquire(self.record_path(id)?.with_extension("lock"))
+    }
+
+    fn save(&self, record: &ApprovalRecord) -> Result<()> {
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("creating {}", self.dir.display()))?;
+        let path = self.record_path(&record.id)?;
+        let tmp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, serde_json::to_vec_pretty(record)?)
+            .with_context(|| format!("writing approval {}", tmp.display()))?;
+        fs::rename(&tmp, &path).with_context(|| format!("saving approval {}", path.display()))
+    }
+
+    fn record_path(&self, id: &str) -> Result<PathBuf> {
+        if !is_valid_id(id) {
+            bail!("invalid approval id '{id}'");
+        }
+        Ok(self.dir.join(format!("{id}.json")))
+    }
+}
+
+/// Exclusive lock file holding the owner's pid; a lock left by a dead
+/// process is taken over.
+struct RecordLock {
+    path: PathBuf,
+}
+
+impl RecordLock {
+    fn acquire(path: PathBuf) -> Result<Self> {
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+        loop {
+            match OpenOptions::new().create_new(true).write(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(err) if err.kind() == ErrorKind::AlreadyExists => {}
+                Err(err) => {
+                    return Err(err).with_context(|| format!("locking {}", path.display()));
+                }
+            }
+            let owner = fs::read_to_string(&path)
+                .ok()
+                .and_then(|raw| raw.trim().parse::<u32>().ok());
+            if owner.is_some_and(|pid| !pid_alive(pid)) {
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+            if Instant::now() >= deadline {
+                bail!("approval is locked by another process ({})", path.display());
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+impl Drop for RecordLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Parse a chat reply into a decision and optional approval id.
+/// Accepts `approve`, `yes`, `/approve <id>`, `deny`, `reject`, `no`, ...
+pub fn parse_approval_reply(text: &str) -> Option<(ApprovalDecision, Option<String>)> {
+    let mut words = text.split_whitespace();
+    let verb = words
+        .next()?
+        .trim_start_matches('/')
+        .trim_end_matches(['.', '!', ','])
+        .to_ascii_lowercase();
+    let decision = match verb.as_str() {
+        "approve" | "approved" | "yes" | "y" | "ok" | "lgtm" => ApprovalDecision::Approve,
+        "deny" | "denied" | "reject" | "no" | "n" => ApprovalDecision::Deny,
+        _ => return None,
+    };
+    let id = words
+        .next()
+        .map(|word| word.trim_matches(|c: char| !c.is_ascii_alphanumeric() && c != '-'))
+        .filter(|word| word.starts_with("apr-"))
+        .map(str::to_string);
+    Some((decision, id))
+}
+
+fn is_valid_id(id: &str) -> bool {
+    !id.is_empty() && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn next_approval_id(now_unix_ms: u64) -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let seq = COUNTER.fetch_add(1, Ordering::Relaxed);
+    format!("apr-{now_unix_ms}-{seq}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChannelConfigRequest, ChannelStore};
+    use std::collections::HashMap;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_store(name: &str) -> ApprovalStore {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        ApprovalStore::at(std::env::temp_dir().join(format!("clawden-approvals-{name}-{stamp}")))
+    }
+
+    fn channels() -> ChannelStore {
+        let mut store = ChannelStore::new();
+        let mut options = HashMap::new();
+        options.insert("allowed_users".to_string(), serde_json::json!(["lead"]));
+        store
+            .upsert_config(ChannelConfigRequest {
+                instance_name: "eng-leads".to_string(),
+                channel_type: "slack".to_string(),
+                credentials: HashMap::new(),
+                options,
+            })
+            .expect("config");
+        store
+    }
+
+    #[test]
+    fn parse_reply_recognizes_decisions_and_ids() {
+        assert_eq!(
+            parse_approval_reply("Approve apr-1-0"),
+            Some((ApprovalDecision::Approve, Some("apr-1-0".to_string())))
+        );
+        assert_eq!(
+            parse_approval_reply("/deny"),
+            Some((ApprovalDecision::Deny, None))
+        );
+        assert_eq!(parse_approval_reply("what is this?"), None);
+    }
+
+    #[test]
+    fn records_persist_and_reply_from_allowlisted_sender_decides() {
+        let store = temp_store("reply");
+        let record = store
+            .create(
+                "coder",
+                "task-1",
+                "drop table users",
+                vec!["eng-leads".to_string()],
+                ApprovalDecision::Deny,
+                Some(60),
+            )
+            .expect("create");
+
+        // A fresh store over the same directory sees the pending request.
+        let reopened = ApprovalStore::at(store.dir());
+        assert_eq!(reopened.pending().expect("pending").len(), 1);
+
+        let channels = channels();
+        let err = reopened
+            .handle_reply(&channels, "eng-leads", "intruder", None, "approve")
+            .expect_err("unlisted sender should be rejected");
+        assert!(err.to_string().contains("not allowed"));
+        assert!(reopened
+            .handle_reply(&channels, "eng-leads", "lead", None, "sounds good")
+            .expect("non-reply")
+            .is_none());
+
+        let decided = reopened
+            .handle_reply(&channels, "eng-leads", "lead", None, "approve")
+            .expect("reply")
+            .expect("decided");
+        assert_eq!(decided.id, record.id);
+        assert_eq!(decided.decision(), Some(ApprovalDecision::Approve));
+        assert_eq!(decided.decided_by.as_deref(), Some("eng-leads:lead"));
+
+        let err = store
+            .decide(&record.id, ApprovalDecision::Deny, "cli")
+            .expect_err("already decided");
+        assert!(err.to_string().contains("already approved"));
+        let _ = fs::remove_dir_all(store.dir());
+    }
+
+    #[test]
+    fn overdue_requests_take_default_decision() {
+        let store = temp_store("expire");
+        let record = store
+            .create(
+                "coder",
+                "task-1",
+                "deploy",
+                Vec::new(),
+                ApprovalDecision::Approve,
+                Some(0),
+            )
+            .expect("create");
+
+        let expired = store.expire_overdue().expect("expire");
+        assert_eq!(expired.len(), 1);
+        let stored = store.get(&record.id).expect("get").expect("record");
+        assert_eq!(stored.status, ApprovalStatus::Expired);
+        assert_eq!(stored.decision(), Some(ApprovalDecision::Approve));
+        assert!(store.pending().expect("pending").is_empty());
+        let _ = fs::remove_dir_all(store.dir());
+    }
+
+    #[test]
+    fn concurrent_decisions_settle_a_request_once() {
+        let store = temp_store("race");
+        let record = store
+            .create(
+                "coder",
+                "task-1",
+                "deploy",
+                Vec::new(),
+                ApprovalDecision::Deny,
+                Some(60),
+            )
+            .expect("create");
+
+        let deciders: Vec<_> = [ApprovalDecision::Approve, ApprovalDecision::Deny]
+            .into_iter()
+            .cycle()
+            .take(8)
+            .map(|decision| {
+                let store = ApprovalStore::at(store.dir());
+                let id = record.id.clone();
+                std::thread::spawn(move || store.decide(&id, decision, "cli").ok())
+            })
+            .collect();
+        let decided: Vec<_> = deciders
+            .into_iter()
+            .filter_map(|decider| decider.join().expect("decider thread"))
+            .collect();
+
+        assert_eq!(decided.len(), 1);
+        let stored = store.get(&record.id).expect("get").expect("record");
+        assert_eq!(stored.status, decided[0].status);
+        assert!(!store.dir().join(format!("{}.lock", record.id)).exists());
+        let _ = fs::remove_dir_all(store.dir());
+    }
+}
@@ -13,13 +13,30 @@ pub struct AuditEvent {
     pub timestamp_unix_ms: u64,
 }
 
+/// Receives every event appended to an [`AuditLog`] built with
+/// [`AuditLog::with_sink`].
+pub type AuditSink = Arc<dyn Fn(&AuditEvent) + Send + Sync>;
+
 #[derive(Clone, Default)]
 pub struct AuditLog {
     inner: Arc<Mutex<Vec<AuditEvent>>>,
+    sink: Option<AuditSink>,
 }
 
 impl AuditLog {
+    /// A log that also hands each event to `sink`, e.g. to persist it in a
+    /// process that exits before anyone reads the in-memory list.
+    pub fn with_sink(sink: AuditSink) -> Self {
+        Self {
+            inner: Arc::default(),
+            sink: Some(sink),
+        }
+    }
+
     pub fn append(&self, event: AuditEvent) {
+        if let Some(sink) = &self.sink {
+            sink(&event);
+        }
         if let Ok(mut guard) = self.inner.lock() {
             guard.push(event);
         }
@@ -45,8 +62,11 @@ pub fn append_audit(audit: &Arc<AuditLog>, actor: &str, action: &str, target: &s
         timestamp_unix_ms: now,
     });
 
-    // Best-effort file mirroring for cross-process audit visibility.
-    let _ = append_file_audit(now, actor, action, target);
+    // Best-effort file mirroring for cross-process audit visibility; a sink
+    // takes care of persistence itself.
+    if audit.sink.is_none() {
+        let _ = append_file_audit(now, actor, action, target);
+    }
 }
 
 fn append_file_audit(
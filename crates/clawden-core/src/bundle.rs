@@ -0,0 +1,498 @@
+//! Offline bundles: one archive holding the installed runtimes, download
+//! cache entries, tool directories and Docker images a project needs, so
+//! another machine's `~/.clawden` can be populated without network access.
+//!
+//! The archive is a tar.gz whose layout mirrors `~/.clawden`, plus
+//! `bundle.json` recording a digest for every entry and `images/` holding
+//! `docker save` output.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::process::Command;
+
+use crate::checksum::{file_digest_like, tree_digest, ArtifactRecord};
+use crate::install::{ensure_runtime_supported, ARTIFACT_RECORD};
+use crate::{version_satisfies, ProcessManager, RuntimeInstaller};
+
+pub const BUNDLE_MANIFEST: &str = "bundle.json";
+const BUNDLE_FORMAT: u32 = 1;
+
+/// `bundle.json`. Paths are relative to `~/.clawden`, except images which
+/// are relative to the archive root.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format: u32,
+    #[serde(default)]
+    pub runtimes: Vec<BundledRuntime>,
+    /// Download cache entries and tool directories.
+    #[serde(default)]
+    pub files: Vec<BundledTree>,
+    #[serde(default)]
+    pub images: Vec<BundledImage>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundledRuntime {
+    pub runtime: String,
+    pub version: String,
+    pub path: String,
+    pub digest: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundledTree {
+    pub path: String,
+    pub digest: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BundledImage {
+    pub image: String,
+    pub path: String,
+    pub digest: String,
+}
+
+/// Result of [`RuntimeInstaller::install_bundle`].
+#[derive(Debug, Clone)]
+pub struct BundleInstall {
+    pub manifest: BundleManifest,
+    /// False when the bundle has images but Docker is not available.
+    pub images_loaded: bool,
+}
+
+/// Collects entries for `clawden bundle create`.
+pub struct BundleBuilder<'a> {
+    installer: &'a RuntimeInstaller,
+    staging: PathBuf,
+    manifest: BundleManifest,
+}
+
+impl RuntimeInstaller {
+    pub fn bundle_builder(&self) -> Result<BundleBuilder<'_>> {
+        let staging = self
+            .root_dir()
+            .join(format!(".bundle-create-{}.tmp", std::process::id()));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        fs::create_dir_all(staging.join("images"))?;
+        Ok(BundleBuilder {
+            installer: self,
+            staging,
+            manifest: BundleManifest {
+                format: BUNDLE_FORMAT,
+                ..BundleManifest::default()
+            },
+        })
+    }
+
+    /// Verifies every entry of `archive` against its recorded digest and
+    /// moves it into `~/.clawden`. `constraints` are the `clawden.yaml`
+    /// version pins by runtime; bundled runtimes must satisfy them and
+    /// match the version and digest in `clawden.lock` when the project has
+    /// one. Nothing is installed unless the whole bundle checks out and its
+    /// images load, and an installed runtime version is never overwritten.
+    pub fn install_bundle(
+        &self,
+        archive: &Path,
+        constraints: &HashMap<String, String>,
+    ) -> Result<BundleInstall> {
+        let _lock = self.acquire_install_lock()?;
+        let staging = self
+            .root_dir()
+            .join(format!(".bundle-install-{}.tmp", std::process::id()));
+        if staging.exists() {
+            fs::remove_dir_all(&staging)?;
+        }
+        fs::create_dir_all(&staging)?;
+        let result = self.install_bundle_from(archive, &staging, constraints);
+        let _ = fs::remove_dir_all(&staging);
+        result
+    }
+
+    fn install_bundle_from(
+        &self,
+        archive: &Path,
+        staging: &Path,
+        constraints: &HashMap<String, String>,
+    ) -> Result<BundleInstall> {
+        let listing = run_tar(Command::new("tar").arg("-tzf").arg(archive), archive)?;
+        for entry in listing.lines().map(|line| line.trim_start_matches("./")) {
+            if entry.is_empty() {
+                continue;
+            }
+            ensure_relative(entry)
+                .with_context(|| format!("{} contains an unsafe path", archive.display()))?;
+        }
+        run_tar(
+            Command::new("tar")
+                .arg("-xzf")
+                .arg(archive)
+                .arg("--no-same-owner")
+                .arg("-C")
+                .arg(staging),
+            archive,
+        )?;
+
+        let raw = fs::read(staging.join(BUNDLE_MANIFEST))
+            .with_context(|| format!("{} has no {BUNDLE_MANIFEST}", archive.display()))?;
+        let manifest: BundleManifest = serde_json::from_slice(&raw)
+            .with_context(|| format!("failed to parse {BUNDLE_MANIFEST}"))?;
+        if manifest.format != BUNDLE_FORMAT {
+            bail!(
+                "unsupported bundle format {} (expected {BUNDLE_FORMAT})",
+                manifest.format
+            );
+        }
+
+        for entry in &manifest.runtimes {
+            let slug = ensure_runtime_supported(&entry.runtime)?.slug;
+            if entry.runtime != slug || entry.path != format!("runtimes/{slug}/{}", entry.version) {
+                bail!(
+                    "bundle entry {} does not match {slug}@{}",
+                    entry.path,
+                    entry.version
+                );
+            }
+            ensure_relative(&entry.path)?;
+            if let Some(constraint) = constraints.get(slug) {
+                if !version_satisfies(&entry.version, constraint) {
+                    bail!(
+                        "bundle has {slug}@{} which does not satisfy clawden.yaml version '{constraint}'",
+                        entry.version
+                    );
+                }
+            }
+            verify(staging, &entry.path, &entry.digest, tree_digest)?;
+            if let Some(locked) = self.locked_runtime(slug)? {
+                if locked.version != entry.version {
+                    bail!(
+                        "bundle has {slug}@{} but clawden.lock pins {}",
+                        entry.version,
+                        locked.version
+                    );
+                }
+                // The tree digest only proves the archive is intact; the
+                // artifact record ties the runtime to the locked download.
+                let artifact =
+                    ArtifactRecord::load(&staging.join(&entry.path).join(ARTIFACT_RECORD));
+                match artifact {
+                    Some(artifact) if artifact.digest == locked.digest => {}
+                    Some(artifact) => bail!(
+                        "bundle has {slug}@{} built from {} but clawden.lock pins {}",
+                        entry.version,
+                        artifact.digest,
+                        locked.digest
+                    ),
+                    None => bail!(
+                        "bundle has {slug}@{} without a download digest to check against clawden.lock",
+                        entry.version
+                    ),
+                }
+            }
+        }
+        for entry in &manifest.files {
+            ensure_relative(&entry.path)?;
+            if !["cache/downloads/", "tools/", "community-tools/"]
+                .iter()
+                .any(|prefix| entry.path.starts_with(prefix))
+            {
+                bail!(
+                    "bundle entry {} is outside the cache and tool directories",
+                    entry.path
+                );
+            }
+            verify(staging, &entry.path, &entry.digest, tree_digest)?;
+        }
+        for image in &manifest.images {
+            ensure_relative(&image.path)?;
+            if !image.path.starts_with("images/") {
+                bail!("bundle image {} is outside images/", image.path);
+            }
+            verify(staging, &image.path, &image.digest, |path| {
+                file_digest_like(path, "sha256:")
+            })?;
+        }
+
+        // An installed version is never replaced: the same tree is left
+        // alone, a different one is refused.
+        let mut runtime_paths = Vec::new();
+        for entry in &manifest.runtimes {
+            let dest = self.root_dir().join(&entry.path);
+            if !dest.exists() && !dest.is_symlink() {
+                runtime_paths.push(entry.path.as_str());
+            } else if tree_digest(&dest)? != entry.digest {
+                bail!(
+                    "{}@{} is already installed with different contents; uninstall it first",
+                    entry.runtime,
+                    entry.version
+                );
+            }
+        }
+
+        // Images go first, since a failed `docker load` cannot be undone
+        // any more cheaply than the moves below.
+        let images_loaded = manifest.images.is_empty() || ProcessManager::docker_available();
+        if !manifest.images.is_empty() && images_loaded {
+            for image in &manifest.images {
+                let output = Command::new("docker")
+                    .arg("load")
+                    .arg("-i")
+                    .arg(staging.join(&image.path))
+                    .output()
+                    .context("failed to run docker load")?;
+                if !output.status.success() {
+                    bail!(
+                        "docker load failed for {}: {}",
+                        image.image,
+                        String::from_utf8_lossy(&output.stderr).trim()
+                    );
+                }
+                self.append_audit("image.bundle.load", &image.image, "ok")?;
+            }
+        }
+
+        let paths = runtime_paths
+            .into_iter()
+            .chain(manifest.files.iter().map(|entry| entry.path.as_str()));
+        self.move_into_place(staging, paths)?;
+
+        for entry in &manifest.runtimes {
+            self.point_current_at(&entry.runtime, &entry.version)?;
+            let artifact =
+                ArtifactRecord::load(&self.root_dir().join(&entry.path).join(ARTIFACT_RECORD));
+            if let Some(artifact) = artifact {
+                if self.locked_version(&entry.runtime)?.is_none() {
+                    self.record_lock(&entry.runtime, &entry.version, &artifact)?;
+                }
+            }
+            self.append_audit("runtime.bundle.install", &entry.runtime, &entry.version)?;
+        }
+
+        Ok(BundleInstall {
+            manifest,
+            images_loaded,
+        })
+    }
+}
+
+impl RuntimeInstaller {
+    /// Moves each staged path over its place in `~/.clawden`, parking what
+    /// was there under `staging`. If any move fails, the ones already made
+    /// are undone and the parked entries put back.
+    fn move_into_place<'p>(
+        &self,
+        staging: &Path,
+        paths: impl Iterator<Item = &'p str>,
+    ) -> Result<()> {
+        let parked_root = staging.join(".replaced");
+        let mut moved: Vec<(&str, bool)> = Vec::new();
+        for path in paths {
+            let dest = self.root_dir().join(path);
+            match swap_in(&staging.join(path), &dest, &parked_root.join(path)) {
+                Ok(had_entry) => moved.push((path, had_entry)),
+                Err(e) => {
+                    for (path, had_entry) in moved.into_iter().rev() {
+                        let dest = self.root_dir().join(path);
+                        let _ = fs::rename(&dest, staging.join(path));
+                        if had_entry {
+                            let _ = fs::rename(parked_root.join(path), &dest);
+                        }
+                    }
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BundleBuilder<'_> {
+    /// Adds `runtimes/<runtime>/<version>` and, when present, its download
+    /// cache entry.
+    pub fn add_runtime(&mut self, runtime: &str, version: &str) -> Result<()> {
+        let path = format!("runtimes/{runtime}/{version}");
+        let dir = self.installer.root_dir().join(&path);
+        if !dir.join(runtime).exists() {
+            bail!("{runtime}@{version} is not installed");
+        }
+        self.manifest.runtimes.push(BundledRuntime {
+            runtime: runtime.to_string(),
+            version: version.to_string(),
+            digest: tree_digest(&dir)?,
+            path,
+        });
+        let cache = format!("cache/downloads/{runtime}/{version}");
+        if self.installer.root_dir().join(&cache).is_dir() {
+            self.add_tree(&cache)?;
+        }
+        Ok(())
+    }
+
+    /// Adds a directory under `~/.clawden`, e.g. `tools/<name>`.
+    pub fn add_tree(&mut self, path: &str) -> Result<()> {
+        ensure_relative(path)?;
+        let dir = self.installer.root_dir().join(path);
+        if !dir.is_dir() {
+            bail!("{} does not exist", dir.display());
+        }
+        self.manifest.files.push(BundledTree {
+            path: path.to_string(),
+            digest: tree_digest(&dir)?,
+        });
+        Ok(())
+    }
+
+    /// Adds a local Docker image via `docker save`.
+    pub fn add_image(&mut self, image: &str) -> Result<()> {
+        let file_name = image
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect::<String>();
+        let path = format!("images/{file_name}.tar");
+        let output = Command::new("docker")
+            .arg("save")
+            .arg("-o")
+            .arg(self.staging.join(&path))
+            .arg(image)
+            .output()
+            .context("failed to run docker save")?;
+        if !output.status.success() {
+            bail!(
+                "docker save failed for {image}: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        self.manifest.images.push(BundledImage {
+            image: image.to_string(),
+            digest: file_digest_like(&self.staging.join(&path), "sha256:")?,
+            path,
+        });
+        Ok(())
+    }
+
+    pub fn manifest(&self) -> &BundleManifest {
+        &self.manifest
+    }
+
+    /// Writes the archive to `output`.
+    pub fn write(self, output: &Path) -> Result<BundleManifest> {
+        let result = self.write_archive(output);
+        let _ = fs::remove_dir_all(&self.staging);
+        result.map(|()| self.manifest)
+    }
+
+    fn write_archive(&self, output: &Path) -> Result<()> {
+        fs::write(
+            self.staging.join(BUNDLE_MANIFEST),
+            serde_json::to_vec_pretty(&self.manifest)?,
+        )?;
+        let mut command = Command::new("tar");
+        command
+            .arg("-czf")
+            .arg(output)
+            .arg("-C")
+            .arg(&self.staging)
+            .arg(BUNDLE_MANIFEST)
+            .arg("images")
+            .arg("-C")
+            .arg(self.installer.root_dir());
+        for entry in &self.manifest.runtimes {
+            command.arg(&entry.path);
+        }
+        for entry in &self.manifest.files {
+            command.arg(&entry.path);
+        }
+        run_tar(&mut command, output).map(|_| ())
+    }
+}
+
+/// Renames `staged` to `dest`, first parking an existing `dest` at
+/// `parked`. Returns whether something was parked.
+fn swap_in(staged: &Path, dest: &Path, parked: &Path) -> Result<bool> {
+    let had_entry = dest.exists() || dest.is_symlink();
+    if had_entry {
+        if let Some(parent) = parked.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(dest, parked)
+            .with_context(|| format!("failed to replace {}", dest.display()))?;
+    }
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if let Err(e) = fs::rename(staged, dest) {
+        if had_entry {
+            let _ = fs::rename(parked, dest);
+        }
+        return Err(e).with_context(|| format!("failed to install {}", dest.display()));
+    }
+    Ok(had_entry)
+}
+
+fn verify(
+    staging: &Path,
+    path: &str,
+    expected: &str,
+    digest: impl Fn(&Path) -> Result<String>,
+) -> Result<()> {
+    let full = staging.join(path);
+    if !full.exists() {
+        bail!("bundle is missing {path}");
+    }
+    let actual = digest(&full)?;
+    if actual != expected {
+        bail!("digest mismatch for {path} in bundle: expected {expected}, got {actual}");
+    }
+    Ok(())
+}
+
+/// Rejects absolute paths and `..` so bundle entries stay inside
+/// `~/.clawden`.
+fn ensure_relative(path: &str) -> Result<()> {
+    let valid = !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !valid {
+        bail!("invalid bundle path '{path}'");
+    }
+    Ok(())
+}
+
+fn run_tar(command: &mut Command, archive: &Path) -> Result<String> {
+    let output = command
+        .output()
+        .context("failed to run tar (is it installed?)")?;
+    if !output.status.success() {
+        bail!(
+            "tar failed for {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ensure_relative;
+
+    #[test]
+    fn bundle_paths_must_stay_relative() {
+        assert!(ensure_relative("runtimes/zeroclaw/1.0.0").is_ok());
+        assert!(ensure_relative("./bundle.json").is_ok());
+        assert!(ensure_relative("/etc/passwd").is_err());
+        assert!(ensure_relative("tools/../../.ssh").is_err());
+        assert!(ensure_relative("").is_err());
+    }
+}
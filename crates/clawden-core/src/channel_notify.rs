@@ -0,0 +1,138 @@
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::{ChannelInstanceConfig, ChannelNotifier, ChannelType};
+
+const NOTIFY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Posts hook messages through each channel instance's own API.
+///
+/// A `webhook_url` on the instance takes precedence for every channel type.
+/// Otherwise Telegram, Slack and Discord are reached with the bot token and
+/// the target chat in `chat_id`/`channel` (or the first allowlisted user or
+/// channel). `api_base` overrides the provider's API host.
+pub struct HttpChannelNotifier {
+    client: reqwest::Client,
+}
+
+impl HttpChannelNotifier {
+    pub fn new() -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(NOTIFY_TIMEOUT)
+            .build()
+            .context("failed to build channel notification client")?;
+        Ok(Self { client })
+    }
+
+    async fn post(&self, request: reqwest::RequestBuilder, what: &str) -> Result<Value> {
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("{what} request failed"))?;
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        if !status.is_success() {
+            bail!("{what} returned HTTP {status}: {}", body.trim());
+        }
+        Ok(serde_json::from_str(&body).unwrap_or(Value::Null))
+    }
+}
+
+#[async_trait]
+impl ChannelNotifier for HttpChannelNotifier {
+    async fn notify(&self, channel: &ChannelInstanceConfig, message: &str) -> Result<()> {
+        let name = &channel.instance_name;
+        if let Some(url) = setting(channel, "webhook_url") {
+            let body = json!({ "text": message, "content": message });
+            self.post(self.client.post(url).json(&body), "webhook")
+                .await?;
+            return Ok(());
+        }
+
+        let token = ["bot_token", "token"]
+            .into_iter()
+            .find_map(|key| setting(channel, key));
+        match channel.channel_type {
+            ChannelType::Telegram => {
+                let token =
+                    token.ok_or_else(|| anyhow!("telegram channel '{name}' has no token"))?;
+                let chat_id = setting(channel, "chat_id")
+                    .or_else(|| first_listed(channel, "allowed_users"))
+                    .ok_or_else(|| anyhow!("telegram channel '{name}' has no chat_id"))?;
+                let base = setting(channel, "api_base").unwrap_or("https://api.telegram.org");
+                let url = format!("{}/bot{token}/sendMessage", base.trim_end_matches('/'));
+                let body = json!({ "chat_id": chat_id, "text": message });
+                self.post(self.client.post(url).json(&body), "telegram sendMessage")
+                    .await?;
+            }
+            ChannelType::Slack => {
+                let token =
+                    token.ok_or_else(|| anyhow!("slack channel '{name}' has no bot_token"))?;
+                let target = setting(channel, "channel")
+                    .or_else(|| first_listed(channel, "allowed_channels"))
+                    .ok_or_else(|| anyhow!("slack channel '{name}' has no channel"))?;
+                let base = setting(channel, "api_base").unwrap_or("https://slack.com/api");
+                let url = format!("{}/chat.postMessage", base.trim_end_matches('/'));
+                let body = json!({ "channel": target, "text": message });
+                let reply = self
+                    .post(
+                        self.client.post(url).bearer_auth(token).json(&body),
+                        "slack chat.postMessage",
+                    )
+                    .await?;
+                // Slack reports API errors with HTTP 200 and `ok: false`.
+                if reply.get("ok").and_then(Value::as_bool) == Some(false) {
+                    let error = reply
+                        .get("error")
+                        .and_then(Value::as_str)
+                        .unwrap_or("unknown");
+                    bail!("slack chat.postMessage failed: {error}");
+                }
+            }
+            ChannelType::Discord => {
+                let token =
+                    token.ok_or_else(|| anyhow!("discord channel '{name}' has no token"))?;
+                let target = setting(channel, "channel")
+                    .or_else(|| first_listed(channel, "allowed_channels"))
+                    .ok_or_else(|| anyhow!("discord channel '{name}' has no channel"))?;
+                let base = setting(channel, "api_base").unwrap_or("https://discord.com/api/v10");
+                let url = format!("{}/channels/{target}/messages", base.trim_end_matches('/'));
+                let body = json!({ "content": message });
+                self.post(
+                    self.client
+                        .post(url)
+                        .header("Authorization", format!("Bot {token}"))
+                        .json(&body),
+                    "discord create message",
+                )
+                .await?;
+            }
+            ref other => bail!("{other} channel '{name}' needs a webhook_url to receive hooks"),
+        }
+        Ok(())
+    }
+}
+
+/// A non-empty string setting from the instance's credentials or options.
+fn setting<'a>(channel: &'a ChannelInstanceConfig, key: &str) -> Option<&'a str> {
+    channel
+        .credentials
+        .get(key)
+        .map(String::as_str)
+        .or_else(|| channel.options.get(key).and_then(Value::as_str))
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn first_listed<'a>(channel: &'a ChannelInstanceConfig, key: &str) -> Option<&'a str> {
+    channel
+        .options
+        .get(key)?
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .map(str::trim)
+        .find(|value| !value.is_empty() && *value != "*")
+}
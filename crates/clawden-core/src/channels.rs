@@ -96,6 +96,13 @@ impl ChannelStore {
         self.configs.remove(instance_name).is_some()
     }
 
+    /// Every configured channel instance, ordered by name.
+    pub fn list_configs(&self) -> Vec<ChannelInstanceConfig> {
+        let mut configs: Vec<_> = self.configs.values().cloned().collect();
+        configs.sort_by(|a, b| a.instance_name.cmp(&b.instance_name));
+        configs
+    }
+
     pub fn list_configs_by_type(&self, channel_type: &ChannelType) -> Vec<&ChannelInstanceConfig> {
         self.configs
             .values()
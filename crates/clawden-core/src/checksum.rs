@@ -0,0 +1,185 @@
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
+
+/// Provenance of a downloaded or installed runtime artifact. Stored next to
+/// cache entries and in each installed version directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactRecord {
+    pub url: String,
+    /// `sha256:<hex>` for release assets, npm's `sha512-<base64>` integrity
+    /// for packages, or `commit:<sha>` for git sources.
+    pub digest: String,
+    /// Whether the digest was checked against a published checksum rather
+    /// than only computed locally.
+    pub verified: bool,
+}
+
+impl ArtifactRecord {
+    pub(crate) fn load(path: &Path) -> Option<Self> {
+        serde_json::from_slice(&fs::read(path).ok()?).ok()
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+            .with_context(|| format!("failed to record digest at {}", path.display()))
+    }
+}
+
+/// Hashes `path` with the algorithm `digest` is written in, so the result
+/// compares directly against it. Anything that is not an SRI `sha512-`
+/// value is treated as sha256.
+pub(crate) fn file_digest_like(path: &Path, digest: &str) -> Result<String> {
+    if digest.starts_with("sha512-") {
+        let bytes = hash_file::<Sha512>(path)?;
+        Ok(format!(
+            "sha512-{}",
+            base64::engine::general_purpose::STANDARD.encode(bytes)
+        ))
+    } else {
+        Ok(format!("sha256:{:x}", hash_file::<Sha256>(path)?))
+    }
+}
+
+fn hash_file<D: Digest>(path: &Path) -> Result<sha2::digest::Output<D>> {
+    let mut file =
+        File::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+    let mut hasher = D::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buf[..read]);
+    }
+    Ok(hasher.finalize())
+}
+
+/// Digest of a directory tree: every file's relative path with its content
+/// hash (symlinks with their target), in sorted order.
+pub(crate) fn tree_digest(dir: &Path) -> Result<String> {
+    let mut entries = Vec::new();
+    collect_tree(dir, dir, &mut entries)?;
+    entries.sort();
+    let mut hasher = Sha256::new();
+    for (path, digest) in entries {
+        hasher.update(path.as_bytes());
+        hasher.update(b"\0");
+        hasher.update(digest.as_bytes());
+        hasher.update(b"\n");
+    }
+    Ok(format!("sha256:{:x}", hasher.finalize()))
+}
+
+fn collect_tree(root: &Path, dir: &Path, entries: &mut Vec<(String, String)>) -> Result<()> {
+    for entry in fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let meta = fs::symlink_metadata(&path)?;
+        let relative = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .to_string_lossy()
+            .into_owned();
+        if meta.file_type().is_symlink() {
+            let target = fs::read_link(&path)?;
+            entries.push((relative, format!("link:{}", target.display())));
+        } else if meta.is_dir() {
+            collect_tree(root, &path, entries)?;
+        } else {
+            entries.push((relative, format!("{:x}", hash_file::<Sha256>(&path)?)));
+        }
+    }
+    Ok(())
+}
+
+/// Finds the sha256 for `artifact_name` in a `checksums.txt`/`SHA256SUMS`
+/// style listing (`<hex>  <name>`, optionally `*<name>` for binary mode).
+/// A bare `.sha256` file holding just the hex matches any name.
+pub(crate) fn parse_checksum_listing(body: &str, artifact_name: &str) -> Result<Option<String>> {
+    for line in body.lines() {
+        let mut parts = line.split_whitespace();
+        let Some(hex) = parts.next() else {
+            continue;
+        };
+        let matches = match parts.next() {
+            Some(name) => {
+                let name = name.trim_start_matches('*');
+                name == artifact_name || name.rsplit('/').next() == Some(artifact_name)
+            }
+            None => true,
+        };
+        if matches {
+            if hex.len() != 64 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                bail!("malformed sha256 checksum for {artifact_name}: {hex}");
+            }
+            return Ok(Some(format!("sha256:{}", hex.to_ascii_lowercase())));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{file_digest_like, parse_checksum_listing, tree_digest};
+    use std::fs;
+
+    #[test]
+    fn checksum_listing_matches_artifact_names() {
+        let hex = "a".repeat(64);
+        let other = "b".repeat(64);
+        let body = format!("{other}  tool-x86_64.tar.gz\n{hex} *tool-aarch64.tar.gz\n");
+        assert_eq!(
+            parse_checksum_listing(&body, "tool-aarch64.tar.gz").expect("parse listing"),
+            Some(format!("sha256:{hex}"))
+        );
+        assert_eq!(
+            parse_checksum_listing(&body, "tool.zip").expect("parse listing"),
+            None
+        );
+        assert_eq!(
+            parse_checksum_listing(&format!("{}\n", hex.to_uppercase()), "any")
+                .expect("parse bare digest"),
+            Some(format!("sha256:{hex}"))
+        );
+        assert!(parse_checksum_listing("xyz  tool.zip\n", "tool.zip").is_err());
+    }
+
+    #[test]
+    fn file_digest_follows_the_expected_algorithm() {
+        let path = std::env::temp_dir().join(format!("clawden-digest-{}", std::process::id()));
+        fs::write(&path, b"hello\n").expect("write file");
+        assert_eq!(
+            file_digest_like(&path, "sha256:").expect("hash file"),
+            "sha256:5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03"
+        );
+        assert_eq!(
+            file_digest_like(&path, "sha512-").expect("hash file"),
+            "sha512-58IrmUxZ2c8rSOVJseJGZmNgRZMNPafBrLKZ0cO3+TH5Sq5B7dosKyB6NuEPi8uNRSI+VIePWzFufOO2vAGWKQ=="
+        );
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn tree_digest_covers_paths_contents_and_links() {
+        let dir = std::env::temp_dir().join(format!("clawden-tree-{}", std::process::id()));
+        fs::create_dir_all(dir.join("bin")).expect("create bin dir");
+        fs::write(dir.join("bin/tool"), b"v1").expect("write tool");
+        std::os::unix::fs::symlink("bin/tool", dir.join("tool")).expect("link tool");
+        let first = tree_digest(&dir).expect("digest tree");
+        assert_eq!(tree_digest(&dir).expect("digest tree"), first);
+
+        fs::write(dir.join("bin/tool"), b"v2").expect("rewrite tool");
+        let changed = tree_digest(&dir).expect("digest tree");
+        assert_ne!(changed, first);
+
+        fs::remove_file(dir.join("tool")).expect("remove link");
+        std::os::unix::fs::symlink("bin", dir.join("tool")).expect("relink tool");
+        assert_ne!(tree_digest(&dir).expect("digest tree"), changed);
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,496 @@
+//! User-defined runtimes declared in `~/.clawden/runtimes.d/*.toml` or under
+//! `custom_runtimes:` in `clawden.yaml`.
+//!
+//! A [`CustomRuntimeSpec`] carries the same fields as a built-in
+//! [`RuntimeDescriptor`]. Registering it leaks the descriptor so lookups keep
+//! handing out `&'static` references; specs are registered once at startup.
+
+use crate::runtime_descriptor::{
+    ConfigDirFlag, ConfigFormat, InstallSource, MessageApi, MessageApiFormat, PortSetting,
+    RuntimeDescriptor, VersionSource, DESCRIPTORS,
+};
+use crate::ClawRuntime;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::sync::RwLock;
+
+/// Directory under `~/.clawden` holding one TOML file per custom runtime.
+pub const CUSTOM_RUNTIMES_DIR: &str = "runtimes.d";
+
+static CUSTOM_DESCRIPTORS: RwLock<Vec<&'static RuntimeDescriptor>> = RwLock::new(Vec::new());
+
+/// Declaration of a custom runtime. `slug` defaults to the file name when
+/// loaded from `runtimes.d`; a runtime with an `install_source` can be
+/// installed and run directly.
+///
+/// ```toml
+/// slug = "myclaw"
+/// display_name = "MyClaw"
+/// default_start_args = ["serve"]
+/// config_format = "toml"
+/// health_port = 9000
+/// image = "ghcr.io/example/myclaw:latest"
+/// extra_env_vars = [["MYCLAW_HOME", "MyClaw data directory"]]
+///
+/// [install_source]
+/// type = "github-release"
+/// owner = "example"
+/// repo = "myclaw"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRuntimeSpec {
+    #[serde(default)]
+    pub slug: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(default)]
+    pub workspace_path: Option<String>,
+    #[serde(default)]
+    pub install_source: Option<InstallSourceSpec>,
+    /// Defaults to the release, package or repository of `install_source`.
+    #[serde(default)]
+    pub version_source: Option<VersionSourceSpec>,
+    #[serde(default)]
+    pub default_start_args: Vec<String>,
+    #[serde(default)]
+    pub subcommand_hints: Vec<(String, String)>,
+    #[serde(default = "default_config_format")]
+    pub config_format: ConfigFormat,
+    #[serde(default)]
+    pub supports_config_dir: bool,
+    #[serde(default)]
+    pub config_dir_flag: ConfigDirFlagSpec,
+    #[serde(default)]
+    pub has_onboard_command: bool,
+    #[serde(default)]
+    pub health_port: Option<u16>,
+    /// Start flag that sets the listen port (e.g. `--port`); without it the
+    /// runtime only gets the port through `CLAWDEN_PORT`.
+    #[serde(default)]
+    pub port_flag: Option<String>,
+    /// Docker image for container mode (e.g. `ghcr.io/example/myclaw:1.2`);
+    /// defaults to `ghcr.io/codervisor/<slug>:latest`.
+    #[serde(default)]
+    pub image: Option<String>,
+    #[serde(default = "default_cost_tier")]
+    pub cost_tier: u8,
+    /// `(section, key, value)` entries written when the config lacks them.
+    #[serde(default)]
+    pub required_config_defaults: Vec<(String, String, String)>,
+    /// `(name, description)` of env vars the runtime reads.
+    #[serde(default)]
+    pub extra_env_vars: Vec<(String, String)>,
+    #[serde(default)]
+    pub message_api: Option<MessageApiSpec>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum InstallSourceSpec {
+    GithubRelease {
+        owner: String,
+        repo: String,
+        #[serde(default = "default_archive_ext")]
+        archive_ext: String,
+    },
+    Npm {
+        package: String,
+    },
+    GitClone {
+        url: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum VersionSourceSpec {
+    GithubLatest { owner: String, repo: String },
+    Npm { package: String },
+    GitHead { url: String },
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case", deny_unknown_fields)]
+pub enum ConfigDirFlagSpec {
+    #[default]
+    ConfigDir,
+    ConfigFile {
+        filename: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MessageApiSpec {
+    pub path: String,
+    pub format: MessageApiFormat,
+}
+
+fn default_config_format() -> ConfigFormat {
+    ConfigFormat::None
+}
+
+fn default_cost_tier() -> u8 {
+    2
+}
+
+fn default_archive_ext() -> String {
+    ".tar.gz".to_string()
+}
+
+/// Reads every `*.toml` file in `dir`, in file name order. A missing
+/// directory yields no specs.
+pub fn load_custom_runtime_specs(dir: &Path) -> Result<Vec<CustomRuntimeSpec>> {
+    let mut paths = match fs::read_dir(dir) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect::<Vec<_>>(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", dir.display())),
+    };
+    paths.sort();
+
+    let mut specs = Vec::new();
+    for path in paths {
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let mut spec: CustomRuntimeSpec =
+            toml::from_str(&raw).with_context(|| format!("invalid {}", path.display()))?;
+        if spec.slug.is_empty() {
+            if let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) {
+                spec.slug = stem.to_string();
+            }
+        }
+        specs.push(spec);
+    }
+    Ok(specs)
+}
+
+/// Makes `spec` resolvable through the descriptor lookups, replacing an
+/// earlier custom runtime with the same slug. Built-in slugs and aliases
+/// cannot be redefined.
+pub fn register_custom_runtime(spec: &CustomRuntimeSpec) -> Result<&'static RuntimeDescriptor> {
+    let slug = spec.slug.trim().to_ascii_lowercase();
+    let aliases = spec
+        .aliases
+        .iter()
+        .map(|alias| alias.trim().to_ascii_lowercase())
+        .collect::<Vec<_>>();
+    for name in std::iter::once(&slug).chain(&aliases) {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            bail!("invalid custom runtime name '{name}' (use letters, digits, '-' or '_')");
+        }
+        if DESCRIPTORS
+            .iter()
+            .any(|d| d.slug == name || d.aliases.contains(&name.as_str()))
+        {
+            bail!("custom runtime '{slug}' conflicts with built-in runtime '{name}'");
+        }
+    }
+
+    let mut registered = CUSTOM_DESCRIPTORS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    for name in std::iter::once(&slug).chain(&aliases) {
+        if let Some(other) = registered
+            .iter()
+            .find(|d| d.slug != slug && (d.slug == name || d.aliases.contains(&name.as_str())))
+        {
+            bail!(
+                "custom runtime '{slug}' conflicts with custom runtime '{}'",
+                other.slug
+            );
+        }
+    }
+
+    let descriptor: &'static RuntimeDescriptor = Box::leak(Box::new(build_descriptor(
+        spec,
+        leak_str(slug.clone()),
+        aliases,
+    )));
+    registered.retain(|d| d.slug != slug);
+    registered.push(descriptor);
+    Ok(descriptor)
+}
+
+/// Custom runtimes registered so far, in registration order.
+pub fn custom_runtime_descriptors() -> Vec<&'static RuntimeDescriptor> {
+    CUSTOM_DESCRIPTORS
+        .read()
+        .map(|registered| registered.clone())
+        .unwrap_or_default()
+}
+
+fn build_descriptor(
+    spec: &CustomRuntimeSpec,
+    slug: &'static str,
+    aliases: Vec<String>,
+) -> RuntimeDescriptor {
+    let install_source = match &spec.install_source {
+        Some(InstallSourceSpec::GithubRelease {
+            owner,
+            repo,
+            archive_ext,
+        }) => InstallSource::GithubRelease {
+            owner: leak_str(owner.clone()),
+            repo: leak_str(repo.clone()),
+            archive_ext: leak_str(archive_ext.clone()),
+        },
+        Some(InstallSourceSpec::Npm { package }) => InstallSource::Npm {
+            package: leak_str(package.clone()),
+        },
+        Some(InstallSourceSpec::GitClone { url }) => InstallSource::GitClone {
+            url: leak_str(url.clone()),
+        },
+        None => InstallSource::NotAvailable,
+    };
+    let version_source = match (&spec.version_source, &install_source) {
+        (Some(VersionSourceSpec::GithubLatest { owner, repo }), _) => VersionSource::GithubLatest {
+            owner: leak_str(owner.clone()),
+            repo: leak_str(repo.clone()),
+        },
+        (Some(VersionSourceSpec::Npm { package }), _) => VersionSource::Npm {
+            package: leak_str(package.clone()),
+        },
+        (Some(VersionSourceSpec::GitHead { url }), _) => VersionSource::GitHead {
+            url: leak_str(url.clone()),
+        },
+        (None, InstallSource::GithubRelease { owner, repo, .. }) => {
+            VersionSource::GithubLatest { owner, repo }
+        }
+        (None, InstallSource::Npm { package }) => VersionSource::Npm { package },
+        (None, InstallSource::GitClone { url }) => VersionSource::GitHead { url },
+        (None, InstallSource::NotAvailable) => VersionSource::NotAvailable,
+    };
+
+    RuntimeDescriptor {
+        runtime: ClawRuntime::Custom(slug.to_string()),
+        slug,
+        display_name: leak_str(
+            spec.display_name
+                .clone()
+                .unwrap_or_else(|| slug.to_string()),
+        ),
+        aliases: leak_slice(aliases.into_iter().map(leak_str).collect()),
+        workspace_path: spec.workspace_path.clone().map(leak_str),
+        direct_install_supported: install_source != InstallSource::NotAvailable,
+        install_source,
+        version_source,
+        default_start_args: leak_slice(
+            spec.default_start_args
+                .iter()
+                .cloned()
+                .map(leak_str)
+                .collect(),
+        ),
+        subcommand_hints: leak_slice(
+            spec.subcommand_hints
+                .iter()
+                .map(|(name, hint)| (leak_str(name.clone()), leak_str(hint.clone())))
+                .collect(),
+        ),
+        config_format: spec.config_format,
+        supports_config_dir: spec.supports_config_dir,
+        config_dir_flag: match &spec.config_dir_flag {
+            ConfigDirFlagSpec::ConfigDir => ConfigDirFlag::ConfigDir,
+            ConfigDirFlagSpec::ConfigFile { filename } => ConfigDirFlag::ConfigFile {
+                filename: leak_str(filename.clone()),
+            },
+        },
+        has_onboard_command: spec.has_onboard_command,
+        health_port: spec.health_port,
+        port_setting: match &spec.port_flag {
+            Some(flag) => PortSetting::Flag(leak_str(flag.clone())),
+            None => PortSetting::Env,
+        },
+        image: spec.image.clone().map(leak_str),
+        cost_tier: spec.cost_tier,
+        required_config_defaults: leak_slice(
+            spec.required_config_defaults
+                .iter()
+                .map(|(section, key, value)| {
+                    (
+                        leak_str(section.clone()),
+                        leak_str(key.clone()),
+                        leak_str(value.clone()),
+                    )
+                })
+                .collect(),
+        ),
+        extra_env_vars: leak_slice(
+            spec.extra_env_vars
+                .iter()
+                .map(|(name, description)| (leak_str(name.clone()), leak_str(description.clone())))
+                .collect(),
+        ),
+        model_transform: None,
+        message_api: spec.message_api.as_ref().map(|api| MessageApi {
+            path: leak_str(api.path.clone()),
+            format: api.format,
+        }),
+    }
+}
+
+fn leak_str(value: String) -> &'static str {
+    Box::leak(value.into_boxed_str())
+}
+
+fn leak_slice<T>(values: Vec<T>) -> &'static [T] {
+    Box::leak(values.into_boxed_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_custom_runtime_specs, register_custom_runtime, CustomRuntimeSpec};
+    use crate::{
+        direct_install_descriptors, runtime_descriptor, ClawRuntime, ConfigFormat, InstallSource,
+        VersionSource,
+    };
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "clawden-custom-runtime-{name}-{}",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).expect("create runtimes.d");
+        dir
+    }
+
+    #[test]
+    fn runtimes_d_specs_register_as_descriptors() {
+        let dir = temp_dir("load");
+        std::fs::write(
+            dir.join("testclaw.toml"),
+            r#"
+display_name = "TestClaw"
+aliases = ["tc"]
+default_start_args = ["serve", "--quiet"]
+config_format = "json"
+health_port = 9911
+extra_env_vars = [["TESTCLAW_HOME", "TestClaw data directory"]]
+message_api = { path = "/chat", format = "webhook" }
+
+[install_source]
+type = "github-release"
+owner = "example"
+repo = "testclaw"
+"#,
+        )
+        .expect("write runtime spec");
+        std::fs::write(dir.join("notes.txt"), "ignored").expect("write stray file");
+
+        let specs = load_custom_runtime_specs(&dir).expect("load specs");
+        assert_eq!(specs.len(), 1);
+        assert_eq!(specs[0].slug, "testclaw");
+        register_custom_runtime(&specs[0]).expect("register runtime");
+
+        let descriptor = runtime_descriptor("tc@work").expect("alias should resolve");
+        assert_eq!(
+            descriptor.runtime,
+            ClawRuntime::Custom("testclaw".to_string())
+        );
+        assert_eq!(descriptor.display_name, "TestClaw");
+        assert_eq!(descriptor.default_start_args, ["serve", "--quiet"]);
+        assert_eq!(descriptor.config_format, ConfigFormat::Json);
+        assert_eq!(
+            descriptor.message_url().as_deref(),
+            Some("http://127.0.0.1:9911/chat")
+        );
+        assert_eq!(
+            descriptor.install_source,
+            InstallSource::GithubRelease {
+                owner: "example",
+                repo: "testclaw",
+                archive_ext: ".tar.gz"
+            }
+        );
+        assert_eq!(
+            descriptor.version_source,
+            VersionSource::GithubLatest {
+                owner: "example",
+                repo: "testclaw"
+            }
+        );
+        assert!(direct_install_descriptors().any(|d| d.slug == "testclaw"));
+
+        let runtime = ClawRuntime::from_str_loose("testclaw").expect("parse custom slug");
+        assert_eq!(runtime.as_slug(), "testclaw");
+        assert_eq!(runtime.to_string(), "TestClaw");
+        assert_eq!(
+            serde_json::to_value(&runtime).expect("serialize runtime"),
+            "testclaw"
+        );
+        assert_eq!(
+            serde_json::from_value::<ClawRuntime>("testclaw".into())
+                .expect("deserialize custom slug"),
+            runtime
+        );
+        assert_eq!(
+            serde_json::from_value::<ClawRuntime>("zero-claw".into()).expect("deserialize alias"),
+            ClawRuntime::ZeroClaw
+        );
+        assert_eq!(
+            serde_json::from_value::<ClawRuntime>("zeroclaw".into()).expect("deserialize slug"),
+            ClawRuntime::ZeroClaw
+        );
+        let err = serde_json::from_value::<ClawRuntime>("undeclaredclaw".into()).unwrap_err();
+        assert!(err.to_string().contains("unknown runtime"), "{err}");
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn custom_runtimes_cannot_shadow_built_ins() {
+        let spec = |slug: &str, aliases: &[&str]| CustomRuntimeSpec {
+            aliases: aliases.iter().map(ToString::to_string).collect(),
+            ..toml::from_str(&format!("slug = \"{slug}\"")).expect("parse spec")
+        };
+        let err = register_custom_runtime(&spec("zeroclaw", &[])).unwrap_err();
+        assert!(err.to_string().contains("built-in"), "{err}");
+        let err = register_custom_runtime(&spec("shadowclaw", &["zero"])).unwrap_err();
+        assert!(err.to_string().contains("built-in"), "{err}");
+        assert!(register_custom_runtime(&spec("bad@name", &[])).is_err());
+
+        let descriptor =
+            register_custom_runtime(&spec("plainclaw", &[])).expect("register runtime");
+        assert!(!descriptor.direct_install_supported);
+        assert_eq!(descriptor.version_source, VersionSource::NotAvailable);
+        assert_eq!(descriptor.config_format, ConfigFormat::None);
+        assert!(runtime_descriptor("zeroclaw").is_some_and(|d| d.runtime == ClawRuntime::ZeroClaw));
+    }
+
+    #[test]
+    fn custom_runtimes_can_name_their_docker_image() {
+        let spec: CustomRuntimeSpec = toml::from_str(
+            "slug = \"imageclaw\"\nimage = \"registry.example:5000/team/imageclaw:1.2\"",
+        )
+        .expect("parse spec");
+        let descriptor = register_custom_runtime(&spec).expect("register runtime");
+        assert_eq!(
+            descriptor.image_ref(None),
+            "registry.example:5000/team/imageclaw:1.2"
+        );
+        assert_eq!(
+            descriptor.image_ref(Some("browser")),
+            "registry.example:5000/team/imageclaw:browser"
+        );
+
+        let plain = toml::from_str("slug = \"noimageclaw\"").expect("parse spec");
+        let descriptor = register_custom_runtime(&plain).expect("register runtime");
+        assert_eq!(
+            descriptor.image_ref(None),
+            "ghcr.io/codervisor/noimageclaw:latest"
+        );
+    }
+}
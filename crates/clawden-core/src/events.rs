@@ -0,0 +1,305 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+
+use anyhow::{bail, Result};
+use futures_core::Stream;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+use crate::current_unix_ms;
+
+/// Buffered events per subscriber before new ones are dropped.
+const EVENT_STREAM_CAPACITY: usize = 1024;
+
+/// A single event emitted by a running agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuntimeEvent {
+    Log {
+        stream: String,
+        line: String,
+        timestamp_ms: u64,
+    },
+    MessageIn {
+        content: String,
+        timestamp_ms: u64,
+    },
+    MessageOut {
+        content: String,
+        timestamp_ms: u64,
+    },
+    StateChanged {
+        state: String,
+        timestamp_ms: u64,
+    },
+}
+
+impl RuntimeEvent {
+    pub fn log(stream: &str, line: impl Into<String>) -> Self {
+        Self::Log {
+            stream: stream.to_string(),
+            line: line.into(),
+            timestamp_ms: current_unix_ms(),
+        }
+    }
+
+    pub fn message_in(content: impl Into<String>) -> Self {
+        Self::MessageIn {
+            content: content.into(),
+            timestamp_ms: current_unix_ms(),
+        }
+    }
+
+    pub fn message_out(content: impl Into<String>) -> Self {
+        Self::MessageOut {
+            content: content.into(),
+            timestamp_ms: current_unix_ms(),
+        }
+    }
+
+    pub fn state_changed(state: impl Into<String>) -> Self {
+        Self::StateChanged {
+            state: state.into(),
+            timestamp_ms: current_unix_ms(),
+        }
+    }
+
+    pub fn kind(&self) -> RuntimeEventKind {
+        match self {
+            Self::Log { .. } => RuntimeEventKind::Log,
+            Self::MessageIn { .. } | Self::MessageOut { .. } => RuntimeEventKind::Message,
+            Self::StateChanged { .. } => RuntimeEventKind::State,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RuntimeEventKind {
+    Log,
+    Message,
+    State,
+}
+
+impl RuntimeEventKind {
+    pub const ALL: [RuntimeEventKind; 3] = [Self::Log, Self::Message, Self::State];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Log => "log",
+            Self::Message => "message",
+            Self::State => "state",
+        }
+    }
+}
+
+/// Which event kinds a subscriber wants, parsed from the `event` argument of
+/// `ClawAdapter::subscribe`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventFilter {
+    log: bool,
+    message: bool,
+    state: bool,
+}
+
+impl EventFilter {
+    pub fn all() -> Self {
+        Self {
+            log: true,
+            message: true,
+            state: true,
+        }
+    }
+
+    /// Accepts `""`, `"*"` or `"all"` for every kind, otherwise a
+    /// comma-separated list of `log`, `message` and `state`.
+    pub fn parse(event: &str) -> Result<Self> {
+        let event = event.trim();
+        if event.is_empty() || event == "*" || event.eq_ignore_ascii_case("all") {
+            return Ok(Self::all());
+        }
+
+        let mut filter = Self {
+            log: false,
+            message: false,
+            state: false,
+        };
+        for part in event.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            match part.to_ascii_lowercase().as_str() {
+                "log" | "logs" => filter.log = true,
+                "message" | "messages" => filter.message = true,
+                "state" | "states" => filter.state = true,
+                other => bail!(
+                    "unknown event kind '{other}' (expected one of: {})",
+                    RuntimeEventKind::ALL.map(|k| k.as_str()).join(", ")
+                ),
+            }
+        }
+        Ok(filter)
+    }
+
+    pub fn wants(&self, kind: RuntimeEventKind) -> bool {
+        match kind {
+            RuntimeEventKind::Log => self.log,
+            RuntimeEventKind::Message => self.message,
+            RuntimeEventKind::State => self.state,
+        }
+    }
+}
+
+/// Producer half of an [`EventStream`]. Cheap to clone; each clone feeds the
+/// same subscriber.
+#[derive(Debug, Clone)]
+pub struct EventSender {
+    tx: mpsc::Sender<RuntimeEvent>,
+    filter: EventFilter,
+}
+
+impl EventSender {
+    /// Delivers the event if the subscriber wants it. Events are dropped when
+    /// the subscriber falls behind; returns `false` once it has gone away.
+    pub fn emit(&self, event: RuntimeEvent) -> bool {
+        if !self.filter.wants(event.kind()) {
+            return !self.tx.is_closed();
+        }
+        match self.tx.try_send(event) {
+            Ok(()) | Err(mpsc::error::TrySendError::Full(_)) => true,
+            Err(mpsc::error::TrySendError::Closed(_)) => false,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.tx.is_closed()
+    }
+
+    pub fn filter(&self) -> EventFilter {
+        self.filter
+    }
+}
+
+/// Live stream of [`RuntimeEvent`]s returned by `ClawAdapter::subscribe`.
+///
+/// Ends once every [`EventSender`] feeding it has been dropped.
+#[derive(Debug)]
+pub struct EventStream {
+    rx: mpsc::Receiver<RuntimeEvent>,
+}
+
+impl EventStream {
+    pub fn channel(filter: EventFilter) -> (EventSender, EventStream) {
+        let (tx, rx) = mpsc::channel(EVENT_STREAM_CAPACITY);
+        (EventSender { tx, filter }, EventStream { rx })
+    }
+
+    pub async fn next(&mut self) -> Option<RuntimeEvent> {
+        self.rx.recv().await
+    }
+
+    pub fn try_next(&mut self) -> Option<RuntimeEvent> {
+        self.rx.try_recv().ok()
+    }
+}
+
+impl Stream for EventStream {
+    type Item = RuntimeEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Fans events out to every subscriber of a given agent handle.
+#[derive(Debug, Default)]
+pub struct EventHub {
+    subscribers: Mutex<HashMap<String, Vec<EventSender>>>,
+}
+
+impl EventHub {
+    pub fn subscribe(&self, handle_id: &str, filter: EventFilter) -> (EventSender, EventStream) {
+        let (tx, stream) = EventStream::channel(filter);
+        if let Ok(mut guard) = self.subscribers.lock() {
+            guard
+                .entry(handle_id.to_string())
+                .or_default()
+                .push(tx.clone());
+        }
+        (tx, stream)
+    }
+
+    pub fn publish(&self, handle_id: &str, event: RuntimeEvent) {
+        let Ok(mut guard) = self.subscribers.lock() else {
+            return;
+        };
+        if let Some(senders) = guard.get_mut(handle_id) {
+            senders.retain(|sender| sender.emit(event.clone()));
+            if senders.is_empty() {
+                guard.remove(handle_id);
+            }
+        }
+    }
+
+    /// Drops the hub's senders for a handle so its streams can end.
+    pub fn close(&self, handle_id: &str) {
+        if let Ok(mut guard) = self.subscribers.lock() {
+            guard.remove(handle_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventFilter, EventHub, EventStream, RuntimeEvent, RuntimeEventKind};
+
+    #[test]
+    fn filter_parses_kinds_and_rejects_unknown() {
+        assert_eq!(EventFilter::parse("").unwrap(), EventFilter::all());
+        assert_eq!(EventFilter::parse("*").unwrap(), EventFilter::all());
+
+        let filter = EventFilter::parse("log, state").unwrap();
+        assert!(filter.wants(RuntimeEventKind::Log));
+        assert!(filter.wants(RuntimeEventKind::State));
+        assert!(!filter.wants(RuntimeEventKind::Message));
+
+        assert!(EventFilter::parse("metrics").is_err());
+    }
+
+    #[test]
+    fn sender_skips_unwanted_events() {
+        let (tx, mut stream) = EventStream::channel(EventFilter::parse("message").unwrap());
+        assert!(tx.emit(RuntimeEvent::log("stdout", "ignored")));
+        assert!(tx.emit(RuntimeEvent::message_in("hello")));
+        drop(tx);
+
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime should build");
+        let events = rt.block_on(async {
+            let mut out = Vec::new();
+            while let Some(event) = stream.next().await {
+                out.push(event);
+            }
+            out
+        });
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].kind(), RuntimeEventKind::Message);
+    }
+
+    #[test]
+    fn hub_prunes_dropped_subscribers() {
+        let hub = EventHub::default();
+        let (_tx, mut kept) = hub.subscribe("h1", EventFilter::all());
+        let (_tx2, dropped) = hub.subscribe("h1", EventFilter::all());
+        drop(dropped);
+
+        hub.publish("h1", RuntimeEvent::state_changed("running"));
+        assert_eq!(
+            kept.try_next().map(|event| event.kind()),
+            Some(RuntimeEventKind::State)
+        );
+        assert_eq!(hub.subscribers.lock().unwrap()["h1"].len(), 1);
+
+        hub.publish("other", RuntimeEvent::state_changed("running"));
+        assert!(kept.try_next().is_none());
+    }
+}
@@ -0,0 +1,418 @@
+use anyhow::{anyhow, bail, Result};
+use regex_lite::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(10);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(2);
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+
+/// A runtime's `health:` block. Exactly one probe is set:
+///
+/// ```yaml
+/// health:
+///   http: /healthz            # or a full URL; a path uses the runtime's port
+///   # tcp: 8080               # or "host:port"
+///   # exec: "nc -z localhost 9000"
+///   # log: "listening on"     # healthy once this launch has logged a match
+///   interval: 10s
+///   timeout: 2s
+///   failure_threshold: 3
+///   start_period: 30s
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HealthCheck {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp: Option<TcpTarget>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log: Option<String>,
+    /// How often `ps`, the server and dashboards re-probe, e.g. `10s`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<String>,
+    /// Consecutive failures before the runtime counts as unhealthy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_threshold: Option<u32>,
+    /// Grace period after start during which failures only mean "starting".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum TcpTarget {
+    Port(u16),
+    Address(String),
+}
+
+impl HealthCheck {
+    /// The built-in check for a runtime with a known health endpoint: one
+    /// failed request is enough, as before probes were configurable.
+    pub fn http(url: impl Into<String>) -> Self {
+        Self {
+            http: Some(url.into()),
+            interval: Some("0s".to_string()),
+            failure_threshold: Some(1),
+            ..Self::default()
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let probes = [
+            self.http.is_some(),
+            self.tcp.is_some(),
+            self.exec.is_some(),
+            self.log.is_some(),
+        ];
+        match probes.iter().filter(|set| **set).count() {
+            0 => bail!("health needs one of 'http', 'tcp', 'exec' or 'log'"),
+            1 => {}
+            _ => bail!("health takes only one of 'http', 'tcp', 'exec' or 'log'"),
+        }
+        if let Some(pattern) = &self.log {
+            Regex::new(pattern).map_err(|err| anyhow!("invalid health.log pattern: {err}"))?;
+        }
+        if let Some(TcpTarget::Address(address)) = &self.tcp {
+            if address.rsplit_once(':').is_none() {
+                bail!("health.tcp must be a port or 'host:port', got '{address}'");
+            }
+        }
+        for (field, value) in [
+            ("interval", &self.interval),
+            ("timeout", &self.timeout),
+            ("start_period", &self.start_period),
+        ] {
+            if let Some(value) = value {
+                parse_probe_duration(value)
+                    .ok_or_else(|| anyhow!("invalid health.{field} '{value}'"))?;
+            }
+        }
+        if self.failure_threshold == Some(0) {
+            bail!("health.failure_threshold must be at least 1");
+        }
+        Ok(())
+    }
+
+    pub fn interval(&self) -> Duration {
+        duration_or(&self.interval, DEFAULT_INTERVAL)
+    }
+
+    pub fn timeout(&self) -> Duration {
+        duration_or(&self.timeout, DEFAULT_TIMEOUT)
+    }
+
+    pub fn start_period(&self) -> Duration {
+        duration_or(&self.start_period, Duration::ZERO)
+    }
+
+    pub fn failure_threshold(&self) -> u32 {
+        self.failure_threshold
+            .unwrap_or(DEFAULT_FAILURE_THRESHOLD)
+            .max(1)
+    }
+
+    /// Short description of the probe, e.g. `http http://127.0.0.1:42617/health`.
+    pub fn describe(&self) -> String {
+        if let Some(url) = &self.http {
+            format!("http {url}")
+        } else if let Some(target) = &self.tcp {
+            match target {
+                TcpTarget::Port(port) => format!("tcp {port}"),
+                TcpTarget::Address(address) => format!("tcp {address}"),
+            }
+        } else if let Some(command) = &self.exec {
+            format!("exec {command}")
+        } else if let Some(pattern) = &self.log {
+            format!("log /{pattern}/")
+        } else {
+            "none".to_string()
+        }
+    }
+
+    /// Runs the probe once. `Err` carries the reason it failed.
+    pub(crate) fn probe(&self, port: Option<u16>, log_path: &Path) -> Result<(), String> {
+        let timeout = self.timeout();
+        if let Some(url) = &self.http {
+            let url = if url.starts_with('/') {
+                let port = port.ok_or("http path probe needs a runtime port")?;
+                format!("http://127.0.0.1:{port}{url}")
+            } else {
+                url.clone()
+            };
+            return probe_http(&url, timeout);
+        }
+        if let Some(target) = &self.tcp {
+            let address = match target {
+                TcpTarget::Port(port) => format!("127.0.0.1:{port}"),
+                TcpTarget::Address(address) => address.clone(),
+            };
+            return probe_tcp(&address, timeout);
+        }
+        if let Some(command) = &self.exec {
+            return probe_exec(command, port, timeout);
+        }
+        if let Some(pattern) = &self.log {
+            return probe_log(pattern, log_path);
+        }
+        Err("no probe configured".to_string())
+    }
+}
+
+fn duration_or(value: &Option<String>, default: Duration) -> Duration {
+    value
+        .as_deref()
+        .and_then(parse_probe_duration)
+        .unwrap_or(default)
+}
+
+/// Parses `500ms`, `2s`, `1m`, `1h`; a bare number is seconds.
+pub fn parse_probe_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let seconds = match unit.trim() {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        "h" => number * 3600.0,
+        _ => return None,
+    };
+    Some(Duration::from_secs_f64(seconds))
+}
+
+fn probe_http(url: &str, timeout: Duration) -> Result<(), String> {
+    let status = Command::new("curl")
+        .args([
+            "-fsS",
+            "--max-time",
+            &format!("{:.3}", timeout.as_secs_f64()),
+            url,
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|err| format!("curl: {err}"))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("GET {url} failed"))
+    }
+}
+
+fn probe_tcp(address: &str, timeout: Duration) -> Result<(), String> {
+    let addrs = address
+        .to_socket_addrs()
+        .map_err(|err| format!("resolve {address}: {err}"))?;
+    let mut last_error = format!("no addresses for {address}");
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(_) => return Ok(()),
+            Err(err) => last_error = format!("connect {addr}: {err}"),
+        }
+    }
+    Err(last_error)
+}
+
+fn probe_exec(command: &str, port: Option<u16>, timeout: Duration) -> Result<(), String> {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    if let Some(port) = port {
+        cmd.env("CLAWDEN_PORT", port.to_string());
+    }
+    let mut child = cmd.spawn().map_err(|err| format!("exec: {err}"))?;
+    let deadline = Instant::now() + timeout;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) if status.success() => return Ok(()),
+            Ok(Some(status)) => return Err(format!("'{command}' exited with {status}")),
+            Ok(None) if Instant::now() >= deadline => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!("'{command}' timed out"));
+            }
+            Ok(None) => thread::sleep(Duration::from_millis(20)),
+            Err(err) => return Err(format!("exec: {err}")),
+        }
+    }
+}
+
+/// Matches the current log file. Size- or age-based rotation can move the
+/// ready line out mid-launch, so callers latch a match for the launch.
+fn probe_log(pattern: &str, log_path: &Path) -> Result<(), String> {
+    let regex = Regex::new(pattern).map_err(|err| err.to_string())?;
+    let body = fs::read_to_string(log_path).unwrap_or_default();
+    if body.lines().any(|line| regex.is_match(line)) {
+        Ok(())
+    } else {
+        Err(format!("no log line matches /{pattern}/"))
+    }
+}
+
+/// Probe history of one runtime, kept next to its pid file so `ps`, the
+/// server and `up` share failure counts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct HealthState {
+    pub status: String,
+    pub consecutive_failures: u32,
+    pub last_check_unix_ms: u64,
+    #[serde(default)]
+    pub ever_healthy: bool,
+    #[serde(default)]
+    pub last_error: Option<String>,
+    /// Pid of the launch whose log already matched a `log:` probe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_matched_pid: Option<u32>,
+}
+
+impl HealthState {
+    pub fn load(path: &Path) -> Option<Self> {
+        serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+    }
+
+    pub fn save(&self, path: &Path) {
+        if let Ok(body) = serde_json::to_string(self) {
+            let _ = fs::write(path, body);
+        }
+    }
+
+    /// Folds one probe result in and returns the resulting status:
+    /// `healthy`, `starting` (failing inside the start period or below the
+    /// threshold before ever passing) or `unhealthy`.
+    pub fn record(
+        &mut self,
+        check: &HealthCheck,
+        result: Result<(), String>,
+        started_at_unix_ms: u64,
+        now_unix_ms: u64,
+    ) -> &str {
+        self.last_check_unix_ms = now_unix_ms;
+        match result {
+            Ok(()) => {
+                self.consecutive_failures = 0;
+                self.ever_healthy = true;
+                self.last_error = None;
+                self.status = "healthy".to_string();
+            }
+            Err(err) => {
+                self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+                self.last_error = Some(err);
+                let in_start_period = now_unix_ms.saturating_sub(started_at_unix_ms)
+                    < check.start_period().as_millis() as u64;
+                self.status = if in_start_period && !self.ever_healthy {
+                    "starting".to_string()
+                } else if self.consecutive_failures >= check.failure_threshold() {
+                    "unhealthy".to_string()
+                } else if self.ever_healthy {
+                    "healthy".to_string()
+                } else {
+                    "starting".to_string()
+                };
+            }
+        }
+        &self.status
+    }
+
+    /// Whether the last result is recent enough to reuse instead of probing.
+    pub fn is_fresh(&self, check: &HealthCheck, now_unix_ms: u64) -> bool {
+        !self.status.is_empty()
+            && now_unix_ms.saturating_sub(self.last_check_unix_ms)
+                < check.interval().as_millis() as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_probe_duration, HealthCheck, HealthState, TcpTarget};
+    use std::net::TcpListener;
+    use std::path::Path;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_health_block_and_durations() {
+        let check: HealthCheck = serde_json::from_str(
+            r#"{"tcp": 8080, "interval": "500ms", "timeout": "1s", "failure_threshold": 2, "start_period": "1m"}"#,
+        )
+        .expect("health block should parse");
+        assert_eq!(check.tcp, Some(TcpTarget::Port(8080)));
+        assert_eq!(check.interval(), Duration::from_millis(500));
+        assert_eq!(check.timeout(), Duration::from_secs(1));
+        assert_eq!(check.start_period(), Duration::from_secs(60));
+        check.validate().expect("valid health block");
+
+        assert_eq!(parse_probe_duration("2"), Some(Duration::from_secs(2)));
+        assert_eq!(parse_probe_duration("soon"), None);
+
+        let both = HealthCheck {
+            exec: Some("true".to_string()),
+            log: Some("ready".to_string()),
+            ..HealthCheck::default()
+        };
+        assert!(both.validate().is_err());
+        assert!(HealthCheck::default().validate().is_err());
+    }
+
+    #[test]
+    fn probes_tcp_exec_and_log() {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind ephemeral port");
+        let port = listener.local_addr().expect("local addr").port();
+        let tcp = HealthCheck {
+            tcp: Some(TcpTarget::Port(port)),
+            ..HealthCheck::default()
+        };
+        assert!(tcp.probe(None, Path::new("/nonexistent")).is_ok());
+        drop(listener);
+        assert!(tcp.probe(None, Path::new("/nonexistent")).is_err());
+
+        let exec = HealthCheck {
+            exec: Some("test \"$CLAWDEN_PORT\" = 4242".to_string()),
+            ..HealthCheck::default()
+        };
+        assert!(exec.probe(Some(4242), Path::new("/nonexistent")).is_ok());
+        assert!(exec.probe(Some(1), Path::new("/nonexistent")).is_err());
+
+        let log_path = std::env::temp_dir().join(format!("clawden-health-{}.log", port));
+        std::fs::write(&log_path, "booting\nserver listening on :9000\n").expect("write log");
+        let log = HealthCheck {
+            log: Some(r"listening on :\d+".to_string()),
+            ..HealthCheck::default()
+        };
+        assert!(log.probe(None, &log_path).is_ok());
+        std::fs::write(&log_path, "booting\n").expect("write log");
+        assert!(log.probe(None, &log_path).is_err());
+        let _ = std::fs::remove_file(log_path);
+    }
+
+    #[test]
+    fn applies_start_period_and_failure_threshold() {
+        let check = HealthCheck {
+            exec: Some("true".to_string()),
+            failure_threshold: Some(2),
+            start_period: Some("10s".to_string()),
+            ..HealthCheck::default()
+        };
+        let mut state = HealthState::default();
+        let fail = || Err("down".to_string());
+
+        assert_eq!(state.record(&check, fail(), 0, 1_000), "starting");
+        assert_eq!(state.record(&check, fail(), 0, 11_000), "unhealthy");
+        assert_eq!(state.record(&check, Ok(()), 0, 12_000), "healthy");
+        assert_eq!(state.record(&check, fail(), 0, 13_000), "healthy");
+        assert_eq!(state.record(&check, fail(), 0, 14_000), "unhealthy");
+    }
+}
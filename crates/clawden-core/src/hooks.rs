@@ -0,0 +1,888 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{
+    append_audit, current_unix_ms, ApprovalDecision, ApprovalRecord, ApprovalStatus, ApprovalStore,
+    AuditLog, ChannelInstanceConfig, Task, TaskResult, TaskStatus,
+};
+
+/// Variables that may appear as `{{name}}` in a hook template.
+pub const HOOK_TEMPLATE_VARIABLES: &[&str] = &[
+    "worker",
+    "task.id",
+    "task.summary",
+    "task.description",
+    "task.output_summary",
+    "task.error",
+    "task.duration",
+    "task.status",
+    "approval.id",
+];
+
+const OUTPUT_SUMMARY_CHARS: usize = 500;
+
+/// Blocking approvals without `timeout_secs` still settle after an hour.
+pub const DEFAULT_APPROVAL_TIMEOUT_SECS: u64 = 3_600;
+
+/// The `hooks:` section of `clawden.yaml`.
+///
+/// ```yaml
+/// hooks:
+///   on_task_complete:
+///     notify:
+///       - channel: slack/eng-leads
+///         template: "✅ {{worker}} completed: {{task.summary}}"
+///   on_task_failed:
+///     notify:
+///       - channel: on-call
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HookConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_task_complete: Option<HookRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_task_failed: Option<HookRule>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_approval_needed: Option<HookRule>,
+}
+
+impl HookConfig {
+    pub fn is_empty(&self) -> bool {
+        self.rules().next().is_none()
+    }
+
+    pub fn rule(&self, kind: HookEventKind) -> Option<&HookRule> {
+        match kind {
+            HookEventKind::TaskComplete => self.on_task_complete.as_ref(),
+            HookEventKind::TaskFailed => self.on_task_failed.as_ref(),
+            HookEventKind::ApprovalNeeded => self.on_approval_needed.as_ref(),
+        }
+    }
+
+    /// Configured rules paired with the event they handle.
+    pub fn rules(&self) -> impl Iterator<Item = (HookEventKind, &HookRule)> {
+        HookEventKind::ALL
+            .into_iter()
+            .filter_map(|kind| self.rule(kind).map(|rule| (kind, rule)))
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HookRule {
+    #[serde(default)]
+    pub notify: Vec<HookNotifyTarget>,
+    /// Block the worker until a human responds (approval hooks only).
+    #[serde(default)]
+    pub wait: bool,
+    /// How long a blocking approval waits before applying `default`
+    /// (default: [`DEFAULT_APPROVAL_TIMEOUT_SECS`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+    /// Decision applied when a blocking approval times out (default: deny).
+    #[serde(rename = "default", default, skip_serializing_if = "Option::is_none")]
+    pub default_decision: Option<ApprovalDecision>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookNotifyTarget {
+    /// Channel instance name, optionally prefixed with its type (`slack/eng-leads`).
+    pub channel: String,
+    /// Message template; the event's default template is used when omitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+impl HookNotifyTarget {
+    /// Split `channel` into an optional channel type and the instance name.
+    pub fn channel_ref(&self) -> (Option<&str>, &str) {
+        match self.channel.split_once('/') {
+            Some((channel_type, instance)) => (Some(channel_type.trim()), instance.trim()),
+            None => (None, self.channel.trim()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEventKind {
+    TaskComplete,
+    TaskFailed,
+    ApprovalNeeded,
+}
+
+impl HookEventKind {
+    pub const ALL: [HookEventKind; 3] = [
+        HookEventKind::TaskComplete,
+        HookEventKind::TaskFailed,
+        HookEventKind::ApprovalNeeded,
+    ];
+
+    /// Key of the rule in the `hooks:` section.
+    pub fn config_key(self) -> &'static str {
+        match self {
+            HookEventKind::TaskComplete => "on_task_complete",
+            HookEventKind::TaskFailed => "on_task_failed",
+            HookEventKind::ApprovalNeeded => "on_approval_needed",
+        }
+    }
+
+    pub fn default_template(self) -> &'static str {
+        match self {
+            HookEventKind::TaskComplete => {
+                "✅ {{worker}} completed: {{task.summary}}\n{{task.output_summary}}"
+            }
+            HookEventKind::TaskFailed => {
+                "❌ {{worker}} failed: {{task.summary}}\nError: {{task.error}}"
+            }
+            HookEventKind::ApprovalNeeded => "🔒 {{worker}} needs approval: {{task.description}}",
+        }
+    }
+}
+
+impl std::fmt::Display for HookEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            HookEventKind::TaskComplete => "task_complete",
+            HookEventKind::TaskFailed => "task_failed",
+            HookEventKind::ApprovalNeeded => "approval_needed",
+        };
+        f.write_str(s)
+    }
+}
+
+/// A task event emitted by the worker dispatch loop.
+#[derive(Debug, Clone)]
+pub enum HookEvent {
+    TaskComplete {
+        worker: String,
+        task: Task,
+        result: TaskResult,
+    },
+    TaskFailed {
+        worker: String,
+        task: Task,
+        error: String,
+        result: Option<TaskResult>,
+    },
+    ApprovalNeeded {
+        worker: String,
+        task: Task,
+        description: String,
+        approval_id: String,
+    },
+}
+
+impl HookEvent {
+    /// Classify a finished task as a completion or failure event.
+    pub fn from_result(worker: &str, task: &Task, result: &TaskResult) -> Self {
+        match result.status {
+            TaskStatus::Completed => HookEvent::TaskComplete {
+                worker: worker.to_string(),
+                task: task.clone(),
+                result: result.clone(),
+            },
+            TaskStatus::TimedOut => HookEvent::TaskFailed {
+                worker: worker.to_string(),
+                task: task.clone(),
+                error: format!("timed out after {}", format_duration(result.duration_ms)),
+                result: Some(result.clone()),
+            },
+            TaskStatus::Failed => {
+                let error = match result.exit_code {
+                    Some(code) if result.output.trim().is_empty() => {
+                        format!("exited with status {code}")
+                    }
+                    _ => first_line(&result.output).to_string(),
+                };
+                HookEvent::TaskFailed {
+                    worker: worker.to_string(),
+                    task: task.clone(),
+                    error,
+                    result: Some(result.clone()),
+                }
+            }
+        }
+    }
+
+    pub fn kind(&self) -> HookEventKind {
+        match self {
+            HookEvent::TaskComplete { .. } => HookEventKind::TaskComplete,
+            HookEvent::TaskFailed { .. } => HookEventKind::TaskFailed,
+            HookEvent::ApprovalNeeded { .. } => HookEventKind::ApprovalNeeded,
+        }
+    }
+
+    pub fn task(&self) -> &Task {
+        match self {
+            HookEvent::TaskComplete { task, .. }
+            | HookEvent::TaskFailed { task, .. }
+            | HookEvent::ApprovalNeeded { task, .. } => task,
+        }
+    }
+
+    /// Values for every variable in [`HOOK_TEMPLATE_VARIABLES`].
+    pub fn template_vars(&self) -> HashMap<&'static str, String> {
+        let task = self.task();
+        let (worker, result, error, description, approval_id) = match self {
+            HookEvent::TaskComplete { worker, result, .. } => {
+                (worker, Some(result), None, None, None)
+            }
+            HookEvent::TaskFailed {
+                worker,
+                result,
+                error,
+                ..
+            } => (worker, result.as_ref(), Some(error.as_str()), None, None),
+            HookEvent::ApprovalNeeded {
+                worker,
+                description,
+                approval_id,
+                ..
+            } => (
+                worker,
+                None,
+                None,
+                Some(description.as_str()),
+                Some(approval_id.as_str()),
+            ),
+        };
+
+        let status = match (self.kind(), result.map(|r| r.status)) {
+            (_, Some(TaskStatus::TimedOut)) => "timed_out",
+            (HookEventKind::TaskComplete, _) => "completed",
+            (HookEventKind::TaskFailed, _) => "failed",
+            (HookEventKind::ApprovalNeeded, _) => "awaiting_approval",
+        };
+
+        let mut vars = HashMap::new();
+        vars.insert("worker", worker.clone());
+        vars.insert("task.id", task.id.clone());
+        vars.insert("task.summary", first_line(&task.prompt).to_string());
+        vars.insert(
+            "task.description",
+            description.unwrap_or(task.prompt.trim()).to_string(),
+        );
+        vars.insert(
+            "task.output_summary",
+            result
+                .map(|r| truncate_chars(r.output.trim(), OUTPUT_SUMMARY_CHARS))
+                .unwrap_or_default(),
+        );
+        vars.insert("task.error", error.unwrap_or_default().to_string());
+        vars.insert(
+            "task.duration",
+            result
+                .map(|r| format_duration(r.duration_ms))
+                .unwrap_or_default(),
+        );
+        vars.insert("task.status", status.to_string());
+        vars.insert("approval.id", approval_id.unwrap_or_default().to_string());
+        vars
+    }
+}
+
+/// Render a hook template with simple `{{variable}}` substitution. Unknown
+/// variables are left in place so typos stay visible in the message.
+pub fn render_hook_template(template: &str, vars: &HashMap<&'static str, String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            out.push_str(&rest[start..]);
+            return out;
+        };
+        let name = after[..end].trim();
+        match vars.get(name) {
+            Some(value) => out.push_str(value),
+            None => out.push_str(&rest[start..start + 2 + end + 2]),
+        }
+        rest = &after[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Names of all `{{variable}}` placeholders used in a template.
+pub fn hook_template_variables(template: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            break;
+        };
+        names.push(after[..end].trim().to_string());
+        rest = &after[end + 2..];
+    }
+    names
+}
+
+/// Delivers a rendered hook message to a channel instance.
+#[async_trait]
+pub trait ChannelNotifier: Send + Sync {
+    async fn notify(&self, channel: &ChannelInstanceConfig, message: &str) -> Result<()>;
+}
+
+/// Outcome of delivering one notification target.
+#[derive(Debug, Clone, Serialize)]
+pub struct HookDelivery {
+    pub event: HookEventKind,
+    pub channel: String,
+    pub message: String,
+    pub attempts: u32,
+    pub delivered: bool,
+    pub error: Option<String>,
+}
+
+/// Fans task events out to the channel instances configured in `hooks:`.
+pub struct HookEngine {
+    config: HookConfig,
+    channels: HashMap<String, ChannelInstanceConfig>,
+    notifier: Arc<dyn ChannelNotifier>,
+    audit: Arc<AuditLog>,
+    max_attempts: u32,
+    base_backoff_ms: u64,
+    approval_poll_ms: u64,
+}
+
+impl HookEngine {
+    pub fn new(
+        config: HookConfig,
+        channels: Vec<ChannelInstanceConfig>,
+        notifier: Arc<dyn ChannelNotifier>,
+        audit: Arc<AuditLog>,
+    ) -> Self {
+        Self {
+            config,
+            channels: channels
+                .into_iter()
+                .map(|channel| (channel.instance_name.clone(), channel))
+                .collect(),
+            notifier,
+            audit,
+            max_attempts: 3,
+            base_backoff_ms: 1_000,
+            approval_poll_ms: 1_000,
+        }
+    }
+
+    /// Override the delivery retry policy (attempts include the first try).
+    pub fn with_retry(mut self, max_attempts: u32, base_backoff_ms: u64) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self.base_backoff_ms = base_backoff_ms;
+        self
+    }
+
+    /// Override how often a blocking approval re-reads its persisted record.
+    pub fn with_approval_poll_ms(mut self, poll_ms: u64) -> Self {
+        self.approval_poll_ms = poll_ms.max(1);
+        self
+    }
+
+    pub fn config(&self) -> &HookConfig {
+        &self.config
+    }
+
+    /// Render the event for every configured target and deliver it, retrying
+    /// failed deliveries with exponential backoff. Every outcome is recorded
+    /// in the audit log; a missing rule or empty `notify` list is a no-op.
+    pub async fn fire(&self, event: &HookEvent) -> Vec<HookDelivery> {
+        self.fire_with_footer(event, None).await
+    }
+
+    async fn fire_with_footer(&self, event: &HookEvent, footer: Option<&str>) -> Vec<HookDelivery> {
+        let kind = event.kind();
+        let Some(rule) = self.config.rule(kind) else {
+            return Vec::new();
+        };
+        let vars = event.template_vars();
+
+        let mut deliveries = Vec::with_capacity(rule.notify.len());
+        for target in &rule.notify {
+            let template = target
+                .template
+                .as_deref()
+                .unwrap_or_else(|| kind.default_template());
+            let mut message = render_hook_template(template, &vars);
+            if let Some(footer) = footer {
+                message.push('\n');
+                message.push_str(footer);
+            }
+            let delivery = self.deliver(kind, target, message).await;
+
+            let action = if delivery.delivered {
+                "hook.delivered"
+            } else {
+                "hook.failed"
+            };
+            let mut audit_target = format!(
+                "{kind}:{}:{}:attempts={}",
+                event.task().id,
+                delivery.channel,
+                delivery.attempts
+            );
+            if let Some(error) = &delivery.error {
+                audit_target.push_str(&format!(":error={error}"));
+            }
+            append_audit(&self.audit, "hooks", action, &audit_target);
+            deliveries.push(delivery);
+        }
+        deliveries
+    }
+
+    /// Ask a human to approve a worker action through `on_approval_needed`.
+    ///
+    /// With `wait: true` a pending record is persisted in `store`, the prompt
+    /// is posted to every target, and this call blocks until the request is
+    /// decided by a channel reply or `clawden approvals`, or until
+    /// `timeout_secs` (an hour when unset) elapses and the default decision
+    /// applies. Without a blocking rule the prompt is sent as a plain
+    /// notification and the action is approved immediately.
+    pub async fn request_approval(
+        &self,
+        store: &ApprovalStore,
+        worker: &str,
+        task: &Task,
+        description: &str,
+    ) -> Result<ApprovalRecord> {
+        let rule = self.config.on_approval_needed.as_ref();
+        let Some(rule) = rule.filter(|rule| rule.wait) else {
+            let now = current_unix_ms();
+            let record = ApprovalRecord {
+                id: String::new(),
+                worker: worker.to_string(),
+                task_id: task.id.clone(),
+                description: description.to_string(),
+                channels: Vec::new(),
+                status: ApprovalStatus::Approved,
+                default_decision: ApprovalDecision::Approve,
+                created_at_unix_ms: now,
+                expires_at_unix_ms: None,
+                decided_by: Some("hooks".to_string()),
+                decided_at_unix_ms: Some(now),
+            };
+            self.fire(&HookEvent::ApprovalNeeded {
+                worker: worker.to_string(),
+                task: task.clone(),
+                description: description.to_string(),
+                approval_id: record.id.clone(),
+            })
+            .await;
+            return Ok(record);
+        };
+
+        let channels = rule
+            .notify
+            .iter()
+            .map(|target| target.channel_ref().1.to_string())
+            .collect();
+        let record = store.create(
+            worker,
+            &task.id,
+            description,
+            channels,
+            rule.default_decision.unwrap_or(ApprovalDecision::Deny),
+            Some(rule.timeout_secs.unwrap_or(DEFAULT_APPROVAL_TIMEOUT_SECS)),
+        )?;
+        append_audit(&self.audit, "hooks", "approval.requested", &record.id);
+
+        let footer = format!(
+            "Reply \"approve {id}\" or \"deny {id}\" (default: {default}).",
+            id = record.id,
+            default = record.default_decision
+        );
+        self.fire_with_footer(
+            &HookEvent::ApprovalNeeded {
+                worker: worker.to_string(),
+                task: task.clone(),
+                description: description.to_string(),
+                approval_id: record.id.clone(),
+            },
+            Some(&footer),
+        )
+        .await;
+
+        loop {
+            let current = store
+                .get(&record.id)?
+                .ok_or_else(|| anyhow::anyhow!("approval '{}' disappeared", record.id))?;
+            let current = if current.is_overdue(current_unix_ms()) {
+                store
+                    .expire_overdue()?
+                    .into_iter()
+                    .find(|expired| expired.id == current.id)
+                    .unwrap_or(current)
+            } else {
+                current
+            };
+            if !current.is_pending() {
+                append_audit(
+                    &self.audit,
+                    current.decided_by.as_deref().unwrap_or("hooks"),
+                    &format!("approval.{}", current.status),
+                    &current.id,
+                );
+                return Ok(current);
+            }
+            tokio::time::sleep(Duration::from_millis(self.approval_poll_ms)).await;
+        }
+    }
+
+    async fn deliver(
+        &self,
+        kind: HookEventKind,
+        target: &HookNotifyTarget,
+        message: String,
+    ) -> HookDelivery {
+        let mut delivery = HookDelivery {
+            event: kind,
+            channel: target.channel.clone(),
+            message,
+            attempts: 0,
+            delivered: false,
+            error: None,
+        };
+
+        let (channel_type, instance) = target.channel_ref();
+        let Some(channel) = self.channels.get(instance) else {
+            delivery.error = Some(format!(
+                "channel instance '{instance}' is not configured in 'channels:'"
+            ));
+            return delivery;
+        };
+        if let Some(channel_type) = channel_type {
+            if !channel_type.eq_ignore_ascii_case(&channel.channel_type.to_string()) {
+                delivery.error = Some(format!(
+                    "channel instance '{instance}' is {}, not {channel_type}",
+                    channel.channel_type
+                ));
+                return delivery;
+            }
+        }
+
+        while delivery.attempts < self.max_attempts {
+            if delivery.attempts > 0 {
+                let delay = retry_backoff_ms(self.base_backoff_ms, delivery.attempts);
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+            }
+            delivery.attempts += 1;
+            match self.notifier.notify(channel, &delivery.message).await {
+                Ok(()) => {
+                    delivery.delivered = true;
+                    delivery.error = None;
+                    break;
+                }
+                Err(err) => delivery.error = Some(err.to_string()),
+            }
+        }
+        delivery
+    }
+}
+
+fn retry_backoff_ms(base_ms: u64, attempts: u32) -> u64 {
+    let exponent = attempts.saturating_sub(1).min(6);
+    base_ms.saturating_mul(1_u64 << exponent).min(60_000)
+}
+
+fn first_line(text: &str) -> &str {
+    text.trim().lines().next().unwrap_or_default().trim()
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &text[..idx]),
+        None => text.to_string(),
+    }
+}
+
+fn format_duration(ms: u64) -> String {
+    let secs = ms / 1000;
+    match (secs / 3600, (secs % 3600) / 60, secs % 60) {
+        (0, 0, 0) => format!("{ms}ms"),
+        (0, 0, s) => format!("{s}s"),
+        (0, m, s) => format!("{m}m {s}s"),
+        (h, m, _) => format!("{h}h {m}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChannelType, TaskStatus};
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        fail_first: u32,
+        calls: Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait]
+    impl ChannelNotifier for RecordingNotifier {
+        async fn notify(&self, channel: &ChannelInstanceConfig, message: &str) -> Result<()> {
+            let mut calls = self.calls.lock().unwrap();
+            calls.push((channel.instance_name.clone(), message.to_string()));
+            if calls.len() as u32 <= self.fail_first {
+                anyhow::bail!("rate limited");
+            }
+            Ok(())
+        }
+    }
+
+    fn channel(name: &str, channel_type: ChannelType) -> ChannelInstanceConfig {
+        ChannelInstanceConfig {
+            instance_name: name.to_string(),
+            channel_type,
+            credentials: HashMap::new(),
+            options: HashMap::new(),
+        }
+    }
+
+    fn task() -> Task {
+        Task {
+            id: "task-7".to_string(),
+            prompt: "Fix the flaky test\nIt fails on CI only.".to_string(),
+            project_dir: PathBuf::from("/tmp"),
+            timeout_secs: None,
+            allowed_tools: None,
+        }
+    }
+
+    fn result(status: TaskStatus, output: &str) -> TaskResult {
+        TaskResult {
+            task_id: "task-7".to_string(),
+            status,
+            output: output.to_string(),
+            exit_code: Some(0),
+            duration_ms: 65_000,
+            cost: None,
+            raw: None,
+        }
+    }
+
+    fn config(yaml_target: HookNotifyTarget) -> HookConfig {
+        HookConfig {
+            on_task_complete: Some(HookRule {
+                notify: vec![yaml_target],
+                ..HookRule::default()
+            }),
+            ..HookConfig::default()
+        }
+    }
+
+    #[test]
+    fn render_substitutes_known_variables_and_keeps_unknown() {
+        let event = HookEvent::from_result("coder", &task(), &result(TaskStatus::Completed, "ok"));
+        let rendered = render_hook_template(
+            "{{worker}} did {{ task.summary }} in {{task.duration}} {{task.nope}}",
+            &event.template_vars(),
+        );
+        assert_eq!(
+            rendered,
+            "coder did Fix the flaky test in 1m 5s {{task.nope}}"
+        );
+        assert_eq!(
+            hook_template_variables("{{worker}} {{ task.error }}"),
+            vec!["worker", "task.error"]
+        );
+    }
+
+    #[test]
+    fn output_summary_is_truncated() {
+        let long = "x".repeat(OUTPUT_SUMMARY_CHARS + 10);
+        let event = HookEvent::from_result("coder", &task(), &result(TaskStatus::Completed, &long));
+        let summary = &event.template_vars()["task.output_summary"];
+        assert_eq!(summary.chars().count(), OUTPUT_SUMMARY_CHARS + 1);
+        assert!(summary.ends_with('…'));
+    }
+
+    #[test]
+    fn failed_results_become_failure_events() {
+        let event = HookEvent::from_result(
+            "coder",
+            &task(),
+            &result(TaskStatus::Failed, "error: tests failed\nmore"),
+        );
+        assert_eq!(event.kind(), HookEventKind::TaskFailed);
+        assert_eq!(event.template_vars()["task.error"], "error: tests failed");
+
+        let timed_out = HookEvent::from_result("coder", &task(), &result(TaskStatus::TimedOut, ""));
+        assert_eq!(
+            timed_out.template_vars()["task.error"],
+            "timed out after 1m 5s"
+        );
+    }
+
+    #[tokio::test]
+    async fn fire_retries_and_records_audit() {
+        let notifier = Arc::new(RecordingNotifier {
+            fail_first: 1,
+            ..RecordingNotifier::default()
+        });
+        let audit = Arc::new(AuditLog::default());
+        let engine = HookEngine::new(
+            config(HookNotifyTarget {
+                channel: "slack/eng-leads".to_string(),
+                template: None,
+            }),
+            vec![channel("eng-leads", ChannelType::Slack)],
+            notifier.clone(),
+            audit.clone(),
+        )
+        .with_retry(3, 1);
+
+        let event = HookEvent::from_result("coder", &task(), &result(TaskStatus::Completed, "ok"));
+        let deliveries = engine.fire(&event).await;
+
+        assert_eq!(deliveries.len(), 1);
+        assert!(deliveries[0].delivered);
+        assert_eq!(deliveries[0].attempts, 2);
+        let calls = notifier.calls.lock().unwrap();
+        assert_eq!(calls[1].0, "eng-leads");
+        assert_eq!(calls[1].1, "✅ coder completed: Fix the flaky test\nok");
+
+        let events = audit.list();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].action, "hook.delivered");
+        assert!(events[0].target.contains("slack/eng-leads:attempts=2"));
+    }
+
+    #[tokio::test]
+    async fn request_approval_blocks_until_decided() {
+        let stamp = current_unix_ms();
+        let store =
+            ApprovalStore::at(std::env::temp_dir().join(format!("clawden-hook-approvals-{stamp}")));
+        let notifier = Arc::new(RecordingNotifier::default());
+        let config = HookConfig {
+            on_approval_needed: Some(HookRule {
+                notify: vec![HookNotifyTarget {
+                    channel: "slack/eng-leads".to_string(),
+                    template: None,
+                }],
+                wait: true,
+                timeout_secs: Some(30),
+                default_decision: None,
+            }),
+            ..HookConfig::default()
+        };
+        let engine = HookEngine::new(
+            config,
+            vec![channel("eng-leads", ChannelType::Slack)],
+            notifier.clone(),
+            Arc::new(AuditLog::default()),
+        )
+        .with_approval_poll_ms(10);
+
+        let decider = {
+            let store = ApprovalStore::at(store.dir());
+            std::thread::spawn(move || loop {
+                if let Some(pending) = store.pending().unwrap().first() {
+                    return store
+                        .decide(&pending.id, ApprovalDecision::Approve, "cli")
+                        .unwrap();
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            })
+        };
+
+        let record = engine
+            .request_approval(&store, "coder", &task(), "drop the users table")
+            .await
+            .expect("approval");
+        assert_eq!(record.decision(), Some(ApprovalDecision::Approve));
+        assert_eq!(record.id, decider.join().unwrap().id);
+        assert_eq!(record.channels, vec!["eng-leads".to_string()]);
+
+        let calls = notifier.calls.lock().unwrap();
+        assert!(calls[0]
+            .1
+            .starts_with("🔒 coder needs approval: drop the users table\n"));
+        assert!(calls[0].1.contains(&format!("approve {}", record.id)));
+        assert!(calls[0].1.ends_with("(default: deny)."));
+        let _ = std::fs::remove_dir_all(store.dir());
+    }
+
+    #[tokio::test]
+    async fn blocking_approval_without_timeout_gets_default_deadline() {
+        let stamp = current_unix_ms();
+        let store = ApprovalStore::at(
+            std::env::temp_dir().join(format!("clawden-hook-approval-deadline-{stamp}")),
+        );
+        let config = HookConfig {
+            on_approval_needed: Some(HookRule {
+                wait: true,
+                ..HookRule::default()
+            }),
+            ..HookConfig::default()
+        };
+        let engine = HookEngine::new(
+            config,
+            Vec::new(),
+            Arc::new(RecordingNotifier::default()),
+            Arc::new(AuditLog::default()),
+        )
+        .with_approval_poll_ms(10);
+
+        let decider = {
+            let store = ApprovalStore::at(store.dir());
+            std::thread::spawn(move || loop {
+                let pending = store.pending().expect("pending approvals should list");
+                if let Some(pending) = pending.first() {
+                    return store
+                        .decide(&pending.id, ApprovalDecision::Deny, "cli")
+                        .expect("pending approval should be decided");
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            })
+        };
+
+        let record = engine
+            .request_approval(&store, "coder", &task(), "force push")
+            .await
+            .expect("approval");
+        decider.join().expect("decider thread should finish");
+        assert_eq!(
+            record.expires_at_unix_ms,
+            Some(record.created_at_unix_ms + DEFAULT_APPROVAL_TIMEOUT_SECS * 1000)
+        );
+        let _ = std::fs::remove_dir_all(store.dir());
+    }
+
+    #[tokio::test]
+    async fn fire_reports_missing_channel_and_ignores_unconfigured_events() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let audit = Arc::new(AuditLog::default());
+        let engine = HookEngine::new(
+            config(HookNotifyTarget {
+                channel: "pm-bot".to_string(),
+                template: Some("{{worker}}".to_string()),
+            }),
+            Vec::new(),
+            notifier.clone(),
+            audit.clone(),
+        );
+
+        let complete =
+            HookEvent::from_result("coder", &task(), &result(TaskStatus::Completed, "ok"));
+        let deliveries = engine.fire(&complete).await;
+        assert!(!deliveries[0].delivered);
+        assert_eq!(deliveries[0].attempts, 0);
+        assert!(deliveries[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .contains("'pm-bot' is not configured"));
+        assert_eq!(audit.list()[0].action, "hook.failed");
+
+        let failed = HookEvent::from_result("coder", &task(), &result(TaskStatus::Failed, "no"));
+        assert!(engine.fire(&failed).await.is_empty());
+        assert!(notifier.calls.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+//! HTTP for the installer: GitHub API and npm registry queries plus
+//! resumable artifact downloads.
+//!
+//! Requests run on `reqwest`'s blocking client from a scoped thread, since
+//! the installer is also called from inside the CLI's tokio runtime where
+//! the blocking client must not be created or dropped.
+
+use anyhow::{bail, Context, Result};
+use reqwest::blocking::{Client, Response};
+use reqwest::header::{ACCEPT, RANGE};
+use reqwest::StatusCode;
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::time::Duration;
+
+/// Base URL every installer request is redirected to, as
+/// `<mirror>/<host>/<path>` (e.g. `https://api.github.com/repos/x` becomes
+/// `<mirror>/api.github.com/repos/x`).
+pub(crate) const MIRROR_ENV: &str = "CLAWDEN_MIRROR";
+
+const QUERY_TIMEOUT: Duration = Duration::from_secs(30);
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+/// Minimum number of bytes between two progress reports.
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Http {
+    mirror: Option<String>,
+}
+
+impl Http {
+    pub(crate) fn from_env() -> Self {
+        let mut http = Self::default();
+        http.set_mirror(std::env::var(MIRROR_ENV).ok());
+        http
+    }
+
+    pub(crate) fn set_mirror(&mut self, mirror: Option<String>) {
+        self.mirror = mirror
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty());
+    }
+
+    pub(crate) fn mirror(&self) -> Option<&str> {
+        self.mirror.as_deref()
+    }
+
+    /// The URL actually requested for `url`.
+    pub(crate) fn resolve(&self, url: &str) -> String {
+        match (&self.mirror, url.strip_prefix("https://")) {
+            (Some(mirror), Some(rest)) => format!("{mirror}/{rest}"),
+            _ => url.to_string(),
+        }
+    }
+
+    pub(crate) fn get_json(&self, url: &str) -> Result<serde_json::Value> {
+        let body = self.get_text(url)?;
+        serde_json::from_str(&body).with_context(|| format!("invalid JSON response from {url}"))
+    }
+
+    pub(crate) fn get_text(&self, url: &str) -> Result<String> {
+        let resolved = self.resolve(url);
+        off_runtime(|| {
+            let accept = if url.starts_with("https://api.github.com/") {
+                "application/vnd.github+json"
+            } else {
+                "application/json, text/plain, */*"
+            };
+            let response = client(Some(QUERY_TIMEOUT))?
+                .get(&resolved)
+                .header(ACCEPT, accept)
+                .send()
+                .with_context(|| format!("failed to fetch {url}"))?;
+            ensure_success(response, url)?
+                .text()
+                .with_context(|| format!("failed to read response from {url}"))
+        })
+    }
+
+    /// Downloads `url` into `dest`. A partial `dest` left by an earlier
+    /// attempt is resumed with a ranged request when the server supports
+    /// it. `progress` receives the bytes written so far and the total size
+    /// when known.
+    pub(crate) fn download(
+        &self,
+        url: &str,
+        dest: &Path,
+        progress: &(dyn Fn(u64, Option<u64>) + Sync),
+    ) -> Result<()> {
+        let resolved = self.resolve(url);
+        off_runtime(|| {
+            let client = client(None)?;
+            let existing = fs::metadata(dest).map(|meta| meta.len()).unwrap_or(0);
+            let mut request = client.get(&resolved);
+            if existing > 0 {
+                request = request.header(RANGE, format!("bytes={existing}-"));
+            }
+            let mut response = request
+                .send()
+                .with_context(|| format!("failed to download {url}"))?;
+
+            if existing > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+                // The partial file does not line up with the server's copy.
+                fs::remove_file(dest)?;
+                response = client
+                    .get(&resolved)
+                    .send()
+                    .with_context(|| format!("failed to download {url}"))?;
+            }
+            let resumed = existing > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+            let mut response = ensure_success(response, url)?;
+            let (mut file, mut written) = if resumed {
+                (OpenOptions::new().append(true).open(dest)?, existing)
+            } else {
+                (File::create(dest)?, 0)
+            };
+            let total = response.content_length().map(|len| len + written);
+
+            let mut buf = [0u8; 64 * 1024];
+            let mut reported = written;
+            progress(written, total);
+            loop {
+                let read = response
+                    .read(&mut buf)
+                    .with_context(|| format!("download of {url} was interrupted"))?;
+                if read == 0 {
+                    break;
+                }
+                file.write_all(&buf[..read])?;
+                written += read as u64;
+                if written - reported >= PROGRESS_STEP {
+                    progress(written, total);
+                    reported = written;
+                }
+            }
+            file.flush()?;
+            if let Some(total) = total.filter(|total| *total != written) {
+                bail!("download of {url} ended after {written} of {total} bytes");
+            }
+            progress(written, total);
+            Ok(())
+        })
+    }
+}
+
+/// Formats a byte count for progress messages.
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
+
+fn client(timeout: Option<Duration>) -> Result<Client> {
+    Client::builder()
+        .user_agent("clawden")
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(timeout)
+        .build()
+        .context("failed to build HTTP client")
+}
+
+fn ensure_success(response: Response, url: &str) -> Result<Response> {
+    let status = response.status();
+    if !status.is_success() {
+        bail!("failed to fetch {url}: HTTP {status}");
+    }
+    Ok(response)
+}
+
+fn off_runtime<T: Send>(f: impl FnOnce() -> Result<T> + Send) -> Result<T> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(f)
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_bytes, Http};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+
+    /// Serves `body` once per connection, honouring `Range: bytes=N-`, and
+    /// records each request line with its range header.
+    fn serve(
+        body: &'static [u8],
+        connections: usize,
+    ) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind test server");
+        let base = format!("http://{}", listener.local_addr().expect("server address"));
+        let handle = std::thread::spawn(move || {
+            let mut seen = Vec::new();
+            for stream in listener.incoming().take(connections) {
+                let mut stream = stream.expect("accept connection");
+                let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+                let mut request = String::new();
+                reader.read_line(&mut request).expect("read request line");
+                let mut start = 0usize;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("read header");
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some(range) = line.to_ascii_lowercase().strip_prefix("range: bytes=") {
+                        start = range
+                            .trim()
+                            .trim_end_matches('-')
+                            .parse()
+                            .expect("parse range start");
+                    }
+                }
+                seen.push(format!("{} from {start}", request.trim()));
+                let status = if start > 0 {
+                    "206 Partial Content"
+                } else {
+                    "200 OK"
+                };
+                let chunk = &body[start..];
+                write!(
+                    stream,
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    chunk.len()
+                )
+                .expect("write response head");
+                stream.write_all(chunk).expect("write response body");
+            }
+            seen
+        });
+        (base, handle)
+    }
+
+    #[test]
+    fn downloads_resume_partial_files_through_the_mirror() {
+        let (base, server) = serve(b"0123456789", 1);
+        let mut http = Http::default();
+        http.set_mirror(Some(format!("{base}/")));
+        assert_eq!(
+            http.resolve("https://github.com/o/r/releases/download/v1/a.tar.gz"),
+            format!("{base}/github.com/o/r/releases/download/v1/a.tar.gz")
+        );
+
+        let dest = std::env::temp_dir().join(format!("clawden-http-{}", std::process::id()));
+        std::fs::write(&dest, b"0123").expect("write partial file");
+        let reports = Mutex::new(Vec::new());
+        http.download("https://example.invalid/a.tar.gz", &dest, &|done, total| {
+            reports.lock().expect("lock reports").push((done, total));
+        })
+        .expect("download");
+
+        assert_eq!(std::fs::read(&dest).expect("read download"), b"0123456789");
+        assert_eq!(
+            server.join().expect("join server"),
+            ["GET /example.invalid/a.tar.gz HTTP/1.1 from 4"]
+        );
+        let reports = reports.into_inner().expect("take reports");
+        assert_eq!(reports.first(), Some(&(4, Some(10))));
+        assert_eq!(reports.last(), Some(&(10, Some(10))));
+        let _ = std::fs::remove_file(dest);
+    }
+
+    #[test]
+    fn byte_counts_are_human_readable() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MiB");
+    }
+}
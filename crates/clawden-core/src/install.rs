@@ -6,6 +6,9 @@ use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
 
+use crate::checksum::{file_digest_like, parse_checksum_listing, ArtifactRecord};
+use crate::http::{format_bytes, Http};
+use crate::lockfile::{LockFile, LockMode, LockedRuntime};
 use crate::{direct_install_descriptors, runtime_descriptor, InstallSource, VersionSource};
 
 #[derive(Debug, Clone, Serialize)]
@@ -31,12 +34,23 @@ pub struct VersionCheck {
 
 type ProgressCallback = Box<dyn Fn(&str) + Send + Sync>;
 
+/// Symlinks next to the version directories of a runtime.
+const CURRENT_LINK: &str = "current";
+const PREVIOUS_LINK: &str = "previous";
+
+/// Per-version record of where an installed runtime came from.
+pub(crate) const ARTIFACT_RECORD: &str = ".artifact.json";
+
 pub struct RuntimeInstaller {
     root_dir: PathBuf,
     runtimes_dir: PathBuf,
     cache_dir: PathBuf,
     logs_dir: PathBuf,
     lock_path: PathBuf,
+    require_checksums: bool,
+    lockfile: Option<PathBuf>,
+    lock_mode: LockMode,
+    http: Http,
     progress: Option<ProgressCallback>,
 }
 
@@ -56,10 +70,57 @@ impl RuntimeInstaller {
             cache_dir,
             logs_dir,
             lock_path: root_dir.join(".install.lock"),
+            require_checksums: std::env::var("CLAWDEN_REQUIRE_CHECKSUMS").is_ok_and(|value| {
+                matches!(
+                    value.trim().to_ascii_lowercase().as_str(),
+                    "1" | "true" | "yes" | "on"
+                )
+            }),
+            lockfile: None,
+            lock_mode: LockMode::default(),
+            http: Http::from_env(),
             progress: None,
         })
     }
 
+    /// Project `clawden.lock` that installs read from and record into.
+    pub fn set_lockfile(&mut self, path: Option<PathBuf>) {
+        self.lockfile = path;
+    }
+
+    pub fn set_lock_mode(&mut self, mode: LockMode) {
+        self.lock_mode = mode;
+    }
+
+    pub fn lockfile(&self) -> Option<&Path> {
+        self.lockfile.as_deref()
+    }
+
+    /// Version `clawden.lock` records for `runtime`, if any.
+    pub fn locked_version(&self, runtime: &str) -> Result<Option<String>> {
+        Ok(self.locked_runtime(runtime)?.map(|entry| entry.version))
+    }
+
+    /// Entry `clawden.lock` records for `runtime`, if any.
+    pub(crate) fn locked_runtime(&self, runtime: &str) -> Result<Option<LockedRuntime>> {
+        let Some(path) = &self.lockfile else {
+            return Ok(None);
+        };
+        Ok(LockFile::load(path)?.runtimes.remove(runtime))
+    }
+
+    /// Refuse artifacts that cannot be checked against a published checksum.
+    /// Defaults to `CLAWDEN_REQUIRE_CHECKSUMS`.
+    pub fn set_require_checksums(&mut self, require: bool) {
+        self.require_checksums = require;
+    }
+
+    /// Fetch GitHub releases and npm packages through `mirror` instead.
+    /// Defaults to `CLAWDEN_MIRROR`.
+    pub fn set_mirror(&mut self, mirror: Option<String>) {
+        self.http.set_mirror(mirror);
+    }
+
     pub fn set_progress_callback(&mut self, cb: impl Fn(&str) + Send + Sync + 'static) {
         self.progress = Some(Box::new(cb));
     }
@@ -80,36 +141,68 @@ impl RuntimeInstaller {
         let _lock = InstallLock::acquire(&self.lock_path)?;
 
         self.report_progress(&format!("Resolving {runtime} version…"));
-        let version = self.resolve_requested_version(runtime, requested_version)?;
+        let locked = self.locked_entry(runtime, requested_version)?;
+        let version = match &locked {
+            Some(entry) => entry.version.clone(),
+            None => self.resolve_requested_version(runtime, requested_version)?,
+        };
         let runtime_dir = self.runtimes_dir.join(runtime);
         let tmp_dir = runtime_dir.join(format!(".{version}.tmp"));
         let final_dir = runtime_dir.join(&version);
 
+        if self.lock_mode == LockMode::Frozen {
+            // Nothing may be fetched; an identical install is reused as is.
+            if let Some(entry) = &locked {
+                if self.installed_artifact(runtime, &version).map(|a| a.digest)
+                    == Some(entry.digest.clone())
+                    && final_dir.join(runtime).exists()
+                {
+                    self.point_current_at(runtime, &version)?;
+                    return Ok(InstalledRuntime {
+                        runtime: runtime.to_string(),
+                        version,
+                        executable: final_dir.join(runtime),
+                    });
+                }
+            }
+        }
+
         if tmp_dir.exists() {
             fs::remove_dir_all(&tmp_dir)?;
         }
 
         fs::create_dir_all(&tmp_dir)?;
         self.report_progress(&format!("Installing {runtime}@{version}…"));
-        let executable = match &descriptor.install_source {
+        let (executable, artifact) = match &descriptor.install_source {
             InstallSource::GithubRelease {
                 owner,
                 repo,
                 archive_ext,
-            } => {
-                self.install_github_release(runtime, owner, repo, archive_ext, &version, &tmp_dir)?
-            }
+            } => match &locked {
+                Some(entry) => {
+                    self.install_locked_release(runtime, entry, archive_ext, &tmp_dir)?
+                }
+                None => self.install_github_release(
+                    runtime,
+                    owner,
+                    repo,
+                    archive_ext,
+                    &version,
+                    &tmp_dir,
+                )?,
+            },
             InstallSource::Npm { package } => {
-                self.install_npm_package(runtime, package, &version, &tmp_dir)?
+                self.install_npm_package(runtime, package, &version, &tmp_dir, locked.as_ref())?
             }
             InstallSource::GitClone { url } => {
-                self.install_git_clone(runtime, url, &version, &tmp_dir)?
+                self.install_git_clone(runtime, url, &version, &tmp_dir, locked.as_ref())?
             }
             InstallSource::NotAvailable => {
                 bail!("runtime '{runtime}' has no direct install implementation")
             }
         };
         validate_runtime_artifact(runtime, &executable)?;
+        artifact.save(&tmp_dir.join(ARTIFACT_RECORD))?;
 
         self.report_progress(&format!("Finalizing {runtime}@{version}…"));
         fs::create_dir_all(&runtime_dir)?;
@@ -117,16 +210,15 @@ impl RuntimeInstaller {
             fs::remove_dir_all(&final_dir)?;
         }
         fs::rename(&tmp_dir, &final_dir)?;
-
-        let current_link = runtime_dir.join("current");
-        if current_link.exists() || current_link.is_symlink() {
-            let _ = fs::remove_file(&current_link);
-            let _ = fs::remove_dir_all(&current_link);
-        }
-        std::os::unix::fs::symlink(&version, &current_link)
-            .with_context(|| format!("updating current symlink for {runtime}"))?;
+        self.point_current_at(runtime, &version)?;
 
         self.append_audit("runtime.install", runtime, "ok")?;
+        if !artifact.verified {
+            self.append_audit("runtime.install.unverified", runtime, &artifact.digest)?;
+        }
+        if locked.is_none() {
+            self.record_lock(runtime, &version, &artifact)?;
+        }
 
         Ok(InstalledRuntime {
             runtime: runtime.to_string(),
@@ -135,6 +227,82 @@ impl RuntimeInstaller {
         })
     }
 
+    /// Switches `current` to `version`, keeping the version it pointed at
+    /// before as `previous` for rollback.
+    pub(crate) fn point_current_at(&self, runtime: &str, version: &str) -> Result<()> {
+        let runtime_dir = self.runtimes_dir.join(runtime);
+        let current_link = runtime_dir.join(CURRENT_LINK);
+        if let Ok(old) = fs::read_link(&current_link) {
+            if old != Path::new(version) && runtime_dir.join(&old).is_dir() {
+                replace_symlink(&old, &runtime_dir.join(PREVIOUS_LINK))
+                    .with_context(|| format!("updating previous symlink for {runtime}"))?;
+            }
+        }
+        replace_symlink(Path::new(version), &current_link)
+            .with_context(|| format!("updating current symlink for {runtime}"))
+    }
+
+    /// The lock entry to install for `requested_version`: the recorded one
+    /// while it still satisfies the request. `--locked` and `--frozen`
+    /// refuse to fall back to resolving a new version.
+    fn locked_entry(
+        &self,
+        runtime: &str,
+        requested_version: Option<&str>,
+    ) -> Result<Option<LockedRuntime>> {
+        let Some(path) = &self.lockfile else {
+            if self.lock_mode != LockMode::Update {
+                bail!("--locked and --frozen need a clawden.yaml project with a clawden.lock");
+            }
+            return Ok(None);
+        };
+        let entry = LockFile::load(path)?.runtimes.remove(runtime);
+        let requested = requested_version
+            .map(str::trim)
+            .filter(|v| !v.is_empty() && !v.eq_ignore_ascii_case("latest"));
+        match entry {
+            Some(entry) if requested.is_none_or(|req| version_satisfies(&entry.version, req)) => {
+                Ok(Some(entry))
+            }
+            entry if self.lock_mode != LockMode::Update => {
+                let reason = match entry {
+                    Some(entry) => format!(
+                        "locks {runtime}@{} which does not satisfy {}",
+                        entry.version,
+                        requested.unwrap_or("latest")
+                    ),
+                    None => format!("has no entry for {runtime}"),
+                };
+                bail!(
+                    "{} {reason}; run 'clawden lock --update {runtime}' first",
+                    path.display()
+                )
+            }
+            _ => Ok(None),
+        }
+    }
+
+    pub(crate) fn record_lock(
+        &self,
+        runtime: &str,
+        version: &str,
+        artifact: &ArtifactRecord,
+    ) -> Result<()> {
+        let Some(path) = &self.lockfile else {
+            return Ok(());
+        };
+        let mut lock = LockFile::load(path)?;
+        lock.runtimes.insert(
+            runtime.to_string(),
+            LockedRuntime {
+                version: version.to_string(),
+                url: artifact.url.clone(),
+                digest: artifact.digest.clone(),
+            },
+        );
+        lock.save(path)
+    }
+
     fn resolve_requested_version(
         &self,
         runtime: &str,
@@ -188,9 +356,9 @@ impl RuntimeInstaller {
         let descriptor = ensure_runtime_supported(runtime)?;
         match &descriptor.version_source {
             VersionSource::GithubLatest { owner, repo } => Ok(normalize_version(
-                &github_release_assets(owner, repo, "latest")?.tag,
+                &github_release_assets(&self.http, owner, repo, "latest")?.tag,
             )),
-            VersionSource::Npm { package } => query_latest_npm_version(package),
+            VersionSource::Npm { package } => query_latest_npm_version(&self.http, package),
             VersionSource::GitHead { url } => query_git_head_branch(url),
             VersionSource::NotAvailable => {
                 bail!(
@@ -216,6 +384,104 @@ impl RuntimeInstaller {
         Ok(checks)
     }
 
+    /// Makes an already installed `version` the current one. A project
+    /// `clawden.lock` entry for the runtime follows the switch.
+    pub fn use_version(&self, runtime: &str, version: &str) -> Result<InstalledRuntime> {
+        let runtime = ensure_runtime_supported(runtime)?.slug;
+        let version = normalize_version(version.trim());
+        let _lock = InstallLock::acquire(&self.lock_path)?;
+        let executable = self.runtimes_dir.join(runtime).join(&version).join(runtime);
+        if !executable.exists() {
+            bail!(
+                "{runtime}@{version} is not installed; run 'clawden install {runtime}@{version}' first"
+            );
+        }
+        self.point_current_at(runtime, &version)?;
+        self.relock_installed(runtime, &version)?;
+        self.append_audit("runtime.use", runtime, &version)?;
+        Ok(InstalledRuntime {
+            runtime: runtime.to_string(),
+            version,
+            executable,
+        })
+    }
+
+    /// Version `rollback_runtime` would switch back to.
+    pub fn previous_version(&self, runtime: &str) -> Option<String> {
+        let runtime_dir = self.runtimes_dir.join(runtime);
+        let version = fs::read_link(runtime_dir.join(PREVIOUS_LINK)).ok()?;
+        runtime_dir
+            .join(&version)
+            .join(runtime)
+            .exists()
+            .then(|| version.to_string_lossy().into_owned())
+    }
+
+    /// Swaps `current` and `previous`, so rolling back twice returns to
+    /// where it started.
+    pub fn rollback_runtime(&self, runtime: &str) -> Result<InstalledRuntime> {
+        let runtime = ensure_runtime_supported(runtime)?.slug;
+        let Some(previous) = self.previous_version(runtime) else {
+            bail!("no previous version of {runtime} to roll back to");
+        };
+        let _lock = InstallLock::acquire(&self.lock_path)?;
+        self.point_current_at(runtime, &previous)?;
+        self.relock_installed(runtime, &previous)?;
+        self.append_audit("runtime.rollback", runtime, &previous)?;
+        Ok(InstalledRuntime {
+            runtime: runtime.to_string(),
+            executable: self
+                .runtimes_dir
+                .join(runtime)
+                .join(&previous)
+                .join(runtime),
+            version: previous,
+        })
+    }
+
+    /// Points an existing lock entry for `runtime` at an installed version,
+    /// so the next `up` does not reinstall the old one.
+    fn relock_installed(&self, runtime: &str, version: &str) -> Result<()> {
+        let Some(path) = &self.lockfile else {
+            return Ok(());
+        };
+        let locked = LockFile::load(path)?.runtimes.remove(runtime);
+        if locked.is_some_and(|entry| entry.version != version) {
+            if let Some(artifact) = self.installed_artifact(runtime, version) {
+                self.record_lock(runtime, version, &artifact)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes one installed version. The current version stays until
+    /// another one is selected.
+    pub fn uninstall_version(&self, runtime: &str, version: &str) -> Result<()> {
+        let runtime = ensure_runtime_supported(runtime)?.slug;
+        let version = normalize_version(version.trim());
+        let _lock = InstallLock::acquire(&self.lock_path)?;
+        let runtime_dir = self.runtimes_dir.join(runtime);
+        let version_dir = runtime_dir.join(&version);
+        if version.starts_with('.')
+            || matches!(version.as_str(), CURRENT_LINK | PREVIOUS_LINK)
+            || !version_dir.is_dir()
+        {
+            bail!("{runtime}@{version} is not installed");
+        }
+        if fs::read_link(runtime_dir.join(CURRENT_LINK)).ok() == Some(PathBuf::from(&version)) {
+            bail!(
+                "{runtime}@{version} is the current version; switch with 'clawden use' first or run 'clawden uninstall {runtime}'"
+            );
+        }
+        fs::remove_dir_all(&version_dir)?;
+        let previous = runtime_dir.join(PREVIOUS_LINK);
+        if fs::read_link(&previous).ok() == Some(PathBuf::from(&version)) {
+            let _ = fs::remove_file(previous);
+        }
+        self.append_audit("runtime.uninstall", runtime, &version)?;
+        Ok(())
+    }
+
     pub fn uninstall_runtime(&self, runtime: &str) -> Result<()> {
         let descriptor = ensure_runtime_supported(runtime)?;
         let _lock = InstallLock::acquire(&self.lock_path)?;
@@ -236,7 +502,7 @@ impl RuntimeInstaller {
         for entry in fs::read_dir(&self.runtimes_dir)? {
             let entry = entry?;
             let runtime = entry.file_name().to_string_lossy().to_string();
-            let current = entry.path().join("current");
+            let current = entry.path().join(CURRENT_LINK);
             if !current.exists() {
                 continue;
             }
@@ -257,8 +523,64 @@ impl RuntimeInstaller {
         Ok(rows)
     }
 
+    /// Every installed version of every runtime, sorted by runtime and then
+    /// version.
+    pub fn list_installed_versions(&self) -> Result<Vec<InstalledRuntime>> {
+        let mut rows = Vec::new();
+        if !self.runtimes_dir.exists() {
+            return Ok(rows);
+        }
+
+        for entry in fs::read_dir(&self.runtimes_dir)? {
+            let entry = entry?;
+            let runtime = entry.file_name().to_string_lossy().to_string();
+            if !entry.path().is_dir() {
+                continue;
+            }
+            for version_entry in fs::read_dir(entry.path())? {
+                let version_entry = version_entry?;
+                let version = version_entry.file_name().to_string_lossy().to_string();
+                if version.starts_with('.')
+                    || matches!(version.as_str(), CURRENT_LINK | PREVIOUS_LINK)
+                    || version_entry.file_type()?.is_symlink()
+                {
+                    continue;
+                }
+                let executable = version_entry.path().join(&runtime);
+                if executable.exists() {
+                    rows.push(InstalledRuntime {
+                        runtime: runtime.clone(),
+                        version,
+                        executable,
+                    });
+                }
+            }
+        }
+
+        rows.sort_by(|a, b| {
+            a.runtime.cmp(&b.runtime).then_with(|| {
+                match (parse_semver(&a.version), parse_semver(&b.version)) {
+                    (Some(x), Some(y)) => x.cmp(&y),
+                    _ => a.version.cmp(&b.version),
+                }
+            })
+        });
+        Ok(rows)
+    }
+
+    /// Source URL and digest recorded when `runtime@version` was installed.
+    pub fn installed_artifact(&self, runtime: &str, version: &str) -> Option<ArtifactRecord> {
+        ArtifactRecord::load(
+            &self
+                .runtimes_dir
+                .join(runtime)
+                .join(version)
+                .join(ARTIFACT_RECORD),
+        )
+    }
+
     pub fn runtime_executable(&self, runtime: &str) -> Option<PathBuf> {
-        let current = self.runtimes_dir.join(runtime).join("current");
+        let current = self.runtimes_dir.join(runtime).join(CURRENT_LINK);
         if !current.exists() {
             return None;
         }
@@ -275,7 +597,7 @@ impl RuntimeInstaller {
         archive_ext: &str,
         version: &str,
         tmp_dir: &Path,
-    ) -> Result<PathBuf> {
+    ) -> Result<(PathBuf, ArtifactRecord)> {
         let (os, arch) = host_os_arch()?;
         // Some runtimes use a non-semver release tag; fall back to "latest"
         // to hit the /releases/latest endpoint.
@@ -284,7 +606,7 @@ impl RuntimeInstaller {
         } else {
             "latest"
         };
-        let release = github_release_assets(owner, repo, query_version)?;
+        let release = github_release_assets(&self.http, owner, repo, query_version)?;
         let patterns = platform_asset_patterns(os, arch);
 
         // Some runtimes publish a single platform-ambiguous archive (e.g. a
@@ -295,11 +617,12 @@ impl RuntimeInstaller {
         } else if archive_ext == ".7z" && release.assets.len() == 1 {
             let only = &release.assets[0];
             if only.name.ends_with(".7z") {
-                let probe = self.download_to_cache(
+                let (probe, _) = self.download_to_cache(
                     slug,
                     release.tag.trim_start_matches('v'),
                     &only.name,
                     &only.url,
+                    published_checksum(&self.http, &release, &only.name)?.as_deref(),
                 )?;
                 probe_runtime_archive(slug, &probe).with_context(|| {
                     format!(
@@ -327,39 +650,78 @@ impl RuntimeInstaller {
             )
         };
 
-        let archive_path = self.download_to_cache(
+        let expected = published_checksum(&self.http, &release, &asset.name)?;
+        let (archive_path, artifact) = self.download_to_cache(
             slug,
             release.tag.trim_start_matches('v'),
             &asset.name,
             &asset.url,
+            expected.as_deref(),
         )?;
 
+        let target =
+            self.unpack_release_archive(slug, &archive_path, &asset.name, archive_ext, tmp_dir)?;
+        Ok((target, artifact))
+    }
+
+    /// Installs the release asset `clawden.lock` recorded, skipping the
+    /// GitHub API lookup; the lock's digest is enforced on download.
+    fn install_locked_release(
+        &self,
+        slug: &str,
+        locked: &LockedRuntime,
+        archive_ext: &str,
+        tmp_dir: &Path,
+    ) -> Result<(PathBuf, ArtifactRecord)> {
+        let asset_name = locked
+            .url
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .ok_or_else(|| anyhow!("clawden.lock has no asset name for {slug}: {}", locked.url))?;
+        let (archive_path, artifact) = self.download_to_cache(
+            slug,
+            &locked.version,
+            asset_name,
+            &locked.url,
+            Some(&locked.digest),
+        )?;
+        let target =
+            self.unpack_release_archive(slug, &archive_path, asset_name, archive_ext, tmp_dir)?;
+        Ok((target, artifact))
+    }
+
+    fn unpack_release_archive(
+        &self,
+        slug: &str,
+        archive_path: &Path,
+        asset_name: &str,
+        archive_ext: &str,
+        tmp_dir: &Path,
+    ) -> Result<PathBuf> {
         if archive_ext == ".7z" {
-            probe_runtime_archive(slug, &archive_path).with_context(|| {
-                format!(
-                    "{slug} release asset '{}' is not runnable on {os}-{arch}",
-                    asset.name
-                )
+            let (os, arch) = host_os_arch()?;
+            probe_runtime_archive(slug, archive_path).with_context(|| {
+                format!("{slug} release asset '{asset_name}' is not runnable on {os}-{arch}")
             })?;
         }
 
         self.report_progress(&format!("Extracting {slug} archive…"));
 
         if archive_ext == ".7z" {
-            sevenz_rust::decompress_file(&archive_path, tmp_dir).with_context(|| {
+            sevenz_rust::decompress_file(archive_path, tmp_dir).with_context(|| {
                 format!(
                     "failed to extract {slug} 7z archive: {}",
                     archive_path.display()
                 )
             })?;
         } else {
-            self.extract_tar_gz(&archive_path, tmp_dir)?;
+            self.extract_tar_gz(archive_path, tmp_dir)?;
         }
 
         let candidate = find_executable_by_name(tmp_dir, slug)?.ok_or_else(|| {
             anyhow!(
-                "Download validation failed for {}: archive is missing expected runtime binary",
-                asset.name
+                "Download validation failed for {asset_name}: archive is missing expected runtime binary"
             )
         })?;
 
@@ -376,27 +738,50 @@ impl RuntimeInstaller {
         package: &str,
         version: &str,
         tmp_dir: &Path,
-    ) -> Result<PathBuf> {
+        locked: Option<&LockedRuntime>,
+    ) -> Result<(PathBuf, ArtifactRecord)> {
         ensure_command_available("node", "node")?;
         ensure_command_available("npm", "npm")?;
 
         let install_prefix = tmp_dir.join(format!("{slug}-prefix"));
         fs::create_dir_all(&install_prefix)?;
 
-        let package_spec = if version == "latest" {
-            format!("{package}@latest")
-        } else {
-            format!("{package}@{}", normalize_version(version))
+        // Install from the registry tarball checked against its published
+        // integrity, so the package itself cannot change under a version.
+        let (tarball_url, integrity) = match locked {
+            Some(entry) => (entry.url.clone(), Some(entry.digest.clone())),
+            None => query_npm_dist(&self.http, package, version)?,
         };
+        let tarball_name = tarball_url
+            .rsplit('/')
+            .next()
+            .filter(|name| !name.is_empty())
+            .unwrap_or("package.tgz")
+            .to_string();
+        let (tarball, artifact) = self.download_to_cache(
+            slug,
+            version,
+            &tarball_name,
+            &tarball_url,
+            integrity.as_deref(),
+        )?;
 
         self.report_progress(&format!("Installing {slug} via npm…"));
+        let mut npm = Command::new("npm");
+        if self.lock_mode == LockMode::Frozen {
+            npm.arg("--offline");
+        }
+        if let Some(mirror) = self.http.mirror() {
+            // Dependencies come from the mirrored registry as well.
+            npm.arg("--registry")
+                .arg(format!("{mirror}/registry.npmjs.org/"));
+        }
         run_command(
-            Command::new("npm")
-                .arg("install")
+            npm.arg("install")
                 .arg("-g")
                 .arg("--prefix")
                 .arg(&install_prefix)
-                .arg(&package_spec),
+                .arg(&tarball),
             &format!("install {slug} with npm"),
         )?;
 
@@ -410,7 +795,7 @@ impl RuntimeInstaller {
             slug,
             &format!("\"$SCRIPT_DIR/{slug}-runtime/current/bin/{slug}\" \"$@\""),
         )?;
-        Ok(launcher)
+        Ok((launcher, artifact))
     }
 
     fn install_git_clone(
@@ -419,7 +804,8 @@ impl RuntimeInstaller {
         url: &str,
         version: &str,
         tmp_dir: &Path,
-    ) -> Result<PathBuf> {
+        locked: Option<&LockedRuntime>,
+    ) -> Result<(PathBuf, ArtifactRecord)> {
         ensure_command_available("git", "git")?;
         ensure_command_available("node", "node")?;
         ensure_command_available("pnpm", "pnpm")?;
@@ -430,19 +816,57 @@ impl RuntimeInstaller {
             normalize_version(version)
         };
 
+        if self.lock_mode == LockMode::Frozen {
+            bail!("{slug} is installed from git and cannot be installed offline (--frozen)");
+        }
+
+        // Pin the clone to the commit the ref resolves to now; a full commit
+        // sha can also be requested directly as the version, and the lock
+        // file supplies one for locked installs.
+        let pinned = locked
+            .and_then(|entry| entry.digest.strip_prefix("commit:"))
+            .map(str::to_string)
+            .or_else(|| is_commit_sha(&ref_name).then(|| ref_name.to_ascii_lowercase()));
+        let commit = match &pinned {
+            Some(commit) => commit.clone(),
+            None => resolve_git_ref(url, &ref_name)?,
+        };
+
         self.report_progress(&format!("Cloning {slug} repository…"));
         let repo_dir = tmp_dir.join(format!("{slug}-src"));
-        run_command(
-            Command::new("git")
-                .arg("clone")
-                .arg("--depth")
-                .arg("1")
-                .arg("--branch")
-                .arg(&ref_name)
-                .arg(url)
-                .arg(&repo_dir),
-            &format!("clone {slug} repository"),
-        )?;
+        if pinned.is_some() {
+            fs::create_dir_all(&repo_dir)?;
+            run_command(
+                command_in_dir("git", &repo_dir).arg("init").arg("--quiet"),
+                &format!("clone {slug} repository"),
+            )?;
+            run_command(
+                command_in_dir("git", &repo_dir)
+                    .args(["fetch", "--depth", "1", url])
+                    .arg(&commit),
+                &format!("clone {slug} repository"),
+            )?;
+            run_command(
+                command_in_dir("git", &repo_dir).args(["checkout", "--detach", "FETCH_HEAD"]),
+                &format!("clone {slug} repository"),
+            )?;
+        } else {
+            run_command(
+                Command::new("git")
+                    .arg("clone")
+                    .arg("--depth")
+                    .arg("1")
+                    .arg("--branch")
+                    .arg(&ref_name)
+                    .arg(url)
+                    .arg(&repo_dir),
+                &format!("clone {slug} repository"),
+            )?;
+        }
+        let head = git_head_commit(&repo_dir)?;
+        if head != commit {
+            bail!("{slug} clone is at {head} but {ref_name} resolved to {commit}; the ref moved during install, retry");
+        }
 
         self.report_progress(&format!("Installing {slug} dependencies…"));
         run_command(
@@ -476,16 +900,27 @@ impl RuntimeInstaller {
             slug,
             &format!("cd \"$SCRIPT_DIR/{slug}-src\" && pnpm start -- \"$@\""),
         )?;
-        Ok(launcher)
+        Ok((
+            launcher,
+            ArtifactRecord {
+                url: url.to_string(),
+                digest: format!("commit:{commit}"),
+                verified: true,
+            },
+        ))
     }
 
+    /// Downloads `url` into the cache, or reuses the cached copy when its
+    /// recorded digest still matches. `expected` is the published digest,
+    /// if any; a mismatch is fatal.
     fn download_to_cache(
         &self,
         runtime: &str,
         version: &str,
         artifact_name: &str,
         url: &str,
-    ) -> Result<PathBuf> {
+        expected: Option<&str>,
+    ) -> Result<(PathBuf, ArtifactRecord)> {
         if !url.starts_with("https://") {
             bail!("refusing non-https runtime download URL: {url}");
         }
@@ -493,32 +928,83 @@ impl RuntimeInstaller {
         let runtime_cache = self.cache_dir.join(runtime).join(version);
         fs::create_dir_all(&runtime_cache)?;
         let final_path = runtime_cache.join(artifact_name);
-        if final_path.exists() && fs::metadata(&final_path)?.len() > 0 {
-            return Ok(final_path);
+        let record_path = runtime_cache.join(format!("{artifact_name}.digest"));
+        if let Some(record) = ArtifactRecord::load(&record_path).filter(|_| final_path.exists()) {
+            let actual = file_digest_like(&final_path, &record.digest)?;
+            if actual == record.digest && expected.is_none_or(|digest| digest == actual) {
+                let record = ArtifactRecord {
+                    verified: record.verified || expected.is_some(),
+                    ..record
+                };
+                self.ensure_verified(artifact_name, &record)?;
+                record.save(&record_path)?;
+                return Ok((final_path, record));
+            }
+            self.report_progress(&format!(
+                "Cached {artifact_name} failed verification, downloading again…"
+            ));
         }
 
+        // A partial download left by an interrupted attempt is resumed.
         let tmp_path = runtime_cache.join(format!(".{artifact_name}.tmp"));
-        if tmp_path.exists() {
-            fs::remove_file(&tmp_path)?;
-        }
 
-        ensure_command_available("curl", "curl")?;
+        if self.lock_mode == LockMode::Frozen {
+            bail!(
+                "{artifact_name} is not in the download cache and --frozen forbids downloading it"
+            );
+        }
         self.report_progress(&format!("Downloading {runtime} {version}…"));
-        run_command(
-            Command::new("curl")
-                .arg("-fsSL")
-                .arg(url)
-                .arg("-o")
-                .arg(&tmp_path),
-            &format!("download runtime artifact from {url}"),
-        )?;
+        self.http.download(url, &tmp_path, &|done, total| {
+            let size = match total {
+                Some(total) if total > 0 => format!(
+                    "{} / {} ({}%)",
+                    format_bytes(done),
+                    format_bytes(total),
+                    done * 100 / total
+                ),
+                _ => format_bytes(done),
+            };
+            self.report_progress(&format!("Downloading {runtime} {version}… {size}"));
+        })?;
 
         if !tmp_path.exists() || fs::metadata(&tmp_path)?.len() == 0 {
             bail!("downloaded artifact is empty: {artifact_name}");
         }
 
+        let actual = file_digest_like(&tmp_path, expected.unwrap_or("sha256:"))?;
+        if let Some(expected) = expected.filter(|digest| *digest != actual) {
+            let _ = fs::remove_file(&tmp_path);
+            bail!("checksum mismatch for {artifact_name}: expected {expected}, got {actual}");
+        }
+        let record = ArtifactRecord {
+            url: url.to_string(),
+            digest: actual,
+            verified: expected.is_some(),
+        };
+        if let Err(err) = self.ensure_verified(artifact_name, &record) {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(err);
+        }
+
         fs::rename(&tmp_path, &final_path)?;
-        Ok(final_path)
+        record.save(&record_path)?;
+        Ok((final_path, record))
+    }
+
+    fn ensure_verified(&self, artifact_name: &str, record: &ArtifactRecord) -> Result<()> {
+        if record.verified {
+            return Ok(());
+        }
+        if self.require_checksums {
+            bail!(
+                "refusing to install {artifact_name}: no published checksum to verify it against (checksums are required)"
+            );
+        }
+        self.report_progress(&format!(
+            "No published checksum for {artifact_name}; recorded {}",
+            record.digest
+        ));
+        Ok(())
     }
 
     fn extract_tar_gz(&self, archive: &Path, output_dir: &Path) -> Result<()> {
@@ -533,7 +1019,7 @@ impl RuntimeInstaller {
         )
     }
 
-    fn append_audit(&self, action: &str, runtime: &str, outcome: &str) -> Result<()> {
+    pub(crate) fn append_audit(&self, action: &str, runtime: &str, outcome: &str) -> Result<()> {
         let audit_path = self.logs_dir.join("audit.log");
         let now_ms = std::time::SystemTime::now()
             .duration_since(std::time::UNIX_EPOCH)
@@ -551,6 +1037,10 @@ impl RuntimeInstaller {
     pub fn root_dir(&self) -> &Path {
         &self.root_dir
     }
+
+    pub(crate) fn acquire_install_lock(&self) -> Result<InstallLock> {
+        InstallLock::acquire(&self.lock_path)
+    }
 }
 
 /// Whether this runtime supports `--config-dir` based config injection.
@@ -629,11 +1119,11 @@ struct GithubAsset {
 }
 
 fn github_release_assets(
+    http: &Http,
     owner: &str,
     repo: &str,
     requested_version: &str,
 ) -> Result<GithubRelease> {
-    ensure_command_available("curl", "curl")?;
     let url = if requested_version == "latest" {
         format!("https://api.github.com/repos/{owner}/{repo}/releases/latest")
     } else {
@@ -641,22 +1131,9 @@ fn github_release_assets(
         format!("https://api.github.com/repos/{owner}/{repo}/releases/tags/v{normalized}")
     };
 
-    let output = Command::new("curl")
-        .arg("-fsSL")
-        .arg("-H")
-        .arg("Accept: application/vnd.github+json")
-        .arg("-H")
-        .arg("User-Agent: clawden")
-        .arg(&url)
-        .output()
-        .with_context(|| format!("failed to query GitHub release API: {url}"))?;
-
-    if !output.status.success() {
-        bail!("failed to query GitHub release API: {url}");
-    }
-
-    let value: serde_json::Value = serde_json::from_slice(&output.stdout)
-        .with_context(|| format!("invalid GitHub release API response: {url}"))?;
+    let value = http
+        .get_json(&url)
+        .context("failed to query GitHub release API")?;
 
     let tag = value
         .get("tag_name")
@@ -774,7 +1251,7 @@ fn runtime_probe_candidates(runtime: &str) -> &'static [&'static [&'static str]]
     }
 }
 
-fn ensure_runtime_supported(runtime: &str) -> Result<&'static crate::RuntimeDescriptor> {
+pub(crate) fn ensure_runtime_supported(runtime: &str) -> Result<&'static crate::RuntimeDescriptor> {
     let Some(descriptor) = runtime_descriptor(runtime) else {
         return Err(anyhow!("runtime '{}' not recognized", runtime));
     };
@@ -793,7 +1270,7 @@ fn clawden_root_dir() -> Result<PathBuf> {
     Ok(PathBuf::from(home).join(".clawden"))
 }
 
-struct InstallLock {
+pub(crate) struct InstallLock {
     path: PathBuf,
 }
 
@@ -939,25 +1416,131 @@ fn is_version_constraint(raw: &str) -> bool {
         || value.starts_with('=')
 }
 
-fn query_latest_npm_version(package: &str) -> Result<String> {
-    ensure_command_available("npm", "npm")?;
-    let output = Command::new("npm")
-        .args(["view", package, "version", "--json"])
-        .output()
+fn query_latest_npm_version(http: &Http, package: &str) -> Result<String> {
+    let parsed = http
+        .get_json(&npm_registry_url(package, "latest"))
         .with_context(|| format!("failed to query npm for {package} latest version"))?;
+    if let Some(version) = parsed.get("version").and_then(|v| v.as_str()) {
+        return Ok(normalize_version(version));
+    }
+    bail!("npm returned unexpected latest version payload for {package}")
+}
+
+/// Tarball URL and SRI integrity npm publishes for `package_spec`.
+fn query_npm_dist(http: &Http, package: &str, version: &str) -> Result<(String, Option<String>)> {
+    let version = if version == "latest" {
+        "latest".to_string()
+    } else {
+        normalize_version(version)
+    };
+    let parsed = http
+        .get_json(&npm_registry_url(package, &version))
+        .with_context(|| format!("failed to query npm for {package}@{version} dist metadata"))?;
+    let dist = parsed.get("dist");
+    let tarball = dist
+        .and_then(|dist| dist.get("tarball"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| anyhow!("npm returned no tarball for {package}@{version}"))?;
+    let integrity = dist
+        .and_then(|dist| dist.get("integrity"))
+        .and_then(|v| v.as_str())
+        .filter(|v| v.starts_with("sha512-"))
+        .map(ToString::to_string);
+    Ok((tarball.to_string(), integrity))
+}
+
+/// Registry document for one version (or dist-tag) of `package`; scoped
+/// names keep their `@` and escape the slash.
+fn npm_registry_url(package: &str, version: &str) -> String {
+    format!(
+        "https://registry.npmjs.org/{}/{version}",
+        package.replace('/', "%2f")
+    )
+}
+
+/// Looks for a checksum the release publishes for `asset_name`: a sibling
+/// `<asset>.sha256` or a `checksums.txt` / `SHA256SUMS` listing.
+fn published_checksum(
+    http: &Http,
+    release: &GithubRelease,
+    asset_name: &str,
+) -> Result<Option<String>> {
+    let sibling = format!("{asset_name}.sha256");
+    let sources = release
+        .assets
+        .iter()
+        .filter(|asset| asset.name == sibling)
+        .chain(
+            release
+                .assets
+                .iter()
+                .filter(|asset| is_checksum_listing(&asset.name)),
+        );
+    for source in sources {
+        let body = fetch_text(http, &source.url)
+            .with_context(|| format!("failed to fetch published checksums {}", source.name))?;
+        if let Some(digest) = parse_checksum_listing(&body, asset_name)? {
+            return Ok(Some(digest));
+        }
+    }
+    Ok(None)
+}
+
+fn is_checksum_listing(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+    name.ends_with("checksums.txt") || name == "sha256sums" || name == "sha256sums.txt"
+}
+
+fn fetch_text(http: &Http, url: &str) -> Result<String> {
+    if !url.starts_with("https://") {
+        bail!("refusing non-https checksum URL: {url}");
+    }
+    http.get_text(url)
+}
+
+fn is_commit_sha(raw: &str) -> bool {
+    raw.len() == 40 && raw.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Commit a branch or tag of `url` currently points at. Annotated tags are
+/// peeled to the commit they tag.
+fn resolve_git_ref(url: &str, ref_name: &str) -> Result<String> {
+    let output = Command::new("git")
+        .arg("ls-remote")
+        .arg(url)
+        .arg(format!("refs/heads/{ref_name}"))
+        .arg(format!("refs/tags/{ref_name}"))
+        .arg(format!("refs/tags/{ref_name}^{{}}"))
+        .output()
+        .with_context(|| format!("failed to resolve {ref_name} in {url}"))?;
     if !output.status.success() {
         bail!(
-            "npm view {package} version failed with status {}",
+            "git ls-remote {url} {ref_name} failed with status {}",
             output.status
         );
     }
 
-    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout)
-        .with_context(|| format!("npm returned invalid JSON for {package} latest version"))?;
-    if let Some(version) = parsed.as_str() {
-        return Ok(normalize_version(version));
+    let body = String::from_utf8_lossy(&output.stdout);
+    let refs = body
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .collect::<Vec<_>>();
+    refs.iter()
+        .find(|(_, name)| name.ends_with("^{}"))
+        .or_else(|| refs.first())
+        .map(|(sha, _)| sha.to_string())
+        .ok_or_else(|| anyhow!("{ref_name} not found in {url}"))
+}
+
+fn git_head_commit(repo_dir: &Path) -> Result<String> {
+    let output = command_in_dir("git", repo_dir)
+        .args(["rev-parse", "HEAD"])
+        .output()
+        .context("failed to read cloned commit")?;
+    if !output.status.success() {
+        bail!("git rev-parse HEAD failed with status {}", output.status);
     }
-    bail!("npm returned unexpected latest version payload for {package}")
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
 }
 
 fn query_git_head_branch(url: &str) -> Result<String> {
@@ -1063,6 +1646,15 @@ fn write_launcher(path: &Path, runtime: &str, body: &str) -> Result<()> {
     make_executable(path)
 }
 
+fn replace_symlink(target: &Path, link: &Path) -> Result<()> {
+    if link.exists() || link.is_symlink() {
+        let _ = fs::remove_file(link);
+        let _ = fs::remove_dir_all(link);
+    }
+    std::os::unix::fs::symlink(target, link)?;
+    Ok(())
+}
+
 fn make_executable(path: &Path) -> Result<()> {
     use std::os::unix::fs::PermissionsExt;
     let mut perms = fs::metadata(path)?.permissions();
@@ -1155,11 +1747,14 @@ fn walkdir(root: &Path) -> Vec<PathBuf> {
 #[cfg(test)]
 mod tests {
     use super::{
-        pick_asset, platform_asset_patterns, runtime_subcommand_hints, runtime_supports_config_dir,
-        validate_runtime_binary_exec, version_satisfies, GithubAsset,
+        pick_asset, platform_asset_patterns, resolve_git_ref, runtime_subcommand_hints,
+        runtime_supports_config_dir, validate_runtime_binary_exec, version_satisfies, GithubAsset,
+        RuntimeInstaller,
     };
+    use crate::checksum::ArtifactRecord;
     use std::fs;
-    use std::path::PathBuf;
+    use std::path::{Path, PathBuf};
+    use std::process::Command;
     use std::time::{SystemTime, UNIX_EPOCH};
 
     fn temp_dir(name: &str) -> PathBuf {
@@ -1177,6 +1772,144 @@ mod tests {
         super::make_executable(path).expect("script should be executable");
     }
 
+    fn installer_at(root: &Path) -> RuntimeInstaller {
+        RuntimeInstaller {
+            root_dir: root.to_path_buf(),
+            runtimes_dir: root.join("runtimes"),
+            cache_dir: root.join("cache"),
+            logs_dir: root.join("logs"),
+            lock_path: root.join(".install.lock"),
+            require_checksums: false,
+            lockfile: None,
+            lock_mode: Default::default(),
+            http: Default::default(),
+            progress: None,
+        }
+    }
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let output = Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .output()
+            .expect("git should run");
+        assert!(output.status.success(), "git {args:?} failed");
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn versions_install_side_by_side_and_roll_back() {
+        let root = temp_dir("side-by-side");
+        let installer = installer_at(&root);
+        fs::create_dir_all(root.join("logs")).expect("create logs dir");
+        for version in ["0.1.6", "0.2.0"] {
+            let dir = root.join("runtimes/zeroclaw").join(version);
+            fs::create_dir_all(&dir).expect("create version dir");
+            write_executable(&dir.join("zeroclaw"), "#!/usr/bin/env sh\nexit 0\n");
+            installer
+                .point_current_at("zeroclaw", version)
+                .expect("point current");
+        }
+        let versions = installer
+            .list_installed_versions()
+            .expect("list versions")
+            .into_iter()
+            .map(|row| row.version)
+            .collect::<Vec<_>>();
+        assert_eq!(versions, ["0.1.6", "0.2.0"]);
+        assert_eq!(
+            installer.previous_version("zeroclaw").as_deref(),
+            Some("0.1.6")
+        );
+
+        let rolled = installer.rollback_runtime("zeroclaw").expect("roll back");
+        assert_eq!(rolled.version, "0.1.6");
+        assert_eq!(
+            installer
+                .installed_version("zeroclaw")
+                .expect("read installed version")
+                .as_deref(),
+            Some("0.1.6")
+        );
+        assert_eq!(
+            installer.previous_version("zeroclaw").as_deref(),
+            Some("0.2.0")
+        );
+
+        let err = installer
+            .uninstall_version("zeroclaw", "0.1.6")
+            .unwrap_err();
+        assert!(err.to_string().contains("current version"), "{err}");
+        installer
+            .uninstall_version("zeroclaw", "0.2.0")
+            .expect("uninstall version");
+        assert!(installer.previous_version("zeroclaw").is_none());
+        assert!(installer.rollback_runtime("zeroclaw").is_err());
+        assert!(installer.use_version("zeroclaw", "0.2.0").is_err());
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn cached_downloads_are_reverified_against_their_recorded_digest() {
+        let root = temp_dir("cache-verify");
+        let mut installer = installer_at(&root);
+        let cache = root.join("cache/zeroclaw/1.0.0");
+        fs::create_dir_all(&cache).expect("create cache dir");
+        fs::write(cache.join("zeroclaw.tar.gz"), b"hello\n").expect("write cached archive");
+        let digest = "sha256:5891b5b522d5df086d0ff0b110fbd9d21bb4fc7163af34d08286a2e846f6be03";
+        ArtifactRecord {
+            url: "https://example.invalid/zeroclaw.tar.gz".to_string(),
+            digest: digest.to_string(),
+            verified: false,
+        }
+        .save(&cache.join("zeroclaw.tar.gz.digest"))
+        .expect("save digest record");
+
+        let download = |installer: &RuntimeInstaller, expected: Option<&str>| {
+            installer.download_to_cache(
+                "zeroclaw",
+                "1.0.0",
+                "zeroclaw.tar.gz",
+                "https://example.invalid/zeroclaw.tar.gz",
+                expected,
+            )
+        };
+        let (_, record) = download(&installer, None).expect("intact cache entry is reused");
+        assert!(!record.verified);
+
+        installer.set_require_checksums(true);
+        let err = download(&installer, None).expect_err("strict mode refuses unverified");
+        assert!(err.to_string().contains("no published checksum"), "{err}");
+
+        let (_, record) = download(&installer, Some(digest)).expect("published digest matches");
+        assert!(record.verified);
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn git_refs_resolve_to_commits_including_annotated_tags() {
+        let repo = temp_dir("git-ref");
+        git(&repo, &["init", "--quiet", "--initial-branch", "main"]);
+        fs::write(repo.join("README"), "one").expect("write readme");
+        git(&repo, &["add", "README"]);
+        git(&repo, &["commit", "--quiet", "-m", "one"]);
+        let commit = git(&repo, &["rev-parse", "HEAD"]);
+        git(&repo, &["tag", "-a", "v1.0.0", "-m", "release"]);
+
+        let url = repo.to_string_lossy();
+        assert_eq!(
+            resolve_git_ref(&url, "main").expect("resolve branch"),
+            commit
+        );
+        assert_eq!(
+            resolve_git_ref(&url, "v1.0.0").expect("resolve tag"),
+            commit
+        );
+        assert!(resolve_git_ref(&url, "missing").is_err());
+        let _ = fs::remove_dir_all(repo);
+    }
+
     #[test]
     fn version_constraints_support_exact_wildcard_range_and_latest() {
         assert!(version_satisfies("0.2.1", "0.2.1"));
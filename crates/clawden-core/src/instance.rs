@@ -0,0 +1,246 @@
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+
+/// Separates the runtime slug from the instance name in `runtime@instance`.
+pub const INSTANCE_SEPARATOR: char = '@';
+
+/// First port handed out to runtimes that do not declare a default port.
+const FALLBACK_PORT_BASE: u16 = 43000;
+
+/// How far past the preferred port the allocator searches for a free one.
+const PORT_SEARCH_SPAN: u16 = 1000;
+
+/// The runtime part of an instance name: `zeroclaw@work` -> `zeroclaw`.
+/// Bare runtime names are returned unchanged.
+pub fn instance_runtime(name: &str) -> &str {
+    name.split_once(INSTANCE_SEPARATOR)
+        .map(|(runtime, _)| runtime)
+        .unwrap_or(name)
+}
+
+/// Builds the instance name for a runtime. `None` (or an instance equal to
+/// the runtime) is the default instance, which keeps the bare runtime name.
+pub fn instance_name(runtime: &str, instance: Option<&str>) -> String {
+    match instance.map(str::trim).filter(|i| !i.is_empty()) {
+        Some(instance) if instance != runtime => {
+            format!("{runtime}{INSTANCE_SEPARATOR}{instance}")
+        }
+        _ => runtime.to_string(),
+    }
+}
+
+/// Validates a `runtime` or `runtime@instance` name for use in state file
+/// names.
+pub fn validate_instance_name(name: &str) -> Result<()> {
+    let (runtime, instance) = match name.split_once(INSTANCE_SEPARATOR) {
+        Some((runtime, instance)) => (runtime, Some(instance)),
+        None => (name, None),
+    };
+    let valid_part = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    if !valid_part(runtime) || instance.is_some_and(|i| !valid_part(i)) {
+        bail!("invalid instance name '{name}' (expected runtime or runtime@instance using letters, digits, '-' or '_')");
+    }
+    Ok(())
+}
+
+/// Per-instance host ports, persisted in `ports.json` under the run dir so
+/// concurrent instances of one runtime never share a port.
+#[derive(Debug, Clone)]
+pub struct PortAllocator {
+    path: PathBuf,
+}
+
+impl PortAllocator {
+    pub fn new(state_dir: &Path) -> Self {
+        Self {
+            path: state_dir.join("ports.json"),
+        }
+    }
+
+    /// Returns the port assigned to `instance`, allocating one if needed.
+    /// The previous assignment is kept while it is still free; otherwise the
+    /// search starts at `preferred` (the runtime's default port).
+    pub fn allocate(&self, instance: &str, preferred: Option<u16>) -> Result<u16> {
+        let _lock = self.lock()?;
+        let mut ports = self.load()?;
+        let previous = ports.remove(instance);
+        let taken = |port: u16| ports.values().any(|assigned| *assigned == port);
+
+        let base = preferred.unwrap_or(FALLBACK_PORT_BASE);
+        let candidates = previous
+            .into_iter()
+            .chain((0..PORT_SEARCH_SPAN).filter_map(|offset| base.checked_add(offset)));
+        let mut chosen = None;
+        for port in candidates {
+            if !taken(port) && port_is_free(port) {
+                chosen = Some(port);
+                break;
+            }
+        }
+        let Some(port) = chosen else {
+            bail!(
+                "no free port found for {instance} in {base}..{}",
+                base.saturating_add(PORT_SEARCH_SPAN)
+            );
+        };
+
+        ports.insert(instance.to_string(), port);
+        self.save(&ports)?;
+        Ok(port)
+    }
+
+    pub fn port(&self, instance: &str) -> Result<Option<u16>> {
+        Ok(self.load()?.get(instance).copied())
+    }
+
+    pub fn release(&self, instance: &str) -> Result<()> {
+        let _lock = self.lock()?;
+        let mut ports = self.load()?;
+        if ports.remove(instance).is_some() {
+            self.save(&ports)?;
+        }
+        Ok(())
+    }
+
+    /// Takes an exclusive advisory lock next to `ports.json`, released when
+    /// the returned file is dropped, so concurrent `clawden` processes do not
+    /// hand out the same port.
+    fn lock(&self) -> Result<File> {
+        let path = self.path.with_extension("json.lock");
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        file.lock()
+            .with_context(|| format!("failed to lock {}", path.display()))?;
+        Ok(file)
+    }
+
+    fn load(&self) -> Result<BTreeMap<String, u16>> {
+        if !self.path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        let raw = fs::read_to_string(&self.path)
+            .with_context(|| format!("failed to read {}", self.path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("invalid port assignments in {}", self.path.display()))
+    }
+
+    /// Replaces the file atomically so unlocked readers never see a
+    /// partial write.
+    fn save(&self, ports: &BTreeMap<String, u16>) -> Result<()> {
+        let tmp = self
+            .path
+            .with_extension(format!("json.{}.tmp", std::process::id()));
+        fs::write(&tmp, serde_json::to_string_pretty(ports)?)
+            .with_context(|| format!("failed to write {}", tmp.display()))?;
+        fs::rename(&tmp, &self.path)
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+fn port_is_free(port: u16) -> bool {
+    TcpListener::bind(("127.0.0.1", port)).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{instance_name, instance_runtime, validate_instance_name, PortAllocator};
+    use std::net::TcpListener;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn instance_names_round_trip() {
+        assert_eq!(instance_name("zeroclaw", None), "zeroclaw");
+        assert_eq!(instance_name("zeroclaw", Some("zeroclaw")), "zeroclaw");
+        assert_eq!(instance_name("zeroclaw", Some("work")), "zeroclaw@work");
+        assert_eq!(instance_runtime("zeroclaw@work"), "zeroclaw");
+        assert_eq!(instance_runtime("zeroclaw"), "zeroclaw");
+
+        assert!(validate_instance_name("zeroclaw@work-2").is_ok());
+        assert!(validate_instance_name("zeroclaw@").is_err());
+        assert!(validate_instance_name("zeroclaw@../x").is_err());
+    }
+
+    #[test]
+    fn allocator_gives_instances_distinct_stable_ports() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("clawden-ports-{stamp}"));
+        std::fs::create_dir_all(&dir).expect("state dir");
+        let allocator = PortAllocator::new(&dir);
+
+        // Reserve an ephemeral port so the preferred one is known to be busy.
+        let busy = TcpListener::bind("127.0.0.1:0").expect("bind ephemeral");
+        let busy_port = busy.local_addr().expect("local addr").port();
+
+        let first = allocator
+            .allocate("zeroclaw", Some(busy_port))
+            .expect("first allocation");
+        let second = allocator
+            .allocate("zeroclaw@work", Some(busy_port))
+            .expect("second allocation");
+        assert_ne!(first, busy_port);
+        assert_ne!(first, second);
+        assert_eq!(
+            allocator
+                .allocate("zeroclaw", Some(busy_port))
+                .expect("re-allocation"),
+            first
+        );
+        assert_eq!(allocator.port("zeroclaw@work").expect("port"), Some(second));
+
+        allocator.release("zeroclaw@work").expect("release");
+        assert_eq!(allocator.port("zeroclaw@work").expect("port"), None);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn concurrent_allocations_never_share_a_port() {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("clawden-ports-race-{stamp}"));
+        std::fs::create_dir_all(&dir).expect("state dir");
+
+        let allocators: Vec<_> = (0..8)
+            .map(|i| {
+                let allocator = PortAllocator::new(&dir);
+                std::thread::spawn(move || {
+                    allocator
+                        .allocate(&format!("zeroclaw@w{i}"), Some(44100))
+                        .expect("allocation")
+                })
+            })
+            .collect();
+        let mut ports: Vec<_> = allocators
+            .into_iter()
+            .map(|allocator| allocator.join().expect("allocator thread"))
+            .collect();
+        ports.sort_unstable();
+        ports.dedup();
+        assert_eq!(ports.len(), 8);
+
+        let allocator = PortAllocator::new(&dir);
+        for i in 0..8 {
+            assert!(allocator
+                .port(&format!("zeroclaw@w{i}"))
+                .expect("port")
+                .is_some());
+        }
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -1,22 +1,52 @@
+mod approvals;
 mod audit;
+mod bundle;
+mod channel_notify;
 mod channel_registry;
 mod channels;
+mod checksum;
+mod custom_runtime;
 mod discovery;
+mod events;
+mod health_check;
+mod hooks;
+mod http;
 mod install;
+mod instance;
 mod lifecycle;
+mod lockfile;
+mod log_parse;
+mod log_rotation;
+mod log_tail;
 mod manager;
+mod metrics;
 mod process;
+mod process_group;
 mod provider_registry;
+mod redact;
+mod resource_limits;
 mod runtime_descriptor;
+mod skills;
+mod supervisor;
 mod swarm;
 mod util;
+mod worker;
 
 use anyhow::Result;
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-pub use audit::{append_audit, AuditEvent, AuditLog};
+pub use approvals::{
+    parse_approval_reply, ApprovalDecision, ApprovalError, ApprovalRecord, ApprovalStatus,
+    ApprovalStore,
+};
+pub use audit::{append_audit, AuditEvent, AuditLog, AuditSink};
+pub use bundle::{
+    BundleBuilder, BundleInstall, BundleManifest, BundledImage, BundledRuntime, BundledTree,
+    BUNDLE_MANIFEST,
+};
+pub use channel_notify::HttpChannelNotifier;
 pub use channel_registry::{
     channel_descriptor, channel_descriptors, channel_token_env_name, known_channel_env_vars,
     ChannelDescriptor, CHANNELS,
@@ -25,30 +55,71 @@ pub use channels::{
     BindChannelRequest, BindingConflict, ChannelConfigRequest, ChannelCredentialCheck,
     ChannelHealthEntry, ChannelStore, ChannelTypeSummary, MatrixRow,
 };
+pub use checksum::ArtifactRecord;
+pub use custom_runtime::{
+    custom_runtime_descriptors, load_custom_runtime_specs, register_custom_runtime,
+    ConfigDirFlagSpec, CustomRuntimeSpec, InstallSourceSpec, MessageApiSpec, VersionSourceSpec,
+    CUSTOM_RUNTIMES_DIR,
+};
 pub use discovery::{DiscoveredEndpoint, DiscoveryMethod, DiscoveryService};
+pub use events::{EventFilter, EventHub, EventSender, EventStream, RuntimeEvent, RuntimeEventKind};
+pub use health_check::{parse_probe_duration, HealthCheck, TcpTarget};
+pub use hooks::{
+    hook_template_variables, render_hook_template, ChannelNotifier, HookConfig, HookDelivery,
+    HookEngine, HookEvent, HookEventKind, HookNotifyTarget, HookRule,
+    DEFAULT_APPROVAL_TIMEOUT_SECS, HOOK_TEMPLATE_VARIABLES,
+};
 pub use install::{
     runtime_default_start_args, runtime_subcommand_hints, runtime_supports_config_dir,
     version_satisfies, InstallOutcome, InstalledRuntime, RuntimeInstaller, VersionCheck,
 };
+pub use instance::{
+    instance_name, instance_runtime, validate_instance_name, PortAllocator, INSTANCE_SEPARATOR,
+};
 pub use lifecycle::AgentState;
-pub use manager::{AgentRecord, LifecycleManager, ManagerError};
+pub use lockfile::{LockFile, LockMode, LockedRuntime};
+pub use log_parse::{parse_log_line, parse_time_bound, parse_timestamp_ms, LogLevel, LogRecord};
+pub use log_rotation::{previous_log_sessions, read_log_session, LogRotation};
+pub use manager::{sample_metrics, AgentRecord, LifecycleManager, ManagerError, MetricsTarget};
+pub use metrics::{
+    process_tree, process_tree_open_fds, MetricsHistory, MetricsSample, ProcessTreeSampler,
+    METRICS_HISTORY_CAPACITY,
+};
 pub use process::{
-    ExecutionMode, LogLine, LogStream, ProcessInfo, ProcessManager, RuntimeProcessStatus,
-    StopOutcome,
+    DirectStartOptions, ExecutionMode, LeftoverProcess, LogLine, LogStream, ProcessInfo,
+    ProcessManager, RuntimeProcessStatus, StopOutcome, TaskExit, TaskProcess,
 };
+pub use process_group::port_is_listening;
 pub use provider_registry::{
     infer_provider_from_host_env, known_provider_env_vars, provider_descriptor,
     provider_descriptors, provider_env_candidates, provider_env_vars, provider_primary_env_var,
     ProviderDescriptor, PROVIDERS,
 };
+pub use redact::Redactor;
+pub use resource_limits::{
+    check_limits, parse_memory_limit, AppliedLimits, LimitCheck, LimitStatus, ResourceLimits,
+};
 pub use runtime_descriptor::{
     direct_install_descriptors, runtime_descriptor, runtime_descriptor_for, runtime_descriptors,
-    ConfigDirFlag, ConfigFormat, InstallSource, RuntimeDescriptor, VersionSource,
+    ConfigDirFlag, ConfigFormat, InstallSource, MessageApi, MessageApiFormat, PortSetting,
+    RuntimeDescriptor, VersionSource,
+};
+pub use skills::{
+    load_skill_package, parse_skill_manifest, runtime_skills_path, validate_skill_name,
+    SkillDirectory, SKILL_MANIFEST_FILE,
+};
+pub use supervisor::{
+    run_supervisor, RestartPolicy, RestartSpec, SupervisorSpec, SupervisorState, SupervisorStatus,
+    SupervisorTuning,
 };
 pub use swarm::{SwarmCoordinator, SwarmMember, SwarmRole};
 pub use util::{current_unix_ms, runtime_env_prefix};
+pub use worker::{
+    worker_descriptor, worker_descriptor_for, worker_descriptors, CodingTool, Task, TaskCost,
+    TaskResult, TaskStatus, WorkerAdapter, WorkerConfig, WorkerDescriptor, WorkerHandle,
+};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum ClawRuntime {
     OpenClaw,
@@ -60,11 +131,15 @@ pub enum ClawRuntime {
     MicroClaw,
     MimiClaw,
     OpenFang,
+    /// A runtime declared in `~/.clawden/runtimes.d` or `clawden.yaml`,
+    /// identified by its slug.
+    #[serde(untagged)]
+    Custom(String),
 }
 
-// Display, from_str_loose, and as_slug are implemented in
+// Display, Deserialize, from_str_loose, and as_slug are implemented in
 // runtime_descriptor.rs — driven by descriptor data so adding a new
-// runtime only requires one DESCRIPTORS entry.
+// runtime only requires one DESCRIPTORS entry, or a custom runtime spec.
 
 // ---------------------------------------------------------------------------
 // Channel types
@@ -251,6 +326,8 @@ pub struct AgentMetrics {
     pub cpu_percent: f32,
     pub memory_mb: f32,
     pub queue_depth: u32,
+    #[serde(default)]
+    pub open_fds: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -273,17 +350,25 @@ pub struct RuntimeConfig {
 pub struct Skill {
     pub name: String,
     pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
+/// Portable skill package manifest, loaded from `skill.toml` (see
+/// [`load_skill_package`]). `runtimes` is the compatibility list enforced at
+/// install time.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SkillManifest {
     pub name: String,
     pub version: String,
+    #[serde(default)]
+    pub description: Option<String>,
     pub runtimes: Vec<ClawRuntime>,
+    /// Package directory the manifest was loaded from.
+    #[serde(skip)]
+    pub source: Option<std::path::PathBuf>,
 }
 
-pub type EventStream = Vec<serde_json::Value>;
-
 #[async_trait]
 pub trait ClawAdapter: Send + Sync {
     fn metadata(&self) -> RuntimeMetadata;
@@ -304,4 +389,5 @@ pub trait ClawAdapter: Send + Sync {
 
     async fn list_skills(&self, handle: &AgentHandle) -> Result<Vec<Skill>>;
     async fn install_skill(&self, handle: &AgentHandle, skill: &SkillManifest) -> Result<()>;
+    async fn remove_skill(&self, handle: &AgentHandle, name: &str) -> Result<bool>;
 }
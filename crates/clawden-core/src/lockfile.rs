@@ -0,0 +1,101 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+const LOCKFILE_VERSION: u32 = 1;
+const LOCKFILE_HEADER: &str = "# This file is generated by clawden. Do not edit it by hand.\n";
+
+/// How the installer treats `clawden.lock`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LockMode {
+    /// Install locked versions when they still satisfy the request and
+    /// record whatever gets resolved otherwise.
+    #[default]
+    Update,
+    /// Only install what the lock file records.
+    Locked,
+    /// Like `Locked`, and without network access.
+    Frozen,
+}
+
+/// `clawden.lock`: the exact version, source URL and digest resolved for
+/// each runtime of a project. Written next to `clawden.yaml`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockFile {
+    pub version: u32,
+    #[serde(default)]
+    pub runtimes: BTreeMap<String, LockedRuntime>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedRuntime {
+    pub version: String,
+    pub url: String,
+    /// Same notation as `ArtifactRecord::digest`.
+    pub digest: String,
+}
+
+impl Default for LockFile {
+    fn default() -> Self {
+        Self {
+            version: LOCKFILE_VERSION,
+            runtimes: BTreeMap::new(),
+        }
+    }
+}
+
+impl LockFile {
+    /// Reads `path`; a missing file is an empty lock.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let lock: Self =
+            toml::from_str(&raw).with_context(|| format!("failed to parse {}", path.display()))?;
+        if lock.version != LOCKFILE_VERSION {
+            bail!(
+                "{} has unsupported version {} (expected {LOCKFILE_VERSION})",
+                path.display(),
+                lock.version
+            );
+        }
+        Ok(lock)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let body = format!("{LOCKFILE_HEADER}{}", toml::to_string_pretty(self)?);
+        fs::write(path, body).with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LockFile, LockedRuntime};
+
+    #[test]
+    fn lock_file_round_trips_through_toml() {
+        let path = std::env::temp_dir().join(format!("clawden-lock-{}", std::process::id()));
+        let missing = LockFile::load(&path).expect("missing lock is empty");
+        assert!(missing.runtimes.is_empty());
+
+        let mut lock = LockFile::default();
+        lock.runtimes.insert(
+            "zeroclaw".to_string(),
+            LockedRuntime {
+                version: "0.2.1".to_string(),
+                url: "https://example.com/zeroclaw.tar.gz".to_string(),
+                digest: format!("sha256:{}", "a".repeat(64)),
+            },
+        );
+        lock.save(&path).expect("lock should save");
+        let raw = std::fs::read_to_string(&path).expect("read lock file");
+        assert!(raw.starts_with("# This file is generated by clawden"));
+        assert!(raw.contains("[runtimes.zeroclaw]"), "{raw}");
+        assert_eq!(LockFile::load(&path).expect("lock should load"), lock);
+        let _ = std::fs::remove_file(path);
+    }
+}
@@ -0,0 +1,498 @@
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// Accepts the level names used by common logging libraries
+    /// (`warning`, `err`, `fatal`, `critical`, ...), case-insensitively.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "trace" | "trc" => Some(Self::Trace),
+            "debug" | "dbg" => Some(Self::Debug),
+            "info" | "inf" | "information" | "notice" => Some(Self::Info),
+            "warn" | "wrn" | "warning" => Some(Self::Warn),
+            "error" | "err" | "eror" | "fatal" | "critical" | "crit" | "panic" | "alert"
+            | "emerg" => Some(Self::Error),
+            _ => None,
+        }
+    }
+
+    /// Numeric levels as written by pino and bunyan.
+    fn from_number(value: i64) -> Option<Self> {
+        match value {
+            ..=10 => Some(Self::Trace),
+            11..=20 => Some(Self::Debug),
+            21..=30 => Some(Self::Info),
+            31..=40 => Some(Self::Warn),
+            _ => Some(Self::Error),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// The structured view of one runtime log line. Fields the line does not
+/// carry stay `None`; `message` falls back to the whole line.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogRecord {
+    pub level: Option<LogLevel>,
+    pub target: Option<String>,
+    pub message: String,
+    /// Time written by the runtime itself, in unix milliseconds.
+    pub timestamp_ms: Option<u64>,
+}
+
+/// Parses JSON lines (tracing-subscriber, pino, bunyan, zap), logfmt
+/// (`level=info msg=...`), tracing's text format
+/// (`2024-05-01T12:00:00Z  INFO zeroclaw::gateway: listening`) and plain
+/// `LEVEL`/`[LEVEL]`/`LEVEL:` prefixes.
+pub fn parse_log_line(line: &str) -> LogRecord {
+    let clean = strip_ansi(line);
+    let trimmed = clean.trim();
+    if trimmed.starts_with('{') {
+        if let Ok(Value::Object(map)) = serde_json::from_str::<Value>(trimmed) {
+            return parse_json(&Value::Object(map), trimmed);
+        }
+    }
+
+    let mut rest = trimmed;
+    let mut timestamp_ms = None;
+    if let Some((ts, after)) = leading_timestamp(rest) {
+        timestamp_ms = Some(ts);
+        rest = after.trim_start();
+    }
+
+    if let Some((level, after)) = leading_level(rest) {
+        let after = after.trim_start();
+        let (target, message) = split_target(after, timestamp_ms.is_some());
+        return LogRecord {
+            level: Some(level),
+            target,
+            message: message.to_string(),
+            timestamp_ms,
+        };
+    }
+
+    if let Some(record) = parse_logfmt(rest, timestamp_ms) {
+        return record;
+    }
+
+    LogRecord {
+        level: None,
+        target: None,
+        message: rest.to_string(),
+        timestamp_ms,
+    }
+}
+
+fn parse_json(value: &Value, raw: &str) -> LogRecord {
+    let lookup = |keys: &[&str]| -> Option<&Value> {
+        keys.iter().find_map(|key| {
+            key.split('.')
+                .try_fold(value, |current, part| current.get(part))
+                .filter(|found| !found.is_null())
+        })
+    };
+    let as_text = |v: &Value| match v {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+
+    let level = lookup(&["level", "lvl", "severity", "log.level"]).and_then(|v| match v {
+        Value::String(s) => LogLevel::parse(s),
+        Value::Number(n) => n.as_i64().and_then(LogLevel::from_number),
+        _ => None,
+    });
+    let message = lookup(&["msg", "message", "fields.message"])
+        .map(as_text)
+        .unwrap_or_else(|| raw.to_string());
+    let target = lookup(&["target", "logger", "module", "component", "name"]).map(as_text);
+    let timestamp_ms = lookup(&["timestamp", "time", "ts", "@timestamp"]).and_then(|v| match v {
+        Value::String(s) => parse_timestamp_ms(s),
+        Value::Number(n) => n.as_f64().map(|secs_or_ms| {
+            // Seconds (possibly fractional) or milliseconds since the epoch.
+            if secs_or_ms > 1e11 {
+                secs_or_ms as u64
+            } else {
+                (secs_or_ms * 1000.0) as u64
+            }
+        }),
+        _ => None,
+    });
+
+    LogRecord {
+        level,
+        target,
+        message,
+        timestamp_ms,
+    }
+}
+
+fn parse_logfmt(line: &str, timestamp_ms: Option<u64>) -> Option<LogRecord> {
+    let pairs = logfmt_pairs(line);
+    let get = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| pairs.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone()))
+    };
+    let level = get(&["level", "lvl", "severity"]).and_then(|v| LogLevel::parse(&v))?;
+    Some(LogRecord {
+        level: Some(level),
+        target: get(&["target", "logger", "module", "component"]),
+        message: get(&["msg", "message"]).unwrap_or_else(|| line.to_string()),
+        timestamp_ms: timestamp_ms
+            .or_else(|| get(&["time", "ts", "timestamp"]).and_then(|v| parse_timestamp_ms(&v))),
+    })
+}
+
+fn logfmt_pairs(line: &str) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.peek().is_some_and(|c| c.is_whitespace()) {
+            chars.next();
+        }
+        let key: String = chars
+            .by_ref()
+            .take_while(|c| *c != '=' && !c.is_whitespace())
+            .collect();
+        if key.is_empty() {
+            if chars.peek().is_none() {
+                return pairs;
+            }
+            continue;
+        }
+        let mut value = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            while let Some(c) = chars.next() {
+                match c {
+                    '\\' => {
+                        if let Some(escaped) = chars.next() {
+                            value.push(escaped);
+                        }
+                    }
+                    '"' => break,
+                    other => value.push(other),
+                }
+            }
+        } else {
+            while let Some(c) = chars.peek() {
+                if c.is_whitespace() {
+                    break;
+                }
+                value.push(*c);
+                chars.next();
+            }
+        }
+        pairs.push((key, value));
+    }
+}
+
+/// A timestamp at the start of the line, bare or in brackets, optionally
+/// split into date and time by a space.
+fn leading_timestamp(line: &str) -> Option<(u64, &str)> {
+    let (open, body) = match line.strip_prefix('[') {
+        Some(body) => (true, body),
+        None => (false, line),
+    };
+    let end_of = |text: &str| {
+        text.find(|c: char| c.is_whitespace() || (open && c == ']'))
+            .unwrap_or(text.len())
+    };
+
+    let first_end = end_of(body);
+    let mut candidates = vec![first_end];
+    // "2024-05-01 12:00:00,123" spans two tokens.
+    if body[first_end..].starts_with(' ') {
+        let second = &body[first_end + 1..];
+        candidates.insert(0, first_end + 1 + end_of(second));
+    }
+    for end in candidates {
+        if let Some(ts) = parse_timestamp_ms(&body[..end]) {
+            let mut rest = &body[end..];
+            if open {
+                rest = rest.trim_start().strip_prefix(']')?;
+            }
+            return Some((ts, rest));
+        }
+    }
+    None
+}
+
+fn leading_level(line: &str) -> Option<(LogLevel, &str)> {
+    for (open, close) in [('[', ']'), ('<', '>')] {
+        if let Some(body) = line.strip_prefix(open) {
+            let end = body.find(close)?;
+            let level = LogLevel::parse(&body[..end])?;
+            let rest = &body[end + 1..];
+            return Some((level, rest.strip_prefix(':').unwrap_or(rest)));
+        }
+    }
+
+    let end = line
+        .find(|c: char| c.is_whitespace() || c == ':')
+        .unwrap_or(line.len());
+    let word = &line[..end];
+    let level = LogLevel::parse(word)?;
+    let rest = &line[end..];
+    if let Some(after_colon) = rest.strip_prefix(':') {
+        return Some((level, after_colon));
+    }
+    // A bare word only counts when written in capitals, so a message that
+    // happens to start with "error" is not mistaken for a level.
+    (word.chars().all(|c| c.is_ascii_uppercase())).then_some((level, rest))
+}
+
+/// Splits `target: message`. Targets are accepted when they look like a
+/// module path (`a::b`, `a.b`, spans in braces), or when the line already
+/// had tracing's leading timestamp.
+fn split_target(rest: &str, tracing_style: bool) -> (Option<String>, &str) {
+    let Some(colon) = rest.find(": ") else {
+        return (None, rest);
+    };
+    let candidate = &rest[..colon];
+    let target = candidate.split('{').next().unwrap_or(candidate);
+    let valid = !target.is_empty()
+        && !target.contains(char::is_whitespace)
+        && (tracing_style
+            || target.contains("::")
+            || target.contains('.')
+            || candidate.contains('{'));
+    if !valid {
+        return (None, rest);
+    }
+    (Some(target.to_string()), &rest[colon + 2..])
+}
+
+fn strip_ansi(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            // CSI sequences end with a letter: ESC [ ... m
+            for next in chars.by_ref() {
+                if next.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Parses RFC 3339 style timestamps (`T` or space between date and time,
+/// optional fraction with `.` or `,`, `Z` or `±HH:MM` offset) and plain
+/// `YYYY-MM-DD` dates. Times without an offset are read as UTC.
+pub fn parse_timestamp_ms(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let bytes = value.as_bytes();
+    if bytes.len() < 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let part = value.get(range)?;
+        part.bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| part.parse().ok())?
+    };
+    let year = number(0..4)?;
+    let month = number(5..7)?;
+    let day = number(8..10)?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let days = days_from_civil(year, month, day);
+    if bytes.len() == 10 {
+        return u64::try_from(days * 86_400_000).ok();
+    }
+    if !matches!(bytes[10], b'T' | b't' | b' ') || bytes.len() < 19 {
+        return None;
+    }
+    let hour = number(11..13)?;
+    let minute = number(14..16)?;
+    let second = number(17..19)?;
+    if bytes[13] != b':' || bytes[16] != b':' || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &value[19..];
+    let mut millis = 0i64;
+    if let Some(fraction) = rest.strip_prefix(['.', ',']) {
+        let digits = fraction
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(fraction.len());
+        if digits == 0 {
+            return None;
+        }
+        let padded = format!("{:0<3}", &fraction[..digits.min(3)]);
+        millis = padded.parse().ok()?;
+        rest = &fraction[digits..];
+    }
+
+    let offset_minutes = match rest {
+        "" | "Z" | "z" => 0,
+        offset => {
+            let sign = match offset.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let digits: String = offset[1..].chars().filter(|c| *c != ':').collect();
+            if digits.len() != 4 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let hours: i64 = digits[..2].parse().ok()?;
+            let minutes: i64 = digits[2..].parse().ok()?;
+            sign * (hours * 60 + minutes)
+        }
+    };
+
+    let seconds = days * 86_400 + hour * 3_600 + minute * 60 + second - offset_minutes * 60;
+    u64::try_from(seconds * 1_000 + millis).ok()
+}
+
+/// Parses a `--since`/`--until` bound: a duration before `now_ms` (`30s`,
+/// `15m`, `2h`, `1d`), unix seconds, or a timestamp/date.
+pub fn parse_time_bound(value: &str, now_ms: u64) -> Result<u64> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    if !amount.is_empty() {
+        if let Ok(amount) = amount.parse::<u64>() {
+            let unit_ms = match unit {
+                "" => return Ok(amount.saturating_mul(1_000)),
+                "ms" => Some(1),
+                "s" => Some(1_000),
+                "m" => Some(60_000),
+                "h" => Some(3_600_000),
+                "d" => Some(86_400_000),
+                _ => None,
+            };
+            if let Some(unit_ms) = unit_ms {
+                return Ok(now_ms.saturating_sub(amount.saturating_mul(unit_ms)));
+            }
+        }
+    }
+    match parse_timestamp_ms(value) {
+        Some(ts) => Ok(ts),
+        None => bail!(
+            "invalid time '{value}' (expected a duration like 10m or 2h, unix seconds, or a timestamp like 2024-05-01T12:00:00Z)"
+        ),
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month_index = (month + 9) % 12;
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_log_line, parse_time_bound, parse_timestamp_ms, LogLevel, LogRecord};
+
+    #[test]
+    fn parses_json_lines() {
+        let record = parse_log_line(
+            r#"{"timestamp":"2024-05-01T12:00:00.250Z","level":"WARN","fields":{"message":"slow reply"},"target":"zeroclaw::agent"}"#,
+        );
+        assert_eq!(
+            record,
+            LogRecord {
+                level: Some(LogLevel::Warn),
+                target: Some("zeroclaw::agent".to_string()),
+                message: "slow reply".to_string(),
+                timestamp_ms: Some(1_714_564_800_250),
+            }
+        );
+
+        let pino = parse_log_line(r#"{"level":50,"time":1714564800250,"msg":"boom"}"#);
+        assert_eq!(pino.level, Some(LogLevel::Error));
+        assert_eq!(pino.timestamp_ms, Some(1_714_564_800_250));
+        assert_eq!(pino.message, "boom");
+    }
+
+    #[test]
+    fn parses_tracing_and_prefixed_text() {
+        let record = parse_log_line(
+            "\x1b[2m2024-05-01T12:00:00.000001Z\x1b[0m \x1b[32m INFO\x1b[0m zeroclaw::gateway{port=42617}: listening",
+        );
+        assert_eq!(record.level, Some(LogLevel::Info));
+        assert_eq!(record.target.as_deref(), Some("zeroclaw::gateway"));
+        assert_eq!(record.message, "listening");
+        assert_eq!(record.timestamp_ms, Some(1_714_564_800_000));
+
+        let bracketed = parse_log_line("[2024-05-01 14:00:00,500] [error] connection lost");
+        assert_eq!(bracketed.level, Some(LogLevel::Error));
+        assert_eq!(bracketed.message, "connection lost");
+        assert_eq!(bracketed.timestamp_ms, Some(1_714_572_000_500));
+
+        let colon = parse_log_line("WARNING: disk almost full");
+        assert_eq!(colon.level, Some(LogLevel::Warn));
+        assert_eq!(colon.target, None);
+        assert_eq!(colon.message, "disk almost full");
+
+        let logfmt = parse_log_line(
+            r#"time=2024-05-01T12:00:00Z level=debug msg="cache miss" module=store"#,
+        );
+        assert_eq!(logfmt.level, Some(LogLevel::Debug));
+        assert_eq!(logfmt.message, "cache miss");
+        assert_eq!(logfmt.target.as_deref(), Some("store"));
+
+        let plain = parse_log_line("error handling is hard");
+        assert_eq!(plain.level, None);
+        assert_eq!(plain.message, "error handling is hard");
+    }
+
+    #[test]
+    fn parses_timestamps_and_time_bounds() {
+        assert_eq!(
+            parse_timestamp_ms("2024-05-01T14:00:00+02:00"),
+            Some(1_714_564_800_000)
+        );
+        assert_eq!(parse_timestamp_ms("2024-05-01"), Some(1_714_521_600_000));
+        assert_eq!(parse_timestamp_ms("not a time"), None);
+
+        let now = 1_714_564_800_000;
+        assert_eq!(
+            parse_time_bound("10m", now).expect("duration"),
+            now - 600_000
+        );
+        assert_eq!(
+            parse_time_bound("1714564800", now).expect("unix seconds"),
+            now
+        );
+        assert_eq!(
+            parse_time_bound("2024-05-01T12:00:00Z", now).expect("timestamp"),
+            now
+        );
+        assert!(parse_time_bound("yesterday", now).is_err());
+    }
+}
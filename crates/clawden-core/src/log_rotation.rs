@@ -0,0 +1,285 @@
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How runtime logs are rotated. Every launch (and every supervisor restart)
+/// starts a new session; a session is also cut once the log grows past
+/// `max_bytes` or gets older than `max_age_secs`.
+///
+/// Previous sessions live next to the log as `<runtime>.log.1` (newest) up to
+/// `<runtime>.log.<keep_sessions>`, gzip-compressed as `.gz` when `compress`
+/// is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LogRotation {
+    pub keep_sessions: usize,
+    pub max_bytes: Option<u64>,
+    pub max_age_secs: Option<u64>,
+    pub compress: bool,
+}
+
+const DEFAULT_KEEP_SESSIONS: usize = 5;
+const DEFAULT_MAX_BYTES: u64 = 10 * 1024 * 1024;
+
+impl Default for LogRotation {
+    fn default() -> Self {
+        Self {
+            keep_sessions: DEFAULT_KEEP_SESSIONS,
+            max_bytes: Some(DEFAULT_MAX_BYTES),
+            max_age_secs: None,
+            compress: false,
+        }
+    }
+}
+
+impl LogRotation {
+    /// Defaults overridden by `CLAWDEN_LOG_KEEP_SESSIONS`,
+    /// `CLAWDEN_LOG_MAX_BYTES` (0 disables), `CLAWDEN_LOG_MAX_AGE_SECS`
+    /// (0 disables) and `CLAWDEN_LOG_COMPRESS`.
+    pub fn from_env() -> Self {
+        let number = |key: &str| {
+            std::env::var(key)
+                .ok()
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+        let mut rotation = Self::default();
+        if let Some(keep) = number("CLAWDEN_LOG_KEEP_SESSIONS") {
+            rotation.keep_sessions = keep as usize;
+        }
+        if let Some(bytes) = number("CLAWDEN_LOG_MAX_BYTES") {
+            rotation.max_bytes = Some(bytes).filter(|b| *b > 0);
+        }
+        if let Some(secs) = number("CLAWDEN_LOG_MAX_AGE_SECS") {
+            rotation.max_age_secs = Some(secs).filter(|s| *s > 0);
+        }
+        if let Ok(value) = std::env::var("CLAWDEN_LOG_COMPRESS") {
+            rotation.compress = matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes" | "on"
+            );
+        }
+        rotation
+    }
+
+    /// Moves the current log into session 1 (shifting older sessions up and
+    /// dropping the oldest) and leaves an empty log behind. The log is
+    /// copied and truncated in place, so a runtime still writing to it in
+    /// append mode simply continues in the new session.
+    pub fn rotate(&self, log_path: &Path) -> Result<()> {
+        let len = match fs::metadata(log_path) {
+            Ok(meta) => meta.len(),
+            Err(_) => return Ok(()),
+        };
+        if len == 0 {
+            return Ok(());
+        }
+
+        if self.keep_sessions > 0 {
+            for path in archive_paths(log_path, self.keep_sessions) {
+                if path.exists() {
+                    fs::remove_file(&path)
+                        .with_context(|| format!("removing old log {}", path.display()))?;
+                }
+            }
+            for session in (1..self.keep_sessions).rev() {
+                for (from, to) in archive_paths(log_path, session)
+                    .into_iter()
+                    .zip(archive_paths(log_path, session + 1))
+                {
+                    if from.exists() {
+                        fs::rename(&from, &to)
+                            .with_context(|| format!("shifting log {}", from.display()))?;
+                    }
+                }
+            }
+            let [plain, _] = archive_paths(log_path, 1);
+            fs::copy(log_path, &plain)
+                .with_context(|| format!("archiving log {}", log_path.display()))?;
+            if self.compress {
+                // Best effort: an uncompressed archive is still readable.
+                let _ = Command::new("gzip")
+                    .arg("-f")
+                    .arg(&plain)
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status();
+            }
+        }
+
+        OpenOptions::new()
+            .write(true)
+            .open(log_path)
+            .and_then(|file| file.set_len(0))
+            .with_context(|| format!("truncating log {}", log_path.display()))
+    }
+
+    fn session_expired(&self, log_path: &Path, started: Instant) -> bool {
+        let too_big = self.max_bytes.is_some_and(|max| {
+            fs::metadata(log_path)
+                .map(|meta| meta.len() > max)
+                .unwrap_or(false)
+        });
+        let too_old = self
+            .max_age_secs
+            .is_some_and(|secs| started.elapsed() >= Duration::from_secs(secs));
+        too_big || too_old
+    }
+}
+
+/// A log that is being written to, rotated once its session outgrows the
+/// size or age limit. Shared by every writer of the same file.
+pub(crate) struct ActiveLog {
+    rotation: LogRotation,
+    path: PathBuf,
+    session_started: Mutex<Instant>,
+}
+
+impl ActiveLog {
+    pub(crate) fn new(rotation: LogRotation, path: PathBuf) -> Self {
+        Self {
+            rotation,
+            path,
+            session_started: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub(crate) fn rotate_if_due(&self) {
+        let Ok(mut started) = self.session_started.lock() else {
+            return;
+        };
+        if self.rotation.session_expired(&self.path, *started)
+            && self.rotation.rotate(&self.path).is_ok()
+        {
+            *started = Instant::now();
+        }
+    }
+
+    pub(crate) fn rotate_now(&self) -> Result<()> {
+        self.rotation.rotate(&self.path)?;
+        if let Ok(mut started) = self.session_started.lock() {
+            *started = Instant::now();
+        }
+        Ok(())
+    }
+}
+
+/// Number of previous sessions kept for `log_path`.
+pub fn previous_log_sessions(log_path: &Path) -> usize {
+    (1..)
+        .take_while(|session| {
+            archive_paths(log_path, *session)
+                .iter()
+                .any(|path| path.exists())
+        })
+        .count()
+}
+
+/// Reads one session of a log: 0 is the current session, 1 the one before
+/// it, and so on. Returns `None` when that session is not kept.
+pub fn read_log_session(log_path: &Path, session: usize) -> Result<Option<String>> {
+    if session == 0 {
+        if !log_path.exists() {
+            return Ok(None);
+        }
+        let bytes = fs::read(log_path)?;
+        return Ok(Some(String::from_utf8_lossy(&bytes).into_owned()));
+    }
+
+    let [plain, compressed] = archive_paths(log_path, session);
+    if plain.exists() {
+        let bytes = fs::read(&plain)?;
+        return Ok(Some(String::from_utf8_lossy(&bytes).into_owned()));
+    }
+    if compressed.exists() {
+        let output = Command::new("gzip")
+            .arg("-dc")
+            .arg(&compressed)
+            .output()
+            .with_context(|| format!("running gzip for {}", compressed.display()))?;
+        if !output.status.success() {
+            return Err(anyhow!(
+                "failed to decompress {}: {}",
+                compressed.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        return Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()));
+    }
+    Ok(None)
+}
+
+fn archive_paths(log_path: &Path, session: usize) -> [PathBuf; 2] {
+    let base = log_path.as_os_str().to_string_lossy();
+    [
+        PathBuf::from(format!("{base}.{session}")),
+        PathBuf::from(format!("{base}.{session}.gz")),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{previous_log_sessions, read_log_session, ActiveLog, LogRotation};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_log(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("clawden-logrotate-{name}-{stamp}"));
+        fs::create_dir_all(&dir).expect("temp dir should be created");
+        dir.join("zeroclaw.log")
+    }
+
+    #[test]
+    fn rotation_keeps_the_newest_sessions() {
+        let log = temp_log("keep");
+        let rotation = LogRotation {
+            keep_sessions: 2,
+            ..LogRotation::default()
+        };
+        for session in ["first", "second", "third"] {
+            fs::write(&log, format!("{session}\n")).expect("write session");
+            rotation.rotate(&log).expect("rotate");
+        }
+        fs::write(&log, "current\n").expect("write current");
+
+        assert_eq!(previous_log_sessions(&log), 2);
+        let read = |n| read_log_session(&log, n).expect("read session");
+        assert_eq!(read(0).as_deref(), Some("current\n"));
+        assert_eq!(read(1).as_deref(), Some("third\n"));
+        assert_eq!(read(2).as_deref(), Some("second\n"));
+        assert_eq!(read(3), None);
+        let _ = fs::remove_dir_all(log.parent().expect("log dir"));
+    }
+
+    #[test]
+    fn compressed_sessions_and_size_limit() {
+        let log = temp_log("gzip");
+        let rotation = LogRotation {
+            keep_sessions: 3,
+            max_bytes: Some(16),
+            max_age_secs: None,
+            compress: true,
+        };
+        let active = ActiveLog::new(rotation, log.clone());
+        fs::write(&log, "short\n").expect("write log");
+        active.rotate_if_due();
+        assert_eq!(previous_log_sessions(&log), 0);
+
+        fs::write(&log, "a line that is well past the limit\n").expect("write log");
+        active.rotate_if_due();
+        assert_eq!(fs::metadata(&log).expect("log metadata").len(), 0);
+        assert_eq!(previous_log_sessions(&log), 1);
+        assert_eq!(
+            read_log_session(&log, 1).expect("read session").as_deref(),
+            Some("a line that is well past the limit\n")
+        );
+        let _ = fs::remove_dir_all(log.parent().expect("log dir"));
+    }
+}
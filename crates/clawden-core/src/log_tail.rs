@@ -0,0 +1,220 @@
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use std::fs::{self, File};
+use std::io::{Read, Seek, SeekFrom};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// A line longer than this is emitted in pieces rather than buffered forever.
+const MAX_PARTIAL_LINE: usize = 64 * 1024;
+
+/// Follows one log file, reading only bytes appended since the last call and
+/// handing out complete lines. Replacement of the file (a new inode) and
+/// truncation (copy-truncate rotation) restart reading from the beginning.
+pub(crate) struct LogTailer {
+    path: PathBuf,
+    file: Option<File>,
+    inode: Option<u64>,
+    offset: u64,
+    partial: Vec<u8>,
+}
+
+impl LogTailer {
+    /// Starts at the current end of `path`, so existing content is skipped.
+    pub(crate) fn at_end(path: PathBuf) -> Self {
+        let meta = fs::metadata(&path).ok();
+        Self {
+            file: File::open(&path).ok(),
+            inode: meta.as_ref().map(|m| m.ino()),
+            offset: meta.map(|m| m.len()).unwrap_or(0),
+            path,
+            partial: Vec::new(),
+        }
+    }
+
+    pub(crate) fn read_new_lines(&mut self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let Ok(meta) = fs::metadata(&self.path) else {
+            return lines;
+        };
+
+        if self.inode != Some(meta.ino()) {
+            // Finish the replaced file before switching to the new one.
+            self.read_available(&mut lines);
+            self.flush_partial(&mut lines);
+            self.file = File::open(&self.path).ok();
+            self.inode = Some(meta.ino());
+            self.offset = 0;
+        } else if meta.len() < self.offset {
+            self.partial.clear();
+            self.offset = 0;
+        }
+        if self.file.is_none() {
+            self.file = File::open(&self.path).ok();
+        }
+
+        if meta.len() > self.offset {
+            self.read_available(&mut lines);
+        }
+        lines
+    }
+
+    fn read_available(&mut self, lines: &mut Vec<String>) {
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        let mut chunk = Vec::new();
+        if file.seek(SeekFrom::Start(self.offset)).is_err() || file.read_to_end(&mut chunk).is_err()
+        {
+            return;
+        }
+        self.offset += chunk.len() as u64;
+        self.partial.extend_from_slice(&chunk);
+
+        let mut start = 0;
+        while let Some(newline) = self.partial[start..].iter().position(|b| *b == b'\n') {
+            lines.push(decode_line(&self.partial[start..start + newline]));
+            start += newline + 1;
+        }
+        self.partial.drain(..start);
+        if self.partial.len() > MAX_PARTIAL_LINE {
+            self.flush_partial(lines);
+        }
+    }
+
+    fn flush_partial(&mut self, lines: &mut Vec<String>) {
+        if !self.partial.is_empty() {
+            lines.push(decode_line(&self.partial));
+            self.partial.clear();
+        }
+    }
+}
+
+/// Decodes a complete line; lines are only cut at `\n`, so multi-byte
+/// characters are never split.
+fn decode_line(bytes: &[u8]) -> String {
+    let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// Wakes a log follower when files in the watched directories change
+/// (inotify on Linux), falling back to plain polling when no watcher can be
+/// set up.
+pub(crate) struct LogWakeup {
+    _watcher: Option<RecommendedWatcher>,
+    events: Option<Receiver<()>>,
+}
+
+impl LogWakeup {
+    pub(crate) fn watch(paths: &[PathBuf]) -> Self {
+        let (tx, rx) = mpsc::channel();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            if event.is_ok() {
+                let _ = tx.send(());
+            }
+        })
+        .ok()
+        .and_then(|mut watcher| {
+            let mut dirs: Vec<&Path> = paths.iter().filter_map(|p| p.parent()).collect();
+            dirs.sort();
+            dirs.dedup();
+            for dir in dirs {
+                watcher.watch(dir, RecursiveMode::NonRecursive).ok()?;
+            }
+            Some(watcher)
+        });
+        Self {
+            events: watcher.as_ref().map(|_| rx),
+            _watcher: watcher,
+        }
+    }
+
+    /// Blocks until a change is reported or `timeout` passes.
+    pub(crate) fn wait(&self, timeout: Duration) {
+        match &self.events {
+            Some(events) => {
+                if events.recv_timeout(timeout).is_ok() {
+                    while events.try_recv().is_ok() {}
+                }
+            }
+            None => thread::sleep(timeout.min(Duration::from_millis(200))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LogTailer;
+    use std::fs::{self, OpenOptions};
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_log(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("clawden-tail-{name}-{stamp}"));
+        fs::create_dir_all(&dir).expect("temp dir should be created");
+        dir.join("zeroclaw.log")
+    }
+
+    fn append(path: &PathBuf, bytes: &[u8]) {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .expect("open log")
+            .write_all(bytes)
+            .expect("append to log");
+    }
+
+    #[test]
+    fn buffers_partial_lines_and_multibyte_characters() {
+        let log = temp_log("partial");
+        fs::write(&log, "old\n").expect("seed log");
+        let mut tailer = LogTailer::at_end(log.clone());
+        assert!(tailer.read_new_lines().is_empty());
+
+        let text = "héllo wörld\n".as_bytes();
+        // Split inside the two-byte 'é'.
+        append(&log, &text[..2]);
+        assert!(tailer.read_new_lines().is_empty());
+        append(&log, &text[2..]);
+        append(&log, b"second");
+        assert_eq!(tailer.read_new_lines(), vec!["héllo wörld".to_string()]);
+        append(&log, b" half\r\n");
+        assert_eq!(tailer.read_new_lines(), vec!["second half".to_string()]);
+        let _ = fs::remove_dir_all(log.parent().expect("log dir"));
+    }
+
+    #[test]
+    fn follows_truncation_and_replacement() {
+        let log = temp_log("rotate");
+        fs::write(&log, "a fairly long first line\n").expect("seed log");
+        let mut tailer = LogTailer::at_end(log.clone());
+
+        // Copy-truncate rotation keeps the inode but shrinks the file.
+        fs::write(&log, "short\n").expect("truncate log");
+        assert_eq!(tailer.read_new_lines(), vec!["short".to_string()]);
+
+        // Rename rotation replaces the file; the tail of the old one is kept.
+        append(&log, b"last of old\n");
+        let rotated = log.with_extension("log.1");
+        fs::rename(&log, &rotated).expect("rotate log");
+        append(&rotated, b"late write\n");
+        fs::write(&log, "new file\n").expect("create new log");
+        assert_eq!(
+            tailer.read_new_lines(),
+            vec![
+                "last of old".to_string(),
+                "late write".to_string(),
+                "new file".to_string()
+            ]
+        );
+        let _ = fs::remove_dir_all(log.parent().expect("log dir"));
+    }
+}
@@ -6,8 +6,9 @@ use serde::Serialize;
 use thiserror::Error;
 
 use crate::{
-    current_unix_ms, AgentConfig, AgentHandle, AgentMessage, AgentResponse, AgentState,
-    ChannelInstanceConfig, ClawAdapter, ClawRuntime, HealthStatus, RuntimeConfig, RuntimeMetadata,
+    current_unix_ms, AgentConfig, AgentHandle, AgentMessage, AgentMetrics, AgentResponse,
+    AgentState, ChannelInstanceConfig, ClawAdapter, ClawRuntime, EventStream, HealthStatus,
+    MetricsHistory, MetricsSample, RuntimeConfig, RuntimeMetadata,
 };
 
 #[derive(Debug, Clone, Serialize)]
@@ -29,10 +30,29 @@ pub struct LifecycleManager {
     agents: HashMap<String, AgentRecord>,
     handles: HashMap<String, AgentHandle>,
     configs: HashMap<String, AgentConfig>,
+    metrics: MetricsHistory,
     next_id: AtomicU64,
     round_robin_index: usize,
 }
 
+/// An agent's adapter and handle, detached from the manager for sampling.
+pub struct MetricsTarget {
+    pub agent_id: String,
+    adapter: Arc<dyn ClawAdapter>,
+    handle: AgentHandle,
+}
+
+/// Sample every target; failed samples are skipped.
+pub async fn sample_metrics(targets: Vec<MetricsTarget>) -> Vec<(String, AgentMetrics)> {
+    let mut samples = Vec::with_capacity(targets.len());
+    for target in targets {
+        if let Ok(metrics) = target.adapter.metrics(&target.handle).await {
+            samples.push((target.agent_id, metrics));
+        }
+    }
+    samples
+}
+
 #[derive(Debug, Error)]
 pub enum ManagerError {
     #[error("agent `{0}` not found")]
@@ -58,6 +78,7 @@ impl LifecycleManager {
             agents: HashMap::new(),
             handles: HashMap::new(),
             configs: HashMap::new(),
+            metrics: MetricsHistory::default(),
             next_id: AtomicU64::new(1),
             round_robin_index: 0,
         }
@@ -257,13 +278,15 @@ impl LifecycleManager {
                 continue;
             };
             match adapter.health(handle).await {
-                Ok(health) => {
+                Ok(health) if !matches!(health, HealthStatus::Unhealthy) => {
                     record.health = health;
                     record.consecutive_health_failures = 0;
                     record.next_recovery_attempt_unix_ms = None;
                 }
-                Err(_) => {
-                    record.health = HealthStatus::Degraded;
+                // A runtime whose probe is failing is recovered like one
+                // whose adapter cannot be reached.
+                result => {
+                    record.health = result.unwrap_or(HealthStatus::Degraded);
                     record.consecutive_health_failures =
                         record.consecutive_health_failures.saturating_add(1);
                     record.next_recovery_attempt_unix_ms =
@@ -278,6 +301,63 @@ impl LifecycleManager {
         self.list_agents()
     }
 
+    /// Samples metrics for every agent with an active handle and appends them
+    /// to the bounded per-agent history. Failed samples are skipped.
+    pub async fn collect_metrics(&mut self) {
+        let samples = sample_metrics(self.metrics_targets()).await;
+        self.record_metrics(samples);
+    }
+
+    /// Agents with an active handle, for sampling metrics without holding
+    /// the manager; pass the results of [`sample_metrics`] to
+    /// [`Self::record_metrics`].
+    pub fn metrics_targets(&self) -> Vec<MetricsTarget> {
+        self.handles
+            .iter()
+            .filter_map(|(id, handle)| {
+                let record = self.agents.get(id)?;
+                let adapter = self.adapters.get(&record.runtime)?;
+                Some(MetricsTarget {
+                    agent_id: id.clone(),
+                    adapter: adapter.clone(),
+                    handle: handle.clone(),
+                })
+            })
+            .collect()
+    }
+
+    /// Append samples to the history of agents that are still registered.
+    pub fn record_metrics(&mut self, samples: Vec<(String, AgentMetrics)>) {
+        for (id, metrics) in samples {
+            if self.agents.contains_key(&id) {
+                self.metrics.record(&id, &metrics);
+            }
+        }
+    }
+
+    /// Forget a stopped agent, including its metrics history.
+    pub fn remove_agent(&mut self, agent_id: &str) -> Result<AgentRecord, ManagerError> {
+        if self.handles.contains_key(agent_id) {
+            return Err(ManagerError::Message(format!(
+                "agent `{agent_id}` is still running; stop it first"
+            )));
+        }
+        let record = self
+            .agents
+            .remove(agent_id)
+            .ok_or_else(|| ManagerError::AgentNotFound(agent_id.to_string()))?;
+        self.configs.remove(agent_id);
+        self.metrics.remove(agent_id);
+        Ok(record)
+    }
+
+    pub fn metrics_history(&self, agent_id: &str) -> Result<Vec<MetricsSample>, ManagerError> {
+        if !self.agents.contains_key(agent_id) {
+            return Err(ManagerError::AgentNotFound(agent_id.to_string()));
+        }
+        Ok(self.metrics.samples(agent_id))
+    }
+
     pub async fn recover_degraded(&mut self) -> Vec<AgentRecord> {
         let now = current_unix_ms();
         let due_ids: Vec<String> = self
@@ -403,6 +483,36 @@ impl LifecycleManager {
         Ok((record.clone(), response))
     }
 
+    /// Opens a live event stream for a running agent. `event` selects the
+    /// kinds to receive (see [`crate::EventFilter::parse`]).
+    pub async fn subscribe(
+        &self,
+        agent_id: &str,
+        event: &str,
+    ) -> Result<EventStream, ManagerError> {
+        let Some(record) = self.agents.get(agent_id) else {
+            return Err(ManagerError::AgentNotFound(agent_id.to_string()));
+        };
+        let Some(handle) = self.handles.get(agent_id) else {
+            return Err(ManagerError::Message(format!(
+                "agent {} has no active handle",
+                record.id
+            )));
+        };
+        let Some(adapter) = self.adapters.get(&record.runtime) else {
+            return Err(ManagerError::NoAdapter(record.runtime.clone()));
+        };
+
+        adapter
+            .subscribe(handle, event)
+            .await
+            .map_err(|source| ManagerError::Adapter {
+                runtime: record.runtime.clone(),
+                action: "subscribe",
+                source,
+            })
+    }
+
     fn select_agent(&mut self, required_capabilities: &[String]) -> Result<String, ManagerError> {
         let eligible: Vec<&AgentRecord> = self
             .agents
@@ -0,0 +1,259 @@
+use std::collections::{HashMap, VecDeque};
+use std::fs;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use anyhow::{anyhow, Context, Result};
+use serde::Serialize;
+
+use crate::{current_unix_ms, AgentMetrics};
+
+/// Samples kept per agent — one hour at the default 5s health interval.
+pub const METRICS_HISTORY_CAPACITY: usize = 720;
+
+/// Kernel clock ticks per second used by `/proc/<pid>/stat`. `USER_HZ` is
+/// fixed at 100 on every mainstream Linux architecture.
+const CLOCK_TICKS_PER_SEC: f64 = 100.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricsSample {
+    pub timestamp_ms: u64,
+    pub cpu_percent: f32,
+    pub memory_mb: f32,
+    pub open_fds: u32,
+    pub queue_depth: u32,
+}
+
+/// Bounded in-memory metrics history, keyed by agent id.
+#[derive(Debug, Default)]
+pub struct MetricsHistory {
+    samples: HashMap<String, VecDeque<MetricsSample>>,
+}
+
+impl MetricsHistory {
+    pub fn record(&mut self, agent_id: &str, metrics: &AgentMetrics) {
+        let samples = self.samples.entry(agent_id.to_string()).or_default();
+        if samples.len() >= METRICS_HISTORY_CAPACITY {
+            samples.pop_front();
+        }
+        samples.push_back(MetricsSample {
+            timestamp_ms: current_unix_ms(),
+            cpu_percent: metrics.cpu_percent,
+            memory_mb: metrics.memory_mb,
+            open_fds: metrics.open_fds,
+            queue_depth: metrics.queue_depth,
+        });
+    }
+
+    pub fn samples(&self, agent_id: &str) -> Vec<MetricsSample> {
+        self.samples
+            .get(agent_id)
+            .map(|samples| samples.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn latest(&self, agent_id: &str) -> Option<MetricsSample> {
+        self.samples
+            .get(agent_id)
+            .and_then(|samples| samples.back().cloned())
+    }
+
+    pub fn remove(&mut self, agent_id: &str) {
+        self.samples.remove(agent_id);
+    }
+}
+
+/// Collects CPU, RSS and open-FD totals for a process and all of its
+/// descendants from `/proc`.
+///
+/// CPU% is measured between consecutive samples of the same root pid; the
+/// first sample reports the average since the process started.
+#[derive(Debug, Default)]
+pub struct ProcessTreeSampler {
+    previous: Mutex<HashMap<u32, (u64, Instant)>>,
+}
+
+impl ProcessTreeSampler {
+    pub fn sample(&self, pid: u32) -> Result<AgentMetrics> {
+        let root = read_stat(pid)?;
+        let tree = process_tree(pid);
+
+        let mut ticks = 0u64;
+        let mut rss_kb = 0u64;
+        let mut open_fds = 0u32;
+        for member in &tree {
+            if let Ok(stat) = read_stat(*member) {
+                ticks += stat.cpu_ticks;
+            }
+            rss_kb += read_rss_kb(*member).unwrap_or(0);
+            open_fds += count_open_fds(*member);
+        }
+
+        let now = Instant::now();
+        let previous = self
+            .previous
+            .lock()
+            .ok()
+            .and_then(|mut guard| guard.insert(pid, (ticks, now)));
+        let cpu_percent = match previous {
+            Some((prev_ticks, prev_at)) if ticks >= prev_ticks => {
+                let elapsed = now.duration_since(prev_at).as_secs_f64();
+                ticks_to_percent(ticks - prev_ticks, elapsed)
+            }
+            _ => {
+                let lifetime = uptime_secs()
+                    .map(|uptime| uptime - root.start_ticks as f64 / CLOCK_TICKS_PER_SEC)
+                    .unwrap_or(0.0);
+                ticks_to_percent(ticks, lifetime)
+            }
+        };
+
+        Ok(AgentMetrics {
+            cpu_percent,
+            memory_mb: rss_kb as f32 / 1024.0,
+            queue_depth: 0,
+            open_fds,
+        })
+    }
+
+    pub fn forget(&self, pid: u32) {
+        if let Ok(mut guard) = self.previous.lock() {
+            guard.remove(&pid);
+        }
+    }
+}
+
+/// Counts open file descriptors across a process and its descendants.
+pub fn process_tree_open_fds(pid: u32) -> u32 {
+    process_tree(pid).into_iter().map(count_open_fds).sum()
+}
+
+/// Returns `pid` followed by every descendant found in `/proc`.
+pub fn process_tree(pid: u32) -> Vec<u32> {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    if let Ok(entries) = fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let Some(child) = entry.file_name().to_str().and_then(|s| s.parse().ok()) else {
+                continue;
+            };
+            if let Ok(stat) = read_stat(child) {
+                children.entry(stat.ppid).or_default().push(child);
+            }
+        }
+    }
+
+    let mut tree = vec![pid];
+    let mut index = 0;
+    while index < tree.len() {
+        if let Some(kids) = children.get(&tree[index]) {
+            tree.extend(kids.iter().copied().filter(|kid| *kid != pid));
+        }
+        index += 1;
+    }
+    tree
+}
+
+struct ProcStat {
+    ppid: u32,
+    cpu_ticks: u64,
+    start_ticks: u64,
+}
+
+fn read_stat(pid: u32) -> Result<ProcStat> {
+    let raw = fs::read_to_string(format!("/proc/{pid}/stat"))
+        .with_context(|| format!("process {pid} is not running"))?;
+    parse_stat(&raw).ok_or_else(|| anyhow!("malformed /proc/{pid}/stat"))
+}
+
+fn parse_stat(raw: &str) -> Option<ProcStat> {
+    // The command name may contain spaces; fields resume after its closing paren.
+    let rest = &raw[raw.rfind(')')? + 1..];
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    // `fields[0]` is field 3 (state) in proc(5) numbering.
+    let field = |n: usize| fields.get(n - 3).and_then(|v| v.parse::<u64>().ok());
+    Some(ProcStat {
+        ppid: field(4)? as u32,
+        cpu_ticks: field(14)? + field(15)?,
+        start_ticks: field(22)?,
+    })
+}
+
+fn read_rss_kb(pid: u32) -> Option<u64> {
+    let status = fs::read_to_string(format!("/proc/{pid}/status")).ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmRSS:"))
+        .and_then(|value| value.split_whitespace().next())
+        .and_then(|kb| kb.parse().ok())
+}
+
+fn count_open_fds(pid: u32) -> u32 {
+    fs::read_dir(format!("/proc/{pid}/fd"))
+        .map(|entries| entries.count() as u32)
+        .unwrap_or(0)
+}
+
+fn uptime_secs() -> Option<f64> {
+    fs::read_to_string("/proc/uptime")
+        .ok()?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn ticks_to_percent(ticks: u64, elapsed_secs: f64) -> f32 {
+    if elapsed_secs <= 0.0 {
+        return 0.0;
+    }
+    ((ticks as f64 / CLOCK_TICKS_PER_SEC) / elapsed_secs * 100.0) as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_stat, MetricsHistory, ProcessTreeSampler, METRICS_HISTORY_CAPACITY};
+    use crate::AgentMetrics;
+
+    #[test]
+    fn parse_stat_handles_spaces_in_command_name() {
+        let raw = "4242 (my agent (v2)) S 1 4242 4242 0 -1 4194560 100 0 0 0 250 50 0 0 20 0 3 0 12345 1000 200";
+        let stat = parse_stat(raw).expect("stat should parse");
+        assert_eq!(stat.ppid, 1);
+        assert_eq!(stat.cpu_ticks, 300);
+        assert_eq!(stat.start_ticks, 12345);
+    }
+
+    #[test]
+    fn history_is_bounded_per_agent() {
+        let mut history = MetricsHistory::default();
+        let metrics = AgentMetrics {
+            cpu_percent: 1.5,
+            memory_mb: 64.0,
+            queue_depth: 0,
+            open_fds: 12,
+        };
+        for _ in 0..METRICS_HISTORY_CAPACITY + 5 {
+            history.record("agent-1", &metrics);
+        }
+        assert_eq!(history.samples("agent-1").len(), METRICS_HISTORY_CAPACITY);
+        assert_eq!(history.latest("agent-1").map(|s| s.open_fds), Some(12));
+        assert!(history.samples("agent-2").is_empty());
+    }
+
+    #[test]
+    fn sampler_reports_current_process_usage() {
+        let sampler = ProcessTreeSampler::default();
+        let first = sampler
+            .sample(std::process::id())
+            .expect("own process should be sampled");
+        assert!(first.memory_mb > 0.0);
+        assert!(first.open_fds > 0);
+        assert!(first.cpu_percent >= 0.0);
+
+        let second = sampler
+            .sample(std::process::id())
+            .expect("own process should be sampled again");
+        assert!(second.cpu_percent >= 0.0);
+        assert!(sampler.sample(u32::MAX).is_err());
+    }
+}
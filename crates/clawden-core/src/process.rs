@@ -1,16 +1,32 @@
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::borrow::Cow;
 use std::collections::{HashMap, VecDeque};
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
+use std::process::{Child, Command, Stdio};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 use std::thread;
-use std::time::{Duration, SystemTime, UNIX_EPOCH};
-
-use crate::runtime_descriptor;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::health_check::HealthState;
+use crate::log_rotation::ActiveLog;
+use crate::log_tail::{LogTailer, LogWakeup};
+use crate::process_group::{
+    is_same_process, pid_alive, process_command, process_start_ticks, signal_group, signal_pid,
+    wait_for_port_release,
+};
+use crate::resource_limits::{ensure_seccomp, limited_command, prepare_cgroup, remove_cgroup};
+use crate::{
+    check_limits, instance_runtime, parse_log_line, previous_log_sessions, process_tree,
+    read_log_session, runtime_descriptor, runtime_env_prefix, validate_instance_name, AgentMetrics,
+    AppliedLimits, ConfigDirFlag, ConfigFormat, EventSender, HealthCheck, LimitCheck, LogLevel,
+    LogRotation, PortAllocator, PortSetting, ProcessTreeSampler, Redactor, ResourceLimits,
+    RestartSpec, RuntimeEvent, SupervisorSpec, SupervisorState, SupervisorStatus, SupervisorTuning,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ExecutionMode {
@@ -19,10 +35,40 @@ pub enum ExecutionMode {
     Auto,
 }
 
+/// What [`ProcessManager::start_direct`] launches and how.
+#[derive(Debug, Clone, Default)]
+pub struct DirectStartOptions {
+    /// Instance name: the runtime slug, or `runtime@instance`.
+    pub runtime: String,
+    pub executable: PathBuf,
+    pub args: Vec<String>,
+    pub env_vars: Vec<(String, String)>,
+    /// Variables kept out of the environment inherited from this process.
+    pub removed_env: Vec<String>,
+    pub project_hash: Option<String>,
+    /// Limits the runtime (and its supervisor, when it has one) is held to.
+    pub limits: ResourceLimits,
+}
+
+impl DirectStartOptions {
+    pub fn new(runtime: impl Into<String>, executable: impl Into<PathBuf>) -> Self {
+        Self {
+            runtime: runtime.into(),
+            executable: executable.into(),
+            ..Self::default()
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProcessInfo {
+    /// Instance name: the runtime slug, or `runtime@instance`.
     pub runtime: String,
     pub pid: u32,
+    /// Start time of `pid` in clock ticks since boot, checked before the
+    /// pid is signalled in case it has been reused.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_ticks: Option<u64>,
     pub started_at_unix_ms: u64,
     pub mode: ExecutionMode,
     pub log_path: PathBuf,
@@ -30,6 +76,24 @@ pub struct ProcessInfo {
     pub health_url: Option<String>,
     #[serde(default)]
     pub project_hash: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Resource limits the runtime was started under.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<AppliedLimits>,
+    /// Probe from the runtime's `health:` block, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<HealthCheck>,
+}
+
+impl ProcessInfo {
+    /// The configured probe, or an HTTP check of the runtime's known health
+    /// endpoint. `None` means health is unknown.
+    pub fn health_check(&self) -> Option<HealthCheck> {
+        self.health
+            .clone()
+            .or_else(|| self.health_url.clone().map(HealthCheck::http))
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -40,20 +104,83 @@ pub struct RuntimeProcessStatus {
     pub mode: ExecutionMode,
     pub log_path: PathBuf,
     pub health: String,
+    pub port: Option<u16>,
+    /// Restarts performed by the supervisor (always 0 without a restart policy).
+    pub restarts: u32,
+    pub last_exit_code: Option<i32>,
+    pub last_exit_signal: Option<i32>,
 }
 
-#[derive(Debug, Clone)]
+/// How an unsupervised runtime exited, written by the thread that reaps it.
+/// Only recorded while the process that started the runtime is alive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct ExitRecord {
+    code: Option<i32>,
+    signal: Option<i32>,
+}
+
+#[derive(Debug, Clone, Default)]
 pub struct StopOutcome {
+    /// The runtime did not exit within the timeout and was killed.
     pub forced: bool,
+    /// Descendants that outlived the stop request and were killed.
+    pub leftovers: Vec<LeftoverProcess>,
+    /// The runtime's port, when something still listens on it afterwards.
+    pub port_in_use: Option<u16>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+pub struct LeftoverProcess {
+    pub pid: u32,
+    pub command: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct LogLine {
     pub runtime: String,
     pub timestamp_ms: u64,
+    pub level: Option<LogLevel>,
+    pub target: Option<String>,
+    pub message: String,
     pub text: String,
 }
 
+impl LogLine {
+    /// Parses `text` into level/target/message. The runtime's own timestamp
+    /// wins over `received_ms`, the time ClawDen read the line.
+    pub fn parse(runtime: impl Into<String>, text: impl Into<String>, received_ms: u64) -> Self {
+        let text = text.into();
+        let record = parse_log_line(&text);
+        Self {
+            runtime: runtime.into(),
+            timestamp_ms: record.timestamp_ms.unwrap_or(received_ms),
+            level: record.level,
+            target: record.target,
+            message: record.message,
+            text,
+        }
+    }
+}
+
+/// A one-shot child process started with [`ProcessManager::spawn_task`].
+///
+/// Output is tee'd into the regular log file (so `stream_logs` works) while
+/// stdout is also captured in memory for result parsing.
+pub struct TaskProcess {
+    pub info: ProcessInfo,
+    child: Child,
+    stdout: Arc<Mutex<String>>,
+    readers: Vec<thread::JoinHandle<()>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TaskExit {
+    pub exit_code: Option<i32>,
+    pub timed_out: bool,
+    pub stdout: String,
+    pub duration_ms: u64,
+}
+
 pub struct LogStream {
     inner: Arc<Mutex<LogStreamInner>>,
     running: Arc<AtomicBool>,
@@ -65,6 +192,14 @@ struct LogStreamInner {
 }
 
 const LOG_STREAM_CAPACITY: usize = 4096;
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long a stopped runtime's port may take to be closed by the kernel.
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(2);
+/// Bounds on how often `wait_until_healthy` re-probes.
+const HEALTH_POLL_MIN: Duration = Duration::from_millis(200);
+const HEALTH_POLL_MAX: Duration = Duration::from_secs(1);
+/// How long `docker version` may take before the daemon counts as down.
+const DOCKER_PROBE_TIMEOUT: Duration = Duration::from_secs(3);
 
 impl LogStream {
     pub fn drain(&self) -> Vec<LogLine> {
@@ -82,11 +217,11 @@ impl LogStream {
             let mut dropped: Vec<_> = inner.dropped.drain().collect();
             dropped.sort_by(|a, b| a.0.cmp(&b.0));
             for (runtime, count) in dropped {
-                out.push(LogLine {
+                out.push(LogLine::parse(
                     runtime,
-                    timestamp_ms: now_ms(),
-                    text: format!("WARNING: {count} log lines dropped (slow consumer)"),
-                });
+                    format!("WARNING: {count} log lines dropped (slow consumer)"),
+                    now_ms(),
+                ));
             }
         }
 
@@ -104,6 +239,9 @@ pub struct ProcessManager {
     mode: ExecutionMode,
     state_dir: PathBuf,
     log_dir: PathBuf,
+    sampler: ProcessTreeSampler,
+    ports: PortAllocator,
+    log_rotation: LogRotation,
 }
 
 impl ProcessManager {
@@ -115,8 +253,11 @@ impl ProcessManager {
         fs::create_dir_all(&log_dir)?;
         Ok(Self {
             mode,
+            ports: PortAllocator::new(&state_dir),
             state_dir,
             log_dir,
+            sampler: ProcessTreeSampler::default(),
+            log_rotation: LogRotation::from_env(),
         })
     }
 
@@ -128,14 +269,11 @@ impl ProcessManager {
         &self.log_dir
     }
 
+    /// Whether a Docker daemon answers, not just whether the CLI is on PATH.
+    /// Probed once per process.
     pub fn docker_available() -> bool {
-        Command::new("docker")
-            .arg("--version")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .map(|status| status.success())
-            .unwrap_or(false)
+        static AVAILABLE: OnceLock<bool> = OnceLock::new();
+        *AVAILABLE.get_or_init(docker_daemon_reachable)
     }
 
     pub fn resolve_mode(&self, force_no_docker: bool) -> ExecutionMode {
@@ -155,81 +293,118 @@ impl ProcessManager {
         }
     }
 
-    pub fn start_direct(
-        &self,
-        runtime: &str,
-        executable: &Path,
-        args: &[String],
-    ) -> Result<ProcessInfo> {
-        self.start_direct_with_env(runtime, executable, args, &[])
-    }
-
-    pub fn start_direct_with_env(
-        &self,
-        runtime: &str,
-        executable: &Path,
-        args: &[String],
-        env_vars: &[(String, String)],
-    ) -> Result<ProcessInfo> {
-        self.start_direct_with_env_and_project(runtime, executable, args, env_vars, None)
-    }
-
-    pub fn start_direct_with_env_and_project(
-        &self,
-        runtime: &str,
-        executable: &Path,
-        args: &[String],
-        env_vars: &[(String, String)],
-        project_hash: Option<String>,
-    ) -> Result<ProcessInfo> {
+    /// Launches a runtime as a host process, under a supervisor when its
+    /// args carry an enabled restart policy.
+    pub fn start_direct(&self, options: &DirectStartOptions) -> Result<ProcessInfo> {
+        let DirectStartOptions {
+            runtime,
+            executable,
+            args,
+            env_vars,
+            removed_env,
+            project_hash,
+            limits,
+        } = options;
+        let runtime = runtime.as_str();
         if !executable.exists() {
             return Err(anyhow!(
                 "runtime executable not found: {}",
                 executable.display()
             ));
         }
+        validate_instance_name(runtime)?;
+        ensure_seccomp(runtime, limits)?;
+
+        // Every instance gets its own port, starting from the runtime's
+        // default so a lone instance keeps the port it always had.
+        let port = match runtime_descriptor(runtime).and_then(|d| d.health_port) {
+            Some(default_port) => Some(self.ports.allocate(runtime, Some(default_port))?),
+            None => None,
+        };
+        let mut args = args.to_vec();
+        if let Some(port) = port {
+            apply_port_setting(runtime, port, &mut args)?;
+        }
+        let mut env_vars = env_vars.to_vec();
+        if let Some(port) = port {
+            let port_vars = [
+                "CLAWDEN_PORT".to_string(),
+                format!("{}_PORT", runtime_env_prefix(runtime)),
+            ];
+            for key in port_vars {
+                if !env_vars.iter().any(|(k, _)| *k == key) {
+                    env_vars.push((key, port.to_string()));
+                }
+            }
+        }
 
         let log_path = self.log_dir.join(format!("{runtime}.log"));
-        // Start each launch session with a fresh log file to avoid replaying stale output.
+        // Each launch starts a new log session; the last one is kept as a
+        // previous session instead of being wiped.
+        self.log_rotation.rotate(&log_path)?;
         OpenOptions::new()
             .create(true)
-            .write(true)
-            .truncate(true)
+            .append(true)
             .open(&log_path)
             .with_context(|| format!("preparing runtime log file {}", log_path.display()))?;
 
-        let (runtime_args, restart_policy) = split_restart_policy(args);
-
-        if restart_policy.as_deref() == Some("on-failure") {
-            let script_path = self.state_dir.join(format!("{runtime}.supervisor.sh"));
-            let audit_path = self.log_dir.join("audit.log");
-            let script = build_restart_supervisor_script();
-            fs::write(&script_path, script)
-                .with_context(|| format!("writing supervisor script {}", script_path.display()))?;
-            #[allow(clippy::permissions_set_readonly_false)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                let mut perms = fs::metadata(&script_path)?.permissions();
-                perms.set_mode(0o755);
-                fs::set_permissions(&script_path, perms)?;
-            }
+        let (runtime_args, restart_policy) = split_restart_policy(&args);
+        let cgroup = prepare_cgroup(runtime, limits);
+        let applied = (!limits.is_empty()).then(|| AppliedLimits {
+            requested: limits.clone(),
+            cgroup: cgroup.clone(),
+        });
 
-            let mut command = Command::new("sh");
-            command
-                .arg(script_path)
-                .arg(executable)
-                .arg(&log_path)
-                .arg(audit_path)
-                .arg(runtime);
-            command.args(&runtime_args);
+        let restart = restart_policy
+            .as_deref()
+            .map(RestartSpec::parse)
+            .transpose()?;
+        if let Some(restart) = restart.filter(RestartSpec::is_enabled) {
+            let spec = SupervisorSpec {
+                runtime: runtime.to_string(),
+                executable: executable.to_path_buf(),
+                args: runtime_args,
+                log_path: log_path.clone(),
+                audit_path: self.log_dir.join("audit.log"),
+                state_path: self.supervisor_state_file(runtime),
+                restart,
+                tuning: SupervisorTuning::default(),
+                log_rotation: self.log_rotation.clone(),
+                redact_env: env_vars.iter().map(|(key, _)| key.clone()).collect(),
+            };
+            let spec_path = self.supervisor_spec_file(runtime);
+            let _ = fs::remove_file(&spec.state_path);
+            spec.save(&spec_path)?;
+
+            let supervisor = supervisor_executable();
+            // The runtime inherits the supervisor's limits and cgroup.
+            let mut command = limited_command(
+                &supervisor,
+                &["supervise".to_string(), spec_path.display().to_string()],
+                limits,
+                cgroup.as_deref(),
+            );
+            for key in removed_env {
+                command.env_remove(key);
+            }
             command.envs(env_vars.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+            std::os::unix::process::CommandExt::process_group(&mut command, 0);
+            command.stdin(Stdio::null());
             command.stdout(Stdio::null());
             command.stderr(Stdio::null());
 
             let child = command
                 .spawn()
-                .with_context(|| format!("failed to spawn {}", executable.display()))?;
-            return self.finish_start(runtime, child.id(), log_path, restart_policy, project_hash);
+                .with_context(|| format!("failed to spawn supervisor {}", supervisor.display()))?;
+            let info = self.finish_start(
+                runtime,
+                child.id(),
+                log_path,
+                restart_policy,
+                project_hash.clone(),
+                port,
+            )?;
+            return self.record_limits(info, applied);
         }
 
         let stdout_file = OpenOptions::new()
@@ -239,9 +414,132 @@ impl ProcessManager {
             .with_context(|| format!("opening runtime log file {}", log_path.display()))?;
         let stderr_file = stdout_file.try_clone()?;
 
+        let mut command = limited_command(executable, &runtime_args, limits, cgroup.as_deref());
+        for key in removed_env {
+            command.env_remove(key);
+        }
+        command.envs(env_vars.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        // Own process group so stopping the runtime also reaches the
+        // children it spawns (npm, node workers, shells).
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        command.stdin(Stdio::null());
+        command.stdout(Stdio::piped());
+        command.stderr(Stdio::piped());
+
+        let exit_path = self.exit_record_file(runtime);
+        if exit_path.exists() {
+            fs::remove_file(&exit_path)?;
+        }
+        let mut child = command
+            .spawn()
+            .with_context(|| format!("failed to spawn {}", executable.display()))?;
+
+        let active_log = Arc::new(ActiveLog::new(self.log_rotation.clone(), log_path.clone()));
+        let redactor =
+            Arc::new(Redactor::new(&env_vars).with_audit(self.log_dir.join("audit.log"), runtime));
+        if let Some(out) = child.stdout.take() {
+            tee_reader_to_log(
+                out,
+                Arc::new(Mutex::new(stdout_file)),
+                None,
+                Some(Arc::clone(&active_log)),
+                Some(Arc::clone(&redactor)),
+            );
+        }
+        if let Some(err) = child.stderr.take() {
+            tee_reader_to_log(
+                err,
+                Arc::new(Mutex::new(stderr_file)),
+                None,
+                Some(active_log),
+                Some(redactor),
+            );
+        }
+
+        let info = self.finish_start(
+            runtime,
+            child.id(),
+            log_path,
+            restart_policy,
+            project_hash.clone(),
+            port,
+        )?;
+        thread::spawn(move || {
+            if let Ok(status) = child.wait() {
+                let record = ExitRecord {
+                    code: status.code(),
+                    signal: std::os::unix::process::ExitStatusExt::signal(&status),
+                };
+                if let Ok(raw) = serde_json::to_string(&record) {
+                    let _ = fs::write(exit_path, raw);
+                }
+            }
+        });
+        self.record_limits(info, applied)
+    }
+
+    fn record_limits(
+        &self,
+        mut info: ProcessInfo,
+        applied: Option<AppliedLimits>,
+    ) -> Result<ProcessInfo> {
+        if applied.is_some() {
+            info.limits = applied;
+            self.write_pid_file(&info.runtime, &info)?;
+        }
+        Ok(info)
+    }
+
+    /// Compares the limits a running runtime was started with against what
+    /// the kernel enforces on it. Returns the pid that was inspected (the
+    /// runtime itself, not its supervisor) with one check per limit, or
+    /// `None` when the runtime is not running.
+    pub fn limit_report(&self, runtime: &str) -> Result<Option<(u32, Vec<LimitCheck>)>> {
+        let Some(info) = self.read_pid_file(runtime)? else {
+            return Ok(None);
+        };
+        if !is_pid_running(info.pid) {
+            return Ok(None);
+        }
+        let pid = SupervisorState::load(&self.supervisor_state_file(runtime))
+            .and_then(|state| state.child_pid)
+            .filter(|pid| is_pid_running(*pid))
+            .unwrap_or(info.pid);
+        let requested = info
+            .limits
+            .map(|applied| applied.requested)
+            .unwrap_or_default();
+        Ok(Some((pid, check_limits(pid, &requested))))
+    }
+
+    /// Launch a one-shot task process (e.g. a coding-tool worker) in `cwd`.
+    /// The process is tracked with a pid file like any other direct-mode
+    /// process until [`ProcessManager::wait_task`] reaps it.
+    pub fn spawn_task(
+        &self,
+        name: &str,
+        executable: &Path,
+        args: &[String],
+        env_vars: &[(String, String)],
+        cwd: &Path,
+    ) -> Result<TaskProcess> {
+        let log_path = self.log_dir.join(format!("{name}.log"));
+        let stdout_file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&log_path)
+            .with_context(|| format!("preparing task log file {}", log_path.display()))?;
+        let stderr_file = stdout_file.try_clone()?;
+
         let mut command = Command::new(executable);
-        command.args(&runtime_args);
+        command.args(args);
         command.envs(env_vars.iter().map(|(k, v)| (k.as_str(), v.as_str())));
+        command.current_dir(cwd);
+        // Own process group so a timeout also takes down grandchildren that
+        // would otherwise keep the output pipes open.
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        command.stdin(Stdio::null());
         command.stdout(Stdio::piped());
         command.stderr(Stdio::piped());
 
@@ -249,14 +547,74 @@ impl ProcessManager {
             .spawn()
             .with_context(|| format!("failed to spawn {}", executable.display()))?;
 
+        let captured = Arc::new(Mutex::new(String::new()));
+        let redactor =
+            Arc::new(Redactor::new(env_vars).with_audit(self.log_dir.join("audit.log"), name));
+        let mut readers = Vec::new();
         if let Some(out) = child.stdout.take() {
-            tee_reader_to_log(out, Arc::new(Mutex::new(stdout_file)));
+            readers.push(tee_reader_to_log(
+                out,
+                Arc::new(Mutex::new(stdout_file)),
+                Some(Arc::clone(&captured)),
+                None,
+                Some(Arc::clone(&redactor)),
+            ));
         }
         if let Some(err) = child.stderr.take() {
-            tee_reader_to_log(err, Arc::new(Mutex::new(stderr_file)));
+            readers.push(tee_reader_to_log(
+                err,
+                Arc::new(Mutex::new(stderr_file)),
+                None,
+                None,
+                Some(redactor),
+            ));
         }
 
-        self.finish_start(runtime, child.id(), log_path, restart_policy, project_hash)
+        let info = self.finish_start(name, child.id(), log_path, None, None, None)?;
+        Ok(TaskProcess {
+            info,
+            child,
+            stdout: captured,
+            readers,
+        })
+    }
+
+    /// Wait for a task process to exit, killing it once `timeout` elapses.
+    pub fn wait_task(&self, mut task: TaskProcess, timeout: Option<Duration>) -> Result<TaskExit> {
+        let started = Instant::now();
+        let mut timed_out = false;
+        let status = loop {
+            if let Some(status) = task.child.try_wait()? {
+                break status;
+            }
+            if timeout.is_some_and(|limit| started.elapsed() >= limit) {
+                timed_out = true;
+                let _ = Command::new("kill")
+                    .args(["-KILL", "--", &format!("-{}", task.info.pid)])
+                    .stderr(Stdio::null())
+                    .status();
+                let _ = task.child.kill();
+                break task.child.wait()?;
+            }
+            thread::sleep(Duration::from_millis(50));
+        };
+
+        for reader in task.readers.drain(..) {
+            let _ = reader.join();
+        }
+        self.remove_pid_file(&task.info.runtime)?;
+
+        let stdout = task
+            .stdout
+            .lock()
+            .map(|buf| buf.clone())
+            .unwrap_or_default();
+        Ok(TaskExit {
+            exit_code: status.code(),
+            timed_out,
+            stdout,
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
     }
 
     pub fn stop(&self, runtime: &str) -> Result<()> {
@@ -264,36 +622,91 @@ impl ProcessManager {
         Ok(())
     }
 
+    /// Stops a runtime together with everything it spawned. The runtime (or
+    /// its supervisor) gets SIGTERM on its process group; whatever is still
+    /// alive when `timeout_secs` runs out is killed, including descendants
+    /// that left the group. Finally the runtime's port is checked.
     pub fn stop_with_timeout(&self, runtime: &str, timeout_secs: u64) -> Result<StopOutcome> {
         let Some(info) = self.read_pid_file(runtime)? else {
-            return Ok(StopOutcome { forced: false });
+            return Ok(StopOutcome::default());
         };
+        if !is_same_process(info.pid, info.start_ticks) {
+            // Already stopped; the pid may now belong to something else.
+            self.clear_instance_state(runtime)?;
+            return Ok(StopOutcome::default());
+        }
 
-        let pid = info.pid.to_string();
-        let _ = Command::new("kill").args(["-TERM", &pid]).status();
-        for _ in 0..(timeout_secs.saturating_mul(10).max(1)) {
-            if !is_pid_running(info.pid) {
-                self.remove_pid_file(runtime)?;
-                return Ok(StopOutcome { forced: false });
-            }
-            thread::sleep(Duration::from_millis(100));
+        // Taken up front: once the runtime exits its children are reparented
+        // and can no longer be found from its pid.
+        let tree = process_tree(info.pid);
+        // A supervisor stops its own runtime, so only its group is asked.
+        if !signal_group(info.pid, "TERM") {
+            signal_pid(info.pid, "TERM");
+        }
+        let deadline = Instant::now() + Duration::from_secs(timeout_secs).max(STOP_POLL_INTERVAL);
+        while Instant::now() < deadline
+            && (is_pid_running(info.pid) || tree.iter().any(|pid| pid_alive(*pid)))
+        {
+            thread::sleep(STOP_POLL_INTERVAL);
         }
 
-        let _ = Command::new("kill").args(["-KILL", &pid]).status();
-        self.remove_pid_file(runtime)?;
-        Ok(StopOutcome { forced: true })
+        let forced = is_pid_running(info.pid);
+        let leftovers = self.kill_tree(runtime, &info, &tree);
+        self.clear_instance_state(runtime)?;
+        Ok(StopOutcome {
+            forced,
+            leftovers,
+            port_in_use: self.port_still_in_use(runtime, &info),
+        })
     }
 
     pub fn force_kill(&self, runtime: &str) -> Result<bool> {
         let Some(info) = self.read_pid_file(runtime)? else {
             return Ok(false);
         };
-        let pid = info.pid.to_string();
-        let _ = Command::new("kill").args(["-KILL", &pid]).status();
-        self.remove_pid_file(runtime)?;
+        if !is_same_process(info.pid, info.start_ticks) {
+            self.clear_instance_state(runtime)?;
+            return Ok(false);
+        }
+        let tree = process_tree(info.pid);
+        self.kill_tree(runtime, &info, &tree);
+        self.clear_instance_state(runtime)?;
         Ok(true)
     }
 
+    /// SIGKILLs the runtime's process groups and every still-running member
+    /// of `tree`. Returns the descendants that had to be killed.
+    fn kill_tree(&self, runtime: &str, info: &ProcessInfo, tree: &[u32]) -> Vec<LeftoverProcess> {
+        let mut groups = vec![info.pid];
+        groups.extend(
+            SupervisorState::load(&self.supervisor_state_file(runtime))
+                .and_then(|state| state.live_child_pid()),
+        );
+        let survivors: Vec<u32> = tree.iter().copied().filter(|pid| pid_alive(*pid)).collect();
+        let leftovers = survivors
+            .iter()
+            .filter(|pid| !groups.contains(pid))
+            .map(|pid| LeftoverProcess {
+                pid: *pid,
+                command: process_command(*pid),
+            })
+            .collect();
+        for pgid in groups {
+            signal_group(pgid, "KILL");
+        }
+        for pid in survivors {
+            signal_pid(pid, "KILL");
+        }
+        leftovers
+    }
+
+    fn port_still_in_use(&self, runtime: &str, info: &ProcessInfo) -> Option<u16> {
+        let port = info.port.or_else(|| {
+            runtime_descriptor(instance_runtime(runtime)).and_then(|d| d.health_port)
+        })?;
+        (!wait_for_port_release(port, PORT_RELEASE_TIMEOUT)).then_some(port)
+    }
+
     pub fn list_processes(&self) -> Result<Vec<ProcessInfo>> {
         let mut infos = Vec::new();
         if !self.state_dir.exists() {
@@ -323,42 +736,187 @@ impl ProcessManager {
         }
 
         for info in self.list_processes()? {
-            let running = is_pid_running(info.pid);
-            let health = if !running {
-                "stopped".to_string()
-            } else if let Some(url) = &info.health_url {
-                if health_check_ok(url) {
-                    "healthy".to_string()
-                } else {
-                    "unhealthy".to_string()
-                }
-            } else {
-                "unknown".to_string()
-            };
-
-            statuses.push(RuntimeProcessStatus {
-                runtime: info.runtime,
-                pid: Some(info.pid),
-                running,
-                mode: info.mode,
-                log_path: info.log_path,
-                health,
-            });
+            statuses.push(self.process_status(info));
         }
 
         statuses.sort_by(|a, b| a.runtime.cmp(&b.runtime));
         Ok(statuses)
     }
 
+    /// Status of one tracked instance, or `None` when it is not tracked.
+    pub fn runtime_status(&self, runtime: &str) -> Result<Option<RuntimeProcessStatus>> {
+        Ok(self
+            .read_pid_file(runtime)?
+            .map(|info| self.process_status(info)))
+    }
+
+    /// Status of the instance `info` was read for, probing its health.
+    pub fn process_status(&self, info: ProcessInfo) -> RuntimeProcessStatus {
+        let running = is_pid_running(info.pid);
+        let supervisor = SupervisorState::load(&self.supervisor_state_file(&info.runtime));
+        let gave_up = supervisor
+            .as_ref()
+            .map(|state| state.status)
+            .filter(|status| {
+                matches!(
+                    status,
+                    SupervisorStatus::CrashLoop | SupervisorStatus::RestartLimit
+                )
+            });
+        let health = if let (false, Some(status)) = (running, gave_up) {
+            status.as_str().to_string()
+        } else if !running {
+            "stopped".to_string()
+        } else {
+            self.probe_health(&info, false)
+        };
+
+        let (last_exit_code, last_exit_signal) = match &supervisor {
+            Some(state) => (state.last_exit_code, state.last_exit_signal),
+            None => fs::read_to_string(self.exit_record_file(&info.runtime))
+                .ok()
+                .and_then(|raw| serde_json::from_str::<ExitRecord>(&raw).ok())
+                .map_or((None, None), |record| (record.code, record.signal)),
+        };
+        RuntimeProcessStatus {
+            runtime: info.runtime,
+            pid: Some(info.pid),
+            running,
+            mode: info.mode,
+            log_path: info.log_path,
+            health,
+            port: info.port,
+            restarts: supervisor.as_ref().map_or(0, |state| state.restarts),
+            last_exit_code,
+            last_exit_signal,
+        }
+    }
+
+    /// Health of a running instance from its probe: `healthy`, `starting`,
+    /// `unhealthy`, or `unknown` without a probe. A result younger than the
+    /// probe interval is reused unless `force` is set.
+    fn probe_health(&self, info: &ProcessInfo, force: bool) -> String {
+        let Some(check) = info.health_check() else {
+            return "unknown".to_string();
+        };
+        let path = self.health_state_file(&info.runtime);
+        let mut state = HealthState::load(&path).unwrap_or_default();
+        let now = now_ms();
+        if !force && state.is_fresh(&check, now) {
+            return state.status;
+        }
+        // A supervised runtime is relaunched under the same supervisor pid.
+        let launch = SupervisorState::load(&self.supervisor_state_file(&info.runtime))
+            .and_then(|supervisor| supervisor.child_pid)
+            .unwrap_or(info.pid);
+        let result = if check.log.is_some() && state.log_matched_pid == Some(launch) {
+            Ok(())
+        } else {
+            check.probe(info.port, &info.log_path)
+        };
+        if check.log.is_some() && result.is_ok() {
+            state.log_matched_pid = Some(launch);
+        }
+        let status = state
+            .record(&check, result, info.started_at_unix_ms, now)
+            .to_string();
+        state.save(&path);
+        status
+    }
+
+    /// Attaches a `health:` probe to a started instance.
+    pub fn set_health_check(&self, runtime: &str, check: HealthCheck) -> Result<ProcessInfo> {
+        let mut info = self
+            .read_pid_file(runtime)?
+            .ok_or_else(|| anyhow!("{runtime} is not running"))?;
+        info.health = Some(check);
+        let _ = fs::remove_file(self.health_state_file(runtime));
+        self.write_pid_file(runtime, &info)?;
+        Ok(info)
+    }
+
+    /// Probes an instance until it passes, it exits or `timeout` runs out.
+    /// Returns whether it became healthy; an instance without a probe counts
+    /// as healthy while it runs.
+    pub fn wait_until_healthy(&self, runtime: &str, timeout: Duration) -> Result<bool> {
+        let info = self
+            .read_pid_file(runtime)?
+            .ok_or_else(|| anyhow!("{runtime} is not running"))?;
+        let Some(check) = info.health_check() else {
+            return Ok(is_pid_running(info.pid));
+        };
+        let poll = check.interval().clamp(HEALTH_POLL_MIN, HEALTH_POLL_MAX);
+        let deadline = Instant::now() + timeout;
+        loop {
+            if !is_pid_running(info.pid) {
+                return Ok(false);
+            }
+            if self.probe_health(&info, true) == "healthy" {
+                return Ok(true);
+            }
+            if Instant::now() >= deadline {
+                return Ok(false);
+            }
+            thread::sleep(poll.min(deadline.saturating_duration_since(Instant::now())));
+        }
+    }
+
+    /// Resolves a name given on the command line to tracked instances: an
+    /// exact instance name, or every instance of a bare runtime name.
+    pub fn resolve_instances(&self, name: &str) -> Result<Vec<String>> {
+        let names: Vec<String> = self
+            .list_processes()?
+            .into_iter()
+            .map(|info| info.runtime)
+            .collect();
+        if names.iter().any(|candidate| candidate == name) {
+            return Ok(vec![name.to_string()]);
+        }
+        Ok(names
+            .into_iter()
+            .filter(|candidate| instance_runtime(candidate) == name)
+            .collect())
+    }
+
+    /// Samples CPU, RSS and open FDs for a direct-mode runtime, summed over
+    /// its whole process tree. Returns `None` when the runtime is not running.
+    pub fn metrics(&self, runtime: &str) -> Result<Option<AgentMetrics>> {
+        let Some(info) = self.read_pid_file(runtime)? else {
+            return Ok(None);
+        };
+        if !is_pid_running(info.pid) {
+            self.sampler.forget(info.pid);
+            return Ok(None);
+        }
+        self.sampler.sample(info.pid).map(Some)
+    }
+
     pub fn tail_logs(&self, runtime: &str, lines: usize) -> Result<String> {
+        Ok(self
+            .tail_log_session(runtime, 0, lines)?
+            .unwrap_or_default())
+    }
+
+    /// Tails one log session: 0 is the current session, 1 the previous one,
+    /// and so on. `None` means that session is not kept.
+    pub fn tail_log_session(
+        &self,
+        runtime: &str,
+        session: usize,
+        lines: usize,
+    ) -> Result<Option<String>> {
         let log_path = self.log_dir.join(format!("{runtime}.log"));
-        if !log_path.exists() {
-            return Ok(String::new());
-        }
-        let content = fs::read_to_string(&log_path)?;
+        let Some(content) = read_log_session(&log_path, session)? else {
+            return Ok(None);
+        };
         let rows: Vec<&str> = content.lines().collect();
         let start = rows.len().saturating_sub(lines);
-        Ok(rows[start..].join("\n"))
+        Ok(Some(rows[start..].join("\n")))
+    }
+
+    /// How many previous log sessions are kept for a runtime.
+    pub fn previous_log_sessions(&self, runtime: &str) -> usize {
+        previous_log_sessions(&self.log_dir.join(format!("{runtime}.log")))
     }
 
     pub fn stream_logs(&self, runtimes: &[String]) -> Result<LogStream> {
@@ -385,36 +943,22 @@ impl ProcessManager {
         let stream_inner = Arc::clone(&inner);
         let stream_running = Arc::clone(&running);
         thread::spawn(move || {
-            let mut offsets: HashMap<String, usize> = watched
-                .iter()
-                .map(|(runtime, path)| {
-                    let offset = fs::metadata(path)
-                        .map(|meta| meta.len() as usize)
-                        .unwrap_or(0);
-                    (runtime.clone(), offset)
-                })
+            let paths: Vec<PathBuf> = watched.iter().map(|(_, path)| path.clone()).collect();
+            let wakeup = LogWakeup::watch(&paths);
+            let mut tailers: Vec<(String, LogTailer)> = watched
+                .into_iter()
+                .map(|(runtime, path)| (runtime, LogTailer::at_end(path)))
                 .collect();
             while stream_running.load(Ordering::Relaxed) {
-                let mut any_sent = false;
-                for (runtime, log_path) in &watched {
-                    let Ok(content) = fs::read_to_string(log_path) else {
-                        continue;
-                    };
-
-                    let offset = offsets.entry(runtime.clone()).or_insert(0usize);
-                    if *offset > content.len() {
-                        *offset = 0;
-                    }
-                    if *offset == content.len() {
+                for (runtime, tailer) in &mut tailers {
+                    let lines = tailer.read_new_lines();
+                    if lines.is_empty() {
                         continue;
                     }
-
-                    let chunk = &content[*offset..];
-                    for line in chunk.lines() {
-                        let Ok(mut state) = stream_inner.lock() else {
-                            return;
-                        };
-
+                    let Ok(mut state) = stream_inner.lock() else {
+                        return;
+                    };
+                    for line in lines {
                         while state.queue.len() >= LOG_STREAM_CAPACITY {
                             if let Some(dropped_line) = state.queue.pop_front() {
                                 *state.dropped.entry(dropped_line.runtime).or_insert(0) += 1;
@@ -423,25 +967,81 @@ impl ProcessManager {
                             }
                         }
 
-                        state.queue.push_back(LogLine {
-                            runtime: runtime.clone(),
-                            timestamp_ms: now_ms(),
-                            text: line.to_string(),
-                        });
-                        any_sent = true;
+                        state
+                            .queue
+                            .push_back(LogLine::parse(runtime.clone(), line, now_ms()));
                     }
-                    *offset = content.len();
                 }
 
-                if !any_sent {
-                    thread::sleep(Duration::from_millis(200));
-                }
+                // The timeout bounds how long a dropped stream keeps its
+                // thread alive and covers changes the watcher missed.
+                wakeup.wait(Duration::from_millis(500));
             }
         });
 
         Ok(LogStream { inner, running })
     }
 
+    /// Feeds new log lines and running/stopped transitions for a direct-mode
+    /// runtime into `events` until the subscriber goes away.
+    pub fn subscribe_events(&self, runtime: &str, events: EventSender) -> Result<()> {
+        let logs = self.stream_logs(&[runtime.to_string()])?;
+        let pid_path = self.pid_file(runtime);
+        let pid_running = move || {
+            fs::read_to_string(&pid_path)
+                .ok()
+                .and_then(|body| serde_json::from_str::<ProcessInfo>(&body).ok())
+                .map(|info| is_pid_running(info.pid))
+                .unwrap_or(false)
+        };
+
+        let state_path = self.supervisor_state_file(runtime);
+        let supervisor_state = move || SupervisorState::load(&state_path);
+
+        thread::spawn(move || {
+            let mut was_running = pid_running();
+            let mut last_supervisor = supervisor_state();
+            while !events.is_closed() {
+                for line in logs.drain() {
+                    if !events.emit(RuntimeEvent::log("combined", line.text)) {
+                        return;
+                    }
+                }
+
+                let running = pid_running();
+                if running != was_running {
+                    let state = if running { "running" } else { "stopped" };
+                    if !events.emit(RuntimeEvent::state_changed(state)) {
+                        return;
+                    }
+                    was_running = running;
+                }
+
+                // Crashes inside a supervised runtime never stop the
+                // supervisor process, so report them from its state file.
+                let supervisor = supervisor_state();
+                if let Some(current) = &supervisor {
+                    let previous = last_supervisor.as_ref();
+                    let restarted = current.restarts > previous.map_or(0, |state| state.restarts);
+                    let gave_up = matches!(
+                        current.status,
+                        SupervisorStatus::CrashLoop | SupervisorStatus::RestartLimit
+                    ) && previous.map(|state| state.status) != Some(current.status);
+                    if restarted && !events.emit(RuntimeEvent::state_changed("restarted")) {
+                        return;
+                    }
+                    if gave_up && !events.emit(RuntimeEvent::state_changed(current.status.as_str()))
+                    {
+                        return;
+                    }
+                }
+                last_supervisor = supervisor;
+                thread::sleep(Duration::from_millis(200));
+            }
+        });
+        Ok(())
+    }
+
     fn finish_start(
         &self,
         runtime: &str,
@@ -449,18 +1049,24 @@ impl ProcessManager {
         log_path: PathBuf,
         restart_policy: Option<String>,
         project_hash: Option<String>,
+        port: Option<u16>,
     ) -> Result<ProcessInfo> {
         let info = ProcessInfo {
             runtime: runtime.to_string(),
             pid,
+            start_ticks: process_start_ticks(pid),
             started_at_unix_ms: now_ms(),
             mode: ExecutionMode::Direct,
             log_path,
             restart_policy,
-            health_url: runtime_health_url(runtime),
+            health_url: runtime_health_url(runtime, port),
             project_hash,
+            port,
+            limits: None,
+            health: None,
         };
 
+        let _ = fs::remove_file(self.health_state_file(runtime));
         self.write_pid_file(runtime, &info)?;
         Ok(info)
     }
@@ -494,27 +1100,102 @@ impl ProcessManager {
     fn pid_file(&self, runtime: &str) -> PathBuf {
         self.state_dir.join(format!("{runtime}.pid"))
     }
+
+    fn supervisor_spec_file(&self, runtime: &str) -> PathBuf {
+        self.state_dir.join(format!("{runtime}.supervisor.json"))
+    }
+
+    fn health_state_file(&self, runtime: &str) -> PathBuf {
+        self.state_dir.join(format!("{runtime}.health.json"))
+    }
+
+    fn supervisor_state_file(&self, runtime: &str) -> PathBuf {
+        self.state_dir
+            .join(format!("{runtime}.supervisor-state.json"))
+    }
+
+    fn exit_record_file(&self, runtime: &str) -> PathBuf {
+        self.state_dir.join(format!("{runtime}.exit.json"))
+    }
+
+    /// Forgets a stopped instance: pid file, port, and supervisor files. A
+    /// runtime left behind by a killed supervisor is killed too.
+    fn clear_instance_state(&self, runtime: &str) -> Result<()> {
+        let cgroup = self
+            .read_pid_file(runtime)
+            .ok()
+            .flatten()
+            .and_then(|info| info.limits)
+            .and_then(|applied| applied.cgroup);
+        let state_path = self.supervisor_state_file(runtime);
+        if let Some(pid) =
+            SupervisorState::load(&state_path).and_then(|state| state.live_child_pid())
+        {
+            if is_pid_running(pid) && !signal_group(pid, "KILL") {
+                signal_pid(pid, "KILL");
+            }
+        }
+        for path in [
+            state_path,
+            self.supervisor_spec_file(runtime),
+            self.health_state_file(runtime),
+            self.exit_record_file(runtime),
+        ] {
+            if path.exists() {
+                fs::remove_file(path)?;
+            }
+        }
+        self.remove_pid_file(runtime)?;
+        if let Some(group) = cgroup {
+            // A group that is still populated is reused by the next start.
+            let _ = remove_cgroup(&group);
+        }
+        self.ports.release(runtime)
+    }
 }
 
-fn tee_reader_to_log<R: std::io::Read + Send + 'static>(reader: R, file: Arc<Mutex<File>>) {
+/// Copies `reader` line by line into `file`, scrubbed by `redactor` first so
+/// secrets never reach the disk.
+pub(crate) fn tee_reader_to_log<R: std::io::Read + Send + 'static>(
+    reader: R,
+    file: Arc<Mutex<File>>,
+    capture: Option<Arc<Mutex<String>>>,
+    active_log: Option<Arc<ActiveLog>>,
+    redactor: Option<Arc<Redactor>>,
+) -> thread::JoinHandle<()> {
     thread::spawn(move || {
         let mut reader = BufReader::new(reader);
-        let mut line = String::new();
+        let mut raw = Vec::new();
         loop {
-            line.clear();
-            let Ok(read) = reader.read_line(&mut line) else {
+            raw.clear();
+            // Runtimes can print non-UTF-8 bytes; keep the line rather than
+            // stop teeing the stream.
+            let Ok(read) = reader.read_until(b'\n', &mut raw) else {
                 return;
             };
             if read == 0 {
                 return;
             }
+            let decoded = String::from_utf8_lossy(&raw);
+            let line = match &redactor {
+                Some(redactor) => redactor.redact(&decoded),
+                None => Cow::Borrowed(decoded.as_ref()),
+            };
 
             if let Ok(mut log_file) = file.lock() {
                 let _ = log_file.write_all(line.as_bytes());
                 let _ = log_file.flush();
             }
+            if let Some(log) = &active_log {
+                log.rotate_if_due();
+            }
+            if let Some(buf) = &capture {
+                if let Ok(mut buf) = buf.lock() {
+                    buf.push_str(&line);
+                }
+            }
         }
-    });
+    })
 }
 
 fn is_pid_running(pid: u32) -> bool {
@@ -573,8 +1254,95 @@ fn split_restart_policy(args: &[String]) -> (Vec<String>, Option<String>) {
     (filtered, restart_policy)
 }
 
-fn runtime_health_url(runtime: &str) -> Option<String> {
-    let runtime_key = runtime.to_ascii_uppercase().replace('-', "_");
+/// Points the runtime at its allocated port: through its port flag, or by
+/// writing the port into the config file its start args name.
+fn apply_port_setting(runtime: &str, port: u16, args: &mut Vec<String>) -> Result<()> {
+    let Some(descriptor) = runtime_descriptor(runtime) else {
+        return Ok(());
+    };
+    let (key, value) = match descriptor.port_setting {
+        PortSetting::Env => return Ok(()),
+        PortSetting::Flag(flag) => {
+            // An explicit port in the start args wins.
+            if !args.iter().any(|arg| arg == flag) {
+                args.push(flag.to_string());
+                args.push(port.to_string());
+            }
+            return Ok(());
+        }
+        PortSetting::ConfigKey(key) => (key, JsonValue::from(port)),
+        PortSetting::ListenAddr(key) => (key, JsonValue::from(format!("127.0.0.1:{port}"))),
+    };
+    let flag_value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|idx| args.get(idx + 1))
+            .map(PathBuf::from)
+    };
+    let config_path = match descriptor.config_dir_flag {
+        ConfigDirFlag::ConfigDir => {
+            let file = match descriptor.config_format {
+                ConfigFormat::Toml => "config.toml",
+                ConfigFormat::Json => "config.json",
+                ConfigFormat::EnvVars | ConfigFormat::None => return Ok(()),
+            };
+            flag_value("--config-dir").map(|dir| dir.join(file))
+        }
+        ConfigDirFlag::ConfigFile { .. } => flag_value("--config"),
+    };
+    match config_path.filter(|path| path.exists()) {
+        Some(path) => set_config_value(&path, descriptor.config_format, key, value),
+        None => Ok(()),
+    }
+}
+
+/// Sets a dotted `key` in a TOML or JSON config file, creating the tables
+/// on the way.
+fn set_config_value(path: &Path, format: ConfigFormat, key: &str, value: JsonValue) -> Result<()> {
+    let body = fs::read_to_string(path)
+        .with_context(|| format!("reading runtime config {}", path.display()))?;
+    let parts = key.split('.').collect::<Vec<_>>();
+    let (leaf, parents) = parts.split_last().expect("split yields at least one part");
+    let body = if format == ConfigFormat::Toml {
+        let mut root: toml::Table = body
+            .parse()
+            .with_context(|| format!("parsing runtime config {}", path.display()))?;
+        let mut table = &mut root;
+        for part in parents {
+            let entry = table
+                .entry(part.to_string())
+                .or_insert_with(|| toml::Value::Table(Default::default()));
+            if !entry.is_table() {
+                *entry = toml::Value::Table(Default::default());
+            }
+            table = entry.as_table_mut().expect("entry was made a table");
+        }
+        table.insert(leaf.to_string(), toml::Value::try_from(value)?);
+        toml::to_string_pretty(&root)?
+    } else {
+        let mut root: JsonValue = serde_json::from_str(&body)
+            .with_context(|| format!("parsing runtime config {}", path.display()))?;
+        if !root.is_object() {
+            return Err(anyhow!(
+                "runtime config {} is not an object",
+                path.display()
+            ));
+        }
+        let mut table = &mut root;
+        for part in parents {
+            if !table.get(part).is_some_and(JsonValue::is_object) {
+                table[part] = JsonValue::Object(Default::default());
+            }
+            table = &mut table[part];
+        }
+        table[leaf] = value;
+        serde_json::to_string_pretty(&root)?
+    };
+    fs::write(path, body).with_context(|| format!("writing runtime config {}", path.display()))
+}
+
+fn runtime_health_url(runtime: &str, port: Option<u16>) -> Option<String> {
+    let runtime_key = runtime.to_ascii_uppercase().replace(['-', '@'], "_");
     let url_key = format!("CLAWDEN_HEALTH_URL_{runtime_key}");
     if let Ok(url) = std::env::var(url_key) {
         if !url.trim().is_empty() {
@@ -589,67 +1357,30 @@ fn runtime_health_url(runtime: &str) -> Option<String> {
         }
     }
 
-    runtime_descriptor(runtime).and_then(|descriptor| descriptor.health_url())
-}
-
-fn health_check_ok(url: &str) -> bool {
-    Command::new("curl")
-        .args(["-fsS", "--max-time", "2", url])
-        .stdout(Stdio::null())
-        .stderr(Stdio::null())
-        .status()
-        .map(|status| status.success())
-        .unwrap_or(false)
-}
-
-fn build_restart_supervisor_script() -> &'static str {
-    r#"#!/usr/bin/env sh
-set -u
-
-exec_path="$1"
-log_path="$2"
-audit_path="$3"
-runtime_name="$4"
-shift 4
-
-backoff=1
-child_pid=""
-
-cleanup() {
-    if [ -n "$child_pid" ]; then
-        kill -TERM "$child_pid" 2>/dev/null || true
-        sleep 2
-        kill -KILL "$child_pid" 2>/dev/null || true
-    fi
-    exit 0
+    match port {
+        Some(port) => Some(format!("http://127.0.0.1:{port}/health")),
+        None => runtime_descriptor(runtime).and_then(|descriptor| descriptor.health_url()),
+    }
 }
 
-trap cleanup INT TERM
-
-while true; do
-    "$exec_path" "$@" >>"$log_path" 2>&1 &
-    child_pid="$!"
-    wait "$child_pid"
-    exit_code="$?"
-    child_pid=""
-
-    if [ "$exit_code" -eq 0 ]; then
-        exit 0
-    fi
-
-    ts="$(date +%s)000"
-    printf "%s\truntime.crash\t%s\texit:%s\n" "$ts" "$runtime_name" "$exit_code" >>"$audit_path"
-    printf "%s\truntime.restart\t%s\tbackoff:%s\n" "$ts" "$runtime_name" "$backoff" >>"$audit_path"
-
-    sleep "$backoff"
-    if [ "$backoff" -lt 30 ]; then
-        backoff=$((backoff * 2))
-        if [ "$backoff" -gt 30 ]; then
-            backoff=30
-        fi
-    fi
-done
-"#
+/// The binary that provides `clawden supervise`: `CLAWDEN_SUPERVISOR_BIN`,
+/// then the running `clawden` itself, then a `clawden` next to the current
+/// executable (e.g. beside `clawden-server`), then `clawden` on `PATH`.
+fn supervisor_executable() -> PathBuf {
+    if let Some(path) = std::env::var_os("CLAWDEN_SUPERVISOR_BIN").filter(|p| !p.is_empty()) {
+        return PathBuf::from(path);
+    }
+    if let Ok(current) = std::env::current_exe() {
+        if current.file_name().and_then(|n| n.to_str()) == Some("clawden") {
+            return current;
+        }
+        if let Some(sibling) = current.parent().map(|dir| dir.join("clawden")) {
+            if sibling.exists() {
+                return sibling;
+            }
+        }
+    }
+    PathBuf::from("clawden")
 }
 
 fn clawden_root_dir() -> Result<PathBuf> {
@@ -657,14 +1388,44 @@ fn clawden_root_dir() -> Result<PathBuf> {
     Ok(PathBuf::from(home).join(".clawden"))
 }
 
+/// Asks the daemon for its version; a CLI without a running (or reachable)
+/// daemon fails or hangs, and both count as unavailable.
+fn docker_daemon_reachable() -> bool {
+    let Ok(mut child) = Command::new("docker")
+        .args(["version", "--format", "{{.Server.Version}}"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    else {
+        return false;
+    };
+    let deadline = Instant::now() + DOCKER_PROBE_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => return status.success(),
+            Ok(None) if Instant::now() < deadline => thread::sleep(Duration::from_millis(20)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return false;
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{ExecutionMode, ProcessManager};
+    use super::{
+        docker_daemon_reachable, process_start_ticks, tee_reader_to_log, DirectStartOptions,
+        ExecutionMode, ProcessInfo, ProcessManager,
+    };
+    use crate::{EventFilter, EventStream, HealthCheck, RuntimeEvent};
     use std::fs;
     use std::io::Write;
     use std::os::unix::fs::PermissionsExt;
     use std::path::Path;
-    use std::sync::{Mutex, OnceLock};
+    use std::sync::{Arc, Mutex, OnceLock};
     use std::thread;
     use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
@@ -673,6 +1434,62 @@ mod tests {
         LOCK.get_or_init(|| Mutex::new(()))
     }
 
+    #[test]
+    fn stop_treats_a_reused_pid_as_already_stopped() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+        let original_home = std::env::var("HOME").ok();
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after UNIX_EPOCH")
+            .as_nanos();
+        let tmp_home = std::env::temp_dir().join(format!("clawden-process-reused-{unique}"));
+        fs::create_dir_all(&tmp_home).expect("failed to create temporary HOME dir");
+        std::env::set_var("HOME", &tmp_home);
+
+        let manager = ProcessManager::new(ExecutionMode::Direct).expect("process manager init");
+        // An unrelated process now holds the pid the runtime was started with.
+        let mut stranger = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .expect("sleep should spawn");
+        let pid = stranger.id();
+        let info = ProcessInfo {
+            runtime: "zeroclaw".to_string(),
+            pid,
+            start_ticks: process_start_ticks(pid).map(|ticks| ticks + 1),
+            started_at_unix_ms: 0,
+            mode: ExecutionMode::Direct,
+            log_path: manager.log_dir().join("zeroclaw.log"),
+            restart_policy: None,
+            health_url: None,
+            project_hash: None,
+            port: None,
+            limits: None,
+            health: None,
+        };
+        manager
+            .write_pid_file("zeroclaw", &info)
+            .expect("pid file should be written");
+
+        let outcome = manager.stop_with_timeout("zeroclaw", 1).expect("stop");
+        assert!(!outcome.forced);
+        assert!(stranger.try_wait().expect("stranger status").is_none());
+        assert!(manager
+            .read_pid_file("zeroclaw")
+            .expect("pid file read")
+            .is_none());
+
+        let _ = stranger.kill();
+        let _ = stranger.wait();
+        if let Some(home) = original_home {
+            std::env::set_var("HOME", home);
+        } else {
+            std::env::remove_var("HOME");
+        }
+        let _ = fs::remove_dir_all(tmp_home);
+    }
+
     #[test]
     fn stream_logs_skips_preexisting_content() {
         let _guard = env_lock().lock().expect("env lock poisoned");
@@ -728,7 +1545,147 @@ mod tests {
     }
 
     #[test]
-    fn start_direct_truncates_previous_log_content() {
+    fn subscribe_events_forwards_new_log_lines() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+        let original_home = std::env::var("HOME").ok();
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after UNIX_EPOCH")
+            .as_nanos();
+        let tmp_home = std::env::temp_dir().join(format!("clawden-process-events-{unique}"));
+        fs::create_dir_all(&tmp_home).expect("failed to create temporary HOME dir");
+        std::env::set_var("HOME", &tmp_home);
+
+        let manager = ProcessManager::new(ExecutionMode::Direct).expect("process manager init");
+        let log_path = manager.log_dir().join("zeroclaw.log");
+        fs::write(&log_path, "stale line\n").expect("seed stale log line");
+
+        let (events, mut stream) = EventStream::channel(EventFilter::all());
+        manager
+            .subscribe_events("zeroclaw", events)
+            .expect("subscribe to runtime events");
+
+        thread::sleep(Duration::from_millis(250));
+        fs::OpenOptions::new()
+            .append(true)
+            .open(&log_path)
+            .expect("open log for append")
+            .write_all(b"fresh line\n")
+            .expect("append fresh line");
+
+        let deadline = Instant::now() + Duration::from_secs(3);
+        let mut received = None;
+        while received.is_none() && Instant::now() < deadline {
+            received = stream.try_next();
+            thread::sleep(Duration::from_millis(50));
+        }
+        match received {
+            Some(RuntimeEvent::Log { line, .. }) => assert_eq!(line, "fresh line"),
+            other => panic!("expected fresh log event, got {other:?}"),
+        }
+        drop(stream);
+
+        if let Some(home) = original_home {
+            std::env::set_var("HOME", home);
+        } else {
+            std::env::remove_var("HOME");
+        }
+        let _ = fs::remove_dir_all(tmp_home);
+    }
+
+    #[test]
+    fn start_direct_redacts_injected_env_values() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+        let original_home = std::env::var("HOME").ok();
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after UNIX_EPOCH")
+            .as_nanos();
+        let tmp_home = std::env::temp_dir().join(format!("clawden-process-test-{unique}"));
+        fs::create_dir_all(&tmp_home).expect("failed to create temporary HOME dir");
+        std::env::set_var("HOME", &tmp_home);
+
+        let manager = ProcessManager::new(ExecutionMode::Direct).expect("process manager init");
+        let runtime = "zeroclaw";
+        let log_path = manager.log_dir().join("zeroclaw.log");
+        let script = tmp_home.join("echo-runtime.sh");
+        write_executable(
+            &script,
+            "#!/usr/bin/env sh\necho \"provider key: $OPENAI_API_KEY\" >&2\necho done\nexit 0\n",
+        );
+
+        let env_vars = vec![(
+            "OPENAI_API_KEY".to_string(),
+            "opaque-provider-secret".to_string(),
+        )];
+        manager
+            .start_direct(&DirectStartOptions {
+                env_vars: env_vars.clone(),
+                ..DirectStartOptions::new(runtime, &script)
+            })
+            .expect("runtime should start");
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let content = loop {
+            let current = fs::read_to_string(&log_path).expect("log file should be readable");
+            if current.contains("done") && current.contains("provider key") {
+                break current;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for runtime output; current log content: {current:?}"
+            );
+            thread::sleep(Duration::from_millis(25));
+        };
+        assert!(content.contains("provider key: [REDACTED:OPENAI_API_KEY]"));
+        assert!(!content.contains("opaque-provider-secret"));
+        let audit = fs::read_to_string(manager.log_dir().join("audit.log")).expect("audit log");
+        assert!(audit.contains("\tlog.redact\tzeroclaw\trule=OPENAI_API_KEY"));
+
+        let _ = manager.stop_with_timeout(runtime, 1);
+
+        if let Some(home) = original_home {
+            std::env::set_var("HOME", home);
+        } else {
+            std::env::remove_var("HOME");
+        }
+        let _ = fs::remove_dir_all(tmp_home);
+    }
+
+    #[test]
+    fn tee_keeps_lines_after_invalid_utf8() {
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after UNIX_EPOCH")
+            .as_nanos();
+        let log_path = std::env::temp_dir().join(format!("clawden-tee-{unique}.log"));
+        let file = fs::File::create(&log_path).expect("log file should be created");
+        let capture = Arc::new(Mutex::new(String::new()));
+
+        let output = b"first\nbad \xff\xfe byte\nlast\n".to_vec();
+        tee_reader_to_log(
+            std::io::Cursor::new(output),
+            Arc::new(Mutex::new(file)),
+            Some(Arc::clone(&capture)),
+            None,
+            None,
+        )
+        .join()
+        .expect("tee thread should finish");
+
+        let expected = "first\nbad \u{fffd}\u{fffd} byte\nlast\n";
+        assert_eq!(*capture.lock().expect("capture lock"), expected);
+        assert_eq!(
+            fs::read_to_string(&log_path).expect("log should be utf-8"),
+            expected
+        );
+        let _ = fs::remove_file(log_path);
+    }
+
+    #[test]
+    fn start_direct_rotates_previous_log_content() {
         let _guard = env_lock().lock().expect("env lock poisoned");
         let original_home = std::env::var("HOME").ok();
 
@@ -749,7 +1706,7 @@ mod tests {
         write_executable(&script, "#!/usr/bin/env sh\necho fresh line\nexit 0\n");
 
         let _info = manager
-            .start_direct_with_env(runtime, &script, &[], &[])
+            .start_direct(&DirectStartOptions::new(runtime, &script))
             .expect("runtime should start");
 
         let deadline = Instant::now() + Duration::from_secs(2);
@@ -765,6 +1722,14 @@ mod tests {
             thread::sleep(Duration::from_millis(25));
         };
         assert!(!content.contains("stale line"));
+        assert_eq!(manager.previous_log_sessions(runtime), 1);
+        assert_eq!(
+            manager
+                .tail_log_session(runtime, 1, 10)
+                .expect("previous session should be readable")
+                .as_deref(),
+            Some("stale line")
+        );
 
         let _ = manager.stop_with_timeout(runtime, 1);
 
@@ -776,6 +1741,211 @@ mod tests {
         let _ = fs::remove_dir_all(tmp_home);
     }
 
+    #[test]
+    fn metrics_include_child_processes() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+        let original_home = std::env::var("HOME").ok();
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after UNIX_EPOCH")
+            .as_nanos();
+        let tmp_home = std::env::temp_dir().join(format!("clawden-process-metrics-{unique}"));
+        fs::create_dir_all(&tmp_home).expect("failed to create temporary HOME dir");
+        std::env::set_var("HOME", &tmp_home);
+
+        let manager = ProcessManager::new(ExecutionMode::Direct).expect("process manager init");
+        let runtime = "zeroclaw";
+        assert!(manager.metrics(runtime).expect("metrics").is_none());
+
+        let script = tmp_home.join("tree-runtime.sh");
+        write_executable(
+            &script,
+            "#!/usr/bin/env sh
+sleep 30 &
+sleep 30
+",
+        );
+        let info = manager
+            .start_direct(&DirectStartOptions::new(runtime, &script))
+            .expect("runtime should start");
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while crate::process_tree(info.pid).len() < 3 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(25));
+        }
+        let metrics = manager
+            .metrics(runtime)
+            .expect("metrics")
+            .expect("running runtime should report metrics");
+        assert!(metrics.memory_mb > 0.0);
+        assert!(metrics.open_fds > 0);
+        assert!(crate::process_tree(info.pid).len() >= 3);
+
+        let _ = manager.stop_with_timeout(runtime, 1);
+
+        if let Some(home) = original_home {
+            std::env::set_var("HOME", home);
+        } else {
+            std::env::remove_var("HOME");
+        }
+        let _ = fs::remove_dir_all(tmp_home);
+    }
+
+    #[test]
+    fn instances_of_one_runtime_get_separate_state_and_ports() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+        let original_home = std::env::var("HOME").ok();
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after UNIX_EPOCH")
+            .as_nanos();
+        let tmp_home = std::env::temp_dir().join(format!("clawden-process-instances-{unique}"));
+        fs::create_dir_all(&tmp_home).expect("failed to create temporary HOME dir");
+        std::env::set_var("HOME", &tmp_home);
+
+        let manager = ProcessManager::new(ExecutionMode::Direct).expect("process manager init");
+        let script = tmp_home.join("port-runtime.sh");
+        write_executable(
+            &script,
+            "#!/usr/bin/env sh\necho \"port=$ZEROCLAW_PORT\"\nexec sleep 30\n",
+        );
+
+        let config_args = |name: &str| {
+            let dir = tmp_home.join(name);
+            fs::create_dir_all(&dir).expect("config dir should be created");
+            fs::write(
+                dir.join("config.toml"),
+                "default_provider = \"openai\"\n\n[gateway]\nhost = \"127.0.0.1\"\n",
+            )
+            .expect("config should be written");
+            let args = vec![
+                "daemon".to_string(),
+                "--config-dir".to_string(),
+                dir.display().to_string(),
+            ];
+            (dir.join("config.toml"), args)
+        };
+        let config_port = |path: &Path| {
+            let config: toml::Table = fs::read_to_string(path)
+                .expect("config should be readable")
+                .parse()
+                .expect("config should stay valid toml");
+            assert_eq!(config["default_provider"].as_str(), Some("openai"));
+            assert_eq!(config["gateway"]["host"].as_str(), Some("127.0.0.1"));
+            config["gateway"]["port"]
+                .as_integer()
+                .and_then(|port| u16::try_from(port).ok())
+        };
+        let (default_config, default_args) = config_args("default-config");
+        let (work_config, work_args) = config_args("work-config");
+
+        let default = manager
+            .start_direct(&DirectStartOptions {
+                args: default_args.clone(),
+                ..DirectStartOptions::new("zeroclaw", &script)
+            })
+            .expect("default instance should start");
+        let work = manager
+            .start_direct(&DirectStartOptions {
+                args: work_args.clone(),
+                ..DirectStartOptions::new("zeroclaw@work", &script)
+            })
+            .expect("second instance should start");
+        assert_ne!(default.port, None);
+        assert_ne!(default.port, work.port);
+        assert_eq!(config_port(&default_config), default.port);
+        assert_eq!(config_port(&work_config), work.port);
+        assert_ne!(default.log_path, work.log_path);
+        assert!(manager.state_dir().join("zeroclaw@work.pid").exists());
+        assert_eq!(
+            work.health_url,
+            work.port
+                .map(|port| format!("http://127.0.0.1:{port}/health"))
+        );
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        let expected = format!("port={}", work.port.expect("port"));
+        while !fs::read_to_string(&work.log_path)
+            .unwrap_or_default()
+            .contains(&expected)
+        {
+            assert!(Instant::now() < deadline, "instance port was not passed");
+            thread::sleep(Duration::from_millis(25));
+        }
+
+        assert_eq!(
+            manager.resolve_instances("zeroclaw").expect("resolve"),
+            vec!["zeroclaw".to_string()]
+        );
+        let _ = manager.stop_with_timeout("zeroclaw", 1);
+        assert_eq!(
+            manager.resolve_instances("zeroclaw").expect("resolve"),
+            vec!["zeroclaw@work".to_string()]
+        );
+        let _ = manager.stop_with_timeout("zeroclaw@work", 1);
+        assert!(manager.list_processes().expect("list").is_empty());
+        assert!(manager
+            .start_direct(&DirectStartOptions::new("zeroclaw@../x", &script))
+            .is_err());
+
+        if let Some(home) = original_home {
+            std::env::set_var("HOME", home);
+        } else {
+            std::env::remove_var("HOME");
+        }
+        let _ = fs::remove_dir_all(tmp_home);
+    }
+
+    #[test]
+    fn log_readiness_survives_copy_truncate_rotation() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+        let original_home = std::env::var("HOME").ok();
+
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after UNIX_EPOCH")
+            .as_nanos();
+        let tmp_home = std::env::temp_dir().join(format!("clawden-process-latch-{unique}"));
+        fs::create_dir_all(&tmp_home).expect("failed to create temporary HOME dir");
+        std::env::set_var("HOME", &tmp_home);
+
+        let manager = ProcessManager::new(ExecutionMode::Direct).expect("process manager init");
+        let script = tmp_home.join("ready-runtime.sh");
+        write_executable(&script, "#!/usr/bin/env sh\necho ready\nexec sleep 30\n");
+        let info = manager
+            .start_direct(&DirectStartOptions::new("nanoclaw", &script))
+            .expect("runtime should start");
+        manager
+            .set_health_check(
+                "nanoclaw",
+                HealthCheck {
+                    log: Some("ready".to_string()),
+                    failure_threshold: Some(1),
+                    ..HealthCheck::default()
+                },
+            )
+            .expect("health check should attach");
+        assert!(manager
+            .wait_until_healthy("nanoclaw", Duration::from_secs(2))
+            .expect("probe"));
+
+        // Copy-truncate rotation empties the file under the running process.
+        fs::write(&info.log_path, "").expect("log should be truncated");
+        assert!(manager
+            .wait_until_healthy("nanoclaw", Duration::from_millis(100))
+            .expect("probe"));
+
+        let _ = manager.stop_with_timeout("nanoclaw", 1);
+        if let Some(home) = original_home {
+            std::env::set_var("HOME", home);
+        } else {
+            std::env::remove_var("HOME");
+        }
+        let _ = fs::remove_dir_all(tmp_home);
+    }
+
     fn write_executable(path: &Path, body: &str) {
         fs::write(path, body).expect("script should be written");
         let mut perms = fs::metadata(path)
@@ -784,4 +1954,35 @@ mod tests {
         perms.set_mode(0o755);
         fs::set_permissions(path, perms).expect("script should be executable");
     }
+
+    #[test]
+    fn docker_probe_requires_a_reachable_daemon() {
+        let _guard = env_lock().lock().expect("env lock poisoned");
+        let unique = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time should be after UNIX_EPOCH")
+            .as_nanos();
+        let bin = std::env::temp_dir().join(format!("clawden-docker-probe-{unique}"));
+        fs::create_dir_all(&bin).expect("create fake bin dir");
+        let docker = bin.join("docker");
+        let original_path = std::env::var("PATH").unwrap_or_default();
+        std::env::set_var("PATH", format!("{}:{original_path}", bin.display()));
+
+        // The CLI is installed but the daemon socket is down.
+        fs::write(
+            &docker,
+            "#!/bin/sh\n[ \"$1\" = --version ] && exit 0\necho 'Cannot connect to the Docker daemon' >&2\nexit 1\n",
+        )
+        .expect("write fake docker");
+        fs::set_permissions(&docker, fs::Permissions::from_mode(0o755)).expect("chmod");
+        let unreachable = docker_daemon_reachable();
+
+        fs::write(&docker, "#!/bin/sh\necho 27.0.1\n").expect("write fake docker");
+        let reachable = docker_daemon_reachable();
+
+        std::env::set_var("PATH", original_path);
+        let _ = fs::remove_dir_all(bin);
+        assert!(!unreachable, "a failing daemon must not count as available");
+        assert!(reachable, "a responding daemon counts as available");
+    }
 }
@@ -0,0 +1,121 @@
+use std::fs;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Socket state for a listening TCP socket in `/proc/net/tcp`.
+const TCP_LISTEN: &str = "0A";
+
+/// Signals every process in the group led by `pgid`. Returns `false` when no
+/// such group exists, e.g. for a runtime started before runtimes got their
+/// own process group.
+pub(crate) fn signal_group(pgid: u32, signal: &str) -> bool {
+    Command::new("kill")
+        .args([&format!("-{signal}"), "--", &format!("-{pgid}")])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+pub(crate) fn signal_pid(pid: u32, signal: &str) {
+    let _ = Command::new("kill")
+        .args([&format!("-{signal}"), &pid.to_string()])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+}
+
+/// Whether `pid` exists and has not exited. Zombies count as exited.
+pub(crate) fn pid_alive(pid: u32) -> bool {
+    let Ok(stat) = fs::read_to_string(format!("/proc/{pid}/stat")) else {
+        return false;
+    };
+    stat.rfind(") ")
+        .map(|end| !stat[end + 2..].starts_with('Z'))
+        .unwrap_or(false)
+}
+
+/// When `pid` started, in clock ticks since boot (field 22 of
+/// `/proc/<pid>/stat`). Together with the pid it names one process even
+/// after the pid is reused.
+pub(crate) fn process_start_ticks(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // Fields after the parenthesised command name, starting at field 3.
+    let end = stat.rfind(") ")?;
+    stat[end + 2..].split_whitespace().nth(19)?.parse().ok()
+}
+
+/// Whether `pid` is still the process recorded as starting at
+/// `start_ticks`. A mismatch means it exited and the pid was reused.
+/// Records without a start time, written before it was tracked, match.
+pub(crate) fn is_same_process(pid: u32, start_ticks: Option<u64>) -> bool {
+    start_ticks.is_none_or(|ticks| process_start_ticks(pid) == Some(ticks))
+}
+
+/// Short command name of `pid` (`/proc/<pid>/comm`).
+pub(crate) fn process_command(pid: u32) -> String {
+    fs::read_to_string(format!("/proc/{pid}/comm"))
+        .map(|comm| comm.trim().to_string())
+        .unwrap_or_else(|_| "?".to_string())
+}
+
+/// Whether any TCP socket, IPv4 or IPv6, is listening on `port`.
+pub fn port_is_listening(port: u16) -> bool {
+    ["/proc/net/tcp", "/proc/net/tcp6"].iter().any(|table| {
+        fs::read_to_string(table)
+            .map(|raw| listening_in_table(&raw, port))
+            .unwrap_or(false)
+    })
+}
+
+fn listening_in_table(raw: &str, port: u16) -> bool {
+    raw.lines().skip(1).any(|line| {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (Some(local), Some(state)) = (fields.get(1), fields.get(3)) else {
+            return false;
+        };
+        *state == TCP_LISTEN
+            && local
+                .rsplit_once(':')
+                .and_then(|(_, hex)| u16::from_str_radix(hex, 16).ok())
+                == Some(port)
+    })
+}
+
+/// Waits up to `timeout` for `port` to stop listening; `true` once it is free.
+pub(crate) fn wait_for_port_release(port: u16, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if !port_is_listening(port) {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{listening_in_table, port_is_listening};
+    use std::net::TcpListener;
+
+    #[test]
+    fn reads_listening_ports_from_proc_tables() {
+        let table = "  sl  local_address rem_address   st tx_queue rx_queue\n   \
+            0: 00000000:A7A1 00000000:0000 0A 00000000:00000000 00:00000000 00000000\n   \
+            1: 0100007F:1F90 0100007F:D431 01 00000000:00000000 00:00000000 00000000\n";
+        assert!(listening_in_table(table, 42913));
+        // 0x1F90 = 8080 is an established connection, not a listener.
+        assert!(!listening_in_table(table, 8080));
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind ephemeral port");
+        let port = listener.local_addr().expect("local addr").port();
+        assert!(port_is_listening(port));
+        drop(listener);
+        assert!(!port_is_listening(port));
+    }
+}
@@ -1,4 +1,6 @@
-use crate::ClawRuntime;
+use crate::custom_runtime::custom_runtime_descriptors;
+use crate::{instance_runtime, AgentMessage, ClawRuntime};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum InstallSource {
@@ -31,7 +33,8 @@ pub enum VersionSource {
     NotAvailable,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum ConfigFormat {
     Toml,
     Json,
@@ -39,12 +42,74 @@ pub enum ConfigFormat {
     None,
 }
 
+/// How a runtime is told which port to listen on. Every runtime also gets
+/// `CLAWDEN_PORT` and `<RUNTIME>_PORT` in its env.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortSetting {
+    /// Only the env vars.
+    Env,
+    /// A start flag followed by the port, e.g. `--port 18789`.
+    Flag(&'static str),
+    /// A dotted key in the generated config file, e.g. `gateway.port`.
+    ConfigKey(&'static str),
+    /// A dotted key holding a `127.0.0.1:<port>` listen address.
+    ListenAddr(&'static str),
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ConfigDirFlag {
     ConfigDir,
     ConfigFile { filename: &'static str },
 }
 
+/// Wire format of a runtime's local chat endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MessageApiFormat {
+    /// OpenAI-compatible `{"messages": [...]}` request answered with `choices[0].message.content`.
+    #[serde(rename = "openai-chat")]
+    OpenAiChat,
+    /// Plain `{"message": ...}` request answered with `{"response": ...}`.
+    Webhook,
+}
+
+/// Local HTTP endpoint that accepts a message and returns the agent's reply.
+/// Served on the runtime's `health_port`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MessageApi {
+    pub path: &'static str,
+    pub format: MessageApiFormat,
+}
+
+impl MessageApi {
+    pub fn request_body(&self, message: &AgentMessage) -> serde_json::Value {
+        match self.format {
+            MessageApiFormat::OpenAiChat => serde_json::json!({
+                "model": "default",
+                "stream": false,
+                "messages": [{ "role": message.role, "content": message.content }],
+            }),
+            MessageApiFormat::Webhook => serde_json::json!({
+                "message": message.content,
+                "role": message.role,
+            }),
+        }
+    }
+
+    /// Extracts the reply text from a response body, or `None` when the
+    /// payload does not match the expected shape.
+    pub fn parse_response(&self, body: &serde_json::Value) -> Option<String> {
+        let content = match self.format {
+            MessageApiFormat::OpenAiChat => body.pointer("/choices/0/message/content"),
+            MessageApiFormat::Webhook => body
+                .get("response")
+                .or_else(|| body.get("reply"))
+                .or_else(|| body.get("content")),
+        };
+        content.and_then(|v| v.as_str()).map(str::to_string)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct RuntimeDescriptor {
     pub runtime: ClawRuntime,
@@ -62,10 +127,14 @@ pub struct RuntimeDescriptor {
     pub config_dir_flag: ConfigDirFlag,
     pub has_onboard_command: bool,
     pub health_port: Option<u16>,
+    pub port_setting: PortSetting,
+    /// Docker image reference; `None` means `ghcr.io/codervisor/<slug>`.
+    pub image: Option<&'static str>,
     pub cost_tier: u8,
     pub required_config_defaults: &'static [(&'static str, &'static str, &'static str)],
     pub extra_env_vars: &'static [(&'static str, &'static str)],
     pub model_transform: Option<fn(provider: &str, model: &str) -> String>,
+    pub message_api: Option<MessageApi>,
 }
 
 impl RuntimeDescriptor {
@@ -73,6 +142,36 @@ impl RuntimeDescriptor {
         self.health_port
             .map(|port| format!("http://127.0.0.1:{port}/health"))
     }
+
+    pub fn message_url(&self) -> Option<String> {
+        let api = self.message_api?;
+        self.health_port
+            .map(|port| format!("http://127.0.0.1:{port}{}", api.path))
+    }
+
+    /// Docker image to run, with `tag` in place of the image's own tag.
+    /// Images pinned by digest are returned as they are.
+    pub fn image_ref(&self, tag: Option<&str>) -> String {
+        let Some(image) = self.image else {
+            return format!(
+                "ghcr.io/codervisor/{}:{}",
+                self.slug,
+                tag.unwrap_or("latest")
+            );
+        };
+        let Some(tag) = tag else {
+            return image.to_string();
+        };
+        if image.contains('@') {
+            return image.to_string();
+        }
+        let name_start = image.rfind('/').map_or(0, |slash| slash + 1);
+        let repository = match image[name_start..].rfind(':') {
+            Some(colon) => &image[..name_start + colon],
+            None => image,
+        };
+        format!("{repository}:{tag}")
+    }
 }
 
 const ZEROCLAW_HINTS: &[(&str, &str)] = &[
@@ -110,7 +209,7 @@ fn openclaw_model_transform(provider: &str, model: &str) -> String {
     }
 }
 
-static DESCRIPTORS: &[RuntimeDescriptor] = &[
+pub(crate) static DESCRIPTORS: &[RuntimeDescriptor] = &[
     RuntimeDescriptor {
         runtime: ClawRuntime::OpenClaw,
         slug: "openclaw",
@@ -131,10 +230,16 @@ static DESCRIPTORS: &[RuntimeDescriptor] = &[
         config_dir_flag: ConfigDirFlag::ConfigDir,
         has_onboard_command: false,
         health_port: Some(18789),
+        port_setting: PortSetting::Flag("--port"),
+        image: None,
         cost_tier: 3,
         required_config_defaults: &[],
         extra_env_vars: &[("OPENCLAW_CONFIG_PATH", "Path to OpenClaw config file")],
         model_transform: Some(openclaw_model_transform),
+        message_api: Some(MessageApi {
+            path: "/v1/chat/completions",
+            format: MessageApiFormat::OpenAiChat,
+        }),
     },
     RuntimeDescriptor {
         runtime: ClawRuntime::ZeroClaw,
@@ -159,10 +264,16 @@ static DESCRIPTORS: &[RuntimeDescriptor] = &[
         config_dir_flag: ConfigDirFlag::ConfigDir,
         has_onboard_command: true,
         health_port: Some(42617),
+        port_setting: PortSetting::ConfigKey("gateway.port"),
+        image: None,
         cost_tier: 2,
         required_config_defaults: &[("channels_config", "cli", "true")],
         extra_env_vars: &[],
         model_transform: None,
+        message_api: Some(MessageApi {
+            path: "/webhook",
+            format: MessageApiFormat::Webhook,
+        }),
     },
     RuntimeDescriptor {
         runtime: ClawRuntime::PicoClaw,
@@ -187,10 +298,16 @@ static DESCRIPTORS: &[RuntimeDescriptor] = &[
         config_dir_flag: ConfigDirFlag::ConfigDir,
         has_onboard_command: false,
         health_port: Some(8080),
+        port_setting: PortSetting::ConfigKey("gateway.port"),
+        image: None,
         cost_tier: 1,
         required_config_defaults: &[],
         extra_env_vars: &[],
         model_transform: None,
+        message_api: Some(MessageApi {
+            path: "/api/chat",
+            format: MessageApiFormat::Webhook,
+        }),
     },
     RuntimeDescriptor {
         runtime: ClawRuntime::NanoClaw,
@@ -212,10 +329,13 @@ static DESCRIPTORS: &[RuntimeDescriptor] = &[
         config_dir_flag: ConfigDirFlag::ConfigDir,
         has_onboard_command: false,
         health_port: None,
+        port_setting: PortSetting::Env,
+        image: None,
         cost_tier: 2,
         required_config_defaults: &[],
         extra_env_vars: &[],
         model_transform: None,
+        message_api: None,
     },
     RuntimeDescriptor {
         runtime: ClawRuntime::IronClaw,
@@ -233,10 +353,13 @@ static DESCRIPTORS: &[RuntimeDescriptor] = &[
         config_dir_flag: ConfigDirFlag::ConfigDir,
         has_onboard_command: false,
         health_port: None,
+        port_setting: PortSetting::Env,
+        image: None,
         cost_tier: 3,
         required_config_defaults: &[],
         extra_env_vars: &[],
         model_transform: None,
+        message_api: None,
     },
     RuntimeDescriptor {
         runtime: ClawRuntime::NullClaw,
@@ -254,10 +377,13 @@ static DESCRIPTORS: &[RuntimeDescriptor] = &[
         config_dir_flag: ConfigDirFlag::ConfigDir,
         has_onboard_command: false,
         health_port: Some(3000),
+        port_setting: PortSetting::ConfigKey("gateway.port"),
+        image: None,
         cost_tier: 1,
         required_config_defaults: &[],
         extra_env_vars: &[],
         model_transform: None,
+        message_api: None,
     },
     RuntimeDescriptor {
         runtime: ClawRuntime::MicroClaw,
@@ -275,10 +401,13 @@ static DESCRIPTORS: &[RuntimeDescriptor] = &[
         config_dir_flag: ConfigDirFlag::ConfigDir,
         has_onboard_command: false,
         health_port: None,
+        port_setting: PortSetting::Env,
+        image: None,
         cost_tier: 1,
         required_config_defaults: &[],
         extra_env_vars: &[],
         model_transform: None,
+        message_api: None,
     },
     RuntimeDescriptor {
         runtime: ClawRuntime::MimiClaw,
@@ -296,10 +425,13 @@ static DESCRIPTORS: &[RuntimeDescriptor] = &[
         config_dir_flag: ConfigDirFlag::ConfigDir,
         has_onboard_command: false,
         health_port: None,
+        port_setting: PortSetting::Env,
+        image: None,
         cost_tier: 2,
         required_config_defaults: &[],
         extra_env_vars: &[],
         model_transform: None,
+        message_api: None,
     },
     RuntimeDescriptor {
         runtime: ClawRuntime::OpenFang,
@@ -326,34 +458,34 @@ static DESCRIPTORS: &[RuntimeDescriptor] = &[
         },
         has_onboard_command: false,
         health_port: Some(50051),
+        port_setting: PortSetting::ListenAddr("api_listen"),
+        image: None,
         cost_tier: 2,
         required_config_defaults: &[],
         extra_env_vars: &[],
         model_transform: None,
+        message_api: None,
     },
 ];
 
-pub fn runtime_descriptors() -> &'static [RuntimeDescriptor] {
-    DESCRIPTORS
+/// Built-in runtimes followed by registered custom runtimes.
+pub fn runtime_descriptors() -> impl Iterator<Item = &'static RuntimeDescriptor> {
+    DESCRIPTORS.iter().chain(custom_runtime_descriptors())
 }
 
+/// Looks up a runtime by slug or alias. Instance names (`zeroclaw@work`)
+/// resolve to their runtime.
 pub fn runtime_descriptor(runtime: &str) -> Option<&'static RuntimeDescriptor> {
-    let lower = runtime.to_ascii_lowercase();
-    DESCRIPTORS
-        .iter()
-        .find(|d| d.slug == lower || d.aliases.iter().any(|a| *a == lower))
+    let lower = instance_runtime(runtime).to_ascii_lowercase();
+    runtime_descriptors().find(|d| d.slug == lower || d.aliases.iter().any(|a| *a == lower))
 }
 
 pub fn runtime_descriptor_for(runtime: &ClawRuntime) -> Option<&'static RuntimeDescriptor> {
-    DESCRIPTORS
-        .iter()
-        .find(|descriptor| descriptor.runtime == *runtime)
+    runtime_descriptors().find(|descriptor| descriptor.runtime == *runtime)
 }
 
 pub fn direct_install_descriptors() -> impl Iterator<Item = &'static RuntimeDescriptor> {
-    DESCRIPTORS
-        .iter()
-        .filter(|descriptor| descriptor.direct_install_supported)
+    runtime_descriptors().filter(|descriptor| descriptor.direct_install_supported)
 }
 
 // --- ClawRuntime impls driven by descriptor data ---
@@ -362,7 +494,10 @@ impl std::fmt::Display for ClawRuntime {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match runtime_descriptor_for(self) {
             Some(d) => f.write_str(d.display_name),
-            None => write!(f, "{self:?}"),
+            None => match self {
+                ClawRuntime::Custom(name) => f.write_str(name),
+                _ => write!(f, "{self:?}"),
+            },
         }
     }
 }
@@ -379,12 +514,41 @@ impl ClawRuntime {
     }
 }
 
+/// Accepts the serialized built-in names (`zero-claw`), built-in slugs and
+/// the slugs of registered custom runtimes. Anything else is an error
+/// rather than a `Custom` runtime nobody declared.
+impl<'de> Deserialize<'de> for ClawRuntime {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        let builtin = match name.as_str() {
+            "open-claw" => Some(ClawRuntime::OpenClaw),
+            "zero-claw" => Some(ClawRuntime::ZeroClaw),
+            "pico-claw" => Some(ClawRuntime::PicoClaw),
+            "nano-claw" => Some(ClawRuntime::NanoClaw),
+            "iron-claw" => Some(ClawRuntime::IronClaw),
+            "null-claw" => Some(ClawRuntime::NullClaw),
+            "micro-claw" => Some(ClawRuntime::MicroClaw),
+            "mimi-claw" => Some(ClawRuntime::MimiClaw),
+            "open-fang" => Some(ClawRuntime::OpenFang),
+            _ => None,
+        };
+        builtin
+            .or_else(|| {
+                runtime_descriptors()
+                    .find(|descriptor| descriptor.slug == name)
+                    .map(|descriptor| descriptor.runtime.clone())
+            })
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown runtime '{name}'")))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         direct_install_descriptors, runtime_descriptor, runtime_descriptors, ConfigDirFlag,
+        MessageApiFormat,
     };
-    use crate::ClawRuntime;
+    use crate::{AgentMessage, ClawRuntime};
 
     #[test]
     fn descriptor_lookup_accepts_aliases() {
@@ -395,6 +559,7 @@ mod tests {
     #[test]
     fn direct_install_runtime_set_is_descriptor_driven() {
         let slugs: Vec<_> = direct_install_descriptors()
+            .filter(|descriptor| !matches!(descriptor.runtime, ClawRuntime::Custom(_)))
             .map(|descriptor| descriptor.slug)
             .collect();
         assert_eq!(
@@ -446,4 +611,46 @@ mod tests {
         assert_eq!(zeroclaw.workspace_path, Some("~/.zeroclaw/workspace"));
         assert_eq!(picoclaw.workspace_path, None);
     }
+
+    #[test]
+    fn message_api_urls_follow_runtime_ports() {
+        let url = |slug: &str| runtime_descriptor(slug).and_then(|d| d.message_url());
+        assert_eq!(
+            url("openclaw").as_deref(),
+            Some("http://127.0.0.1:18789/v1/chat/completions")
+        );
+        assert_eq!(
+            url("zeroclaw").as_deref(),
+            Some("http://127.0.0.1:42617/webhook")
+        );
+        assert_eq!(
+            url("picoclaw").as_deref(),
+            Some("http://127.0.0.1:8080/api/chat")
+        );
+        assert_eq!(url("nanoclaw"), None);
+    }
+
+    #[test]
+    fn message_api_formats_roundtrip() {
+        let message = AgentMessage {
+            role: "user".to_string(),
+            content: "hi".to_string(),
+        };
+        let openclaw = runtime_descriptor("openclaw")
+            .and_then(|d| d.message_api)
+            .expect("openclaw message api");
+        assert_eq!(openclaw.format, MessageApiFormat::OpenAiChat);
+        let body = openclaw.request_body(&message);
+        assert_eq!(body["messages"][0]["content"], "hi");
+        let reply = serde_json::json!({"choices": [{"message": {"content": "hello"}}]});
+        assert_eq!(openclaw.parse_response(&reply).as_deref(), Some("hello"));
+
+        let zeroclaw = runtime_descriptor("zeroclaw")
+            .and_then(|d| d.message_api)
+            .expect("zeroclaw message api");
+        assert_eq!(zeroclaw.request_body(&message)["message"], "hi");
+        let reply = serde_json::json!({"response": "pong"});
+        assert_eq!(zeroclaw.parse_response(&reply).as_deref(), Some("pong"));
+        assert_eq!(zeroclaw.parse_response(&serde_json::json!({})), None);
+    }
 }
@@ -0,0 +1,307 @@
+use anyhow::{anyhow, bail, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{current_unix_ms, runtime_descriptor_for, ClawRuntime, Skill, SkillManifest};
+
+/// File name of the manifest at the root of every skill package.
+pub const SKILL_MANIFEST_FILE: &str = "skill.toml";
+
+/// On-disk manifest shape. Runtimes are accepted by slug or alias and
+/// resolved through the runtime descriptors.
+#[derive(Debug, Deserialize)]
+struct SkillManifestFile {
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: Option<String>,
+    runtimes: Vec<String>,
+}
+
+/// Parses and validates a `skill.toml` body.
+pub fn parse_skill_manifest(raw: &str) -> Result<SkillManifest> {
+    let file: SkillManifestFile =
+        toml::from_str(raw).context("invalid skill manifest (skill.toml)")?;
+    validate_skill_name(&file.name)?;
+    semver::Version::parse(&file.version).with_context(|| {
+        format!(
+            "skill '{}' has invalid version '{}'",
+            file.name, file.version
+        )
+    })?;
+    if file.runtimes.is_empty() {
+        bail!("skill '{}' must list at least one runtime", file.name);
+    }
+
+    let mut runtimes = Vec::new();
+    for slug in &file.runtimes {
+        let runtime = ClawRuntime::from_str_loose(slug)
+            .ok_or_else(|| anyhow!("skill '{}' lists unknown runtime '{slug}'", file.name))?;
+        if !runtimes.contains(&runtime) {
+            runtimes.push(runtime);
+        }
+    }
+
+    Ok(SkillManifest {
+        name: file.name,
+        version: file.version,
+        description: file.description,
+        runtimes,
+        source: None,
+    })
+}
+
+/// Rejects names that could escape the skills directory (`..`, `/`).
+pub fn validate_skill_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        && !name.starts_with('.');
+    if !valid {
+        bail!("invalid skill name '{name}' (use letters, digits, '-', '_' or '.')");
+    }
+    Ok(())
+}
+
+impl SkillManifest {
+    pub fn supports(&self, runtime: &ClawRuntime) -> bool {
+        self.runtimes.contains(runtime)
+    }
+
+    /// Fails unless `runtime` is in the manifest's compatibility list.
+    pub fn ensure_supports(&self, runtime: &ClawRuntime) -> Result<()> {
+        if self.supports(runtime) {
+            return Ok(());
+        }
+        bail!(
+            "skill '{}' does not support {} (supported: {})",
+            self.name,
+            runtime.as_slug(),
+            self.runtimes
+                .iter()
+                .map(|r| r.as_slug())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    pub fn to_skill(&self) -> Skill {
+        Skill {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            description: self.description.clone(),
+        }
+    }
+}
+
+/// Loads a skill package directory: `skill.toml` plus the files shipped
+/// alongside it. The returned manifest's `source` points at the directory.
+pub fn load_skill_package(dir: &Path) -> Result<SkillManifest> {
+    let manifest_path = dir.join(SKILL_MANIFEST_FILE);
+    let raw = fs::read_to_string(&manifest_path)
+        .with_context(|| format!("failed to read {}", manifest_path.display()))?;
+    let mut manifest = parse_skill_manifest(&raw)?;
+    manifest.source = Some(dir.to_path_buf());
+    Ok(manifest)
+}
+
+/// Relative skills directory inside a runtime workspace, e.g.
+/// `~/.zeroclaw/workspace/skills`. `None` when the runtime has no workspace.
+pub fn runtime_skills_path(runtime: &ClawRuntime) -> Option<String> {
+    runtime_descriptor_for(runtime)
+        .and_then(|d| d.workspace_path)
+        .map(|workspace| format!("{}/skills", workspace.trim_end_matches('/')))
+}
+
+/// The installed skills of one runtime workspace, one sub-directory per skill.
+#[derive(Debug, Clone)]
+pub struct SkillDirectory {
+    root: PathBuf,
+}
+
+impl SkillDirectory {
+    /// Host skills directory for a runtime, resolved against `$HOME`.
+    pub fn for_runtime(runtime: &ClawRuntime) -> Result<Self> {
+        let path = runtime_skills_path(runtime).ok_or_else(|| {
+            anyhow!(
+                "{} has no workspace directory for skills",
+                runtime.as_slug()
+            )
+        })?;
+        let root = match path.strip_prefix("~/") {
+            Some(rest) => {
+                let home = std::env::var("HOME").context("HOME environment variable is not set")?;
+                PathBuf::from(home).join(rest)
+            }
+            None => PathBuf::from(path),
+        };
+        Ok(Self { root })
+    }
+
+    pub fn at(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Copies a loaded package into `<root>/<name>`, replacing any previous
+    /// version. The copy is staged next to the target and renamed into place.
+    pub fn install(&self, manifest: &SkillManifest) -> Result<Skill> {
+        validate_skill_name(&manifest.name)?;
+        let source = manifest
+            .source
+            .as_deref()
+            .ok_or_else(|| anyhow!("skill '{}' has no package directory", manifest.name))?;
+        fs::create_dir_all(&self.root)
+            .with_context(|| format!("failed to create {}", self.root.display()))?;
+
+        let target = self.root.join(&manifest.name);
+        let staging = self
+            .root
+            .join(format!(".{}.tmp-{}", manifest.name, current_unix_ms()));
+        copy_dir(source, &staging)?;
+        if target.exists() {
+            fs::remove_dir_all(&target)
+                .with_context(|| format!("failed to replace {}", target.display()))?;
+        }
+        fs::rename(&staging, &target)
+            .with_context(|| format!("failed to install skill into {}", target.display()))?;
+        Ok(manifest.to_skill())
+    }
+
+    pub fn list(&self) -> Result<Vec<Skill>> {
+        let mut skills = Vec::new();
+        if !self.root.exists() {
+            return Ok(skills);
+        }
+        for entry in fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            let manifest_path = path.join(SKILL_MANIFEST_FILE);
+            if !path.is_dir() || !manifest_path.exists() {
+                continue;
+            }
+            // Hand-edited or foreign directories without a valid manifest
+            // are not ClawDen-managed skills; leave them out of the listing.
+            let raw = fs::read_to_string(&manifest_path)?;
+            if let Ok(manifest) = parse_skill_manifest(&raw) {
+                skills.push(manifest.to_skill());
+            }
+        }
+        skills.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(skills)
+    }
+
+    /// Removes an installed skill. Returns `false` when it was not installed.
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        validate_skill_name(name)?;
+        let target = self.root.join(name);
+        if !target.exists() {
+            return Ok(false);
+        }
+        fs::remove_dir_all(&target)
+            .with_context(|| format!("failed to remove {}", target.display()))?;
+        Ok(true)
+    }
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<()> {
+    fs::create_dir_all(to).with_context(|| format!("failed to create {}", to.display()))?;
+    for entry in fs::read_dir(from).with_context(|| format!("failed to read {}", from.display()))? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let target = to.join(entry.file_name());
+        if file_type.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else if file_type.is_file() {
+            fs::copy(entry.path(), &target)
+                .with_context(|| format!("failed to copy {}", entry.path().display()))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_skill_package, parse_skill_manifest, SkillDirectory};
+    use crate::ClawRuntime;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("clawden-skills-{name}-{stamp}"));
+        fs::create_dir_all(&dir).expect("temp dir should be created");
+        dir
+    }
+
+    #[test]
+    fn manifest_resolves_runtime_aliases_and_validates_fields() {
+        let manifest = parse_skill_manifest(
+            "name = \"web-digest\"\nversion = \"0.2.0\"\nruntimes = [\"zero\", \"openclaw\"]\n",
+        )
+        .expect("manifest should parse");
+        assert_eq!(
+            manifest.runtimes,
+            vec![ClawRuntime::ZeroClaw, ClawRuntime::OpenClaw]
+        );
+        assert!(manifest.ensure_supports(&ClawRuntime::ZeroClaw).is_ok());
+        let err = manifest
+            .ensure_supports(&ClawRuntime::PicoClaw)
+            .expect_err("picoclaw is not listed");
+        assert!(err.to_string().contains("supported: zeroclaw, openclaw"));
+
+        assert!(
+            parse_skill_manifest("name = \"x\"\nversion = \"1\"\nruntimes = [\"zeroclaw\"]\n")
+                .is_err()
+        );
+        assert!(
+            parse_skill_manifest("name = \"x\"\nversion = \"1.0.0\"\nruntimes = []\n").is_err()
+        );
+        assert!(parse_skill_manifest(
+            "name = \"../x\"\nversion = \"1.0.0\"\nruntimes = [\"zeroclaw\"]\n"
+        )
+        .is_err());
+        assert!(
+            parse_skill_manifest("name = \"x\"\nversion = \"1.0.0\"\nruntimes = [\"nope\"]\n")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn install_list_and_remove_roundtrip() {
+        let root = temp_dir("roundtrip");
+        let package = root.join("package");
+        fs::create_dir_all(package.join("prompts")).expect("package dir");
+        fs::write(
+            package.join("skill.toml"),
+            "name = \"web-digest\"\nversion = \"0.1.0\"\ndescription = \"Daily digest\"\nruntimes = [\"zeroclaw\"]\n",
+        )
+        .expect("manifest");
+        fs::write(package.join("prompts/main.md"), "Summarize").expect("prompt");
+
+        let dir = SkillDirectory::at(root.join("skills"));
+        let manifest = load_skill_package(&package).expect("package should load");
+        dir.install(&manifest).expect("first install");
+        dir.install(&manifest).expect("reinstall replaces");
+
+        assert_eq!(
+            fs::read_to_string(dir.root().join("web-digest/prompts/main.md")).expect("copied"),
+            "Summarize"
+        );
+        let skills = dir.list().expect("list");
+        assert_eq!(skills.len(), 1);
+        assert_eq!(skills[0].description.as_deref(), Some("Daily digest"));
+
+        assert!(dir.remove("web-digest").expect("remove"));
+        assert!(!dir.remove("web-digest").expect("second remove"));
+        assert!(dir.list().expect("list").is_empty());
+        let _ = fs::remove_dir_all(root);
+    }
+}
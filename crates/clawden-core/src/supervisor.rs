@@ -0,0 +1,618 @@
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::os::unix::process::{CommandExt, ExitStatusExt};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::log_rotation::ActiveLog;
+use crate::process::tee_reader_to_log;
+use crate::process_group::{is_same_process, process_start_ticks, signal_group};
+use crate::{current_unix_ms, LogRotation, Redactor};
+
+/// Signal numbers that mean "someone asked the runtime to stop".
+const SIGINT: i32 = 2;
+const SIGTERM: i32 = 15;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const READER_DRAIN_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RestartPolicy {
+    No,
+    Always,
+    OnFailure,
+    /// Like `always`, except a runtime terminated with SIGTERM/SIGINT by
+    /// someone other than the supervisor stays down.
+    UnlessStopped,
+}
+
+/// A parsed `--restart=` value: `no`, `always`, `unless-stopped`, or
+/// `on-failure[:max-restarts]` (Docker's syntax).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestartSpec {
+    pub policy: RestartPolicy,
+    pub max_restarts: Option<u32>,
+}
+
+impl RestartSpec {
+    pub fn parse(value: &str) -> Result<Self> {
+        let value = value.trim();
+        let (name, max) = match value.split_once(':') {
+            Some((name, max)) => (name, Some(max)),
+            None => (value, None),
+        };
+        let policy = match name {
+            "no" => RestartPolicy::No,
+            "always" => RestartPolicy::Always,
+            "on-failure" => RestartPolicy::OnFailure,
+            "unless-stopped" => RestartPolicy::UnlessStopped,
+            other => bail!(
+                "unknown restart policy '{other}' (expected no, always, on-failure[:max] or unless-stopped)"
+            ),
+        };
+        let max_restarts = match max {
+            Some(_) if policy != RestartPolicy::OnFailure => {
+                bail!("a maximum restart count is only supported with on-failure")
+            }
+            Some(max) => Some(
+                max.parse::<u32>()
+                    .with_context(|| format!("invalid maximum restart count '{max}'"))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            policy,
+            max_restarts,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.policy != RestartPolicy::No
+    }
+
+    /// Whether an exit with `status` should lead to another launch.
+    pub fn should_restart(&self, status: &ExitStatus) -> bool {
+        match self.policy {
+            RestartPolicy::No => false,
+            RestartPolicy::Always => true,
+            RestartPolicy::OnFailure => !status.success(),
+            RestartPolicy::UnlessStopped => {
+                !matches!(status.signal(), Some(SIGTERM) | Some(SIGINT))
+            }
+        }
+    }
+}
+
+/// Backoff and crash-loop thresholds for a supervised runtime.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorTuning {
+    pub initial_backoff_ms: u64,
+    pub max_backoff_ms: u64,
+    /// A run that lasts this long resets the backoff to its initial value.
+    pub stable_after_ms: u64,
+    /// This many restarts inside `crash_loop_window_ms` is a crash loop,
+    /// after which the supervisor gives up.
+    pub crash_loop_restarts: usize,
+    pub crash_loop_window_ms: u64,
+    /// How long a runtime gets to exit after SIGTERM before it is killed.
+    pub stop_grace_ms: u64,
+}
+
+impl Default for SupervisorTuning {
+    fn default() -> Self {
+        Self {
+            initial_backoff_ms: 1_000,
+            max_backoff_ms: 30_000,
+            stable_after_ms: 60_000,
+            crash_loop_restarts: 5,
+            crash_loop_window_ms: 60_000,
+            stop_grace_ms: 2_000,
+        }
+    }
+}
+
+/// Everything `clawden supervise` needs to run a runtime. Written next to the
+/// pid file; the runtime's environment is inherited rather than stored here
+/// so credentials never land on disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SupervisorSpec {
+    pub runtime: String,
+    pub executable: PathBuf,
+    pub args: Vec<String>,
+    pub log_path: PathBuf,
+    pub audit_path: PathBuf,
+    pub state_path: PathBuf,
+    pub restart: RestartSpec,
+    #[serde(default)]
+    pub tuning: SupervisorTuning,
+    #[serde(default)]
+    pub log_rotation: LogRotation,
+    /// Names of the injected env vars whose values are scrubbed from the log.
+    /// The values themselves come from the supervisor's own environment.
+    #[serde(default)]
+    pub redact_env: Vec<String>,
+}
+
+impl SupervisorSpec {
+    pub fn load(path: &Path) -> Result<Self> {
+        let raw = fs::read_to_string(path)
+            .with_context(|| format!("failed to read supervisor spec {}", path.display()))?;
+        serde_json::from_str(&raw)
+            .with_context(|| format!("invalid supervisor spec {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("failed to write supervisor spec {}", path.display()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SupervisorStatus {
+    #[default]
+    Starting,
+    Running,
+    BackingOff,
+    /// The runtime exited and the policy did not ask for a restart.
+    Exited,
+    /// `on-failure:<max>` ran out of restarts.
+    RestartLimit,
+    CrashLoop,
+    Stopped,
+}
+
+impl SupervisorStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Starting => "starting",
+            Self::Running => "running",
+            Self::BackingOff => "backing-off",
+            Self::Exited => "exited",
+            Self::RestartLimit => "restart-limit",
+            Self::CrashLoop => "crash-loop",
+            Self::Stopped => "stopped",
+        }
+    }
+}
+
+/// Live supervisor bookkeeping, rewritten after every state change so `ps`
+/// can report restarts and exit codes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SupervisorState {
+    pub status: SupervisorStatus,
+    pub child_pid: Option<u32>,
+    /// Start time of `child_pid` in clock ticks since boot, so a reused pid
+    /// is not mistaken for the child.
+    #[serde(default)]
+    pub child_start_ticks: Option<u64>,
+    pub restarts: u32,
+    pub last_exit_code: Option<i32>,
+    pub last_exit_signal: Option<i32>,
+    pub updated_at_unix_ms: u64,
+}
+
+impl SupervisorState {
+    pub fn load(path: &Path) -> Option<Self> {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+    }
+
+    /// The runtime child, unless it has exited and its pid was reused.
+    pub fn live_child_pid(&self) -> Option<u32> {
+        self.child_pid
+            .filter(|pid| is_same_process(*pid, self.child_start_ticks))
+    }
+
+    fn save(&mut self, path: &Path) -> Result<()> {
+        self.updated_at_unix_ms = current_unix_ms();
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("failed to write supervisor state {}", path.display()))
+    }
+}
+
+/// Runs `spec.executable` until it exits for good or `shutdown` is set,
+/// relaunching it according to the restart policy. Blocks the caller.
+pub fn run_supervisor(spec: &SupervisorSpec, shutdown: &AtomicBool) -> Result<SupervisorState> {
+    let tuning = &spec.tuning;
+    let mut state = SupervisorState::default();
+    let mut backoff_ms = tuning.initial_backoff_ms;
+    let mut recent_restarts: VecDeque<Instant> = VecDeque::new();
+    let log = Arc::new(ActiveLog::new(
+        spec.log_rotation.clone(),
+        spec.log_path.clone(),
+    ));
+    let redactor = Arc::new(
+        Redactor::from_env_names(&spec.redact_env)
+            .with_audit(spec.audit_path.clone(), &spec.runtime),
+    );
+
+    loop {
+        let started = Instant::now();
+        let (mut child, readers) = spawn_child(spec, &log, &redactor)?;
+        state.status = SupervisorStatus::Running;
+        state.child_pid = Some(child.id());
+        state.child_start_ticks = process_start_ticks(child.id());
+        state.save(&spec.state_path)?;
+
+        let status = match wait_child(&mut child, &log, shutdown)? {
+            Some(status) => status,
+            None => {
+                terminate_child(&mut child, tuning.stop_grace_ms);
+                finish_readers(readers);
+                state.status = SupervisorStatus::Stopped;
+                state.child_pid = None;
+                state.child_start_ticks = None;
+                state.save(&spec.state_path)?;
+                return Ok(state);
+            }
+        };
+
+        let uptime = started.elapsed();
+        // Children of the exited runtime would keep its ports and block the
+        // next run.
+        signal_group(child.id(), "KILL");
+        finish_readers(readers);
+        state.child_pid = None;
+        state.child_start_ticks = None;
+        state.last_exit_code = status.code();
+        state.last_exit_signal = status.signal();
+        if !status.success() {
+            append_event(
+                &spec.audit_path,
+                "runtime.crash",
+                &spec.runtime,
+                &format!(
+                    "exit_code={} signal={} uptime_ms={} restarts={}",
+                    describe(status.code()),
+                    describe(status.signal()),
+                    uptime.as_millis(),
+                    state.restarts
+                ),
+            );
+        }
+
+        if !spec.restart.should_restart(&status) {
+            state.status = SupervisorStatus::Exited;
+            state.save(&spec.state_path)?;
+            return Ok(state);
+        }
+        if spec
+            .restart
+            .max_restarts
+            .is_some_and(|max| state.restarts >= max)
+        {
+            append_event(
+                &spec.audit_path,
+                "runtime.restart_limit",
+                &spec.runtime,
+                &format!("restarts={}", state.restarts),
+            );
+            state.status = SupervisorStatus::RestartLimit;
+            state.save(&spec.state_path)?;
+            return Ok(state);
+        }
+
+        let now = Instant::now();
+        let window = Duration::from_millis(tuning.crash_loop_window_ms);
+        recent_restarts.retain(|at| now.duration_since(*at) < window);
+        if recent_restarts.len() >= tuning.crash_loop_restarts {
+            append_event(
+                &spec.audit_path,
+                "runtime.crash_loop",
+                &spec.runtime,
+                &format!(
+                    "restarts_in_window={} window_ms={}",
+                    recent_restarts.len(),
+                    tuning.crash_loop_window_ms
+                ),
+            );
+            state.status = SupervisorStatus::CrashLoop;
+            state.save(&spec.state_path)?;
+            return Ok(state);
+        }
+
+        if uptime >= Duration::from_millis(tuning.stable_after_ms) {
+            backoff_ms = tuning.initial_backoff_ms;
+        }
+        state.status = SupervisorStatus::BackingOff;
+        state.save(&spec.state_path)?;
+        append_event(
+            &spec.audit_path,
+            "runtime.restart",
+            &spec.runtime,
+            &format!("attempt={} backoff_ms={backoff_ms}", state.restarts + 1),
+        );
+        if !sleep_unless_shutdown(Duration::from_millis(backoff_ms), shutdown) {
+            state.status = SupervisorStatus::Stopped;
+            state.save(&spec.state_path)?;
+            return Ok(state);
+        }
+        backoff_ms = backoff_ms.saturating_mul(2).min(tuning.max_backoff_ms);
+        recent_restarts.push_back(Instant::now());
+        state.restarts += 1;
+        // Keep the crashed run's output as the previous session.
+        log.rotate_now()?;
+    }
+}
+
+/// Starts the runtime with its output piped through the redactor into the log.
+fn spawn_child(
+    spec: &SupervisorSpec,
+    log: &Arc<ActiveLog>,
+    redactor: &Arc<Redactor>,
+) -> Result<(Child, Vec<JoinHandle<()>>)> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&spec.log_path)
+        .with_context(|| format!("opening runtime log file {}", spec.log_path.display()))?;
+    let file = Arc::new(Mutex::new(file));
+    let mut child = Command::new(&spec.executable)
+        .args(&spec.args)
+        .process_group(0)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("failed to spawn {}", spec.executable.display()))?;
+
+    let mut readers = Vec::new();
+    if let Some(out) = child.stdout.take() {
+        readers.push(tee_reader_to_log(
+            out,
+            Arc::clone(&file),
+            None,
+            Some(Arc::clone(log)),
+            Some(Arc::clone(redactor)),
+        ));
+    }
+    if let Some(err) = child.stderr.take() {
+        readers.push(tee_reader_to_log(
+            err,
+            file,
+            None,
+            Some(Arc::clone(log)),
+            Some(Arc::clone(redactor)),
+        ));
+    }
+    Ok((child, readers))
+}
+
+/// Gives the log readers a moment to write the exited runtime's last lines,
+/// so they land in its session rather than the next one. A grandchild still
+/// holding the pipes open is not waited for.
+fn finish_readers(readers: Vec<JoinHandle<()>>) {
+    let deadline = Instant::now() + READER_DRAIN_TIMEOUT;
+    while readers.iter().any(|reader| !reader.is_finished()) && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(10));
+    }
+    for reader in readers.into_iter().filter(JoinHandle::is_finished) {
+        let _ = reader.join();
+    }
+}
+
+/// Waits for the child to exit; `None` means shutdown was requested first.
+fn wait_child(
+    child: &mut Child,
+    log: &ActiveLog,
+    shutdown: &AtomicBool,
+) -> Result<Option<ExitStatus>> {
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        log.rotate_if_due();
+        if shutdown.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Stops the runtime and its process group, killing the group once
+/// `grace_ms` has passed.
+fn terminate_child(child: &mut Child, grace_ms: u64) {
+    let pgid = child.id();
+    signal_group(pgid, "TERM");
+    let deadline = Instant::now() + Duration::from_millis(grace_ms);
+    while Instant::now() < deadline {
+        if matches!(child.try_wait(), Ok(Some(_))) {
+            return;
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    signal_group(pgid, "KILL");
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Sleeps for `duration`; returns `false` if shutdown was requested meanwhile.
+fn sleep_unless_shutdown(duration: Duration, shutdown: &AtomicBool) -> bool {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        if shutdown.load(Ordering::Relaxed) {
+            return false;
+        }
+        thread::sleep(POLL_INTERVAL.min(deadline.saturating_duration_since(Instant::now())));
+    }
+    !shutdown.load(Ordering::Relaxed)
+}
+
+pub(crate) fn append_event(audit_path: &Path, action: &str, runtime: &str, outcome: &str) {
+    let line = format!("{}\t{action}\t{runtime}\t{outcome}\n", current_unix_ms());
+    if let Ok(mut file) = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(audit_path)
+    {
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+fn describe(value: Option<i32>) -> String {
+    value.map_or_else(|| "-".to_string(), |v| v.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        run_supervisor, RestartPolicy, RestartSpec, SupervisorSpec, SupervisorStatus,
+        SupervisorTuning,
+    };
+    use crate::{read_log_session, LogRotation};
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::{Path, PathBuf};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("clock should be after unix epoch")
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("clawden-supervisor-{name}-{stamp}"));
+        fs::create_dir_all(&path).expect("temp dir should be created");
+        path
+    }
+
+    fn spec(dir: &Path, script: &str, restart: &str) -> SupervisorSpec {
+        let executable = dir.join("runtime.sh");
+        fs::write(&executable, script).expect("script should be written");
+        let mut perms = fs::metadata(&executable)
+            .expect("metadata should be available")
+            .permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&executable, perms).expect("script should be executable");
+
+        SupervisorSpec {
+            runtime: "zeroclaw".to_string(),
+            executable,
+            args: Vec::new(),
+            log_path: dir.join("zeroclaw.log"),
+            audit_path: dir.join("audit.log"),
+            state_path: dir.join("zeroclaw.supervisor-state.json"),
+            restart: RestartSpec::parse(restart).expect("restart policy should parse"),
+            tuning: SupervisorTuning {
+                initial_backoff_ms: 10,
+                max_backoff_ms: 40,
+                stable_after_ms: 60_000,
+                crash_loop_restarts: 3,
+                crash_loop_window_ms: 60_000,
+                stop_grace_ms: 500,
+            },
+            log_rotation: LogRotation::default(),
+            redact_env: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn restart_policies_parse() {
+        let spec = RestartSpec::parse("on-failure:5").expect("on-failure with max");
+        assert_eq!(spec.policy, RestartPolicy::OnFailure);
+        assert_eq!(spec.max_restarts, Some(5));
+        assert_eq!(
+            RestartSpec::parse("unless-stopped")
+                .expect("unless-stopped")
+                .policy,
+            RestartPolicy::UnlessStopped
+        );
+        assert!(!RestartSpec::parse("no").expect("no").is_enabled());
+        assert!(RestartSpec::parse("always:3").is_err());
+        assert!(RestartSpec::parse("sometimes").is_err());
+    }
+
+    #[test]
+    fn on_failure_stops_at_max_restarts() {
+        let dir = temp_dir("max");
+        let spec = spec(
+            &dir,
+            "#!/usr/bin/env sh\necho boom\nexit 3\n",
+            "on-failure:2",
+        );
+
+        let state = run_supervisor(&spec, &AtomicBool::new(false)).expect("supervisor runs");
+        assert_eq!(state.status, SupervisorStatus::RestartLimit);
+        assert_eq!(state.restarts, 2);
+        assert_eq!(state.last_exit_code, Some(3));
+
+        let audit = fs::read_to_string(&spec.audit_path).expect("audit log");
+        assert_eq!(audit.matches("\truntime.crash\t").count(), 3);
+        assert!(audit.contains("exit_code=3"));
+        assert!(audit.contains("\truntime.restart_limit\tzeroclaw\trestarts=2"));
+        for session in 0..3 {
+            let log = read_log_session(&spec.log_path, session)
+                .expect("read log session")
+                .expect("each run should keep its own session");
+            assert_eq!(log, "boom\n");
+        }
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn always_detects_crash_loops_and_on_failure_accepts_clean_exit() {
+        let dir = temp_dir("loop");
+        let spec = spec(&dir, "#!/usr/bin/env sh\nexit 0\n", "always");
+        let state = run_supervisor(&spec, &AtomicBool::new(false)).expect("supervisor runs");
+        assert_eq!(state.status, SupervisorStatus::CrashLoop);
+        assert_eq!(state.restarts, 3);
+        let audit = fs::read_to_string(&spec.audit_path).expect("audit log");
+        assert!(audit.contains("\truntime.crash_loop\tzeroclaw\t"));
+
+        let spec = spec_with_policy(spec, "on-failure");
+        let state = run_supervisor(&spec, &AtomicBool::new(false)).expect("supervisor runs");
+        assert_eq!(state.status, SupervisorStatus::Exited);
+        assert_eq!(state.restarts, 0);
+        assert_eq!(state.last_exit_code, Some(0));
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    fn spec_with_policy(mut spec: SupervisorSpec, restart: &str) -> SupervisorSpec {
+        spec.restart = RestartSpec::parse(restart).expect("restart policy should parse");
+        spec
+    }
+
+    #[test]
+    fn shutdown_terminates_the_runtime() {
+        let dir = temp_dir("shutdown");
+        let spec = spec(&dir, "#!/usr/bin/env sh\nsleep 30\n", "always");
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let spec = spec.clone();
+            let shutdown = shutdown.clone();
+            thread::spawn(move || run_supervisor(&spec, &shutdown))
+        };
+        let deadline = Instant::now() + Duration::from_secs(3);
+        let child_pid = loop {
+            if let Some(pid) =
+                super::SupervisorState::load(&spec.state_path).and_then(|state| state.child_pid)
+            {
+                break pid;
+            }
+            assert!(Instant::now() < deadline, "runtime never started");
+            thread::sleep(Duration::from_millis(20));
+        };
+
+        shutdown.store(true, Ordering::Relaxed);
+        let state = handle
+            .join()
+            .expect("supervisor thread")
+            .expect("supervisor runs");
+        assert_eq!(state.status, SupervisorStatus::Stopped);
+        assert!(!Path::new(&format!("/proc/{child_pid}")).exists());
+        let _ = fs::remove_dir_all(dir);
+    }
+}
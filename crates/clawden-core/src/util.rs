@@ -6,5 +6,7 @@ pub fn current_unix_ms() -> u64 {
 }
 
 pub fn runtime_env_prefix(runtime: &str) -> String {
-    runtime.to_ascii_uppercase().replace('-', "_")
+    crate::instance_runtime(runtime)
+        .to_ascii_uppercase()
+        .replace('-', "_")
 }
@@ -0,0 +1,209 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::{HealthStatus, LogStream};
+
+/// Coding tools that ClawDen can drive as task workers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CodingTool {
+    ClaudeCode,
+    CodexCli,
+}
+
+/// Static metadata describing how to launch a coding tool as a one-shot
+/// task worker. Mirrors `RuntimeDescriptor` for claw runtimes.
+#[derive(Debug, Clone)]
+pub struct WorkerDescriptor {
+    pub tool: CodingTool,
+    pub name: &'static str,
+    pub display_name: &'static str,
+    pub aliases: &'static [&'static str],
+    pub binary: &'static str,
+    pub version_args: &'static [&'static str],
+    pub install_hint: &'static str,
+    /// Arguments for a one-shot task; `{prompt}` is replaced with the task prompt.
+    pub task_args: &'static [&'static str],
+    /// Flag used to pass `Task::allowed_tools` as a comma-separated list.
+    pub allowed_tools_flag: Option<&'static str>,
+    pub supports_json_output: bool,
+    pub supports_streaming: bool,
+    pub supports_session_resume: bool,
+}
+
+static WORKER_DESCRIPTORS: &[WorkerDescriptor] = &[
+    WorkerDescriptor {
+        tool: CodingTool::ClaudeCode,
+        name: "claude-code",
+        display_name: "Claude Code",
+        aliases: &["claude"],
+        binary: "claude",
+        version_args: &["--version"],
+        install_hint: "npm i -g @anthropic-ai/claude-code",
+        task_args: &["-p", "{prompt}", "--output-format", "json"],
+        allowed_tools_flag: Some("--allowedTools"),
+        supports_json_output: true,
+        supports_streaming: true,
+        supports_session_resume: true,
+    },
+    WorkerDescriptor {
+        tool: CodingTool::CodexCli,
+        name: "codex",
+        display_name: "Codex CLI",
+        aliases: &["codex-cli"],
+        binary: "codex",
+        version_args: &["--version"],
+        install_hint: "npm i -g @openai/codex",
+        task_args: &["exec", "--json", "{prompt}"],
+        allowed_tools_flag: None,
+        supports_json_output: true,
+        supports_streaming: true,
+        supports_session_resume: false,
+    },
+];
+
+pub fn worker_descriptors() -> &'static [WorkerDescriptor] {
+    WORKER_DESCRIPTORS
+}
+
+pub fn worker_descriptor(name: &str) -> Option<&'static WorkerDescriptor> {
+    let lower = name.trim().to_ascii_lowercase();
+    WORKER_DESCRIPTORS
+        .iter()
+        .find(|d| d.name == lower || d.aliases.contains(&lower.as_str()))
+}
+
+pub fn worker_descriptor_for(tool: CodingTool) -> &'static WorkerDescriptor {
+    WORKER_DESCRIPTORS
+        .iter()
+        .find(|d| d.tool == tool)
+        .expect("every CodingTool has a descriptor")
+}
+
+impl std::fmt::Display for CodingTool {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(worker_descriptor_for(*self).name)
+    }
+}
+
+impl CodingTool {
+    pub fn from_str_loose(s: &str) -> Option<Self> {
+        worker_descriptor(s).map(|d| d.tool)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerConfig {
+    pub name: String,
+    pub tool: CodingTool,
+    /// Explicit path to the tool binary; defaults to a `PATH` lookup.
+    #[serde(default)]
+    pub binary: Option<PathBuf>,
+    #[serde(default)]
+    pub env_vars: Vec<(String, String)>,
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkerHandle {
+    pub id: String,
+    pub name: String,
+    pub tool: CodingTool,
+    pub binary: PathBuf,
+    /// Name used for the worker's pid and log files under `~/.clawden`.
+    pub process_name: String,
+    pub env_vars: Vec<(String, String)>,
+    pub extra_args: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Task {
+    pub id: String,
+    pub prompt: String,
+    pub project_dir: PathBuf,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub allowed_tools: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    Completed,
+    Failed,
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaskCost {
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub usd: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaskResult {
+    pub task_id: String,
+    pub status: TaskStatus,
+    pub output: String,
+    pub exit_code: Option<i32>,
+    pub duration_ms: u64,
+    pub cost: Option<TaskCost>,
+    /// Parsed JSON payload when the tool emitted structured output.
+    pub raw: Option<serde_json::Value>,
+}
+
+#[async_trait]
+pub trait WorkerAdapter: Send + Sync {
+    fn metadata(&self) -> &'static WorkerDescriptor;
+
+    /// Resolve the tool binary and return a handle for dispatching tasks.
+    async fn start(&self, config: &WorkerConfig) -> Result<WorkerHandle>;
+    /// Stop any task currently running on the handle.
+    async fn stop(&self, handle: &WorkerHandle) -> Result<()>;
+    async fn health(&self, handle: &WorkerHandle) -> Result<HealthStatus>;
+
+    /// Run a task to completion and capture its result.
+    async fn dispatch(&self, handle: &WorkerHandle, task: &Task) -> Result<TaskResult>;
+    /// Stream output lines of the task currently running on the handle.
+    async fn stream_output(&self, handle: &WorkerHandle) -> Result<LogStream>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn worker_descriptor_resolves_names_and_aliases() {
+        assert_eq!(
+            worker_descriptor("claude-code").map(|d| d.tool),
+            Some(CodingTool::ClaudeCode)
+        );
+        assert_eq!(
+            worker_descriptor("Claude").map(|d| d.tool),
+            Some(CodingTool::ClaudeCode)
+        );
+        assert_eq!(
+            CodingTool::from_str_loose("codex"),
+            Some(CodingTool::CodexCli)
+        );
+        assert!(worker_descriptor("copilot").is_none());
+    }
+
+    #[test]
+    fn every_tool_has_prompt_placeholder_and_install_hint() {
+        for descriptor in worker_descriptors() {
+            assert!(
+                descriptor.task_args.contains(&"{prompt}"),
+                "{}",
+                descriptor.name
+            );
+            assert!(!descriptor.install_hint.is_empty());
+            assert_eq!(worker_descriptor_for(descriptor.tool).name, descriptor.name);
+        }
+    }
+}
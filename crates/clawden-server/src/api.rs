@@ -1,14 +1,26 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::task::{Context, Poll};
 
-use axum::extract::{Path, State};
+use axum::extract::{Path, Query, State};
 use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
 use axum::Json;
 use clawden_core::{
-    append_audit, AgentRecord, AgentState, AuditEvent, AuditLog, BindChannelRequest,
-    BindingConflict, ChannelConfigRequest, ChannelHealthEntry, ChannelInstanceConfig, ChannelStore,
+    append_audit, current_unix_ms, AgentRecord, AgentState, ApprovalDecision, ApprovalError,
+    ApprovalRecord, ApprovalStore, AuditEvent, AuditLog, BindChannelRequest, BindingConflict,
+    ChannelConfigRequest, ChannelHealthEntry, ChannelInstanceConfig, ChannelNotifier, ChannelStore,
     ChannelTypeSummary, ClawRuntime, DiscoveredEndpoint, DiscoveryMethod, DiscoveryService,
-    LifecycleManager, MatrixRow, RuntimeMetadata, SwarmCoordinator, SwarmMember, SwarmRole,
+    EventStream, HookConfig, HookEngine, HookEvent, LifecycleManager, ManagerError, MatrixRow,
+    RuntimeMetadata, SwarmCoordinator, SwarmMember, SwarmRole, Task, TaskResult, TaskStatus,
+    METRICS_HISTORY_CAPACITY,
 };
+use futures_core::Stream;
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
@@ -68,6 +80,38 @@ pub struct AppState {
     pub discovery: Arc<RwLock<DiscoveryService>>,
     pub swarm: Arc<RwLock<SwarmCoordinator>>,
     pub channels: Arc<RwLock<ChannelStore>>,
+    pub approvals: Arc<ApprovalStore>,
+    pub hooks: Arc<HookConfig>,
+    pub notifier: Arc<dyn ChannelNotifier>,
+    /// Outcomes of tasks accepted while waiting for approval, by task id.
+    pub tasks: Arc<RwLock<HashMap<String, TaskSendState>>>,
+}
+
+/// A hook engine over the channel instances currently in the channel store.
+async fn hook_engine(state: &AppState) -> HookEngine {
+    let channels = state.channels.read().await.list_configs();
+    HookEngine::new(
+        (*state.hooks).clone(),
+        channels,
+        state.notifier.clone(),
+        state.audit.clone(),
+    )
+}
+
+/// Delivers a hook event in the background so retries never hold up the
+/// request or health monitor that raised it.
+pub fn fire_hook(state: &AppState, event: HookEvent) {
+    if state.hooks.rule(event.kind()).is_none() {
+        return;
+    }
+    let state = state.clone();
+    tokio::spawn(async move {
+        for delivery in hook_engine(&state).await.fire(&event).await {
+            if let Some(error) = delivery.error {
+                tracing::warn!(channel = %delivery.channel, %error, "hook delivery failed");
+            }
+        }
+    });
 }
 
 #[derive(Debug, Deserialize)]
@@ -84,6 +128,10 @@ pub struct SendTaskRequest {
     #[serde(default)]
     pub required_capabilities: Vec<String>,
     pub agent_id: Option<String>,
+    /// Action the task is about to take that needs human sign-off. The task
+    /// is only sent once `on_approval_needed` approves it.
+    #[serde(default)]
+    pub approval: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -99,6 +147,58 @@ pub struct TaskSendResponse {
     pub content: String,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskSendStatus {
+    AwaitingApproval,
+    Completed,
+    Failed,
+}
+
+/// A task accepted with `202 Accepted`, as reported by `GET /task/{task_id}`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskSendState {
+    pub task_id: String,
+    pub status: TaskSendStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent: Option<AgentRecord>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl TaskSendState {
+    fn awaiting_approval(task_id: &str) -> Self {
+        Self {
+            task_id: task_id.to_string(),
+            status: TaskSendStatus::AwaitingApproval,
+            agent: None,
+            content: None,
+            error: None,
+        }
+    }
+
+    fn finished(task_id: String, outcome: Result<TaskSendResponse, (StatusCode, String)>) -> Self {
+        match outcome {
+            Ok(sent) => Self {
+                task_id,
+                status: TaskSendStatus::Completed,
+                agent: Some(sent.agent),
+                content: Some(sent.content),
+                error: None,
+            },
+            Err((_, error)) => Self {
+                task_id,
+                status: TaskSendStatus::Failed,
+                agent: None,
+                content: None,
+                error: Some(error),
+            },
+        }
+    }
+}
+
 pub async fn register_agent(
     State(state): State<AppState>,
     Json(request): Json<RegisterAgentRequest>,
@@ -140,6 +240,19 @@ pub async fn stop_agent(
     Ok(Json(record))
 }
 
+pub async fn remove_agent(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+) -> Result<Json<AgentRecord>, (StatusCode, String)> {
+    let mut manager = state.manager.write().await;
+    let record = manager.remove_agent(&agent_id).map_err(|e| match e {
+        ManagerError::AgentNotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+        _ => (StatusCode::CONFLICT, e.to_string()),
+    })?;
+    append_audit(&state.audit, "api", "agent.remove", &agent_id);
+    Ok(Json(record))
+}
+
 pub async fn health_summary(State(state): State<AppState>) -> Json<Vec<AgentRecord>> {
     let mut manager = state.manager.write().await;
     Json(manager.refresh_health().await)
@@ -162,26 +275,156 @@ pub async fn fleet_status(State(state): State<AppState>) -> Json<FleetStatusResp
     })
 }
 
+/// Sends a task to an agent. A task that needs approval is answered with
+/// `202 Accepted` and its id straight away; the approval wait and the send
+/// continue in the background and `GET /task/{task_id}` reports the outcome.
 pub async fn send_task(
     State(state): State<AppState>,
     Json(request): Json<SendTaskRequest>,
-) -> Result<Json<TaskSendResponse>, (StatusCode, String)> {
+) -> Result<Response, (StatusCode, String)> {
+    static NEXT_TASK: AtomicU64 = AtomicU64::new(0);
+    let started_ms = current_unix_ms();
+    let task = Task {
+        id: format!(
+            "task-{started_ms}-{}",
+            NEXT_TASK.fetch_add(1, Ordering::Relaxed)
+        ),
+        prompt: request.message.clone(),
+        project_dir: PathBuf::new(),
+        timeout_secs: None,
+        allowed_tools: None,
+    };
+
+    let Some(description) = request.approval.clone() else {
+        let sent = deliver_task(&state, request, task, started_ms).await?;
+        return Ok(Json(sent).into_response());
+    };
+
+    let accepted = TaskSendState::awaiting_approval(&task.id);
+    state
+        .tasks
+        .write()
+        .await
+        .insert(task.id.clone(), accepted.clone());
+    let background = state.clone();
+    tokio::spawn(async move {
+        let task_id = task.id.clone();
+        let outcome = match await_task_approval(&background, &request, &task, &description).await {
+            Ok(()) => deliver_task(&background, request, task, started_ms).await,
+            Err(err) => Err(err),
+        };
+        background
+            .tasks
+            .write()
+            .await
+            .insert(task_id.clone(), TaskSendState::finished(task_id, outcome));
+    });
+    Ok((StatusCode::ACCEPTED, Json(accepted)).into_response())
+}
+
+/// GET /task/{task_id} — progress of a task accepted with `202 Accepted`.
+pub async fn task_status(
+    State(state): State<AppState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<TaskSendState>, (StatusCode, String)> {
+    state
+        .tasks
+        .read()
+        .await
+        .get(&task_id)
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| (StatusCode::NOT_FOUND, format!("task {task_id} not found")))
+}
+
+/// Waits for `on_approval_needed` to decide on `task`, firing
+/// `on_task_failed` when it is not approved.
+async fn await_task_approval(
+    state: &AppState,
+    request: &SendTaskRequest,
+    task: &Task,
+    description: &str,
+) -> Result<(), (StatusCode, String)> {
+    let worker = request
+        .agent_id
+        .clone()
+        .unwrap_or_else(|| "fleet".to_string());
+    let record = hook_engine(state)
+        .await
+        .request_approval(&state.approvals, &worker, task, description)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    if record.decision() == Some(ApprovalDecision::Approve) {
+        return Ok(());
+    }
+    let error = format!("approval {} was {}", record.id, record.status);
+    fire_hook(
+        state,
+        HookEvent::TaskFailed {
+            worker,
+            task: task.clone(),
+            error: error.clone(),
+            result: None,
+        },
+    );
+    Err((StatusCode::FORBIDDEN, error))
+}
+
+/// Routes `task` to an agent and fires the matching completion hook.
+async fn deliver_task(
+    state: &AppState,
+    request: SendTaskRequest,
+    task: Task,
+    started_ms: u64,
+) -> Result<TaskSendResponse, (StatusCode, String)> {
     let mut manager = state.manager.write().await;
-    let (agent, response) = manager
+    let sent = manager
         .route_and_send(
             &request.required_capabilities,
             request.message,
             request.agent_id.clone(),
         )
-        .await
-        .map_err(|e| (StatusCode::BAD_REQUEST, e.to_string()))?;
+        .await;
+    drop(manager);
+
+    let (agent, response) = match sent {
+        Ok(sent) => sent,
+        Err(err) => {
+            fire_hook(
+                state,
+                HookEvent::TaskFailed {
+                    worker: request.agent_id.unwrap_or_else(|| "fleet".to_string()),
+                    task,
+                    error: err.to_string(),
+                    result: None,
+                },
+            );
+            return Err((StatusCode::BAD_REQUEST, err.to_string()));
+        }
+    };
 
     append_audit(&state.audit, "api", "task.send", &agent.id);
+    fire_hook(
+        state,
+        HookEvent::TaskComplete {
+            worker: agent.name.clone(),
+            result: TaskResult {
+                task_id: task.id.clone(),
+                status: TaskStatus::Completed,
+                output: response.content.clone(),
+                exit_code: None,
+                duration_ms: current_unix_ms().saturating_sub(started_ms),
+                cost: None,
+                raw: None,
+            },
+            task,
+        },
+    );
 
-    Ok(Json(TaskSendResponse {
+    Ok(TaskSendResponse {
         agent,
         content: response.content,
-    }))
+    })
 }
 
 pub async fn audit_log(State(state): State<AppState>) -> Json<Vec<AuditEvent>> {
@@ -416,16 +659,14 @@ pub async fn agent_metrics_history(
     Path(agent_id): Path<String>,
 ) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
     let manager = state.manager.read().await;
-    let agents = manager.list_agents();
-    let _agent = agents
-        .into_iter()
-        .find(|a| a.id == agent_id)
-        .ok_or_else(|| (StatusCode::NOT_FOUND, "agent not found".to_string()))?;
+    let data_points = manager
+        .metrics_history(&agent_id)
+        .map_err(|_| (StatusCode::NOT_FOUND, "agent not found".to_string()))?;
 
-    // Return stub metrics history
     Ok(Json(serde_json::json!({
-        "data_points": [],
-        "message": "metrics history collection not yet implemented"
+        "agent_id": agent_id,
+        "capacity": METRICS_HISTORY_CAPACITY,
+        "data_points": data_points,
     })))
 }
 
@@ -747,6 +988,45 @@ pub async fn agent_logs(
     })))
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct AgentEventsQuery {
+    #[serde(default)]
+    pub event: String,
+}
+
+/// Adapts a runtime [`EventStream`] into SSE frames named after the event kind.
+pub struct SseEvents(EventStream);
+
+impl Stream for SseEvents {
+    type Item = Result<Event, Infallible>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.0).poll_next(cx).map(|next| {
+            next.map(|event| {
+                Ok(Event::default()
+                    .event(event.kind().as_str())
+                    .data(serde_json::to_string(&event).unwrap_or_default()))
+            })
+        })
+    }
+}
+
+pub async fn agent_events(
+    State(state): State<AppState>,
+    Path(agent_id): Path<String>,
+    Query(query): Query<AgentEventsQuery>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let manager = state.manager.read().await;
+    let stream = manager
+        .subscribe(&agent_id, &query.event)
+        .await
+        .map_err(|e| match e {
+            ManagerError::AgentNotFound(_) => (StatusCode::NOT_FOUND, e.to_string()),
+            _ => (StatusCode::BAD_REQUEST, e.to_string()),
+        })?;
+    Ok(Sse::new(SseEvents(stream)).keep_alive(KeepAlive::default()))
+}
+
 // --- Channel proxy status endpoint (spec 018) ---
 
 pub async fn proxy_status_endpoint(
@@ -781,3 +1061,93 @@ pub async fn proxy_status_endpoint(
     let status = crate::proxy::proxy_status(metadata, &ct);
     Ok(Json(serde_json::to_value(status).unwrap_or_default()))
 }
+
+// --- Approval endpoints (spec 063) ---
+
+pub async fn list_approvals(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<ApprovalRecord>>, (StatusCode, String)> {
+    state
+        .approvals
+        .list()
+        .map(Json)
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ApprovalReplyRequest {
+    pub instance_name: String,
+    pub sender_id: String,
+    #[serde(default)]
+    pub sender_role: Option<String>,
+    pub text: String,
+}
+
+/// POST /approvals/reply — a chat reply forwarded by a channel runtime.
+pub async fn approval_reply(
+    State(state): State<AppState>,
+    Json(req): Json<ApprovalReplyRequest>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    let channels = state.channels.read().await;
+    let decided = state
+        .approvals
+        .handle_reply(
+            &channels,
+            &req.instance_name,
+            &req.sender_id,
+            req.sender_role.as_deref(),
+            &req.text,
+        )
+        .map_err(approval_error)?;
+    if let Some(record) = &decided {
+        append_audit(
+            &state.audit,
+            &format!("{}:{}", req.instance_name, req.sender_id),
+            &format!("approval.{}", record.status),
+            &record.id,
+        );
+    }
+    Ok(Json(serde_json::json!({ "decided": decided })))
+}
+
+pub async fn approve_approval(
+    State(state): State<AppState>,
+    Path(approval_id): Path<String>,
+) -> Result<Json<ApprovalRecord>, (StatusCode, String)> {
+    decide_approval(&state, &approval_id, ApprovalDecision::Approve)
+}
+
+pub async fn deny_approval(
+    State(state): State<AppState>,
+    Path(approval_id): Path<String>,
+) -> Result<Json<ApprovalRecord>, (StatusCode, String)> {
+    decide_approval(&state, &approval_id, ApprovalDecision::Deny)
+}
+
+fn decide_approval(
+    state: &AppState,
+    approval_id: &str,
+    decision: ApprovalDecision,
+) -> Result<Json<ApprovalRecord>, (StatusCode, String)> {
+    let record = state
+        .approvals
+        .decide(approval_id, decision, "api")
+        .map_err(approval_error)?;
+    append_audit(
+        &state.audit,
+        "api",
+        &format!("approval.{}", record.status),
+        approval_id,
+    );
+    Ok(Json(record))
+}
+
+fn approval_error(err: ApprovalError) -> (StatusCode, String) {
+    let status = match &err {
+        ApprovalError::NotFound(_) => StatusCode::NOT_FOUND,
+        ApprovalError::AlreadyDecided { .. } => StatusCode::CONFLICT,
+        ApprovalError::NotAllowed { .. } => StatusCode::FORBIDDEN,
+        ApprovalError::Other(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    };
+    (status, err.to_string())
+}
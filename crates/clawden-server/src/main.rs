@@ -2,19 +2,22 @@ mod api;
 mod proxy;
 
 use crate::api::{
-    agent_channels, agent_logs, agent_metrics_history, audit_log, authorize_channel_sender,
-    binding_conflicts, channel_health, channel_instances, channel_matrix, channel_support_matrix,
-    create_binding, create_team, delete_binding, delete_channel_config, deploy_runtime,
-    deploy_status, fan_out_task, fleet_status, get_channel_config, health_summary, list_agents,
-    list_bindings, list_channels, list_endpoints, list_runtimes, list_swarm_tasks, list_teams,
-    proxy_status_endpoint, register_agent, register_endpoint, restart_agent, scan_endpoints,
-    send_task, start_agent, stop_agent, test_channel, update_channel_instances,
-    upsert_channel_config, AppState,
+    agent_channels, agent_events, agent_logs, agent_metrics_history, approval_reply,
+    approve_approval, audit_log, authorize_channel_sender, binding_conflicts, channel_health,
+    channel_instances, channel_matrix, channel_support_matrix, create_binding, create_team,
+    delete_binding, delete_channel_config, deny_approval, deploy_runtime, deploy_status,
+    fan_out_task, fire_hook, fleet_status, get_channel_config, health_summary, list_agents,
+    list_approvals, list_bindings, list_channels, list_endpoints, list_runtimes, list_swarm_tasks,
+    list_teams, proxy_status_endpoint, register_agent, register_endpoint, remove_agent,
+    restart_agent, scan_endpoints, send_task, start_agent, stop_agent, task_status, test_channel,
+    update_channel_instances, upsert_channel_config, AppState,
 };
 use axum::{routing::get, Json, Router};
+use clawden_config::ClawDenYaml;
 use clawden_core::{
-    append_audit, AgentState, AuditEvent, AuditLog, ChannelStore, DiscoveryService,
-    LifecycleManager, SwarmCoordinator,
+    append_audit, current_unix_ms, sample_metrics, AgentState, ApprovalStore, AuditEvent, AuditLog,
+    ChannelConfigRequest, ChannelStore, DiscoveryService, ExecutionMode, HookConfig, HookEvent,
+    HttpChannelNotifier, LifecycleManager, SwarmCoordinator, Task,
 };
 use serde::Serialize;
 use std::net::SocketAddr;
@@ -44,9 +47,11 @@ fn build_app(shared_state: AppState) -> Router {
         .route("/agents/register", axum::routing::post(register_agent))
         .route("/agents/{agent_id}/start", axum::routing::post(start_agent))
         .route("/agents/{agent_id}/stop", axum::routing::post(stop_agent))
+        .route("/agents/{agent_id}", axum::routing::delete(remove_agent))
         .route("/agents/health", get(health_summary))
         .route("/fleet/status", get(fleet_status))
         .route("/task/send", axum::routing::post(send_task))
+        .route("/task/{task_id}", get(task_status))
         .route("/audit", get(audit_log))
         .route("/discovery/endpoints", get(list_endpoints))
         .route(
@@ -69,6 +74,7 @@ fn build_app(shared_state: AppState) -> Router {
             axum::routing::post(restart_agent),
         )
         .route("/agents/{agent_id}/logs", get(agent_logs))
+        .route("/agents/{agent_id}/events", get(agent_events))
         .route(
             "/agents/{agent_id}/metrics/history",
             get(agent_metrics_history),
@@ -109,9 +115,72 @@ fn build_app(shared_state: AppState) -> Router {
         )
         .route("/channels/bindings/conflicts", get(binding_conflicts))
         .route("/channels/health", get(channel_health))
+        .route("/approvals", get(list_approvals))
+        .route("/approvals/reply", axum::routing::post(approval_reply))
+        .route(
+            "/approvals/{approval_id}/approve",
+            axum::routing::post(approve_approval),
+        )
+        .route(
+            "/approvals/{approval_id}/deny",
+            axum::routing::post(deny_approval),
+        )
         .with_state(shared_state)
 }
 
+/// Makes runtimes declared in `~/.clawden/runtimes.d` available to the
+/// adapter registry.
+fn register_custom_runtimes() {
+    let Ok(home) = std::env::var("HOME") else {
+        return;
+    };
+    let dir = std::path::Path::new(&home)
+        .join(".clawden")
+        .join(clawden_core::CUSTOM_RUNTIMES_DIR);
+    let specs = match clawden_core::load_custom_runtime_specs(&dir) {
+        Ok(specs) => specs,
+        Err(err) => {
+            tracing::warn!(error = %format!("{err:#}"), "failed to load custom runtimes");
+            return;
+        }
+    };
+    for spec in &specs {
+        match clawden_core::register_custom_runtime(spec) {
+            Ok(descriptor) => info!(runtime = descriptor.slug, "custom runtime registered"),
+            Err(err) => tracing::warn!(error = %err, "skipping custom runtime"),
+        }
+    }
+}
+
+/// Seeds the channel store with the channel instances of `clawden.yaml` in
+/// the working directory and returns its `hooks:` section.
+fn load_project_config(channels: &mut ChannelStore) -> HookConfig {
+    let path = std::path::Path::new("clawden.yaml");
+    if !path.exists() {
+        return HookConfig::default();
+    }
+    let mut config = match ClawDenYaml::from_file(path) {
+        Ok(config) => config,
+        Err(err) => {
+            tracing::warn!(error = %err, "ignoring clawden.yaml");
+            return HookConfig::default();
+        }
+    };
+    if let Err(errors) = config.resolve_env_vars().and_then(|()| config.validate()) {
+        tracing::warn!(errors = %errors.join("; "), "ignoring clawden.yaml");
+        return HookConfig::default();
+    }
+    for channel in config.channel_instance_configs() {
+        let _ = channels.upsert_config(ChannelConfigRequest {
+            instance_name: channel.instance_name,
+            channel_type: channel.channel_type.to_string(),
+            credentials: channel.credentials,
+            options: channel.options,
+        });
+    }
+    config.hooks
+}
+
 #[tokio::main]
 async fn main() {
     tracing_subscriber::fmt()
@@ -121,16 +190,55 @@ async fn main() {
         .init();
 
     let audit_store = Arc::new(AuditLog::default());
-    let registry = clawden_adapters::builtin_registry();
+    register_custom_runtimes();
+    let registry = clawden_adapters::builtin_registry(ExecutionMode::Auto);
     let manager = LifecycleManager::new(registry.adapters_map());
+    let approvals = match ApprovalStore::new() {
+        Ok(approvals) => approvals,
+        Err(err) => {
+            tracing::error!(error = %err, "cannot open the approval store");
+            std::process::exit(1);
+        }
+    };
+    let mut channels = ChannelStore::new();
+    let hooks = load_project_config(&mut channels);
+    if !hooks.is_empty() {
+        info!(
+            rules = hooks.rules().count(),
+            "hooks loaded from clawden.yaml"
+        );
+    }
     let shared_state = AppState {
         manager: Arc::new(RwLock::new(manager)),
         audit: audit_store.clone(),
         discovery: Arc::new(RwLock::new(DiscoveryService::new())),
         swarm: Arc::new(RwLock::new(SwarmCoordinator::new())),
-        channels: Arc::new(RwLock::new(ChannelStore::new())),
+        channels: Arc::new(RwLock::new(channels)),
+        approvals: Arc::new(approvals),
+        hooks: Arc::new(hooks),
+        notifier: Arc::new(
+            HttpChannelNotifier::new().expect("failed to build channel notification client"),
+        ),
+        tasks: Arc::default(),
     };
 
+    // Pending approvals survive restarts; settle any whose deadline passed
+    // while the server was down.
+    match shared_state.approvals.expire_overdue() {
+        Ok(expired) => {
+            for record in &expired {
+                append_audit(&audit_store, "timeout", "approval.expired", &record.id);
+            }
+            let pending = shared_state
+                .approvals
+                .pending()
+                .map(|p| p.len())
+                .unwrap_or(0);
+            info!(expired = expired.len(), pending, "approval store loaded");
+        }
+        Err(err) => tracing::warn!(error = %err, "failed to load approval store"),
+    }
+
     let health_interval_ms = std::env::var("CLAWDEN_HEALTH_INTERVAL_MS")
         .ok()
         .and_then(|value| value.parse::<u64>().ok())
@@ -140,18 +248,56 @@ async fn main() {
         .and_then(|value| value.parse::<u64>().ok())
         .unwrap_or(1_000);
 
+    let monitor_state = shared_state.clone();
     let monitor_manager = shared_state.manager.clone();
     let monitor_channels = shared_state.channels.clone();
     let monitor_audit = shared_state.audit.clone();
+    let monitor_approvals = shared_state.approvals.clone();
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_millis(health_interval_ms));
         loop {
             interval.tick().await;
             let mut manager = monitor_manager.write().await;
-            manager
+            let previous: std::collections::HashMap<String, AgentState> = manager
+                .list_agents()
+                .into_iter()
+                .map(|a| (a.id, a.state))
+                .collect();
+            let refreshed = manager
                 .refresh_health_with_base_backoff_ms(recovery_base_backoff_ms)
                 .await;
+            // A running agent that starts failing its health checks is
+            // reported to `on_task_failed`.
+            for agent in refreshed {
+                if agent.state == AgentState::Degraded
+                    && previous.get(&agent.id) != Some(&AgentState::Degraded)
+                {
+                    fire_hook(
+                        &monitor_state,
+                        HookEvent::TaskFailed {
+                            worker: agent.name.clone(),
+                            task: Task {
+                                id: format!("{}-{}", agent.id, current_unix_ms()),
+                                prompt: format!("run {}", agent.runtime),
+                                project_dir: std::path::PathBuf::new(),
+                                timeout_secs: None,
+                                allowed_tools: None,
+                            },
+                            error: "health check failed".to_string(),
+                            result: None,
+                        },
+                    );
+                }
+            }
             let recovered = manager.recover_degraded().await;
+            let targets = manager.metrics_targets();
+            drop(manager);
+
+            // Sampling shells out to docker and reads /proc; keep the manager
+            // available to API handlers meanwhile.
+            let samples = sample_metrics(targets).await;
+            let mut manager = monitor_manager.write().await;
+            manager.record_metrics(samples);
 
             // Refresh channel health based on current agent states
             let agent_states: std::collections::HashMap<String, AgentState> = manager
@@ -166,6 +312,10 @@ async fn main() {
             channels.refresh_channel_health(&agent_states, &proxy_pairs);
             drop(channels);
 
+            for record in monitor_approvals.expire_overdue().unwrap_or_default() {
+                append_audit(&monitor_audit, "timeout", "approval.expired", &record.id);
+            }
+
             append_audit(&monitor_audit, "api", "health.tick", "fleet");
             info!(
                 checked_agents = recovered.len(),
@@ -239,7 +389,7 @@ mod tests {
     use tower::util::ServiceExt;
 
     fn test_state() -> AppState {
-        let registry = clawden_adapters::builtin_registry();
+        let registry = clawden_adapters::builtin_registry(ExecutionMode::Docker);
         let manager = LifecycleManager::new(registry.adapters_map());
         AppState {
             manager: Arc::new(RwLock::new(manager)),
@@ -247,6 +397,18 @@ mod tests {
             discovery: Arc::new(RwLock::new(DiscoveryService::new())),
             swarm: Arc::new(RwLock::new(SwarmCoordinator::new())),
             channels: Arc::new(RwLock::new(ChannelStore::new())),
+            approvals: Arc::new(ApprovalStore::at(std::env::temp_dir().join(format!(
+                "clawden-server-approvals-{}",
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .expect("clock should be after unix epoch")
+                    .as_nanos()
+            )))),
+            hooks: Arc::new(HookConfig::default()),
+            notifier: Arc::new(
+                HttpChannelNotifier::new().expect("notification client should build"),
+            ),
+            tasks: Arc::default(),
         }
     }
 
@@ -342,4 +504,471 @@ mod tests {
             Some("<redacted>")
         );
     }
+
+    #[tokio::test]
+    async fn approval_reply_requires_allowlisted_sender() {
+        let state = test_state();
+        {
+            let mut channels = state.channels.write().await;
+            channels
+                .upsert_config(clawden_core::ChannelConfigRequest {
+                    instance_name: "eng-leads".to_string(),
+                    channel_type: "slack".to_string(),
+                    credentials: Default::default(),
+                    options: [("allowed_users".to_string(), serde_json::json!(["lead"]))]
+                        .into_iter()
+                        .collect(),
+                })
+                .expect("channel config");
+        }
+        let record = state
+            .approvals
+            .create(
+                "coder",
+                "task-1",
+                "rm -rf build",
+                vec!["eng-leads".to_string()],
+                clawden_core::ApprovalDecision::Deny,
+                None,
+            )
+            .expect("approval");
+        let app = build_app(state.clone());
+
+        let reply = |sender: &str, text: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri("/approvals/reply")
+                .header("content-type", "application/json")
+                .body(Body::from(
+                    serde_json::json!({
+                        "instance_name": "eng-leads",
+                        "sender_id": sender,
+                        "text": text,
+                    })
+                    .to_string(),
+                ))
+                .expect("request should build")
+        };
+
+        let res = app
+            .clone()
+            .oneshot(reply("intruder", "deny"))
+            .await
+            .expect("call");
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+        let res = app
+            .clone()
+            .oneshot(reply("lead", "deny apr-404"))
+            .await
+            .expect("call");
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let res = app
+            .clone()
+            .oneshot(reply("lead", "deny"))
+            .await
+            .expect("call");
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let stored = state
+            .approvals
+            .get(&record.id)
+            .expect("get")
+            .expect("record");
+        assert_eq!(
+            stored.decision(),
+            Some(clawden_core::ApprovalDecision::Deny)
+        );
+        let _ = std::fs::remove_dir_all(state.approvals.dir());
+    }
+
+    #[tokio::test]
+    async fn failed_task_send_notifies_on_task_failed_hook() {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("listener should bind");
+        let url = format!("http://{}/hook", listener.local_addr().expect("local addr"));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("webhook should be called");
+            let mut reader = BufReader::new(stream.try_clone().expect("stream should clone"));
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).map_or(true, |read| read == 0)
+                    || line.trim().is_empty()
+                {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+            }
+            let mut body = vec![0; length];
+            let _ = reader.read_exact(&mut body);
+            let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n");
+            let _ = sender.send(String::from_utf8_lossy(&body).to_string());
+        });
+
+        let mut state = test_state();
+        state.hooks = Arc::new(
+            serde_json::from_value(serde_json::json!({
+                "on_task_failed": {
+                    "notify": [{ "channel": "alerts", "template": "{{worker}}: {{task.error}}" }]
+                }
+            }))
+            .expect("hooks should parse"),
+        );
+        state
+            .channels
+            .write()
+            .await
+            .upsert_config(clawden_core::ChannelConfigRequest {
+                instance_name: "alerts".to_string(),
+                channel_type: "slack".to_string(),
+                credentials: [("webhook_url".to_string(), url)].into_iter().collect(),
+                options: Default::default(),
+            })
+            .expect("channel config");
+        let app = build_app(state.clone());
+
+        let res = app
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/task/send")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({ "message": "deploy", "agent_id": "agent-404" })
+                            .to_string(),
+                    ))
+                    .expect("request should build"),
+            )
+            .await
+            .expect("call");
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let body =
+            tokio::task::spawn_blocking(move || receiver.recv_timeout(Duration::from_secs(10)))
+                .await
+                .expect("receiver task")
+                .expect("hook should be delivered");
+        let payload: serde_json::Value = serde_json::from_str(&body).expect("json body");
+        assert!(
+            payload["text"]
+                .as_str()
+                .is_some_and(|text| text.starts_with("agent-404: ")),
+            "payload: {payload}"
+        );
+        let _ = std::fs::remove_dir_all(state.approvals.dir());
+    }
+
+    #[tokio::test]
+    async fn task_needing_approval_is_accepted_and_settles_by_default_decision() {
+        let mut state = test_state();
+        state.hooks = Arc::new(
+            serde_json::from_value(serde_json::json!({
+                "on_approval_needed": { "wait": true, "timeout_secs": 1, "default": "deny" }
+            }))
+            .expect("hooks should parse"),
+        );
+        let app = build_app(state.clone());
+
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::POST)
+                    .uri("/task/send")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({
+                            "message": "clean up",
+                            "agent_id": "coder",
+                            "approval": "rm -rf build",
+                        })
+                        .to_string(),
+                    ))
+                    .expect("request should build"),
+            )
+            .await
+            .expect("call");
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let accepted: serde_json::Value = serde_json::from_slice(&body).expect("json body");
+        assert_eq!(accepted["status"], "awaiting_approval");
+        let task_id = accepted["task_id"].as_str().expect("task id").to_string();
+
+        let deadline = std::time::Instant::now() + Duration::from_secs(10);
+        let settled = loop {
+            let res = app
+                .clone()
+                .oneshot(
+                    Request::builder()
+                        .uri(format!("/task/{task_id}"))
+                        .body(Body::empty())
+                        .expect("request should build"),
+                )
+                .await
+                .expect("call");
+            assert_eq!(res.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+                .await
+                .expect("body");
+            let polled: serde_json::Value = serde_json::from_slice(&body).expect("json body");
+            if polled["status"] != "awaiting_approval" || std::time::Instant::now() > deadline {
+                break polled;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        };
+        assert_eq!(settled["status"], "failed", "task: {settled}");
+        assert!(
+            settled["error"]
+                .as_str()
+                .is_some_and(|error| error.ends_with("was expired")),
+            "task: {settled}"
+        );
+
+        let records = state.approvals.list().expect("approvals should list");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].task_id, task_id);
+        assert_eq!(records[0].worker, "coder");
+        assert_eq!(records[0].description, "rm -rf build");
+        assert_eq!(records[0].status, clawden_core::ApprovalStatus::Expired);
+        let _ = std::fs::remove_dir_all(state.approvals.dir());
+    }
+
+    #[tokio::test]
+    async fn approval_decisions_report_unknown_and_settled_requests() {
+        let state = test_state();
+        let record = state
+            .approvals
+            .create(
+                "coder",
+                "task-1",
+                "rm -rf build",
+                Vec::new(),
+                clawden_core::ApprovalDecision::Deny,
+                None,
+            )
+            .expect("approval");
+        let app = build_app(state.clone());
+        let approve = |id: &str| {
+            Request::builder()
+                .method(Method::POST)
+                .uri(format!("/approvals/{id}/approve"))
+                .body(Body::empty())
+                .expect("request should build")
+        };
+
+        let res = app.clone().oneshot(approve("apr-404")).await.expect("call");
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+        let res = app
+            .clone()
+            .oneshot(approve(&record.id))
+            .await
+            .expect("call");
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = app
+            .clone()
+            .oneshot(approve(&record.id))
+            .await
+            .expect("call");
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+        let _ = std::fs::remove_dir_all(state.approvals.dir());
+    }
+
+    #[tokio::test]
+    async fn agent_events_reports_unknown_agent() {
+        let app = build_app(test_state());
+        let res = app
+            .oneshot(
+                Request::builder()
+                    .uri("/agents/agent-404/events?event=log")
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("call");
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+
+    /// Runs "agents" as the test process itself so metrics come from /proc.
+    struct SelfAdapter {
+        sampler: clawden_core::ProcessTreeSampler,
+    }
+
+    #[async_trait::async_trait]
+    impl clawden_core::ClawAdapter for SelfAdapter {
+        fn metadata(&self) -> clawden_core::RuntimeMetadata {
+            clawden_core::RuntimeMetadata {
+                runtime: clawden_core::ClawRuntime::ZeroClaw,
+                version: "test".to_string(),
+                language: "rust".to_string(),
+                capabilities: Vec::new(),
+                default_port: None,
+                config_format: None,
+                channel_support: Default::default(),
+            }
+        }
+        async fn install(&self, _: &clawden_core::InstallConfig) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn start(
+            &self,
+            config: &clawden_core::AgentConfig,
+        ) -> anyhow::Result<clawden_core::AgentHandle> {
+            Ok(clawden_core::AgentHandle {
+                id: std::process::id().to_string(),
+                name: config.name.clone(),
+                runtime: config.runtime.clone(),
+            })
+        }
+        async fn stop(&self, _: &clawden_core::AgentHandle) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn restart(&self, _: &clawden_core::AgentHandle) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn health(
+            &self,
+            _: &clawden_core::AgentHandle,
+        ) -> anyhow::Result<clawden_core::HealthStatus> {
+            Ok(clawden_core::HealthStatus::Healthy)
+        }
+        async fn metrics(
+            &self,
+            handle: &clawden_core::AgentHandle,
+        ) -> anyhow::Result<clawden_core::AgentMetrics> {
+            self.sampler.sample(handle.id.parse()?)
+        }
+        async fn send(
+            &self,
+            _: &clawden_core::AgentHandle,
+            _: &clawden_core::AgentMessage,
+        ) -> anyhow::Result<clawden_core::AgentResponse> {
+            anyhow::bail!("not supported")
+        }
+        async fn subscribe(
+            &self,
+            _: &clawden_core::AgentHandle,
+            _: &str,
+        ) -> anyhow::Result<clawden_core::EventStream> {
+            anyhow::bail!("not supported")
+        }
+        async fn get_config(
+            &self,
+            _: &clawden_core::AgentHandle,
+        ) -> anyhow::Result<clawden_core::RuntimeConfig> {
+            anyhow::bail!("not supported")
+        }
+        async fn set_config(
+            &self,
+            _: &clawden_core::AgentHandle,
+            _: &clawden_core::RuntimeConfig,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn list_skills(
+            &self,
+            _: &clawden_core::AgentHandle,
+        ) -> anyhow::Result<Vec<clawden_core::Skill>> {
+            Ok(Vec::new())
+        }
+        async fn install_skill(
+            &self,
+            _: &clawden_core::AgentHandle,
+            _: &clawden_core::SkillManifest,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+        async fn remove_skill(
+            &self,
+            _: &clawden_core::AgentHandle,
+            _: &str,
+        ) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+    }
+
+    #[tokio::test]
+    async fn metrics_history_endpoint_serves_agent_history() {
+        let mut state = test_state();
+        let adapter: Arc<dyn clawden_core::ClawAdapter> = Arc::new(SelfAdapter {
+            sampler: clawden_core::ProcessTreeSampler::default(),
+        });
+        state.manager = Arc::new(RwLock::new(LifecycleManager::new(
+            [(clawden_core::ClawRuntime::ZeroClaw, adapter)]
+                .into_iter()
+                .collect(),
+        )));
+        let agent_id = {
+            let mut manager = state.manager.write().await;
+            let record = manager.register_agent(
+                "alpha".to_string(),
+                clawden_core::ClawRuntime::ZeroClaw,
+                vec![],
+            );
+            manager
+                .start_agent(&record.id)
+                .await
+                .expect("agent should start");
+            record.id
+        };
+        for _ in 0..2 {
+            let targets = state.manager.read().await.metrics_targets();
+            let samples = sample_metrics(targets).await;
+            state.manager.write().await.record_metrics(samples);
+        }
+        let app = build_app(state.clone());
+
+        let history = |agent_id: &str| {
+            Request::builder()
+                .uri(format!("/agents/{agent_id}/metrics/history"))
+                .body(Body::empty())
+                .expect("request should build")
+        };
+        let res = app.clone().oneshot(history(&agent_id)).await.expect("call");
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .expect("body");
+        let json: serde_json::Value = serde_json::from_slice(&body).expect("json");
+        assert_eq!(json["agent_id"], agent_id.as_str());
+        let points = json["data_points"].as_array().expect("data points");
+        assert_eq!(points.len(), 2);
+        assert!(points
+            .iter()
+            .all(|point| point["memory_mb"].as_f64().is_some_and(|mb| mb > 0.0)));
+        assert!(points
+            .iter()
+            .all(|point| point["open_fds"].as_u64().is_some_and(|fds| fds > 0)));
+        assert!(json.get("message").is_none());
+
+        // Removing a stopped agent drops its history with it.
+        state
+            .manager
+            .write()
+            .await
+            .stop_agent(&agent_id)
+            .await
+            .expect("agent should stop");
+        let res = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri(format!("/agents/{agent_id}"))
+                    .body(Body::empty())
+                    .expect("request should build"),
+            )
+            .await
+            .expect("call");
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = app.oneshot(history(&agent_id)).await.expect("call");
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
 }
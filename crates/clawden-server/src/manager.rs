@@ -376,11 +376,11 @@ mod tests {
     use clawden_adapters::builtin_registry;
 
     use super::LifecycleManager;
-    use clawden_core::ClawRuntime;
+    use clawden_core::{ClawRuntime, ExecutionMode};
 
     #[test]
     fn registers_and_lists_agents() {
-        let mut manager = LifecycleManager::new(builtin_registry());
+        let mut manager = LifecycleManager::new(builtin_registry(ExecutionMode::Docker));
         manager.register_agent(
             "alpha".to_string(),
             ClawRuntime::ZeroClaw,